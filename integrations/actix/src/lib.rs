@@ -34,8 +34,12 @@ use leptos_meta::ServerMetaContext;
 use leptos_router::{
     components::provide_server_redirect,
     location::RequestUrl,
-    static_routes::{RegenerationFn, ResolvedStaticPath},
-    ExpandOptionals, Method, PathSegment, RouteList, RouteListing, SsrMode,
+    sitemap::Sitemap,
+    static_routes::{
+        ExportReport, RegenerationFn, ResolvedStaticPath, StaticExport,
+    },
+    ExpandOptionals, Method, PathSegment, RouteData, RouteList, RouteListing,
+    SsrMode, TrailingSlash,
 };
 use or_poisoned::OrPoisoned;
 use send_wrapper::SendWrapper;
@@ -47,6 +51,7 @@ use std::{
     collections::{HashMap, HashSet},
     fmt::{Debug, Display},
     future::Future,
+    io,
     ops::{Deref, DerefMut},
     path::Path,
     sync::{Arc, LazyLock, RwLock},
@@ -788,6 +793,17 @@ fn provide_contexts(
 ) {
     let path = leptos_corrected_path(&req);
 
+    let csrf_cookie = leptos::csrf::provide_csrf_token(
+        req.headers()
+            .get(header::COOKIE)
+            .and_then(|cookies| cookies.to_str().ok()),
+    );
+    if let Some(cookie) =
+        csrf_cookie.and_then(|cookie| HeaderValue::from_str(&cookie).ok())
+    {
+        res_options.append_header(header::SET_COOKIE, cookie);
+    }
+
     provide_context(RequestUrl::new(&path));
     provide_context(meta_context.clone());
     provide_context(res_options.clone());
@@ -931,7 +947,6 @@ impl ActixPath for Vec<PathSegment> {
     fn to_actix_path(&self) -> String {
         let mut path = String::new();
         for segment in self.iter() {
-            // TODO trailing slash handling
             let raw = segment.as_raw_str();
             if !raw.is_empty() && !raw.starts_with('/') {
                 path.push('/');
@@ -943,11 +958,21 @@ impl ActixPath for Vec<PathSegment> {
                     path.push_str(s);
                     path.push('}');
                 }
+                PathSegment::ConstrainedParam(s, pattern) => {
+                    path.push('{');
+                    path.push_str(s);
+                    path.push(':');
+                    path.push_str(pattern);
+                    path.push('}');
+                }
                 PathSegment::Splat(s) => {
                     path.push('{');
                     path.push_str(s);
                     path.push_str(":.*}");
                 }
+                // generated routes have their localized segments in their locale already, so
+                // this is only reached by a listing created by hand, in the default locale
+                PathSegment::Localized(_) => path.push_str(raw),
                 PathSegment::Unit => {}
                 PathSegment::OptionalParam(_) => {
                     #[cfg(feature = "tracing")]
@@ -957,6 +982,10 @@ impl ActixPath for Vec<PathSegment> {
                     );
                     Default::default()
                 }
+                _ => unreachable!(
+                    "to_actix_path does not support the path segment \
+                     {segment:?}"
+                ),
             }
         }
         path
@@ -971,6 +1000,9 @@ pub struct ActixRouteListing {
     methods: Vec<leptos_router::Method>,
     regenerate: Vec<RegenerationFn>,
     exclude: bool,
+    data: Vec<RouteData>,
+    trailing_slash: TrailingSlash,
+    redirect_trailing_slash: bool,
 }
 
 trait IntoRouteListing: Sized {
@@ -999,6 +1031,9 @@ impl IntoRouteListing for RouteListing {
                     methods,
                     regenerate,
                     exclude: false,
+                    data: self.data().to_vec(),
+                    trailing_slash: self.trailing_slash(),
+                    redirect_trailing_slash: false,
                 }
             })
             .collect()
@@ -1019,6 +1054,9 @@ impl ActixRouteListing {
             methods: methods.into_iter().collect(),
             regenerate: regenerate.into(),
             exclude: false,
+            data: Vec::new(),
+            trailing_slash: TrailingSlash::default(),
+            redirect_trailing_slash: false,
         }
     }
 
@@ -1036,6 +1074,70 @@ impl ActixRouteListing {
     pub fn methods(&self) -> impl Iterator<Item = leptos_router::Method> + '_ {
         self.methods.iter().copied()
     }
+
+    /// The data attached to this route and its parents, outermost first.
+    pub fn data(&self) -> &[RouteData] {
+        &self.data
+    }
+
+    /// The data of the innermost route that has attached a `T`.
+    pub fn find_data<T: 'static>(&self) -> Option<&T> {
+        self.data.iter().rev().find_map(RouteData::get)
+    }
+
+    /// Whether requests to this path are permanently redirected to the same path with its
+    /// trailing slash added or removed, rather than rendered.
+    pub fn redirect_trailing_slash(&self) -> bool {
+        self.redirect_trailing_slash
+    }
+}
+
+/// Adds the form of each path with its trailing slash added or removed, which redirects to the
+/// original, for routes that use [`TrailingSlash::Redirect`]. Other modes only serve the paths as
+/// they are written.
+fn add_trailing_slash_variants(
+    routes: Vec<ActixRouteListing>,
+) -> Vec<ActixRouteListing> {
+    let paths = routes
+        .iter()
+        .map(|route| route.path.clone())
+        .collect::<HashSet<_>>();
+    let variants = routes
+        .iter()
+        .filter(|route| {
+            route.trailing_slash == TrailingSlash::Redirect
+                && route.path != "/"
+                && !route.path.ends_with(":.*}")
+        })
+        .filter_map(|route| {
+            let path = toggle_trailing_slash(&route.path);
+            (!paths.contains(&path)).then(|| ActixRouteListing {
+                path,
+                redirect_trailing_slash: true,
+                ..route.clone()
+            })
+        })
+        .collect::<Vec<_>>();
+    routes.into_iter().chain(variants).collect()
+}
+
+fn toggle_trailing_slash(path: &str) -> String {
+    match path.strip_suffix('/') {
+        Some(path) => path.to_string(),
+        None => format!("{path}/"),
+    }
+}
+
+/// Permanently redirects a request to the same URL with its trailing slash added or removed.
+async fn handle_trailing_slash_redirect(req: HttpRequest) -> HttpResponse {
+    let path = toggle_trailing_slash(req.path());
+    let location = match req.uri().query() {
+        Some(query) => format!("{path}?{query}"),
+        None => path,
+    };
+    HttpResponse::PermanentRedirect()
+        .insert_header((LOCATION, location))
+        .finish()
 }
 
 /// Generates a list of all routes defined in Leptos's Router in your app. We can then use this to automatically
@@ -1051,20 +1153,7 @@ pub fn generate_route_list_with_exclusions_and_ssg_and_context<IV>(
 where
     IV: IntoView + 'static,
 {
-    let _ = any_spawner::Executor::init_tokio();
-
-    let owner = Owner::new_root(Some(Arc::new(SsrSharedContext::new())));
-    let (mock_meta, _) = ServerMetaContext::new();
-    let routes = owner
-        .with(|| {
-            // stub out a path for now
-            provide_context(RequestUrl::new(""));
-            provide_context(ResponseOptions::default());
-            provide_context(mock_meta);
-            additional_context();
-            RouteList::generate(&app_fn)
-        })
-        .unwrap_or_default();
+    let routes = generate_leptos_route_list(&app_fn, &additional_context);
 
     let generator = StaticRouteGenerator::new(
         &routes,
@@ -1073,7 +1162,7 @@ where
     );
 
     // Axum's Router defines Root routes as "/" not ""
-    let mut routes = routes
+    let routes = routes
         .into_inner()
         .into_iter()
         .flat_map(IntoRouteListing::into_route_listing)
@@ -1087,6 +1176,7 @@ where
             vec![],
         )]
     } else {
+        let mut routes = add_trailing_slash_variants(routes);
         // Routes to exclude from auto generation
         if let Some(excluded_routes) = &excluded_routes {
             routes.retain(|p| !excluded_routes.iter().any(|e| e == p.path()))
@@ -1104,11 +1194,86 @@ where
                 methods: Vec::new(),
                 regenerate: Vec::new(),
                 exclude: true,
+                data: Vec::new(),
+                trailing_slash: TrailingSlash::default(),
+                redirect_trailing_slash: false,
             });
 
     (routes.into_iter().chain(excluded).collect(), generator)
 }
 
+/// Walks the app tree to generate its route list, before it is converted into Actix paths.
+fn generate_leptos_route_list<IV>(
+    app_fn: &impl Fn() -> IV,
+    additional_context: &impl Fn(),
+) -> RouteList
+where
+    IV: IntoView + 'static,
+{
+    let _ = any_spawner::Executor::init_tokio();
+
+    let owner = Owner::new_root(Some(Arc::new(SsrSharedContext::new())));
+    let (mock_meta, _) = ServerMetaContext::new();
+    owner
+        .with(|| {
+            // stub out a path for now
+            provide_context(RequestUrl::new(""));
+            provide_context(ResponseOptions::default());
+            provide_context(mock_meta);
+            additional_context();
+            RouteList::generate(app_fn)
+        })
+        .unwrap_or_default()
+}
+
+/// An Actix [struct@Route](actix_web::Route) that serves a `sitemap.xml` generated from the routes
+/// defined in your app. The route list is generated once, and the params of any
+/// statically-rendered routes are loaded again for each request, so that new pages are listed
+/// without restarting the server.
+///
+/// ```rust,ignore
+/// let sitemap = Sitemap::new("https://example.com");
+/// App::new()
+///     .route("/sitemap.xml", sitemap_route(App, sitemap))
+///     .leptos_routes(routes, {
+///         let leptos_options = leptos_options.clone();
+///         move || shell(leptos_options.clone())
+///     })
+/// ```
+pub fn sitemap_route<IV>(
+    app_fn: impl Fn() -> IV + 'static + Send + Clone,
+    sitemap: Sitemap,
+) -> Route
+where
+    IV: IntoView + 'static,
+{
+    sitemap_route_with_context(app_fn, sitemap, || {})
+}
+
+/// An Actix [struct@Route](actix_web::Route) that serves a `sitemap.xml` generated from the routes
+/// defined in your app, like [`sitemap_route`]. Additional context will be provided to the app
+/// while its routes are generated.
+pub fn sitemap_route_with_context<IV>(
+    app_fn: impl Fn() -> IV + 'static + Send + Clone,
+    sitemap: Sitemap,
+    additional_context: impl Fn() + 'static + Send + Clone,
+) -> Route
+where
+    IV: IntoView + 'static,
+{
+    let routes =
+        Arc::new(generate_leptos_route_list(&app_fn, &additional_context));
+    web::get().to(move || {
+        let routes = Arc::clone(&routes);
+        let sitemap = sitemap.clone();
+        async move {
+            HttpResponse::Ok()
+                .content_type("application/xml")
+                .body(sitemap.generate(&routes).await)
+        }
+    })
+}
+
 /// Allows generating any prerendered routes.
 #[allow(clippy::type_complexity)]
 pub struct StaticRouteGenerator(
@@ -1222,6 +1387,75 @@ impl StaticRouteGenerator {
     }
 }
 
+/// Exports every page of your app to static files in the output directory of the
+/// [`StaticExport`], along with the files of the site root, such as the `pkg` directory, so that
+/// it can be deployed to a CDN without a server.
+///
+/// This does not bind a socket, so it can be run from a plain binary:
+/// ```rust,ignore
+/// #[tokio::main]
+/// async fn main() -> std::io::Result<()> {
+///     let conf = get_configuration(None).unwrap();
+///     let mut posts = StaticParamsMap::new();
+///     posts.insert("id", vec!["1".to_string(), "2".to_string()]);
+///     let export = StaticExport::new("dist").params("/posts/:id", posts);
+///
+///     let report =
+///         export_static_site(App, &conf.leptos_options, export).await?;
+///     print!("{report}");
+///     Ok(())
+/// }
+/// ```
+///
+/// Routes with params are only exported for the params given to the [`StaticExport`], or
+/// returned by their `prerender_params`. Pages that redirect, fail to render, or link to a
+/// server function, e.g., from an `<ActionForm/>`, are not written, and are listed in the
+/// returned [`ExportReport`] instead.
+pub async fn export_static_site<IV>(
+    app_fn: impl Fn() -> IV + 'static + Send + Clone,
+    options: &LeptosOptions,
+    export: StaticExport,
+) -> io::Result<ExportReport>
+where
+    IV: IntoView + 'static,
+{
+    export_static_site_with_context(app_fn, options, export, || {}).await
+}
+
+/// Exports every page of your app to static files, like [`export_static_site`]. Additional context
+/// will be provided to the app while each page is rendered.
+pub async fn export_static_site_with_context<IV>(
+    app_fn: impl Fn() -> IV + 'static + Send + Clone,
+    options: &LeptosOptions,
+    export: StaticExport,
+    additional_context: impl Fn() + 'static + Send + Clone,
+) -> io::Result<ExportReport>
+where
+    IV: IntoView + 'static,
+{
+    let routes = generate_leptos_route_list(&app_fn, &additional_context);
+    leptos_integration_utils::export_static_site(
+        options,
+        &export,
+        &routes,
+        |path| {
+            StaticRouteGenerator::render_route(
+                path,
+                app_fn.clone(),
+                additional_context.clone(),
+            )
+        },
+        |owner| {
+            owner
+                .with(use_context::<ResponseOptions>)
+                .and_then(|res| res.0.read().or_poisoned().status)
+                .map(|status| status.as_u16())
+        },
+        server_fn::actix::server_fn_paths().map(|(path, _)| path),
+    )
+    .await
+}
+
 static STATIC_HEADERS: LazyLock<
     std::sync::RwLock<HashMap<String, ResponseOptions>>,
 > = LazyLock::new(Default::default);
@@ -1453,6 +1687,14 @@ where
             let path = listing.path();
             let mode = listing.mode();
 
+            if listing.redirect_trailing_slash() {
+                router = router.route(
+                    path,
+                    web::route().to(handle_trailing_slash_redirect),
+                );
+                continue;
+            }
+
             for method in listing.methods() {
                 let additional_context = additional_context.clone();
                 let additional_context_and_method = move || {
@@ -1566,6 +1808,14 @@ impl LeptosRoutes for &mut ServiceConfig {
             let path = listing.path();
             let mode = listing.mode();
 
+            if listing.redirect_trailing_slash() {
+                router = router.route(
+                    path,
+                    web::route().to(handle_trailing_slash_redirect),
+                );
+                continue;
+            }
+
             for method in listing.methods() {
                 if matches!(listing.mode(), SsrMode::Static(_)) {
                     router = router.route(
@@ -38,14 +38,14 @@
 use axum::http::Uri;
 use axum::{
     body::{Body, Bytes},
-    extract::{FromRef, FromRequestParts, MatchedPath, State},
+    extract::{FromRef, FromRequestParts, MatchedPath, OriginalUri, State},
     http::{
         header::{self, HeaderName, HeaderValue, ACCEPT, LOCATION, REFERER},
         request::Parts,
         HeaderMap, Method, Request, Response, StatusCode,
     },
     response::IntoResponse,
-    routing::{delete, get, patch, post, put},
+    routing::{any, delete, get, patch, post, put},
 };
 use futures::{stream::once, Future, Stream, StreamExt};
 use hydration_context::SsrSharedContext;
@@ -61,11 +61,14 @@ use leptos_integration_utils::{
 };
 use leptos_meta::ServerMetaContext;
 #[cfg(feature = "default")]
-use leptos_router::static_routes::ResolvedStaticPath;
+use leptos_router::static_routes::{
+    ExportReport, ResolvedStaticPath, StaticExport,
+};
 use leptos_router::{
     components::provide_server_redirect, location::RequestUrl,
-    static_routes::RegenerationFn, ExpandOptionals, PathSegment, RouteList,
-    RouteListing, SsrMode,
+    sitemap::Sitemap, static_routes::RegenerationFn, ExpandOptionals,
+    PathSegment, RouteData, RouteList, RouteListMatcher, RouteListing,
+    RouteMatchId, SsrMode, TrailingSlash,
 };
 use or_poisoned::OrPoisoned;
 use server_fn::{error::ServerFnErrorErr, redirect::REDIRECT_HEADER};
@@ -76,7 +79,7 @@ use std::{collections::HashMap, path::Path};
 use std::{
     collections::HashSet,
     fmt::Debug,
-    io,
+    io, iter,
     pin::Pin,
     sync::{Arc, RwLock},
 };
@@ -958,6 +961,18 @@ fn provide_contexts(
     parts: Parts,
     default_res_options: ResponseOptions,
 ) {
+    let csrf_cookie = leptos::csrf::provide_csrf_token(
+        parts
+            .headers
+            .get(header::COOKIE)
+            .and_then(|cookies| cookies.to_str().ok()),
+    );
+    if let Some(cookie) =
+        csrf_cookie.and_then(|cookie| HeaderValue::from_str(&cookie).ok())
+    {
+        default_res_options.append_header(header::SET_COOKIE, cookie);
+    }
+
     provide_context(RequestUrl::new(path));
     provide_context(meta_context.clone());
     provide_context(parts);
@@ -1251,6 +1266,14 @@ pub struct AxumRouteListing {
     #[allow(unused)]
     regenerate: Vec<RegenerationFn>,
     exclude: bool,
+    data: Vec<RouteData>,
+    trailing_slash: TrailingSlash,
+    redirect_trailing_slash: bool,
+    route_ids: Vec<RouteMatchId>,
+    /// The routes that share this path in Axum's router but are served differently, which are
+    /// told apart for each request by the route that the app matches.
+    alternatives: Vec<AxumRouteListing>,
+    matcher: Option<RouteListMatcher>,
 }
 
 trait IntoRouteListing: Sized {
@@ -1279,6 +1302,12 @@ impl IntoRouteListing for RouteListing {
                     methods,
                     regenerate,
                     exclude: false,
+                    data: self.data().to_vec(),
+                    trailing_slash: self.trailing_slash(),
+                    redirect_trailing_slash: false,
+                    route_ids: self.route_ids().to_vec(),
+                    alternatives: Vec::new(),
+                    matcher: None,
                 }
             })
             .collect()
@@ -1299,6 +1328,12 @@ impl AxumRouteListing {
             methods: methods.into_iter().collect(),
             regenerate: regenerate.into(),
             exclude: false,
+            data: Vec::new(),
+            trailing_slash: TrailingSlash::default(),
+            redirect_trailing_slash: false,
+            route_ids: Vec::new(),
+            alternatives: Vec::new(),
+            matcher: None,
         }
     }
 
@@ -1316,6 +1351,74 @@ impl AxumRouteListing {
     pub fn methods(&self) -> impl Iterator<Item = leptos_router::Method> + '_ {
         self.methods.iter().copied()
     }
+
+    /// The data attached to this route and its parents, outermost first.
+    pub fn data(&self) -> &[RouteData] {
+        &self.data
+    }
+
+    /// The data of the innermost route that has attached a `T`.
+    pub fn find_data<T: 'static>(&self) -> Option<&T> {
+        self.data.iter().rev().find_map(RouteData::get)
+    }
+
+    /// Whether requests to this path are permanently redirected to the same path with its
+    /// trailing slash added or removed, rather than rendered.
+    pub fn redirect_trailing_slash(&self) -> bool {
+        self.redirect_trailing_slash
+    }
+}
+
+/// Adds the form of each path with its trailing slash added or removed, which redirects to the
+/// original, for routes that use [`TrailingSlash::Redirect`]. Other modes only serve the paths as
+/// they are written.
+fn add_trailing_slash_variants(
+    routes: Vec<AxumRouteListing>,
+) -> Vec<AxumRouteListing> {
+    let paths = routes
+        .iter()
+        .map(|route| route.path.clone())
+        .collect::<HashSet<_>>();
+    let variants = routes
+        .iter()
+        .filter(|route| {
+            route.trailing_slash == TrailingSlash::Redirect
+                && route.path != "/"
+                && !route.path.contains("{*")
+        })
+        .filter_map(|route| {
+            let path = toggle_trailing_slash(&route.path);
+            (!paths.contains(&path)).then(|| AxumRouteListing {
+                path,
+                redirect_trailing_slash: true,
+                ..route.clone()
+            })
+        })
+        .collect::<Vec<_>>();
+    routes.into_iter().chain(variants).collect()
+}
+
+fn toggle_trailing_slash(path: &str) -> String {
+    match path.strip_suffix('/') {
+        Some(path) => path.to_string(),
+        None => format!("{path}/"),
+    }
+}
+
+/// Permanently redirects a request to the same URL with its trailing slash added or removed.
+async fn handle_trailing_slash_redirect(
+    OriginalUri(uri): OriginalUri,
+) -> Response<Body> {
+    let path = toggle_trailing_slash(uri.path());
+    let location = match uri.query() {
+        Some(query) => format!("{path}?{query}"),
+        None => path,
+    };
+    Response::builder()
+        .status(StatusCode::PERMANENT_REDIRECT)
+        .header(LOCATION, location)
+        .body(Body::empty())
+        .expect("a redirect response should be valid")
 }
 
 /// Generates a list of all routes defined in Leptos's Router in your app. We can then use this to automatically
@@ -1335,34 +1438,47 @@ pub fn generate_route_list_with_exclusions_and_ssg_and_context<IV>(
 where
     IV: IntoView + 'static,
 {
-    // do some basic reactive setup
-    init_executor();
-    let owner = Owner::new_root(Some(Arc::new(SsrSharedContext::new())));
-
-    let routes = owner
-        .with(|| {
-            // stub out a path for now
-            provide_context(RequestUrl::new(""));
-            let (mock_parts, _) = Request::new(Body::from("")).into_parts();
-            let (mock_meta, _) = ServerMetaContext::new();
-            provide_contexts("", &mock_meta, mock_parts, Default::default());
-            additional_context();
-            RouteList::generate(&app_fn)
-        })
-        .unwrap_or_default();
+    let routes = generate_leptos_route_list(&app_fn, &additional_context);
 
     let generator = StaticRouteGenerator::new(
         &routes,
         app_fn.clone(),
         additional_context.clone(),
     );
+    let matcher = routes.matcher();
 
     // Axum's Router defines Root routes as "/" not ""
-    let mut routes = routes
+    let routes = routes
         .into_inner()
         .into_iter()
         .flat_map(IntoRouteListing::into_route_listing)
-        .collect::<Vec<_>>();
+        .fold(Vec::<AxumRouteListing>::new(), |mut routes, route| {
+            // routes whose params only differ in their names or constraints overlap in Axum's
+            // router, so they are registered once; if they are served differently, each request
+            // is served as the route that the app matches for it
+            let key = overlap_key(&route.path);
+            match routes
+                .iter_mut()
+                .find(|prev| overlap_key(&prev.path) == key)
+            {
+                Some(prev) => {
+                    for method in &route.methods {
+                        if !prev.methods.contains(method) {
+                            prev.methods.push(*method);
+                        }
+                    }
+                    if prev.mode != route.mode
+                        || prev.data != route.data
+                        || prev.regenerate != route.regenerate
+                    {
+                        prev.matcher = matcher.clone();
+                        prev.alternatives.push(route);
+                    }
+                }
+                None => routes.push(route),
+            }
+            routes
+        });
 
     let routes = if routes.is_empty() {
         vec![AxumRouteListing::new(
@@ -1372,6 +1488,7 @@ where
             vec![],
         )]
     } else {
+        let mut routes = add_trailing_slash_variants(routes);
         // Routes to exclude from auto generation
         if let Some(excluded_routes) = &excluded_routes {
             routes.retain(|p| !excluded_routes.iter().any(|e| e == p.path()))
@@ -1384,15 +1501,108 @@ where
             .flatten()
             .map(|path| AxumRouteListing {
                 path,
-                mode: Default::default(),
-                methods: Vec::new(),
-                regenerate: Vec::new(),
                 exclude: true,
+                ..Default::default()
             });
 
     (routes.into_iter().chain(excluded).collect(), generator)
 }
 
+/// The path with the names of its params removed, which is the same for paths that Axum
+/// considers to overlap.
+fn overlap_key(path: &str) -> String {
+    path.split('/')
+        .map(|segment| match segment.strip_prefix("{*") {
+            Some(_) => "{*}",
+            None if segment.starts_with('{') => "{}",
+            None => segment,
+        })
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+/// Walks the app tree to generate its route list, before it is converted into Axum paths.
+fn generate_leptos_route_list<IV>(
+    app_fn: &impl Fn() -> IV,
+    additional_context: &impl Fn(),
+) -> RouteList
+where
+    IV: IntoView + 'static,
+{
+    // do some basic reactive setup
+    init_executor();
+    let owner = Owner::new_root(Some(Arc::new(SsrSharedContext::new())));
+
+    owner
+        .with(|| {
+            // stub out a path for now
+            provide_context(RequestUrl::new(""));
+            let (mock_parts, _) = Request::new(Body::from("")).into_parts();
+            let (mock_meta, _) = ServerMetaContext::new();
+            provide_contexts("", &mock_meta, mock_parts, Default::default());
+            additional_context();
+            RouteList::generate(app_fn)
+        })
+        .unwrap_or_default()
+}
+
+/// Serves a `sitemap.xml` generated from the routes defined in your app. The route list is
+/// generated once, and the params of any statically-rendered routes are loaded again for each
+/// request, so that new pages are listed without restarting the server.
+///
+/// ```rust,ignore
+/// let sitemap = Sitemap::new("https://example.com");
+/// let app = Router::new()
+///     .route("/sitemap.xml", get(sitemap_handler(App, sitemap)))
+///     .leptos_routes(&leptos_options, routes, {
+///         let leptos_options = leptos_options.clone();
+///         move || shell(leptos_options.clone())
+///     });
+/// ```
+pub fn sitemap_handler<IV>(
+    app_fn: impl Fn() -> IV + Clone + Send + 'static,
+    sitemap: Sitemap,
+) -> impl Fn() -> Pin<Box<dyn Future<Output = Response<Body>> + Send + 'static>>
+       + Clone
+       + Send
+       + 'static
+where
+    IV: IntoView + 'static,
+{
+    sitemap_handler_with_context(app_fn, sitemap, || {})
+}
+
+/// Serves a `sitemap.xml` generated from the routes defined in your app, like
+/// [`sitemap_handler`]. Additional context will be provided to the app while its routes are
+/// generated.
+pub fn sitemap_handler_with_context<IV>(
+    app_fn: impl Fn() -> IV + Clone + Send + 'static,
+    sitemap: Sitemap,
+    additional_context: impl Fn() + Clone + Send + 'static,
+) -> impl Fn() -> Pin<Box<dyn Future<Output = Response<Body>> + Send + 'static>>
+       + Clone
+       + Send
+       + 'static
+where
+    IV: IntoView + 'static,
+{
+    let routes =
+        Arc::new(generate_leptos_route_list(&app_fn, &additional_context));
+    move || {
+        let routes = Arc::clone(&routes);
+        let sitemap = sitemap.clone();
+        Box::pin(async move {
+            let xml = sitemap.generate(&routes).await;
+            let mut res = Response::new(Body::from(xml));
+            res.headers_mut().insert(
+                header::CONTENT_TYPE,
+                HeaderValue::from_static("application/xml"),
+            );
+            res
+        })
+    }
+}
+
 /// Allows generating any prerendered routes.
 #[allow(clippy::type_complexity)]
 pub struct StaticRouteGenerator(
@@ -1530,6 +1740,77 @@ impl StaticRouteGenerator {
     }
 }
 
+/// Exports every page of your app to static files in the output directory of the
+/// [`StaticExport`], along with the files of the site root, such as the `pkg` directory, so that
+/// it can be deployed to a CDN without a server.
+///
+/// This does not bind a socket, so it can be run from a plain binary:
+/// ```rust,ignore
+/// #[tokio::main]
+/// async fn main() -> std::io::Result<()> {
+///     let conf = get_configuration(None).unwrap();
+///     let mut posts = StaticParamsMap::new();
+///     posts.insert("id", vec!["1".to_string(), "2".to_string()]);
+///     let export = StaticExport::new("dist").params("/posts/:id", posts);
+///
+///     let report =
+///         export_static_site(App, &conf.leptos_options, export).await?;
+///     print!("{report}");
+///     Ok(())
+/// }
+/// ```
+///
+/// Routes with params are only exported for the params given to the [`StaticExport`], or
+/// returned by their `prerender_params`. Pages that redirect, fail to render, or link to a
+/// server function, e.g., from an `<ActionForm/>`, are not written, and are listed in the
+/// returned [`ExportReport`] instead.
+#[cfg(feature = "default")]
+pub async fn export_static_site<IV>(
+    app_fn: impl Fn() -> IV + Clone + Send + 'static,
+    options: &LeptosOptions,
+    export: StaticExport,
+) -> io::Result<ExportReport>
+where
+    IV: IntoView + 'static,
+{
+    export_static_site_with_context(app_fn, options, export, || {}).await
+}
+
+/// Exports every page of your app to static files, like [`export_static_site`]. Additional context
+/// will be provided to the app while each page is rendered.
+#[cfg(feature = "default")]
+pub async fn export_static_site_with_context<IV>(
+    app_fn: impl Fn() -> IV + Clone + Send + 'static,
+    options: &LeptosOptions,
+    export: StaticExport,
+    additional_context: impl Fn() + Clone + Send + 'static,
+) -> io::Result<ExportReport>
+where
+    IV: IntoView + 'static,
+{
+    let routes = generate_leptos_route_list(&app_fn, &additional_context);
+    leptos_integration_utils::export_static_site(
+        options,
+        &export,
+        &routes,
+        |path| {
+            StaticRouteGenerator::render_route(
+                path,
+                app_fn.clone(),
+                additional_context.clone(),
+            )
+        },
+        |owner| {
+            owner
+                .with(use_context::<ResponseOptions>)
+                .and_then(|res| res.0.read().or_poisoned().status)
+                .map(|status| status.as_u16())
+        },
+        server_fn::axum::server_fn_paths().map(|(path, _)| path),
+    )
+    .await
+}
+
 #[cfg(feature = "default")]
 static STATIC_HEADERS: LazyLock<
     std::sync::RwLock<HashMap<String, ResponseOptions>>,
@@ -1726,6 +2007,73 @@ where
         T: 'static;
 }
 
+/// Renders the app for a request to a route the way the route is served.
+type RouteHandler<S> = Arc<
+    dyn Fn(State<S>, Request<Body>) -> PinnedFuture<Response<Body>>
+        + Send
+        + Sync,
+>;
+
+/// The handler for a route that shares its path with routes that are served differently, which
+/// is chosen for each request by the route that the app matches.
+fn route_handler<S, IV>(
+    listing: &AxumRouteListing,
+    additional_context: impl Fn() + 'static + Clone + Send + Sync,
+    app_fn: impl Fn() -> IV + Clone + Send + Sync + 'static,
+) -> RouteHandler<S>
+where
+    LeptosOptions: FromRef<S>,
+    S: Send + 'static,
+    IV: IntoView + 'static,
+{
+    match listing.mode() {
+        SsrMode::Static(_) => {
+            #[cfg(feature = "default")]
+            {
+                let handler = handle_static_route(
+                    additional_context,
+                    app_fn,
+                    listing.regenerate.clone(),
+                );
+                Arc::new(move |state, req| handler(state, req))
+            }
+            #[cfg(not(feature = "default"))]
+            {
+                _ = (additional_context, app_fn);
+                panic!(
+                    "Static routes are not currently supported on WASM32 \
+                     server targets."
+                );
+            }
+        }
+        SsrMode::OutOfOrder => {
+            let handler =
+                render_app_to_stream_with_context(additional_context, app_fn);
+            Arc::new(move |_, req| handler(req))
+        }
+        SsrMode::PartiallyBlocked => {
+            let handler = render_app_to_stream_with_context_and_replace_blocks(
+                additional_context,
+                app_fn,
+                true,
+            );
+            Arc::new(move |_, req| handler(req))
+        }
+        SsrMode::InOrder => {
+            let handler = render_app_to_stream_in_order_with_context(
+                additional_context,
+                app_fn,
+            );
+            Arc::new(move |_, req| handler(req))
+        }
+        SsrMode::Async => {
+            let handler =
+                render_app_async_with_context(additional_context, app_fn);
+            Arc::new(move |_, req| handler(req))
+        }
+    }
+}
+
 trait AxumPath {
     fn to_axum_path(&self) -> String;
 }
@@ -1734,14 +2082,15 @@ impl AxumPath for Vec<PathSegment> {
     fn to_axum_path(&self) -> String {
         let mut path = String::new();
         for segment in self.iter() {
-            // TODO trailing slash handling
             let raw = segment.as_raw_str();
             if !raw.is_empty() && !raw.starts_with('/') {
                 path.push('/');
             }
             match segment {
                 PathSegment::Static(s) => path.push_str(s),
-                PathSegment::Param(s) => {
+                // Axum's router has no way to constrain a param, so the value is checked when
+                // the app's routes are matched
+                PathSegment::Param(s) | PathSegment::ConstrainedParam(s, _) => {
                     path.push('{');
                     path.push_str(s);
                     path.push('}');
@@ -1752,6 +2101,9 @@ impl AxumPath for Vec<PathSegment> {
                     path.push_str(s);
                     path.push('}');
                 }
+                // generated routes have their localized segments in their locale already, so
+                // this is only reached by a listing created by hand, in the default locale
+                PathSegment::Localized(_) => path.push_str(raw),
                 PathSegment::Unit => {}
                 PathSegment::OptionalParam(_) => {
                     #[cfg(feature = "tracing")]
@@ -1761,6 +2113,10 @@ impl AxumPath for Vec<PathSegment> {
                     );
                     Default::default()
                 }
+                _ => unreachable!(
+                    "to_axum_path does not support the path segment \
+                     {segment:?}"
+                ),
             }
         }
         path
@@ -1853,13 +2209,53 @@ where
         for listing in paths.iter().filter(|p| !p.exclude) {
             let path = listing.path();
 
+            if listing.redirect_trailing_slash() {
+                router =
+                    router.route(path, any(handle_trailing_slash_redirect));
+                continue;
+            }
+
             for method in listing.methods() {
                 let cx_with_state = cx_with_state.clone();
                 let cx_with_state_and_method = move || {
                     provide_context(method);
                     cx_with_state();
                 };
-                router = if matches!(listing.mode(), SsrMode::Static(_)) {
+                router = if let Some(matcher) = listing.matcher.clone() {
+                    let handlers = iter::once(listing)
+                        .chain(&listing.alternatives)
+                        .map(|route| {
+                            (
+                                route.route_ids.clone(),
+                                route_handler(
+                                    route,
+                                    cx_with_state_and_method.clone(),
+                                    app_fn.clone(),
+                                ),
+                            )
+                        })
+                        .collect::<Vec<_>>();
+                    // each request is served as the route that the app matches for it, or as
+                    // the first of these routes if it matches none of them
+                    let handler = move |state: State<S>, req: Request<Body>| {
+                        let route_ids = matcher.route_ids(req.uri().path());
+                        let (_, handler) = handlers
+                            .iter()
+                            .find(|(ids, _)| *ids == route_ids)
+                            .unwrap_or(&handlers[0]);
+                        handler(state, req)
+                    };
+                    router.route(
+                        path,
+                        match method {
+                            leptos_router::Method::Get => get(handler),
+                            leptos_router::Method::Post => post(handler),
+                            leptos_router::Method::Put => put(handler),
+                            leptos_router::Method::Delete => delete(handler),
+                            leptos_router::Method::Patch => patch(handler),
+                        },
+                    )
+                } else if matches!(listing.mode(), SsrMode::Static(_)) {
                     #[cfg(feature = "default")]
                     {
                         router.route(
@@ -1960,6 +2356,11 @@ where
     {
         let mut router = self;
         for listing in paths.iter().filter(|p| !p.exclude) {
+            if listing.redirect_trailing_slash() {
+                router = router
+                    .route(listing.path(), any(handle_trailing_slash_redirect));
+                continue;
+            }
             for method in listing.methods() {
                 router = router.route(
                     listing.path(),
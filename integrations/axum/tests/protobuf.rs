@@ -0,0 +1,94 @@
+use futures::{stream, StreamExt};
+use leptos::{
+    prelude::ServerFnError,
+    server,
+    server_fn::{
+        client::in_process::{InProcessClient, InProcessContext},
+        codec::{FramedStream, Protobuf, ProtobufEncoding, StreamingFramed},
+        Decodes, Encodes, ServerFn,
+    },
+};
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct Greeting {
+    #[prost(string, tag = "1")]
+    pub name: String,
+    #[prost(uint32, tag = "2")]
+    pub count: u32,
+}
+
+#[server(input = Protobuf, output = Protobuf, client = InProcessClient)]
+pub async fn greet(greeting: Greeting) -> Result<Greeting, ServerFnError> {
+    Ok(Greeting {
+        name: format!("Hello, {}!", greeting.name),
+        count: greeting.count + 1,
+    })
+}
+
+#[server(
+    input = Protobuf,
+    output = StreamingFramed<ProtobufEncoding>,
+    client = InProcessClient
+)]
+pub async fn repeat(
+    greeting: Greeting,
+) -> Result<FramedStream<Greeting>, ServerFnError> {
+    Ok(stream::iter((0..greeting.count).map(move |count| Greeting {
+        name: greeting.name.clone(),
+        count,
+    }))
+    .into())
+}
+
+fn alice(count: u32) -> Greeting {
+    Greeting {
+        name: "alice".into(),
+        count,
+    }
+}
+
+#[test]
+fn protobuf_arguments_are_sent_as_the_request_message() {
+    // the arguments struct is encoded exactly as its single argument
+    let bytes =
+        ProtobufEncoding::encode(&Greet { greeting: alice(2) }).unwrap();
+    assert_eq!(bytes, ProtobufEncoding::encode(&alice(2)).unwrap());
+
+    let decoded: Greet = ProtobufEncoding::decode(bytes).unwrap();
+    assert_eq!(decoded.greeting, alice(2));
+}
+
+#[tokio::test]
+async fn protobuf_messages_round_trip() {
+    let greeting = InProcessContext::new()
+        .run(Greet { greeting: alice(2) }.run_on_client())
+        .await
+        .unwrap();
+    assert_eq!(
+        greeting,
+        Greeting {
+            name: "Hello, alice!".into(),
+            count: 3,
+        }
+    );
+}
+
+#[tokio::test]
+async fn protobuf_streams_round_trip() {
+    let greetings = InProcessContext::new()
+        .run(async {
+            Repeat { greeting: alice(3) }
+                .run_on_client()
+                .await
+                .unwrap()
+                .into_inner()
+                .collect::<Vec<_>>()
+                .await
+        })
+        .await;
+    let greetings = greetings
+        .into_iter()
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap();
+    assert_eq!(greetings, vec![alice(0), alice(1), alice(2)]);
+}
@@ -11,7 +11,21 @@ use leptos::{
 };
 use leptos_config::LeptosOptions;
 use leptos_meta::{Link, ServerMetaContextOutput};
-use std::{future::Future, pin::Pin, sync::Arc};
+use leptos_router::{
+    static_routes::{
+        ExportReport, StaticExport, UnsupportedPage, UnsupportedReason,
+    },
+    Method, PathSegment, RouteList, RouteListing, SsrMode,
+};
+use std::{
+    collections::HashSet,
+    fs,
+    future::Future,
+    io,
+    path::{Component, Path, PathBuf},
+    pin::Pin,
+    sync::Arc,
+};
 
 pub type PinnedStream<T> = Pin<Box<dyn Stream<Item = T> + Send>>;
 pub type PinnedFuture<T> = Pin<Box<dyn Future<Output = T> + Send>>;
@@ -211,3 +225,140 @@ pub fn static_file_path(options: &LeptosOptions, path: &str) -> String {
     };
     format!("{}/{}.html", options.site_root, path)
 }
+
+/// Renders the pages of a [`StaticExport`] one at a time, and writes them into its output
+/// directory along with the files of the site root, such as the `pkg` directory. Pages that set a
+/// status other than `200 OK`, or that link to one of the registered `server_fn_paths`, e.g., as
+/// the `action` of a form, are left out and added to the report.
+pub async fn export_static_site<Fut>(
+    options: &LeptosOptions,
+    export: &StaticExport,
+    routes: &RouteList,
+    render_fn: impl Fn(String) -> Fut,
+    status: impl Fn(&Owner) -> Option<u16>,
+    server_fn_paths: impl IntoIterator<Item = &'static str>,
+) -> io::Result<ExportReport>
+where
+    Fut: Future<Output = (Owner, String)>,
+{
+    let out_dir = export.out_dir().to_path_buf();
+    tokio::fs::create_dir_all(&out_dir).await?;
+    let site_root = PathBuf::from(&*options.site_root);
+    tokio::task::spawn_blocking({
+        let out_dir = out_dir.clone();
+        move || {
+            let out_dir_path = out_dir.canonicalize()?;
+            if site_root.canonicalize()? != out_dir_path {
+                copy_dir(&site_root, &out_dir, &out_dir_path)?;
+            }
+            Ok::<_, io::Error>(())
+        }
+    })
+    .await??;
+    let server_fn_paths = server_fn_paths.into_iter().collect::<HashSet<_>>();
+
+    // an app without a <Router/> has a single page
+    let plan = if routes.iter().next().is_none() {
+        let root = RouteListing::new(
+            [PathSegment::Unit],
+            SsrMode::default(),
+            [Method::Get],
+            [],
+        );
+        export.plan(&RouteList::from(vec![root])).await
+    } else {
+        export.plan(routes).await
+    };
+    let mut report = ExportReport {
+        pages: Vec::new(),
+        unsupported: plan.unsupported,
+    };
+    for path in plan.paths {
+        let path = match path.as_ref() {
+            "" => "/".to_string(),
+            path => path.to_string(),
+        };
+        let (owner, html) = render_fn(path.clone()).await;
+        let status = status(&owner);
+        owner.unset_with_forced_cleanup();
+
+        let reason = match status {
+            Some(status) if status != 200 => {
+                Some(UnsupportedReason::Status(status))
+            }
+            _ => linked_server_fn(&html, &server_fn_paths).map(|server_fn| {
+                UnsupportedReason::ServerFunction(server_fn.to_string())
+            }),
+        };
+        if let Some(reason) = reason {
+            report.unsupported.push(UnsupportedPage { path, reason });
+            continue;
+        }
+
+        let file = export_file_path(&out_dir, &path)?;
+        if let Some(dir) = file.parent() {
+            tokio::fs::create_dir_all(dir).await?;
+        }
+        tokio::fs::write(file, html).await?;
+        report.pages.push(path);
+    }
+    Ok(report)
+}
+
+/// The first registered server function that is linked from an attribute of the page, e.g., the
+/// `action` of an `<ActionForm/>`.
+fn linked_server_fn<'a>(
+    html: &str,
+    server_fn_paths: &HashSet<&'a str>,
+) -> Option<&'a str> {
+    // attribute values are always double-quoted in rendered HTML
+    html.split("=\"").skip(1).find_map(|value| {
+        let value = value.split('"').next().unwrap_or_default();
+        let path = value.split(['?', '#']).next().unwrap_or_default();
+        server_fn_paths.get(path).copied()
+    })
+}
+
+/// The file a page is exported to, laid out like the files of `SsrMode::Static` routes. Paths
+/// that would be written outside of `out_dir` are rejected.
+fn export_file_path(out_dir: &Path, path: &str) -> io::Result<PathBuf> {
+    let path = if path != "/" && path.ends_with('/') {
+        format!("{path}index")
+    } else {
+        path.to_string()
+    };
+    let path = match path.trim_start_matches('/') {
+        "" => "index",
+        path => path,
+    };
+    let file = PathBuf::from(format!("{path}.html"));
+    if !file
+        .components()
+        .all(|component| matches!(component, Component::Normal(_)))
+    {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("cannot export the page at {path} to a file"),
+        ));
+    }
+    Ok(out_dir.join(file))
+}
+
+/// Copies the contents of a directory, except for `skip`.
+fn copy_dir(from: &Path, to: &Path, skip: &Path) -> io::Result<()> {
+    for entry in fs::read_dir(from)? {
+        let entry = entry?;
+        let source = entry.path();
+        if source.canonicalize()? == skip {
+            continue;
+        }
+        let target = to.join(entry.file_name());
+        if entry.file_type()?.is_dir() {
+            fs::create_dir_all(&target)?;
+            copy_dir(&source, &target, skip)?;
+        } else {
+            fs::copy(&source, &target)?;
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,146 @@
+use crate::{context::use_context, server::SharedValue, IntoView};
+use server_fn::csrf::CSRF_FIELD;
+use std::{fmt::Display, ops::Deref, sync::Arc};
+use tachys::html::element::input;
+
+/// A token that protects server functions against cross-site request forgery.
+///
+/// When server functions use [`CsrfMode::Token`](server_fn::csrf::CsrfMode::Token), the server
+/// integrations issue a token in a cookie while rendering each page, and requests to server
+/// functions must repeat it. Server functions called from the browser send the token
+/// automatically, and [`ActionForm`](crate::form::ActionForm) adds it to the form in a hidden
+/// field.
+///
+/// If you build your own requests or forms, the current token can be accessed using
+/// [`use_csrf_token`]:
+///
+/// ```rust,ignore
+/// #[component]
+/// pub fn Logout() -> impl IntoView {
+///     view! {
+///         <form method="post" action="/api/logout">
+///             {use_csrf_token().map(|token| view! {
+///                 <input type="hidden" name="__csrf" value=token.to_string()/>
+///             })}
+///             <button>"Log out"</button>
+///         </form>
+///     }
+/// }
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct CsrfToken(pub(crate) Arc<str>);
+
+impl CsrfToken {
+    /// Returns a reference to the inner reference-counted string slice representing the token.
+    pub fn as_inner(&self) -> &Arc<str> {
+        &self.0
+    }
+}
+
+impl Deref for CsrfToken {
+    type Target = str;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl Display for CsrfToken {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+/// Accesses the CSRF token for the current user.
+///
+/// On the server, this is the token provided by the server integration for the current response.
+/// In the browser, it is read from the cookie in which the token was issued. Returns `None` if
+/// CSRF tokens are not being used.
+pub fn use_csrf_token() -> Option<CsrfToken> {
+    use_context::<CsrfToken>().or_else(token_from_document)
+}
+
+#[cfg(target_arch = "wasm32")]
+fn token_from_document() -> Option<CsrfToken> {
+    server_fn::csrf::token_from_document().map(|token| CsrfToken(token.into()))
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn token_from_document() -> Option<CsrfToken> {
+    None
+}
+
+/// Renders a hidden field with the current CSRF token, if any, so that a form that is submitted
+/// before WASM has loaded repeats the token in its body.
+///
+/// The token is serialized with the page, so the field hydrates with the value it was rendered
+/// with on the server, even if the cookie cannot be read in the browser.
+pub(crate) fn form_field() -> impl IntoView {
+    let token =
+        SharedValue::new(|| use_csrf_token().map(|token| token.to_string()))
+            .into_inner();
+    token.map(|token| input().r#type("hidden").name(CSRF_FIELD).value(token))
+}
+
+/// Provides the CSRF token for the current request via context, if server functions are using
+/// [`CsrfMode::Token`](server_fn::csrf::CsrfMode::Token).
+///
+/// `cookies` is the value of the request’s `Cookie` header. If it already contains a token that
+/// is valid for the current session (see
+/// [`set_csrf_session`](server_fn::csrf::set_csrf_session)), that token is reused. Otherwise, a
+/// new token is generated and the value of the `Set-Cookie` header that issues it is returned, to
+/// be added to the response.
+#[cfg(feature = "csrf")]
+pub fn provide_csrf_token(cookies: Option<&str>) -> Option<String> {
+    use server_fn::csrf::{
+        csrf_mode, session_token_from_cookies, token_cookie, token_for_session,
+        CsrfMode,
+    };
+
+    if csrf_mode() != CsrfMode::Token {
+        return None;
+    }
+    match cookies.and_then(session_token_from_cookies) {
+        Some(token) => {
+            crate::context::provide_context(CsrfToken(token.into()));
+            None
+        }
+        None => {
+            let nonce = CsrfToken::new();
+            let token = CsrfToken(token_for_session(&nonce, cookies).into());
+            let cookie = token_cookie(&token);
+            crate::context::provide_context(token);
+            Some(cookie)
+        }
+    }
+}
+
+#[cfg(feature = "csrf")]
+impl CsrfToken {
+    /// Generates a new token from 32 bytes (256 bits) of random data.
+    pub fn new() -> Self {
+        use base64::{
+            alphabet,
+            engine::{self, general_purpose},
+            Engine as _,
+        };
+        use rand::{rng, RngCore as _};
+        const TOKEN_ENGINE: engine::GeneralPurpose =
+            engine::GeneralPurpose::new(
+                &alphabet::URL_SAFE,
+                general_purpose::NO_PAD,
+            );
+
+        let mut rng = rng();
+        let mut bytes = [0; 32];
+        rng.fill_bytes(&mut bytes);
+        CsrfToken(TOKEN_ENGINE.encode(bytes).into())
+    }
+}
+
+#[cfg(feature = "csrf")]
+impl Default for CsrfToken {
+    fn default() -> Self {
+        Self::new()
+    }
+}
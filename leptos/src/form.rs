@@ -140,7 +140,7 @@ where
         .action(ServFn::url())
         .method("post")
         .on(submit, on_submit)
-        .child(children());
+        .child((crate::csrf::form_field(), children()));
     if let Some(node_ref) = node_ref {
         Either::Left(action_form.node_ref(node_ref))
     } else {
@@ -207,7 +207,7 @@ where
         .method("post")
         .attr("method", "post")
         .on(submit, on_submit)
-        .child(children());
+        .child((crate::csrf::form_field(), children()));
     if let Some(node_ref) = node_ref {
         Either::Left(action_form.node_ref(node_ref))
     } else {
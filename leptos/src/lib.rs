@@ -92,6 +92,8 @@
 //!   client unless they use the `#[island]` macro.
 //! - **`hydrate`** Hydration: use this to add interactivity to an SSRed Leptos app.
 //! - **`nonce`** Adds support for nonces to be added as part of a Content Security Policy.
+//! - **`csrf`** Adds support for issuing tokens that protect server functions against cross-site
+//!   request forgery.
 //! - **`rkyv`** In SSR/hydrate mode, enables using [`rkyv`](https://docs.rs/rkyv/latest/rkyv/) to serialize resources.
 //! - **`tracing`** Adds support for [`tracing`](https://docs.rs/tracing/latest/tracing/).
 //! - **`trace-component-props`** Adds `tracing` support for component props.
@@ -164,8 +166,8 @@ pub mod prelude {
     // However, it is definitely useful relative to looking up every struct etc.
     mod export_types {
         pub use crate::{
-            callback::*, children::*, component::*, control_flow::*, error::*,
-            form::*, hydration::*, into_view::*, mount::*, nonce::*,
+            callback::*, children::*, component::*, control_flow::*, csrf::*,
+            error::*, form::*, hydration::*, into_view::*, mount::*, nonce::*,
             suspense::*, text_prop::*,
         };
         pub use leptos_config::*;
@@ -238,6 +240,13 @@ pub mod hydration;
 /// Utilities for exporting nonces to be used for a Content Security Policy.
 pub mod nonce;
 
+/// Tokens that protect server functions against cross-site request forgery.
+pub mod csrf;
+
+/// Reactive progress for chunked file uploads.
+#[cfg(feature = "multipart")]
+pub mod upload;
+
 /// Components to load asynchronous data.
 pub mod suspense {
     pub use crate::{suspense_component::*, transition::*};
@@ -0,0 +1,68 @@
+use reactive_graph::{
+    signal::{ReadSignal, RwSignal},
+    traits::Set,
+};
+use server_fn::codec::upload::{FileUpload, UploadProgress};
+
+/// Tracks the progress of chunked file uploads in a reactive signal.
+///
+/// Pass a [`FileUpload`] through [`track`](UploadProgressSignal::track) before calling a server
+/// function that uses the [`ChunkedUpload`](server_fn::codec::upload::ChunkedUpload) protocol,
+/// and the signal is updated after every chunk the server receives. This works with server
+/// functions that are called directly and with [`ServerAction`](crate::prelude::ServerAction):
+///
+/// ```rust,ignore
+/// #[component]
+/// pub fn Uploader() -> impl IntoView {
+///     let upload = ServerAction::<UploadFile>::new();
+///     let progress = UploadProgressSignal::new();
+///
+///     let on_change = move |ev: web_sys::Event| {
+///         let input: web_sys::HtmlInputElement = event_target(&ev);
+///         if let Some(file) = input.files().and_then(|files| files.get(0)) {
+///             upload.dispatch(progress.track(FileUpload::from_file(&file)).into());
+///         }
+///     };
+///
+///     view! {
+///         <input type="file" on:change=on_change/>
+///         <progress max="1" value=move || {
+///             progress.progress().get().map(|p| p.fraction()).unwrap_or_default()
+///         }/>
+///     }
+/// }
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct UploadProgressSignal(RwSignal<Option<UploadProgress>>);
+
+impl UploadProgressSignal {
+    /// Creates a new signal, which is `None` until an upload reports its progress.
+    #[track_caller]
+    pub fn new() -> Self {
+        Self(RwSignal::new(None))
+    }
+
+    /// Returns a read-only signal with the progress of the most recent upload.
+    pub fn progress(&self) -> ReadSignal<Option<UploadProgress>> {
+        self.0.read_only()
+    }
+
+    /// Resets the progress to `None`.
+    pub fn clear(&self) {
+        self.0.set(None);
+    }
+
+    /// Makes the upload report its progress to this signal.
+    ///
+    /// Any progress callback already set on the upload is replaced.
+    pub fn track(&self, upload: FileUpload) -> FileUpload {
+        let signal = self.0;
+        upload.on_progress(move |progress| signal.set(Some(progress)))
+    }
+}
+
+impl Default for UploadProgressSignal {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -869,6 +869,35 @@ pub fn slot(args: proc_macro::TokenStream, s: TokenStream) -> TokenStream {
 ///   to convert from the argument type to the server function type, and vice versa, allowing you to convert
 ///   between them easily. Setting `impl_from` to `false` disables this, which can be necessary for argument types
 ///   for which this would create a conflicting implementation. (defaults to `true`)
+/// - `max_body`: the largest request body the server function accepts, as a number of bytes or a
+///   string like `"512KB"` or `"1MB"`. Larger requests are rejected with `413 Payload Too Large`.
+/// - `rate_limit`: the number of calls each client may make in a period, like `"10/s"`, `"100/min"`
+///   or `"5/30s"`. Further calls are rejected with `429 Too Many Requests`. See
+///   [`server_fn::limits`](../server_fn/limits/index.html) for how clients are identified and how
+///   to share limits between servers.
+/// - `csrf`: set to `false` to exempt the server function from the cross-site request forgery
+///   check configured with
+///   [`server_fn::csrf::set_csrf_mode`](../server_fn/csrf/fn.set_csrf_mode.html), e.g., for a
+///   webhook called by another service.
+/// - `cache`: the `Cache-Control` header for successful responses of a server function that uses
+///   `GET` requests, like `"public, max-age=60"`. Cached responses also get an `ETag`, and
+///   conditional requests are answered with `304 Not Modified`. See
+///   [`server_fn::cache`](../server_fn/cache/index.html).
+/// - `compress`: set to `false` to send the server function’s responses uncompressed when one of
+///   the `gzip`, `brotli` or `zstd` features is enabled, e.g., for data that is already compressed.
+///   See [`server_fn::compression`](../server_fn/compression/index.html).
+/// - `version`: a version number that is added to the server function’s URL from version 2 on
+///   (e.g., `/api/add_todo/v2`), so that a new version can be served alongside the old one while
+///   older clients are still deployed. See [`server_fn::manifest`](../server_fn/manifest/index.html)
+///   for how to detect breaking changes between releases, and the limits of that check.
+/// - `adapt`: the struct of a newer version of the server function, e.g., `adapt = AddTodoV2`. The
+///   function’s body must be empty: on the server, its arguments are converted into the newer
+///   version with [`From`], which is run instead. See
+///   [`server_fn::manifest::run_adapted`](../server_fn/manifest/fn.run_adapted.html).
+/// - `protocol`: the protocol used to call the server function, if not plain HTTP. With the `worker`
+///   feature, `protocol = Worker<InputEncoding, OutputEncoding>` runs the function in a web worker
+///   instead of on the server, and compiles its body into the client. See
+///   [`server_fn::worker`](../server_fn/worker/index.html).
 ///
 /// ```rust,ignore
 /// #[server(
@@ -1,19 +1,23 @@
-pub use super::{form::*, link::*};
-#[cfg(feature = "ssr")]
-use crate::location::RequestUrl;
+pub use super::{form::*, link::*, scroll::*};
 pub use crate::nested_router::Outlet;
 use crate::{
     flat_router::FlatRoutesView,
     hooks::{use_matched, use_navigate},
     location::{
-        BrowserUrl, Location, LocationChange, LocationProvider, State, Url,
+        AnyLocation, BrowserUrl, Location, LocationChange, LocationProvider,
+        RequestUrl, RouterLocation, State, Url,
     },
-    navigate::NavigateOptions,
+    navigate::{NavigateOptions, NavigationDecision, NavigationGuards},
     nested_router::NestedRoutesView,
+    prefetch::Prefetcher,
     resolve_path::resolve_path,
-    ChooseView, MatchNestedRoutes, NestedRoute, PossibleRouteMatch, RouteDefs,
-    SsrMode,
+    route_data::{RouteMatcher, TrailingSlashRedirect},
+    scroll::{current_entry, ScrollRestorer},
+    strip_base, ChooseView, Locales, MatchNestedRoutes, NestedRoute,
+    PossibleRouteMatch, RouteData, RouteDefs, RouteLoader, SsrMode,
+    TrailingSlash,
 };
+use any_spawner::Executor;
 use either_of::EitherOf3;
 use leptos::{children, prelude::*};
 use reactive_graph::{
@@ -59,10 +63,21 @@ pub fn Router<Chil>(
     /// A signal that will be set while the navigation process is underway.
     #[prop(optional, into)]
     set_is_routing: Option<SignalSetter<bool>>,
-    // TODO trailing slashes
-    ///// How trailing slashes should be handled in [`Route`] paths.
-    //#[prop(optional)]
-    //trailing_slash: TrailingSlash,
+    /// How the router reads and updates the URL: with the History API (the default), in the hash
+    /// fragment ([`RouterLocation::Hash`]), or in an in-memory history
+    /// ([`MemoryUrl`](crate::location::MemoryUrl)).
+    #[prop(optional, into)]
+    location: RouterLocation,
+    /// How a trailing slash in the URL is matched against the paths of [`Route`]s: ignored
+    /// ([`TrailingSlash::Drop`], the default), matched exactly ([`TrailingSlash::Exact`]), or
+    /// matched exactly with a redirect from the other form of each path
+    /// ([`TrailingSlash::Redirect`]).
+    #[prop(optional)]
+    trailing_slash: TrailingSlash,
+    /// The locales that the routes are localized into. Each locale prefixes the paths of the
+    /// routes, e.g. `/de/produkte`, and a path without a prefix falls back to the default locale.
+    #[prop(optional, into)]
+    locales: Option<Locales>,
     /// The `<Router/>` should usually wrap your whole page. It can contain
     /// any elements, and should include a [`Routes`] component somewhere
     /// to define and display [`Route`]s.
@@ -71,32 +86,61 @@ pub fn Router<Chil>(
 where
     Chil: IntoView,
 {
-    #[cfg(feature = "ssr")]
-    let (location_provider, current_url, redirect_hook) = {
-        let req = use_context::<RequestUrl>().expect("no RequestUrl provided");
-        let parsed = req.parse().expect("could not parse RequestUrl");
-        let current_url = ArcRwSignal::new(parsed);
+    // links are rendered the same way on the server, where there is no hash fragment to read
+    let hash_routing = matches!(location, RouterLocation::Hash);
 
-        (None, current_url, Box::new(move |_: &str| {}))
+    // on the server, the URL is taken from the request, unless an in-memory history is used
+    #[cfg(feature = "ssr")]
+    let location_provider = match location {
+        RouterLocation::Memory(memory) => Some(AnyLocation::Memory(memory)),
+        _ => None,
     };
 
     #[cfg(not(feature = "ssr"))]
-    let (location_provider, current_url, redirect_hook) = {
-        let owner = Owner::current();
-        let location =
-            BrowserUrl::new().expect("could not access browser navigation"); // TODO options here
-        location.init(base.clone());
-        provide_context(location.clone());
-        let current_url = location.as_url().clone();
-
-        let redirect_hook = Box::new(move |loc: &str| {
-            if let Some(owner) = &owner {
-                owner.with(|| BrowserUrl::redirect(loc));
+    let location_provider = Some(
+        AnyLocation::new(location)
+            .expect("could not access browser navigation"),
+    );
+
+    // the location provider consults the guards for navigations that start in the browser
+    let guards = NavigationGuards::default();
+    provide_context(guards.clone());
+    // and records scroll positions, once a <ScrollRestoration/> has been mounted
+    let scroll = ScrollRestorer::default();
+    provide_context(scroll.clone());
+    // and redirects trailing slashes, once the <Routes/> have been defined
+    let route_matcher = ArcRwSignal::new(None);
+    let trailing_slash_redirect =
+        TrailingSlashRedirect::new(trailing_slash, route_matcher.clone());
+    provide_context(trailing_slash_redirect.clone());
+
+    #[allow(clippy::type_complexity)]
+    let (current_url, redirect_hook): (_, Box<dyn Fn(&str) + Send + Sync>) =
+        match &location_provider {
+            Some(location) => {
+                let owner = Owner::current();
+                location.init(base.clone());
+                location.provide_context();
+                let current_url = location.as_url().clone();
+
+                let location = location.clone();
+                let redirect_hook = Box::new(move |loc: &str| {
+                    if let Some(owner) = &owner {
+                        owner.with(|| location.redirect_to(loc));
+                    }
+                });
+
+                (current_url, redirect_hook)
             }
-        });
+            None => {
+                let req = use_context::<RequestUrl>()
+                    .expect("no RequestUrl provided");
+                let parsed = req.parse().expect("could not parse RequestUrl");
+                let current_url = ArcRwSignal::new(parsed);
 
-        (Some(location), current_url, redirect_hook)
-    };
+                (current_url, Box::new(move |_: &str| {}))
+            }
+        };
     // provide router context
     let state = ArcRwSignal::new(State::new(None));
     let location = Location::new(current_url.read_only(), state.read_only());
@@ -112,6 +156,14 @@ where
         set_is_routing,
         query_mutations: Default::default(),
         location_provider,
+        hash_routing,
+        prefetcher: Default::default(),
+        route_matcher,
+        guards,
+        scroll,
+        trailing_slash,
+        trailing_slash_redirect,
+        locales,
     });
 
     let children = children.into_inner();
@@ -127,7 +179,15 @@ pub(crate) struct RouterContext {
     pub set_is_routing: Option<SignalSetter<bool>>,
     pub query_mutations:
         ArcStoredValue<Vec<(Oco<'static, str>, Option<String>)>>,
-    pub location_provider: Option<BrowserUrl>,
+    pub location_provider: Option<AnyLocation>,
+    pub hash_routing: bool,
+    pub prefetcher: ArcStoredValue<Option<Prefetcher>>,
+    pub route_matcher: ArcRwSignal<Option<RouteMatcher>>,
+    pub guards: NavigationGuards,
+    pub scroll: ScrollRestorer,
+    pub trailing_slash: TrailingSlash,
+    pub trailing_slash_redirect: TrailingSlashRedirect,
+    pub locales: Option<Locales>,
 }
 
 impl RouterContext {
@@ -144,7 +204,7 @@ impl RouterContext {
             resolve_path("", path, None)
         };
 
-        let mut url = match BrowserUrl::parse(&resolved_to) {
+        let mut url = match self.parse_url(&resolved_to) {
             Ok(url) => url,
             Err(e) => {
                 leptos::logging::error!("Error parsing URL: {e:?}");
@@ -168,8 +228,82 @@ impl RouterContext {
                 .into()
         }
 
+        // the path is written the way the matching route defines it
+        self.trailing_slash_redirect.apply(&mut url);
+
+        if self.guards.is_empty() {
+            drop(current);
+            self.navigate_unguarded(path, url, options);
+        } else {
+            drop(current);
+            let this = self.clone();
+            let path = path.to_string();
+            Executor::spawn_local(async move {
+                if this.check_guards(&url).await {
+                    this.navigate_unguarded(&path, url, options);
+                }
+            });
+        }
+    }
+
+    /// Consults the guards about leaving the current URL for `url`, and returns whether the
+    /// navigation may continue. A redirect is followed without consulting them again.
+    pub(crate) async fn check_guards(&self, url: &Url) -> bool {
+        let from = self.current_url.read_untracked().clone();
+        match self.guards.check(&from, url).await {
+            NavigationDecision::Allow => true,
+            NavigationDecision::Cancel => false,
+            NavigationDecision::Redirect(to) => {
+                match self.parse_url(&self.resolve_path(&to, None)) {
+                    Ok(url) => self.navigate_unguarded(
+                        &to,
+                        url,
+                        NavigateOptions::default(),
+                    ),
+                    Err(e) => {
+                        leptos::logging::error!("Error parsing URL: {e:?}")
+                    }
+                }
+                false
+            }
+        }
+    }
+
+    /// Follows a redirect to `path` after the guards have allowed the navigation that led to it,
+    /// without consulting them again.
+    pub(crate) fn follow_redirect(&self, path: &str, options: NavigateOptions) {
+        match self.parse_url(&self.resolve_path(path, None)) {
+            Ok(mut url) => {
+                self.trailing_slash_redirect.apply(&mut url);
+                self.navigate_unguarded(path, url, options);
+            }
+            Err(e) => leptos::logging::error!("Error parsing URL: {e:?}"),
+        }
+    }
+
+    fn parse_url(&self, url: &str) -> Result<Url, String> {
+        match &self.location_provider {
+            Some(location) => location.parse_url(url),
+            None => BrowserUrl::parse(url).map_err(|e| format!("{e:?}")),
+        }
+    }
+
+    /// Navigates to a URL that has already been resolved, without consulting the guards.
+    fn navigate_unguarded(
+        &self,
+        path: &str,
+        url: Url,
+        options: NavigateOptions,
+    ) {
+        let current = self.current_url.read_untracked();
         if url.origin() != current.origin() {
-            window().location().set_href(path).unwrap();
+            if let Some(AnyLocation::Memory(_)) = &self.location_provider {
+                leptos::logging::error!(
+                    "Cannot navigate to {path} from an in-memory history."
+                );
+            } else {
+                window().location().set_href(path).unwrap();
+            }
             return;
         }
 
@@ -178,6 +312,13 @@ impl RouterContext {
             self.state.set(options.state.clone());
         }
 
+        if matches!(
+            self.location_provider,
+            Some(AnyLocation::Browser(_) | AnyLocation::Hash(_))
+        ) {
+            self.scroll.leave(current_entry());
+        }
+
         // update URL signal, if necessary
         let value = url.to_full_path();
         if current != url {
@@ -201,7 +342,35 @@ impl RouterContext {
         from: Option<&'a str>,
     ) -> Cow<'a, str> {
         let base = self.base.as_deref().unwrap_or_default();
-        resolve_path(base, path, from)
+        match self.locale_prefix() {
+            // relative paths stay in the locale of the current URL
+            Some(prefix) if !prefix.is_empty() => {
+                resolve_path(&format!("{base}{prefix}"), path, from)
+                    .into_owned()
+                    .into()
+            }
+            _ => resolve_path(base, path, from),
+        }
+    }
+
+    /// The locale of the current URL, if the router is localized.
+    pub fn locale(&self) -> Option<&'static str> {
+        let locales = self.locales.as_ref()?;
+        self.current_url.with(|url| {
+            let path = strip_base(self.base.as_deref(), url.path())
+                .unwrap_or(url.path());
+            Some(locales.split_path(path).0)
+        })
+    }
+
+    /// The locale prefix of the current URL, if the router is localized.
+    fn locale_prefix(&self) -> Option<String> {
+        let locales = self.locales.as_ref()?;
+        self.current_url.with(|url| {
+            let path = strip_base(self.base.as_deref(), url.path())
+                .unwrap_or(url.path());
+            Some(locales.split_path(path).1.to_string())
+        })
     }
 }
 
@@ -231,11 +400,16 @@ where
     FallbackFn: FnOnce() -> Fallback + Clone + Send + 'static,
     Fallback: IntoView + 'static,
 {
-    let location = use_context::<BrowserUrl>();
+    let location = use_context::<AnyLocation>();
     let RouterContext {
         current_url,
         base,
         set_is_routing,
+        prefetcher,
+        route_matcher,
+        trailing_slash,
+        trailing_slash_redirect,
+        locales,
         ..
     } = use_context()
         .expect("<Routes> should be used inside a <Router> component");
@@ -247,9 +421,23 @@ where
     let routes = RouteDefs::new_with_base(
         children.into_inner(),
         base.clone().unwrap_or_default(),
-    );
+    )
+    .with_trailing_slash(trailing_slash)
+    .with_locales(locales);
     let outer_owner =
         Owner::current().expect("creating Routes, but no Owner was found");
+    let matcher = RouteMatcher::new(routes.clone());
+    prefetcher.set_value(Some(Prefetcher::new(
+        matcher.clone(),
+        outer_owner.clone(),
+        false,
+    )));
+    route_matcher.set(Some(matcher));
+    redirect_first_url(
+        &trailing_slash_redirect,
+        location.as_ref(),
+        &current_url,
+    );
     move || {
         current_url.track();
         outer_owner.with(|| {
@@ -268,6 +456,28 @@ where
     }
 }
 
+/// Writes the URL that the router started on the way its route defines it, once the routes have
+/// been defined. The URLs navigated to later are redirected as they are committed.
+fn redirect_first_url(
+    trailing_slash_redirect: &TrailingSlashRedirect,
+    location: Option<&AnyLocation>,
+    current_url: &ArcRwSignal<Url>,
+) {
+    let Some(location) = location else {
+        return;
+    };
+    let mut url = current_url.get_untracked();
+    if trailing_slash_redirect.apply(&mut url) {
+        location.complete_navigation(&LocationChange {
+            value: url.to_full_path(),
+            replace: true,
+            scroll: false,
+            ..Default::default()
+        });
+        current_url.set(url);
+    }
+}
+
 #[component(transparent)]
 pub fn FlatRoutes<Defs, FallbackFn, Fallback>(
     /// A function that returns the view that should be shown if no route is matched.
@@ -284,11 +494,16 @@ where
     FallbackFn: FnOnce() -> Fallback + Clone + Send + 'static,
     Fallback: IntoView + 'static,
 {
-    let location = use_context::<BrowserUrl>();
+    let location = use_context::<AnyLocation>();
     let RouterContext {
         current_url,
         base,
         set_is_routing,
+        prefetcher,
+        route_matcher,
+        trailing_slash,
+        trailing_slash_redirect,
+        locales,
         ..
     } = use_context()
         .expect("<FlatRoutes> should be used inside a <Router> component");
@@ -303,10 +518,24 @@ where
     let routes = RouteDefs::new_with_base(
         children.into_inner(),
         base.clone().unwrap_or_default(),
-    );
+    )
+    .with_trailing_slash(trailing_slash)
+    .with_locales(locales);
 
     let outer_owner =
         Owner::current().expect("creating Router, but no Owner was found");
+    let matcher = RouteMatcher::new(routes.clone());
+    prefetcher.set_value(Some(Prefetcher::new(
+        matcher.clone(),
+        outer_owner.clone(),
+        true,
+    )));
+    route_matcher.set(Some(matcher));
+    redirect_first_url(
+        &trailing_slash_redirect,
+        location.as_ref(),
+        &current_url,
+    );
 
     move || {
         current_url.track();
@@ -340,14 +569,37 @@ pub fn Route<Segments, View>(
     /// Defaults to out-of-order streaming.
     #[prop(optional)]
     ssr: SsrMode,
+    /// An async function that loads data for this route from its params. It starts as soon as
+    /// the route is matched, and its data can be read with
+    /// [`use_loader_data`](crate::hooks::use_loader_data).
+    #[prop(optional, into)]
+    loader: Option<RouteLoader>,
+    /// Data attached to this route, such as its title. It can be read for the matched routes with
+    /// [`use_matched_routes`](crate::hooks::use_matched_routes), and from the
+    /// [`RouteListing`](crate::RouteListing)s generated on the server.
+    #[prop(optional)]
+    data: Option<RouteData>,
+    /// The name of the [`Outlet`] of the parent route that this route is displayed in, instead
+    /// of its default outlet. The route is then matched against the path in the query param
+    /// with that name, e.g. `?sidebar=/users/1`.
+    #[prop(optional)]
+    outlet: Option<&'static str>,
 ) -> <NestedRoute<Segments, (), (), View> as IntoMaybeErased>::Output
 where
     View: ChooseView + Clone + 'static,
     Segments: PossibleRouteMatch + Clone + Send + 'static,
 {
-    NestedRoute::new(path, view)
-        .ssr_mode(ssr)
-        .into_maybe_erased()
+    let mut route = NestedRoute::new(path, view).ssr_mode(ssr);
+    if let Some(loader) = loader {
+        route = route.loader(loader);
+    }
+    if let Some(data) = data {
+        route = route.data(data);
+    }
+    if let Some(outlet) = outlet {
+        route = route.outlet(outlet);
+    }
+    route.into_maybe_erased()
 }
 
 /// Describes a portion of the nested layout of the app, specifying the route it should match
@@ -367,6 +619,22 @@ pub fn ParentRoute<Segments, View, Children>(
     /// Defaults to out-of-order streaming.
     #[prop(optional)]
     ssr: SsrMode,
+    /// An async function that loads data for this route from its params. It starts as soon as
+    /// the route is matched, in parallel with the loaders of its children, and its data can be
+    /// read with [`use_loader_data`](crate::hooks::use_loader_data).
+    #[prop(optional, into)]
+    loader: Option<RouteLoader>,
+    /// Data attached to this route, such as its title. It can be read for the matched routes with
+    /// [`use_matched_routes`](crate::hooks::use_matched_routes), and from the
+    /// [`RouteListing`](crate::RouteListing)s generated on the server, which also carry the data
+    /// of their parent routes.
+    #[prop(optional)]
+    data: Option<RouteData>,
+    /// The name of the [`Outlet`] of the parent route that this route is displayed in, instead
+    /// of its default outlet. The route is then matched against the path in the query param
+    /// with that name, and its children against the rest of that path.
+    #[prop(optional)]
+    outlet: Option<&'static str>,
 ) -> <NestedRoute<Segments, Children, (), View> as IntoMaybeErased>::Output
 where
     View: ChooseView + Clone + 'static,
@@ -374,10 +642,17 @@ where
     Segments: PossibleRouteMatch + Clone + Send + 'static,
 {
     let children = children.into_inner();
-    NestedRoute::new(path, view)
-        .ssr_mode(ssr)
-        .child(children)
-        .into_maybe_erased()
+    let mut route = NestedRoute::new(path, view).ssr_mode(ssr).child(children);
+    if let Some(loader) = loader {
+        route = route.loader(loader);
+    }
+    if let Some(data) = data {
+        route = route.data(data);
+    }
+    if let Some(outlet) = outlet {
+        route = route.outlet(outlet);
+    }
+    route.into_maybe_erased()
 }
 
 /// With the `impl Fn` in the return signature, IntoMaybeErased::Output isn't accepted by the compiler, so changing return type depending on the erasure flag.
@@ -691,3 +966,153 @@ pub fn RoutingProgress(
         </Show>
     }
 }
+
+/// Adds an alternate `<link>` to the document head for the current page in each locale of the
+/// [`Router`], with its `hreflang`, so that search engines can find the translations of each page.
+/// An `x-default` link points to the page without a locale prefix.
+///
+/// This requires the `meta` feature, and uses `leptos_meta`, so the app should call
+/// `provide_meta_context()`. It should be rendered inside the [`Routes`] or [`FlatRoutes`], as
+/// the translations of a path are known from the routes.
+#[cfg(feature = "meta")]
+#[component]
+pub fn AlternateLinks(
+    /// The origin that is added to each path, e.g. `https://example.com`, as search engines
+    /// expect fully-qualified URLs.
+    #[prop(optional, into)]
+    origin: Option<Oco<'static, str>>,
+) -> impl IntoView {
+    let RouterContext {
+        current_url,
+        route_matcher,
+        locales,
+        ..
+    } = use_context()
+        .expect("<AlternateLinks> should be used inside a <Router> component");
+    let locales = locales.expect(
+        "<AlternateLinks> should be used inside a <Router> with `locales`",
+    );
+    let origin = origin.unwrap_or_default();
+    move || {
+        let path = current_url.with(|url| url.path().to_string());
+        let alternates = route_matcher.with(|matcher| {
+            let Some(matcher) = matcher else {
+                return Vec::new();
+            };
+            locales
+                .locales()
+                .iter()
+                .map(|locale| (*locale, Some(*locale)))
+                .chain([("x-default", None)])
+                .filter_map(|(hreflang, locale)| {
+                    let href = matcher.localize(&path, locale)?;
+                    Some((hreflang, format!("{origin}{href}")))
+                })
+                .collect::<Vec<_>>()
+        });
+        alternates
+            .into_iter()
+            .map(|(hreflang, href)| {
+                view! { <leptos_meta::Link rel="alternate" hreflang=hreflang href=href/> }
+            })
+            .collect_view()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{redirect_first_url, RouterContext};
+    use crate::{
+        location::{AnyLocation, Location, LocationProvider, MemoryUrl, State},
+        navigate::NavigationDecision,
+        route_data::{RouteMatcher, TrailingSlashRedirect},
+        NestedRoute, RouteDefs, StaticSegment, TrailingSlash,
+    };
+    use any_spawner::Executor;
+    use reactive_graph::{owner::Owner, signal::ArcRwSignal, traits::Set};
+
+    fn memory_router(history: &MemoryUrl) -> RouterContext {
+        let current_url = history.as_url().clone();
+        let state = ArcRwSignal::new(State::new(None));
+        RouterContext {
+            base: None,
+            location: Location::new(current_url.read_only(), state.read_only()),
+            current_url,
+            state,
+            set_is_routing: None,
+            query_mutations: Default::default(),
+            location_provider: Some(AnyLocation::Memory(history.clone())),
+            hash_routing: false,
+            prefetcher: Default::default(),
+            route_matcher: Default::default(),
+            guards: Default::default(),
+            scroll: Default::default(),
+            trailing_slash: Default::default(),
+            trailing_slash_redirect: Default::default(),
+            locales: None,
+        }
+    }
+
+    #[test]
+    fn guards_cancel_and_redirect_navigations() {
+        _ = Executor::init_futures_executor();
+        let owner = Owner::new();
+        owner.set();
+
+        let history = MemoryUrl::with_path("/").unwrap();
+        let router = memory_router(&history);
+        router.guards.add(|_, to| {
+            let decision = match to.path() {
+                "/locked" => NavigationDecision::Cancel,
+                "/admin" => NavigationDecision::Redirect("/login".into()),
+                _ => NavigationDecision::Allow,
+            };
+            Box::pin(async move { decision })
+        });
+
+        router.navigate("/locked", Default::default());
+        Executor::poll_local();
+        assert_eq!(history.path(), "/");
+
+        // the redirect is not guarded again
+        router.navigate("/admin", Default::default());
+        Executor::poll_local();
+        assert_eq!(history.path(), "/login");
+
+        router.navigate("/posts", Default::default());
+        Executor::poll_local();
+        assert_eq!(history.path(), "/posts");
+        assert_eq!(history.entries(), ["/", "/login", "/posts"]);
+    }
+
+    #[test]
+    fn redirects_trailing_slashes_of_the_first_url_and_navigations() {
+        _ = Executor::init_futures_executor();
+        let owner = Owner::new();
+        owner.set();
+
+        let history = MemoryUrl::with_path("/about/").unwrap();
+        let mut router = memory_router(&history);
+        let routes =
+            RouteDefs::new((NestedRoute::new(StaticSegment("about"), || ()),))
+                .with_trailing_slash(TrailingSlash::Redirect);
+        router.route_matcher.set(Some(RouteMatcher::new(routes)));
+        router.trailing_slash_redirect = TrailingSlashRedirect::new(
+            TrailingSlash::Redirect,
+            router.route_matcher.clone(),
+        );
+
+        redirect_first_url(
+            &router.trailing_slash_redirect,
+            router.location_provider.as_ref(),
+            &router.current_url,
+        );
+        assert_eq!(history.path(), "/about");
+
+        router.navigate("/", Default::default());
+        router.navigate("/about/", Default::default());
+        Executor::poll_local();
+        assert_eq!(history.path(), "/about");
+        assert_eq!(history.entries(), ["/about", "/", "/about"]);
+    }
+}
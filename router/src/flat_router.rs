@@ -1,11 +1,12 @@
 use crate::{
     hooks::Matched,
-    location::{LocationProvider, Url},
+    loader::{RouteLoader, RouteLoaders},
+    location::{AnyLocation, Url},
     matching::{MatchParams, RouteDefs},
     params::ParamsMap,
     view_transition::start_view_transition,
     ChooseView, MatchInterface, MatchNestedRoutes, PathSegment, RouteList,
-    RouteListing, RouteMatchId,
+    RouteListing, RouteMatchId, RouteMatcher,
 };
 use any_spawner::Executor;
 use either_of::Either;
@@ -31,9 +32,26 @@ use tachys::{
     },
 };
 
-pub(crate) struct FlatRoutesView<Loc, Defs, FalFn> {
+/// Provides the context that the view of a matched route reads: the data of its loader, its
+/// params, the URL and the matched path.
+fn provide_route_context(
+    loader: Option<RouteLoader>,
+    params: ArcMemo<ParamsMap>,
+    url: ArcRwSignal<Url>,
+    matched: impl Into<ArcMemo<String>>,
+) {
+    provide_context(
+        RouteLoaders::default()
+            .with(loader.map(|loader| loader.load(params.clone()))),
+    );
+    provide_context(params);
+    provide_context(url);
+    provide_context(Matched(matched.into()));
+}
+
+pub(crate) struct FlatRoutesView<Defs, FalFn> {
     pub current_url: ArcRwSignal<Url>,
-    pub location: Option<Loc>,
+    pub location: Option<AnyLocation>,
     pub routes: RouteDefs<Defs>,
     pub fallback: FalFn,
     pub outer_owner: Owner,
@@ -75,9 +93,8 @@ impl Mountable for FlatRoutesViewState {
     }
 }
 
-impl<Loc, Defs, FalFn, Fal> Render for FlatRoutesView<Loc, Defs, FalFn>
+impl<Defs, FalFn, Fal> Render for FlatRoutesView<Defs, FalFn>
 where
-    Loc: LocationProvider,
     Defs: MatchNestedRoutes + 'static,
     FalFn: FnOnce() -> Fal + Send,
     Fal: IntoAny,
@@ -131,6 +148,7 @@ where
                 matched,
             })),
             Some(new_match) => {
+                let loader = new_match.as_loader().cloned();
                 let (view, child) = new_match.into_view_and_child();
 
                 #[cfg(debug_assertions)]
@@ -141,9 +159,12 @@ where
                 }
 
                 let mut view = Box::pin(owner.with(|| {
-                    provide_context(params_memo);
-                    provide_context(url.clone());
-                    provide_context(Matched(ArcMemo::from(matched.clone())));
+                    provide_route_context(
+                        loader,
+                        params_memo,
+                        url.clone(),
+                        matched.clone(),
+                    );
 
                     ScopedFuture::new(async move {
                         OwnedView::new(view.choose().await)
@@ -273,6 +294,7 @@ where
                 }
             }
             Some(new_match) => {
+                let loader = new_match.as_loader().cloned();
                 let (view, child) = new_match.into_view_and_child();
 
                 #[cfg(debug_assertions)]
@@ -289,9 +311,12 @@ where
                     .map(|nav| nav.is_back().get_untracked())
                     .unwrap_or(false);
                 Executor::spawn_local(owner.with(|| {
-                    provide_context(url);
-                    provide_context(params_memo);
-                    provide_context(Matched(ArcMemo::from(new_matched)));
+                    provide_route_context(
+                        loader,
+                        params_memo,
+                        url,
+                        new_matched,
+                    );
 
                     ScopedFuture::new({
                         let state = Rc::clone(state);
@@ -340,15 +365,14 @@ where
     }
 }
 
-impl<Loc, Defs, FalFn, Fal> AddAnyAttr for FlatRoutesView<Loc, Defs, FalFn>
+impl<Defs, FalFn, Fal> AddAnyAttr for FlatRoutesView<Defs, FalFn>
 where
-    Loc: LocationProvider + Send,
     Defs: MatchNestedRoutes + Send + 'static,
     FalFn: FnOnce() -> Fal + Send + 'static,
     Fal: RenderHtml + 'static,
 {
     type Output<SomeNewAttr: leptos::attr::Attribute> =
-        FlatRoutesView<Loc, Defs, FalFn>;
+        FlatRoutesView<Defs, FalFn>;
 
     fn add_any_attr<NewAttr: leptos::attr::Attribute>(
         self,
@@ -489,9 +513,8 @@ impl RenderHtml for MatchedRoute {
     }
 }
 
-impl<Loc, Defs, FalFn, Fal> FlatRoutesView<Loc, Defs, FalFn>
+impl<Defs, FalFn, Fal> FlatRoutesView<Defs, FalFn>
 where
-    Loc: LocationProvider + Send,
     Defs: MatchNestedRoutes + Send + 'static,
     FalFn: FnOnce() -> Fal + Send,
     Fal: RenderHtml + 'static,
@@ -522,12 +545,16 @@ where
             None => (self.fallback)().into_any(),
             Some(new_match) => {
                 let id = new_match.as_matched().to_string();
+                let loader = new_match.as_loader().cloned();
                 let (view, _) = new_match.into_view_and_child();
                 let view = owner
                     .with(|| {
-                        provide_context(url);
-                        provide_context(params_memo);
-                        provide_context(Matched(ArcMemo::from(matched)));
+                        provide_route_context(
+                            loader,
+                            params_memo,
+                            url,
+                            matched,
+                        );
 
                         ScopedFuture::new(async move { view.choose().await })
                     })
@@ -542,9 +569,8 @@ where
     }
 }
 
-impl<Loc, Defs, FalFn, Fal> RenderHtml for FlatRoutesView<Loc, Defs, FalFn>
+impl<Defs, FalFn, Fal> RenderHtml for FlatRoutesView<Defs, FalFn>
 where
-    Loc: LocationProvider + Send,
     Defs: MatchNestedRoutes + Send + 'static,
     FalFn: FnOnce() -> Fal + Send + 'static,
     Fal: RenderHtml + 'static,
@@ -571,6 +597,7 @@ where
         // if this is being run on the server for the first time, generating all possible routes
         if RouteList::is_generating() {
             // add routes
+            let trailing_slash = self.routes.trailing_slash();
             let (base, routes) = self.routes.generate_routes();
             let routes = routes
                 .into_iter()
@@ -590,6 +617,9 @@ where
                         data.methods,
                         data.regenerate,
                     )
+                    .with_data(data.data)
+                    .with_trailing_slash(trailing_slash)
+                    .with_route_ids(data.ids)
                 })
                 .collect::<Vec<_>>();
 
@@ -610,7 +640,10 @@ where
                 None,
             ));*/
 
-            RouteList::register(RouteList::from(routes));
+            RouteList::register(
+                RouteList::from(routes)
+                    .with_matcher(RouteMatcher::new(self.routes)),
+            );
         } else {
             let view = self.choose_ssr();
             view.to_html_with_buf(
@@ -697,6 +730,7 @@ where
                 matched,
             })),
             Some(new_match) => {
+                let loader = new_match.as_loader().cloned();
                 let (view, child) = new_match.into_view_and_child();
 
                 #[cfg(debug_assertions)]
@@ -707,9 +741,12 @@ where
                 }
 
                 let mut view = Box::pin(owner.with(|| {
-                    provide_context(params_memo);
-                    provide_context(url.clone());
-                    provide_context(Matched(ArcMemo::from(matched.clone())));
+                    provide_route_context(
+                        loader,
+                        params_memo,
+                        url.clone(),
+                        matched.clone(),
+                    );
 
                     ScopedFuture::new(async move {
                         OwnedView::new(view.choose().await)
@@ -793,6 +830,7 @@ where
                 matched,
             })),
             Some(new_match) => {
+                let loader = new_match.as_loader().cloned();
                 let (view, child) = new_match.into_view_and_child();
 
                 #[cfg(debug_assertions)]
@@ -803,9 +841,12 @@ where
                 }
 
                 let view = Box::pin(owner.with(|| {
-                    provide_context(params_memo);
-                    provide_context(url.clone());
-                    provide_context(Matched(ArcMemo::from(matched.clone())));
+                    provide_route_context(
+                        loader,
+                        params_memo,
+                        url.clone(),
+                        matched.clone(),
+                    );
 
                     ScopedFuture::new(async move {
                         OwnedView::new(view.choose().await)
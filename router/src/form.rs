@@ -1,5 +1,5 @@
 use crate::{
-    components::ToHref,
+    components::{RouterContext, ToHref},
     hooks::{has_router, use_navigate, use_resolved_path},
     location::{BrowserUrl, LocationProvider},
     NavigateOptions,
@@ -70,6 +70,18 @@ where
             .await
     }
 
+    // a form that is sent to the server leaves the current page, so the guards are consulted
+    // about its action first, as they are for other navigations; a redirect in the response is
+    // then followed without consulting them again
+    async fn guards_allow(router: Option<RouterContext>, action: &str) -> bool {
+        match (router, BrowserUrl::parse(action)) {
+            (Some(router), Ok(to)) if !router.guards.is_empty() => {
+                router.check_guards(&to).await
+            }
+            _ => true,
+        }
+    }
+
     async fn post_params(
         action: &str,
         enctype: &str,
@@ -100,6 +112,7 @@ where
     ) -> impl IntoView {
         let action_version = version;
         let navigate = has_router.then(use_navigate);
+        let router = has_router.then(use_context::<RouterContext>).flatten();
         let on_submit = {
             move |ev: web_sys::SubmitEvent| {
                 let navigate = navigate.clone();
@@ -135,7 +148,11 @@ where
 
                     let on_response = on_response.clone();
                     let on_error = on_error.clone();
+                    let router = router.clone();
                     spawn_local(async move {
+                        if !guards_allow(router.clone(), &action).await {
+                            return;
+                        }
                         let res = post_form_data(&action, form_data).await;
                         match res {
                             Err(e) => {
@@ -163,23 +180,14 @@ where
                                 if resp.redirected() {
                                     let resp_url = &resp.url();
                                     match BrowserUrl::parse(resp_url.as_str()) {
-                                        Ok(url) => {
-                                            if url.origin()
-                                                != current_window_origin()
-                                                || navigate.is_none()
+                                        Ok(url) => match router {
+                                            // the guards already allowed leaving for the form's
+                                            // action, so they are not consulted again
+                                            Some(router)
+                                                if url.origin()
+                                                    == current_window_origin() =>
                                             {
-                                                _ = window()
-                                                    .location()
-                                                    .set_href(
-                                                        resp_url.as_str(),
-                                                    );
-                                            } else {
-                                                #[allow(
-                                                    clippy::unnecessary_unwrap
-                                                )]
-                                                let navigate =
-                                                    navigate.unwrap();
-                                                navigate(
+                                                router.follow_redirect(
                                                     &format!(
                                                         "{}{}{}",
                                                         url.path(),
@@ -196,7 +204,14 @@ where
                                                     navigate_options,
                                                 )
                                             }
-                                        }
+                                            _ => {
+                                                _ = window()
+                                                    .location()
+                                                    .set_href(
+                                                        resp_url.as_str(),
+                                                    );
+                                            }
+                                        },
                                         Err(e) => warn!("{:?}", e),
                                     }
                                 }
@@ -211,7 +226,11 @@ where
 
                     let on_response = on_response.clone();
                     let on_error = on_error.clone();
+                    let router = router.clone();
                     spawn_local(async move {
+                        if !guards_allow(router.clone(), &action).await {
+                            return;
+                        }
                         let res = post_params(&action, &enctype, params).await;
                         match res {
                             Err(e) => {
@@ -239,23 +258,14 @@ where
                                 if resp.redirected() {
                                     let resp_url = &resp.url();
                                     match BrowserUrl::parse(resp_url.as_str()) {
-                                        Ok(url) => {
-                                            if url.origin()
-                                                != current_window_origin()
-                                                || navigate.is_none()
+                                        Ok(url) => match router {
+                                            // the guards already allowed leaving for the form's
+                                            // action, so they are not consulted again
+                                            Some(router)
+                                                if url.origin()
+                                                    == current_window_origin() =>
                                             {
-                                                _ = window()
-                                                    .location()
-                                                    .set_href(
-                                                        resp_url.as_str(),
-                                                    );
-                                            } else {
-                                                #[allow(
-                                                    clippy::unnecessary_unwrap
-                                                )]
-                                                let navigate =
-                                                    navigate.unwrap();
-                                                navigate(
+                                                router.follow_redirect(
                                                     &format!(
                                                         "{}{}{}",
                                                         url.path(),
@@ -272,7 +282,14 @@ where
                                                     navigate_options,
                                                 )
                                             }
-                                        }
+                                            _ => {
+                                                _ = window()
+                                                    .location()
+                                                    .set_href(
+                                                        resp_url.as_str(),
+                                                    );
+                                            }
+                                        },
                                         Err(e) => warn!("{:?}", e),
                                     }
                                 }
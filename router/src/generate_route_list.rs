@@ -3,7 +3,7 @@ use crate::{
     static_routes::{
         RegenerationFn, ResolvedStaticPath, StaticPath, StaticRoute,
     },
-    Method, SsrMode,
+    Method, RouteData, RouteMatchId, RouteMatcher, SsrMode, TrailingSlash,
 };
 use futures::future::join_all;
 use reactive_graph::owner::Owner;
@@ -22,6 +22,9 @@ pub struct RouteListing {
     mode: SsrMode,
     methods: HashSet<Method>,
     regenerate: Vec<RegenerationFn>,
+    data: Vec<RouteData>,
+    trailing_slash: TrailingSlash,
+    ids: Vec<RouteMatchId>,
 }
 
 impl RouteListing {
@@ -37,9 +40,39 @@ impl RouteListing {
             mode,
             methods: methods.into_iter().collect(),
             regenerate: regenerate.into_iter().collect(),
+            data: Vec::new(),
+            trailing_slash: TrailingSlash::default(),
+            ids: Vec::new(),
         }
     }
 
+    /// Sets the data attached to this route and its parents, outermost first.
+    pub fn with_data(
+        mut self,
+        data: impl IntoIterator<Item = RouteData>,
+    ) -> Self {
+        self.data = data.into_iter().collect();
+        self
+    }
+
+    /// Sets how the router that defines this route handles a trailing slash.
+    pub fn with_trailing_slash(
+        mut self,
+        trailing_slash: TrailingSlash,
+    ) -> Self {
+        self.trailing_slash = trailing_slash;
+        self
+    }
+
+    /// Sets the ids of this route and its parents, outermost first.
+    pub fn with_route_ids(
+        mut self,
+        ids: impl IntoIterator<Item = RouteMatchId>,
+    ) -> Self {
+        self.ids = ids.into_iter().collect();
+        self
+    }
+
     /// Create a route listing from a path, with the other fields set to default values.
     pub fn from_path(path: impl IntoIterator<Item = PathSegment>) -> Self {
         Self::new(path, SsrMode::Async, [], [])
@@ -66,6 +99,28 @@ impl RouteListing {
         &self.regenerate
     }
 
+    /// The data attached to this route and its parents, outermost first.
+    pub fn data(&self) -> &[RouteData] {
+        &self.data
+    }
+
+    /// The data of the innermost route that has attached a `T`.
+    pub fn find_data<T: 'static>(&self) -> Option<&T> {
+        self.data.iter().rev().find_map(RouteData::get)
+    }
+
+    /// How the router that defines this route handles a trailing slash, which decides whether a
+    /// server integration also serves, or redirects from, the other form of the path.
+    pub fn trailing_slash(&self) -> TrailingSlash {
+        self.trailing_slash
+    }
+
+    /// The ids of this route and its parents, outermost first, which a [`RouteListMatcher`]
+    /// returns for the paths that the app renders this route for.
+    pub fn route_ids(&self) -> &[RouteMatchId] {
+        &self.ids
+    }
+
     /// Whether this route is statically rendered.
     #[inline(always)]
     pub fn static_route(&self) -> Option<&StaticRoute> {
@@ -152,35 +207,53 @@ impl RouteListing {
 
 /// A set of routes generated from the route definitions.
 #[derive(Debug, Default, Clone)]
-pub struct RouteList(Vec<RouteListing>);
+pub struct RouteList {
+    routes: Vec<RouteListing>,
+    matcher: Option<RouteMatcher>,
+}
 
 impl From<Vec<RouteListing>> for RouteList {
-    fn from(value: Vec<RouteListing>) -> Self {
-        Self(value)
+    fn from(routes: Vec<RouteListing>) -> Self {
+        Self {
+            routes,
+            matcher: None,
+        }
     }
 }
 
 impl RouteList {
     /// Adds a route listing.
     pub fn push(&mut self, data: RouteListing) {
-        self.0.push(data);
+        self.routes.push(data);
+    }
+
+    /// Sets the route definitions that the routes were generated from.
+    pub(crate) fn with_matcher(mut self, matcher: RouteMatcher) -> Self {
+        self.matcher = Some(matcher);
+        self
     }
 }
 
 impl RouteList {
     /// Creates an empty list of routes.
     pub fn new() -> Self {
-        Self(Vec::new())
+        Self::default()
     }
 
     /// Returns the list of routes.
     pub fn into_inner(self) -> Vec<RouteListing> {
-        self.0
+        self.routes
     }
 
     /// Returns and iterator over the list of routes.
     pub fn iter(&self) -> impl Iterator<Item = &RouteListing> {
-        self.0.iter()
+        self.routes.iter()
+    }
+
+    /// Matches paths against the route definitions that the routes were generated from, if they
+    /// were generated from an app with [`RouteList::generate`].
+    pub fn matcher(&self) -> Option<RouteListMatcher> {
+        self.matcher.clone().map(RouteListMatcher)
     }
 
     /// Generates a list of resolved static paths based on the inner list of route listings.
@@ -221,6 +294,22 @@ impl RouteList {
     }
 }
 
+/// Finds the route that an app renders for a path, by matching it against the route definitions
+/// that a [`RouteList`] was generated from.
+///
+/// The server integrations use this to tell apart routes whose paths they cannot distinguish,
+/// such as `/posts/:id` with a constrained `id` and `/posts/:slug`.
+#[derive(Debug, Clone)]
+pub struct RouteListMatcher(RouteMatcher);
+
+impl RouteListMatcher {
+    /// The [ids](RouteListing::route_ids) of the route that the app renders for a path and of
+    /// its parents, outermost first, or an empty list if no route matches the path.
+    pub fn route_ids(&self, path: &str) -> Vec<RouteMatchId> {
+        self.0.route_ids(path)
+    }
+}
+
 impl RouteList {
     // this is used to indicate to the Router that we are generating
     // a RouteList for server path generation
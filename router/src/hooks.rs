@@ -1,20 +1,36 @@
 use crate::{
     components::RouterContext,
+    loader::RouteLoaders,
     location::{Location, Url},
-    navigate::NavigateOptions,
+    navigate::{NavigateOptions, NavigationDecision},
     params::{Params, ParamsError, ParamsMap},
+    route_data::MatchedRoute,
+};
+use leptos::{
+    leptos_dom::helpers::{
+        request_animation_frame, set_timeout_with_handle, TimeoutHandle,
+    },
+    oco::Oco,
+    prelude::Resource,
 };
-use leptos::{leptos_dom::helpers::request_animation_frame, oco::Oco};
 use reactive_graph::{
     computed::{ArcMemo, Memo},
-    owner::{expect_context, use_context},
+    effect::Effect,
+    owner::{expect_context, on_cleanup, use_context, StoredValue},
     signal::{ArcRwSignal, ReadSignal},
-    traits::{Get, GetUntracked, ReadUntracked, With, WriteValue},
+    traits::{
+        Get, GetUntracked, GetValue, ReadUntracked, Set, SetValue, Track, With,
+        WriteValue,
+    },
     wrappers::write::SignalSetter,
 };
+use serde::{de::DeserializeOwned, Serialize};
 use std::{
+    collections::HashSet,
+    future::IntoFuture,
     str::FromStr,
     sync::atomic::{AtomicBool, Ordering},
+    time::Duration,
 };
 
 /// See [`query_signal`].
@@ -145,6 +161,255 @@ where
     (get, set)
 }
 
+/// How long [`use_query_state`] waits for the state to stop changing before writing it to the URL.
+const QUERY_STATE_DEBOUNCE: Duration = Duration::from_millis(250);
+
+/// Constructs a signal synchronized with the whole query string, which is deserialized into a
+/// struct with [`serde_qs`].
+///
+/// This is useful when there are many query parameters, as each field of the struct is a query
+/// parameter. Nested structs and arrays use the bracket syntax of `serde_qs`, e.g.,
+/// `tags[0]=rust&range[min]=10`, and keys are always written in the order of the fields. If the
+/// parameters of a field cannot be deserialized, that field keeps its value from `T::default()`;
+/// add `#[serde(default)]` to the struct so that missing parameters use their defaults too.
+///
+/// Setting the signal updates its value immediately, but only writes it to the URL once it has
+/// stopped changing for a moment, replacing the current history entry. Query parameters that do
+/// not belong to the struct are kept.
+///
+/// ```rust
+/// use leptos::prelude::*;
+/// use leptos_router::hooks::use_query_state;
+/// use serde::{Deserialize, Serialize};
+///
+/// #[derive(Clone, Default, PartialEq, Serialize, Deserialize)]
+/// #[serde(default)]
+/// struct Filters {
+///     search: String,
+///     tags: Vec<String>,
+///     page: u32,
+/// }
+///
+/// #[component]
+/// pub fn FilterPanel() -> impl IntoView {
+///     let (filters, set_filters) = use_query_state::<Filters>();
+///
+///     view! {
+///         <input
+///             prop:value=move || filters.read().search.clone()
+///             on:input:target=move |ev| {
+///                 set_filters.set(Filters {
+///                     search: ev.target().value(),
+///                     page: 0,
+///                     ..filters.get()
+///                 })
+///             }
+///         />
+///         <p>"Page " {move || filters.read().page + 1}</p>
+///     }
+/// }
+/// ```
+#[track_caller]
+pub fn use_query_state<T>() -> (Memo<T>, SignalSetter<T>)
+where
+    T: Serialize
+        + DeserializeOwned
+        + Default
+        + Clone
+        + PartialEq
+        + Send
+        + Sync
+        + 'static,
+{
+    use_query_state_with_options(
+        QUERY_STATE_DEBOUNCE,
+        NavigateOptions {
+            replace: true,
+            scroll: false,
+            ..Default::default()
+        },
+    )
+}
+
+/// Constructs a signal synchronized with the whole query string.
+///
+/// This is the same as [`use_query_state`], but allows you to specify how long to wait before
+/// writing the state to the URL, and the navigation options used to write it.
+#[track_caller]
+pub fn use_query_state_with_options<T>(
+    debounce: Duration,
+    nav_options: NavigateOptions,
+) -> (Memo<T>, SignalSetter<T>)
+where
+    T: Serialize
+        + DeserializeOwned
+        + Default
+        + Clone
+        + PartialEq
+        + Send
+        + Sync
+        + 'static,
+{
+    let location = use_location();
+    let navigate = use_navigate();
+
+    let from_url = Memo::new(move |_| {
+        location
+            .search
+            .with(|search| query_state_from_str::<T>(search))
+    });
+    // the value that has been set, but not yet written to the URL
+    let pending = ArcRwSignal::new(None::<T>);
+    Effect::new({
+        let pending = pending.clone();
+        move |prev: Option<()>| {
+            from_url.track();
+            // the URL takes over once it changes, whether from writing the state or from
+            // navigating through the history
+            if prev.is_some() {
+                pending.set(None);
+            }
+        }
+    });
+
+    let get = Memo::new({
+        let pending = pending.clone();
+        move |_| pending.get().unwrap_or_else(|| from_url.get())
+    });
+
+    let timeout = StoredValue::new(None::<TimeoutHandle>);
+    let set = SignalSetter::map(move |value: T| {
+        pending.set(Some(value));
+        if let Some(handle) = timeout.get_value() {
+            handle.clear();
+        }
+        let handle = set_timeout_with_handle(
+            {
+                let pending = pending.clone();
+                let navigate = navigate.clone();
+                let nav_options = nav_options.clone();
+                move || {
+                    let Some(value) = pending.get_untracked() else {
+                        return;
+                    };
+                    let current = from_url.get_untracked();
+                    if value == current {
+                        pending.set(None);
+                        return;
+                    }
+                    let search = location.search.read_untracked();
+                    let qs = query_state_to_string(&value, &current, &search);
+                    let path = location.pathname.get_untracked();
+                    let hash = location.hash.get_untracked();
+                    let new_url = if qs.is_empty() {
+                        format!("{path}{hash}")
+                    } else {
+                        format!("{path}?{qs}{hash}")
+                    };
+                    navigate(&new_url, nav_options);
+                }
+            },
+            debounce,
+        );
+        timeout.set_value(handle.ok());
+    });
+
+    (get, set)
+}
+
+fn query_state_config() -> serde_qs::Config {
+    // not strict, so that brackets encoded by the browser or by `serde_qs` are accepted
+    serde_qs::Config::new(5, false)
+}
+
+/// Deserializes the state of a [`use_query_state`] from a query string. A field whose parameters
+/// cannot be deserialized keeps its value from `T::default()`, without resetting the others.
+fn query_state_from_str<T>(search: &str) -> T
+where
+    T: Serialize + DeserializeOwned + Default,
+{
+    let search = search.trim_start_matches('?');
+    let config = query_state_config();
+    if let Ok(state) = config.deserialize_str(search) {
+        return state;
+    }
+
+    // apply the parameters of each field to the state in turn, skipping the ones that fail
+    let mut fields = Vec::new();
+    for pair in search.split('&').filter(|pair| !pair.is_empty()) {
+        let field = query_state_field(pair);
+        if !fields.contains(&field) {
+            fields.push(field);
+        }
+    }
+    let mut state = T::default();
+    for field in fields {
+        let Ok(qs) = serde_qs::to_string(&state) else {
+            break;
+        };
+        let qs = qs
+            .split('&')
+            .filter(|pair| !pair.is_empty() && query_state_field(pair) != field)
+            .chain(
+                search
+                    .split('&')
+                    .filter(|pair| query_state_field(pair) == field),
+            )
+            .collect::<Vec<_>>()
+            .join("&");
+        match config.deserialize_str(&qs) {
+            Ok(value) => state = value,
+            Err(e) => leptos::logging::debug_warn!(
+                "Error deserializing query state field `{field}`: {e}"
+            ),
+        }
+    }
+    state
+}
+
+/// The field a query parameter belongs to, i.e., the name before any brackets, e.g., `tags` for
+/// `tags[0]`.
+fn query_state_field(pair: &str) -> String {
+    let key = pair.split('=').next().unwrap_or_default();
+    let key = Url::unescape(key);
+    key.split('[').next().unwrap_or_default().to_string()
+}
+
+/// Serializes the state of a [`use_query_state`] into the query string, in place of the
+/// parameters of the `current` state. Any other parameters in `search` are kept after it.
+fn query_state_to_string<T>(value: &T, current: &T, search: &str) -> String
+where
+    T: Serialize,
+{
+    let serialize = |value: &T| match serde_qs::to_string(value) {
+        Ok(qs) => Some(qs),
+        Err(e) => {
+            leptos::logging::error!("Error serializing query state: {e}");
+            None
+        }
+    };
+    let Some(qs) = serialize(value) else {
+        return search.trim_start_matches('?').to_string();
+    };
+    let fields = qs
+        .split('&')
+        .chain(serialize(current).as_deref().unwrap_or_default().split('&'))
+        .filter(|pair| !pair.is_empty())
+        .map(query_state_field)
+        .collect::<HashSet<_>>();
+
+    qs.split('&')
+        .chain(
+            search
+                .trim_start_matches('?')
+                .split('&')
+                .filter(|pair| !fields.contains(&query_state_field(pair))),
+        )
+        .filter(|pair| !pair.is_empty())
+        .collect::<Vec<_>>()
+        .join("&")
+}
+
 #[track_caller]
 pub(crate) fn has_router() -> bool {
     use_context::<RouterContext>().is_some()
@@ -200,6 +465,28 @@ where
     Memo::new(move |_| params.with(T::from_map))
 }
 
+/// Returns the data loaded by the [`loader`](crate::RouteLoader) of the current route, or of
+/// the closest parent route whose loader returns a `T`.
+///
+/// # Panics
+/// Panics if none of the matched routes has a loader that returns a `T`.
+#[track_caller]
+pub fn use_loader_data<T>() -> Resource<T>
+where
+    T: Send + Sync + 'static,
+{
+    use_context::<RouteLoaders>()
+        .and_then(|loaders| loaders.get::<T>())
+        .unwrap_or_else(|| {
+            panic!(
+                "Tried to access the data of a route loader, but no matched \
+                 <Route> has a loader that returns `{}`.",
+                std::any::type_name::<T>()
+            )
+        })
+        .into()
+}
+
 #[track_caller]
 fn use_url_raw() -> ArcRwSignal<Url> {
     use_context().unwrap_or_else(|| {
@@ -278,6 +565,49 @@ pub fn use_navigate() -> impl Fn(&str, NavigateOptions) + Clone {
     move |path: &str, options: NavigateOptions| cx.navigate(path, options)
 }
 
+/// Registers a guard that is consulted before each navigation, as long as the component that
+/// called this is mounted.
+///
+/// The guard is called with the current URL and the URL being navigated to, and returns a
+/// [`NavigationDecision`] to allow or cancel the navigation or redirect elsewhere, or a
+/// `Future` that resolves to one. Guards are consulted for links, [`use_navigate`], `<Form>`
+/// submissions and the browser's back and forward buttons; when one of the latter is cancelled,
+/// the URL is restored.
+///
+/// This can be used to warn about unsaved changes:
+/// ```rust
+/// # use leptos::prelude::*;
+/// # use leptos_router::{hooks::use_before_navigate, NavigationDecision};
+/// # fn editor() -> impl IntoView {
+/// let (unsaved, set_unsaved) = signal(false);
+/// use_before_navigate(move |_from, _to| {
+///     if unsaved.get_untracked()
+///         && !window()
+///             .confirm_with_message("Discard your changes?")
+///             .unwrap_or(true)
+///     {
+///         NavigationDecision::Cancel
+///     } else {
+///         NavigationDecision::Allow
+///     }
+/// });
+/// view! { <textarea on:input=move |_| set_unsaved.set(true)/> }
+/// # }
+/// ```
+#[track_caller]
+pub fn use_before_navigate<F, R>(guard: F)
+where
+    F: Fn(&Url, &Url) -> R + Send + Sync + 'static,
+    R: IntoFuture<Output = NavigationDecision>,
+    R::IntoFuture: 'static,
+{
+    let RouterContext { guards, .. } = use_context()
+        .expect("You cannot call `use_before_navigate` outside a <Router>.");
+    let id =
+        guards.add(move |from, to| Box::pin(guard(from, to).into_future()));
+    on_cleanup(move || guards.remove(id));
+}
+
 /// Returns a reactive string that contains the route that was matched for
 /// this [`Route`](crate::components::Route).
 #[track_caller]
@@ -287,3 +617,148 @@ pub fn use_matched() -> Memo<String> {
         .0
         .into()
 }
+
+/// Returns the chain of routes that match the current URL, outermost first, with their
+/// [`RouteData`](crate::RouteData) and params.
+///
+/// This can be used anywhere inside the [`Router`](crate::components::Router), e.g., to build
+/// breadcrumbs or the document title. The routes are only known once the
+/// [`Routes`](crate::components::Routes) or [`FlatRoutes`](crate::components::FlatRoutes) have
+/// been created, so while rendering on the server, views created before them see no matches.
+#[track_caller]
+pub fn use_matched_routes() -> Memo<Vec<MatchedRoute>> {
+    let RouterContext {
+        current_url,
+        route_matcher,
+        ..
+    } = use_context()
+        .expect("You cannot call `use_matched_routes` outside a <Router>.");
+    Memo::new(move |_| {
+        let path = current_url.with(|url| url.path().to_string());
+        route_matcher.with(|matcher| {
+            matcher
+                .as_ref()
+                .map(|matcher| matcher.match_path(&path))
+                .unwrap_or_default()
+        })
+    })
+}
+
+/// Returns the locale of the current URL, for a [`Router`](crate::components::Router) that is
+/// localized with [`Locales`](crate::Locales). A URL without a locale prefix is in the default
+/// locale.
+///
+/// # Panics
+/// Panics if called outside a [`Router`](crate::components::Router), or if the router has no
+/// `locales`.
+#[track_caller]
+pub fn use_locale() -> Memo<&'static str> {
+    let router = use_context::<RouterContext>()
+        .expect("You cannot call `use_locale` outside a <Router>.");
+    assert!(
+        router.locales.is_some(),
+        "You cannot call `use_locale` in a <Router> without `locales`."
+    );
+    Memo::new(move |_| router.locale().unwrap_or_default())
+}
+
+/// Translates a path into another locale of a [`Router`](crate::components::Router) that is
+/// localized with [`Locales`](crate::Locales), e.g. `/de/produkte/1` into `/en/products/1`, to
+/// link to the same page in each locale.
+///
+/// Returns `None` if the path does not match any route, or if called outside a
+/// [`Routes`](crate::components::Routes) or [`FlatRoutes`](crate::components::FlatRoutes) of a
+/// localized router.
+///
+/// ```rust
+/// # use leptos::prelude::*;
+/// # use leptos_router::hooks::{localized_href, use_location};
+/// #[component]
+/// fn LocaleSwitcher() -> impl IntoView {
+///     let pathname = use_location().pathname;
+///     move || {
+///         ["en", "de"]
+///             .into_iter()
+///             .filter_map(|locale| {
+///                 let href = localized_href(&pathname.get(), locale)?;
+///                 Some(view! { <a href=href>{locale}</a> })
+///             })
+///             .collect_view()
+///     }
+/// }
+/// ```
+pub fn localized_href(path: &str, locale: &str) -> Option<String> {
+    let RouterContext { route_matcher, .. } = use_context()?;
+    route_matcher.with(|matcher| matcher.as_ref()?.localize(path, Some(locale)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{query_state_from_str, query_state_to_string};
+    use serde::{Deserialize, Serialize};
+
+    #[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+    #[serde(default)]
+    struct Range {
+        min: u32,
+        max: u32,
+    }
+
+    #[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+    #[serde(default)]
+    struct Filters {
+        search: String,
+        tags: Vec<String>,
+        range: Range,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        sort: Option<String>,
+    }
+
+    #[test]
+    fn query_state_reads_nested_fields_and_defaults() {
+        let filters = query_state_from_str::<Filters>(
+            "?tags%5B0%5D=rust&tags[1]=web&range[max]=10&utm_source=feed",
+        );
+        assert_eq!(
+            filters,
+            Filters {
+                tags: vec!["rust".into(), "web".into()],
+                range: Range { min: 0, max: 10 },
+                ..Default::default()
+            }
+        );
+        assert_eq!(query_state_from_str::<Filters>(""), Filters::default());
+    }
+
+    #[test]
+    fn query_state_defaults_only_the_fields_that_fail() {
+        let filters = query_state_from_str::<Filters>(
+            "search=leptos&range[min]=low&range[max]=10&tags[0]=rust",
+        );
+        assert_eq!(
+            filters,
+            Filters {
+                search: "leptos".into(),
+                tags: vec!["rust".into()],
+                ..Default::default()
+            }
+        );
+    }
+
+    #[test]
+    fn query_state_replaces_its_own_params_in_field_order() {
+        let search = "sort=name&utm_source=feed&tags%5B0%5D=rust";
+        let current = query_state_from_str::<Filters>(search);
+        let filters = Filters {
+            search: "leptos".into(),
+            range: Range { min: 1, max: 5 },
+            ..Default::default()
+        };
+        let qs = query_state_to_string(&filters, &current, search);
+        assert_eq!(
+            qs,
+            "search=leptos&range[min]=1&range[max]=5&utm_source=feed"
+        );
+        assert_eq!(query_state_from_str::<Filters>(&qs), filters);
+    }
+}
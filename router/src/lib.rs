@@ -131,6 +131,7 @@ mod generate_route_list;
 /// Hooks that can be used to access router state inside your components.
 pub mod hooks;
 mod link;
+mod loader;
 /// Utilities for accessing the current location.
 pub mod location;
 mod matching;
@@ -140,16 +141,23 @@ mod navigate;
 pub mod nested_router;
 /// Support for maps of parameters in the path or in the query.
 pub mod params;
+mod prefetch;
+mod route_data;
+mod scroll;
+/// Generation of `sitemap.xml` and `robots.txt` from the routes of an app.
+pub mod sitemap;
 mod ssr_mode;
 /// Support for static routing.
 pub mod static_routes;
 
 pub use generate_route_list::*;
 #[doc(inline)]
-pub use leptos_router_macro::{lazy_route, path};
+pub use leptos_router_macro::{lazy_route, path, Routes};
+pub use loader::*;
 pub use matching::*;
 pub use method::*;
 pub use navigate::*;
+pub use route_data::*;
 pub use ssr_mode::*;
 
 pub(crate) mod view_transition {
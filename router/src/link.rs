@@ -1,7 +1,14 @@
-use crate::{components::RouterContext, hooks::use_resolved_path};
-use leptos::{children::Children, oco::Oco, prelude::*};
+use crate::{
+    components::RouterContext, hooks::use_resolved_path,
+    prefetch::PrefetchedRoute,
+};
+use leptos::{children::Children, ev, html, oco::Oco, prelude::*};
 use reactive_graph::{computed::ArcMemo, owner::use_context};
+use send_wrapper::SendWrapper;
 use std::{borrow::Cow, rc::Rc};
+use tachys::html::element::ElementExt;
+use wasm_bindgen::{closure::Closure, JsCast};
+use web_sys::{IntersectionObserver, IntersectionObserverEntry};
 
 /// Describes a value that is either a static or a reactive URL, i.e.,
 /// a [`String`], a [`&str`], or a reactive `Fn() -> String`.
@@ -55,6 +62,47 @@ where
     }
 }
 
+/// When an [`<A/>`](A) starts loading the route it links to, before it is clicked.
+///
+/// Prefetching matches the link against the routes, preloads the views of any
+/// [lazy routes](crate::lazy_route), and starts the [loaders](crate::RouteLoader) of the matched
+/// routes, so that they are ready (or at least on their way) when the link is followed.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Prefetch {
+    /// Only load the route once the link has been clicked.
+    #[default]
+    None,
+    /// Prefetch when the pointer moves over the link, or it receives focus. The prefetched data
+    /// is dropped when the pointer moves away again, unless another link still wants it.
+    Hover,
+    /// Prefetch when the link scrolls into the viewport. The prefetched data is dropped when the
+    /// link leaves the viewport, unless another link still wants it.
+    Viewport,
+}
+
+impl From<&str> for Prefetch {
+    fn from(value: &str) -> Self {
+        match value {
+            "hover" => Prefetch::Hover,
+            "viewport" => Prefetch::Viewport,
+            "none" => Prefetch::None,
+            _ => {
+                leptos::logging::warn!(
+                    "unknown prefetch mode {value:?}, expected \"hover\", \
+                     \"viewport\" or \"none\""
+                );
+                Prefetch::None
+            }
+        }
+    }
+}
+
+fn is_intersecting(entry: wasm_bindgen::JsValue) -> bool {
+    entry
+        .unchecked_into::<IntersectionObserverEntry>()
+        .is_intersecting()
+}
+
 /// An HTML [`a`](https://developer.mozilla.org/en-US/docs/Web/HTML/Element/a)
 /// progressively enhanced to use client-side routing.
 ///
@@ -66,6 +114,9 @@ where
 ///    This is helpful for accessibility and for styling. For example, maybe you want to set the link a
 ///    different color if it’s a link to the page you’re currently on.
 ///
+/// It can also start loading the route it links to ahead of time, with `prefetch="hover"` or
+/// `prefetch="viewport"`. See [`Prefetch`].
+///
 /// ### Additional Attributes
 ///
 /// You can add additional HTML attributes to the `<a>` element created by this component using the attribute
@@ -108,12 +159,16 @@ pub fn A<H>(
     #[prop(optional)]
     exact: bool,
     /// If `true`, and when `href` has a trailing slash, `aria-current` be only be set if `current_url` also has
-    /// a trailing slash.
+    /// a trailing slash. This is always the case when the [`Router`](crate::components::Router)
+    /// matches trailing slashes exactly.
     #[prop(optional)]
     strict_trailing_slash: bool,
     /// If `true`, the router will scroll to the top of the window at the end of navigation. Defaults to `true`.
     #[prop(default = true)]
     scroll: bool,
+    /// When to start loading the linked route: `"hover"`, `"viewport"`, or `"none"` (the default).
+    #[prop(optional, into)]
+    prefetch: Prefetch,
     /// The nodes or elements to be shown inside the link.
     children: Children,
 ) -> impl IntoView + 'static
@@ -127,9 +182,20 @@ where
         children: Children,
         strict_trailing_slash: bool,
         scroll: bool,
+        prefetch: Prefetch,
     ) -> impl IntoView {
-        let RouterContext { current_url, .. } =
-            use_context().expect("tried to use <A/> outside a <Router/>.");
+        let RouterContext {
+            current_url,
+            hash_routing,
+            prefetcher,
+            trailing_slash,
+            ..
+        } = use_context().expect("tried to use <A/> outside a <Router/>.");
+        // a router that matches trailing slashes exactly treats `/about` and `/about/` as
+        // different pages, so its links do too
+        let strict_trailing_slash =
+            strict_trailing_slash || trailing_slash.is_strict();
+        // with hash-based routing, the link points to the hash fragment of the current page
         let is_active = {
             let href = href.clone();
             move || {
@@ -145,9 +211,115 @@ where
             }
         };
 
+        // the path that is being prefetched, and the loaders that were started for it
+        let prefetched =
+            StoredValue::new(None::<(String, Option<PrefetchedRoute>)>);
+        let start_prefetch = {
+            let href = href.clone();
+            move || {
+                let href = href.get_untracked();
+                let Some(path) = href
+                    .split(['?', '#'])
+                    .next()
+                    .filter(|path| path.starts_with('/'))
+                else {
+                    return;
+                };
+                if prefetched.with_value(|prefetched| {
+                    matches!(prefetched, Some((prev, _)) if prev == path)
+                }) {
+                    return;
+                }
+                let route = prefetcher.with_value(|prefetcher| {
+                    prefetcher.as_ref().and_then(|p| p.prefetch(path))
+                });
+                let prev = prefetched
+                    .try_update_value(|prefetched| {
+                        prefetched.replace((path.to_string(), route))
+                    })
+                    .flatten();
+                if let Some((_, Some(prev))) = prev {
+                    prev.cancel();
+                }
+            }
+        };
+        let cancel_prefetch = move || {
+            if let Some((_, Some(route))) =
+                prefetched.try_update_value(Option::take).flatten()
+            {
+                route.cancel();
+            }
+        };
+        // once the link is followed, the prefetched data is left for the route to use
+        let keep_prefetch = move || {
+            if let Some((_, Some(route))) =
+                prefetched.try_update_value(Option::take).flatten()
+            {
+                route.keep();
+            }
+        };
+
+        let node_ref = NodeRef::<html::A>::new();
+        // only the listeners the prefetch mode needs are attached
+        if prefetch != Prefetch::None {
+            Effect::new(move |_| {
+                let Some(el) = node_ref.get() else {
+                    return;
+                };
+                let mut listeners =
+                    vec![el.on(ev::click, move |_| keep_prefetch())];
+                match prefetch {
+                    Prefetch::Hover => {
+                        listeners.extend([
+                            el.on(ev::pointerenter, {
+                                let start_prefetch = start_prefetch.clone();
+                                move |_| start_prefetch()
+                            }),
+                            el.on(ev::focus, {
+                                let start_prefetch = start_prefetch.clone();
+                                move |_| start_prefetch()
+                            }),
+                            el.on(ev::pointerleave, move |_| cancel_prefetch()),
+                            el.on(ev::blur, move |_| cancel_prefetch()),
+                        ]);
+                    }
+                    Prefetch::Viewport => {
+                        let start_prefetch = start_prefetch.clone();
+                        let on_intersect =
+                            Closure::<dyn Fn(js_sys::Array)>::new(
+                                move |entries: js_sys::Array| {
+                                    if entries.iter().any(is_intersecting) {
+                                        start_prefetch();
+                                    } else {
+                                        cancel_prefetch();
+                                    }
+                                },
+                            );
+                        if let Ok(observer) = IntersectionObserver::new(
+                            on_intersect.as_ref().unchecked_ref(),
+                        ) {
+                            observer.observe(&el);
+                            let observer =
+                                SendWrapper::new((observer, on_intersect));
+                            on_cleanup(move || observer.0.disconnect());
+                        }
+                    }
+                    Prefetch::None => {}
+                }
+                // data that was prefetched but not followed is not kept once the link is gone
+                on_cleanup(move || {
+                    drop(listeners);
+                    cancel_prefetch();
+                });
+            });
+        }
+
         view! {
             <a
-                href=move || href.get()
+                node_ref=node_ref
+                href=move || {
+                    if hash_routing { format!("#{}", href.read()) } else { href.get() }
+                }
                 target=target
                 aria-current=move || if is_active() { Some("page") } else { None }
                 data-noscroll=!scroll
@@ -159,7 +331,15 @@ where
     }
 
     let href = use_resolved_path(move || href.to_href()());
-    inner(href, target, exact, children, strict_trailing_slash, scroll)
+    inner(
+        href,
+        target,
+        exact,
+        children,
+        strict_trailing_slash,
+        scroll,
+        prefetch,
+    )
 }
 
 // Test if `href` is active for `location`.  Assumes _both_ `href` and `location` begin with a `'/'`.
@@ -0,0 +1,391 @@
+use crate::params::ParamsMap;
+use any_spawner::Executor;
+use futures::{future::RemoteHandle, FutureExt};
+use leptos::prelude::ArcResource;
+use or_poisoned::OrPoisoned;
+use reactive_graph::{computed::ArcMemo, traits::Get};
+use serde::{de::DeserializeOwned, Serialize};
+use std::{
+    any::Any,
+    fmt,
+    future::Future,
+    sync::{Arc, Mutex},
+};
+
+/// An async function that loads the data for a route from its params.
+///
+/// A loader is set with the `loader` prop of a [`Route`](crate::components::Route) or
+/// [`ParentRoute`](crate::components::ParentRoute). As soon as the URL is matched, the loaders
+/// of all the matched routes start in parallel, before any of their views are rendered, and they
+/// run again whenever the params change. Views read the data with
+/// [`use_loader_data`](crate::hooks::use_loader_data).
+///
+/// The data is loaded by a [`Resource`](leptos::prelude::Resource), so during server-side
+/// rendering it is serialized to the client, which does not run the loader again while hydrating.
+/// Links created with [`<A prefetch=...>`](crate::components::A) can start a loader before the
+/// navigation; the route then uses the prefetched data instead of loading it again.
+///
+/// ```rust
+/// # use leptos::prelude::*;
+/// # use leptos_router::{components::*, hooks::use_loader_data, params::ParamsMap, path};
+/// async fn load_post(params: ParamsMap) -> String {
+///     format!("post #{}", params.get("id").unwrap_or_default())
+/// }
+///
+/// #[component]
+/// fn Post() -> impl IntoView {
+///     let post = use_loader_data::<String>();
+///     view! { <Suspense>{move || post.get()}</Suspense> }
+/// }
+///
+/// # fn app() -> impl IntoView {
+/// view! {
+///     <Router>
+///         <Routes fallback=|| "Not found.">
+///             <Route path=path!("/posts/:id") view=Post loader=load_post/>
+///         </Routes>
+///     </Router>
+/// }
+/// # }
+/// ```
+#[derive(Clone)]
+pub struct RouteLoader(Arc<dyn ErasedLoader>);
+
+trait ErasedLoader: Send + Sync {
+    fn load(self: Arc<Self>, params: ArcMemo<ParamsMap>) -> LoaderData;
+
+    fn prefetch(&self, params: ParamsMap);
+
+    fn cancel_prefetch(&self, params: &ParamsMap);
+
+    fn release_prefetch(&self, params: &ParamsMap);
+}
+
+/// The most prefetched results that are kept for routes that have not been navigated to.
+const MAX_PREFETCHED: usize = 8;
+
+/// How long prefetched data is used for, before the route loads it again.
+const PREFETCH_MAX_AGE_MS: f64 = 30_000.0;
+
+struct Loader<F, T> {
+    fetch: F,
+    prefetched: Mutex<Vec<Prefetched<T>>>,
+}
+
+struct Prefetched<T> {
+    params: ParamsMap,
+    /// The data, which stops loading when this is dropped.
+    data: RemoteHandle<T>,
+    started_at: f64,
+    /// The number of links that are currently prefetching these params.
+    interested: usize,
+}
+
+impl<T> Prefetched<T> {
+    fn is_stale(&self, now: f64) -> bool {
+        now - self.started_at > PREFETCH_MAX_AGE_MS
+    }
+}
+
+/// The current time, in milliseconds.
+fn now_ms() -> f64 {
+    #[cfg(target_arch = "wasm32")]
+    {
+        js_sys::Date::now()
+    }
+    #[cfg(not(target_arch = "wasm32"))]
+    {
+        std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|since| since.as_secs_f64() * 1000.0)
+            .unwrap_or_default()
+    }
+}
+
+impl<F, Fut, T> ErasedLoader for Loader<F, T>
+where
+    F: Fn(ParamsMap) -> Fut + Send + Sync + 'static,
+    T: Serialize + DeserializeOwned + Send + Sync + 'static,
+    Fut: Future<Output = T> + Send + 'static,
+{
+    fn load(self: Arc<Self>, params: ArcMemo<ParamsMap>) -> LoaderData {
+        let resource = ArcResource::new(
+            move || params.get(),
+            move |params| {
+                let prefetched = {
+                    let mut prefetched = self.prefetched.lock().or_poisoned();
+                    let now = now_ms();
+                    prefetched.retain(|entry| !entry.is_stale(now));
+                    prefetched
+                        .iter()
+                        .position(|entry| entry.params == params)
+                        .map(|idx| prefetched.remove(idx).data)
+                };
+                let this = Arc::clone(&self);
+                async move {
+                    match prefetched {
+                        Some(data) => data.await,
+                        None => (this.fetch)(params).await,
+                    }
+                }
+            },
+        );
+        LoaderData(Arc::new(resource))
+    }
+
+    fn prefetch(&self, params: ParamsMap) {
+        let mut prefetched = self.prefetched.lock().or_poisoned();
+        let now = now_ms();
+        prefetched.retain(|entry| !entry.is_stale(now));
+        if let Some(entry) =
+            prefetched.iter_mut().find(|entry| entry.params == params)
+        {
+            entry.interested += 1;
+            return;
+        }
+
+        if prefetched.len() >= MAX_PREFETCHED {
+            // only drop data that no link is waiting for; if every link still is, this one is
+            // loaded when it is navigated to instead
+            let Some(idx) =
+                prefetched.iter().position(|entry| entry.interested == 0)
+            else {
+                return;
+            };
+            prefetched.remove(idx);
+        }
+
+        let (remote, data) = (self.fetch)(params.clone()).remote_handle();
+        Executor::spawn(remote);
+        prefetched.push(Prefetched {
+            params,
+            data,
+            started_at: now,
+            interested: 1,
+        });
+    }
+
+    fn cancel_prefetch(&self, params: &ParamsMap) {
+        let mut prefetched = self.prefetched.lock().or_poisoned();
+        let Some(idx) =
+            prefetched.iter().position(|entry| &entry.params == params)
+        else {
+            return;
+        };
+        let entry = &mut prefetched[idx];
+        entry.interested = entry.interested.saturating_sub(1);
+        if entry.interested == 0 {
+            prefetched.remove(idx);
+        }
+    }
+
+    fn release_prefetch(&self, params: &ParamsMap) {
+        let mut prefetched = self.prefetched.lock().or_poisoned();
+        if let Some(entry) =
+            prefetched.iter_mut().find(|entry| &entry.params == params)
+        {
+            entry.interested = entry.interested.saturating_sub(1);
+        }
+    }
+}
+
+impl RouteLoader {
+    /// Creates a loader from an async function of the route's params.
+    pub fn new<T, Fut>(
+        loader: impl Fn(ParamsMap) -> Fut + Send + Sync + 'static,
+    ) -> Self
+    where
+        T: Serialize + DeserializeOwned + Send + Sync + 'static,
+        Fut: Future<Output = T> + Send + 'static,
+    {
+        Self(Arc::new(Loader {
+            fetch: loader,
+            prefetched: Default::default(),
+        }))
+    }
+
+    /// Starts loading, with the given params as the source.
+    pub(crate) fn load(&self, params: ArcMemo<ParamsMap>) -> LoaderData {
+        Arc::clone(&self.0).load(params)
+    }
+
+    /// Starts loading the data for the given params ahead of a navigation.
+    pub(crate) fn prefetch(&self, params: ParamsMap) {
+        self.0.prefetch(params);
+    }
+
+    /// Stops a prefetch started by [`prefetch`](Self::prefetch) and drops its data, unless it
+    /// is still wanted by another link.
+    pub(crate) fn cancel_prefetch(&self, params: &ParamsMap) {
+        self.0.cancel_prefetch(params);
+    }
+
+    /// Gives up the interest of a link in a prefetch started by [`prefetch`](Self::prefetch),
+    /// but keeps its data for the navigation the link started.
+    pub(crate) fn release_prefetch(&self, params: &ParamsMap) {
+        self.0.release_prefetch(params);
+    }
+}
+
+impl<F, Fut, T> From<F> for RouteLoader
+where
+    F: Fn(ParamsMap) -> Fut + Send + Sync + 'static,
+    T: Serialize + DeserializeOwned + Send + Sync + 'static,
+    Fut: Future<Output = T> + Send + 'static,
+{
+    fn from(loader: F) -> Self {
+        Self::new(loader)
+    }
+}
+
+impl fmt::Debug for RouteLoader {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RouteLoader").finish_non_exhaustive()
+    }
+}
+
+impl PartialEq for RouteLoader {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+impl Eq for RouteLoader {}
+
+/// The resource created by a [`RouteLoader`].
+#[derive(Clone)]
+pub(crate) struct LoaderData(Arc<dyn Any + Send + Sync>);
+
+/// The data loaded for the current route and all of its parents, outermost first.
+#[derive(Clone, Default)]
+pub(crate) struct RouteLoaders(Arc<Vec<LoaderData>>);
+
+impl RouteLoaders {
+    /// Adds the data loaded by a nested route, if it has a loader.
+    pub fn with(&self, data: Option<LoaderData>) -> Self {
+        match data {
+            None => self.clone(),
+            Some(data) => {
+                let mut loaders = Vec::clone(&self.0);
+                loaders.push(data);
+                Self(Arc::new(loaders))
+            }
+        }
+    }
+
+    /// The innermost resource that loads a `T`.
+    pub fn get<T: 'static>(&self) -> Option<ArcResource<T>> {
+        self.0
+            .iter()
+            .rev()
+            .find_map(|data| data.0.downcast_ref::<ArcResource<T>>())
+            .cloned()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{RouteLoader, MAX_PREFETCHED};
+    use crate::params::ParamsMap;
+    use any_spawner::Executor;
+    use futures::{executor::block_on, future};
+    use leptos::prelude::ArcResource;
+    use reactive_graph::{computed::ArcMemo, owner::Owner};
+    use std::{
+        future::IntoFuture,
+        sync::{
+            atomic::{AtomicUsize, Ordering},
+            Arc,
+        },
+    };
+
+    fn counting_loader(pending: bool) -> (RouteLoader, Arc<AtomicUsize>) {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let loader = RouteLoader::new({
+            let calls = Arc::clone(&calls);
+            move |params: ParamsMap| {
+                calls.fetch_add(1, Ordering::Relaxed);
+                async move {
+                    if pending {
+                        future::pending::<()>().await;
+                    }
+                    params.get("id")
+                }
+            }
+        });
+        (loader, calls)
+    }
+
+    #[test]
+    fn loads_prefetched_data_once() {
+        _ = Executor::init_futures_executor();
+        let owner = Owner::new();
+        owner.set();
+
+        let (loader, calls) = counting_loader(false);
+        let params = [("id", "1")].into_iter().collect::<ParamsMap>();
+        loader.prefetch(params.clone());
+        loader.prefetch(params.clone());
+        assert_eq!(calls.load(Ordering::Relaxed), 1);
+
+        let data = loader.load(ArcMemo::new(move |_| params.clone()));
+        let data = data
+            .0
+            .downcast_ref::<ArcResource<Option<String>>>()
+            .cloned()
+            .unwrap();
+        assert_eq!(block_on(data.into_future()), Some("1".to_string()));
+        assert_eq!(calls.load(Ordering::Relaxed), 1);
+    }
+
+    #[test]
+    fn cancels_prefetches_no_link_wants() {
+        _ = Executor::init_futures_executor();
+
+        let (loader, calls) = counting_loader(true);
+        let params = [("id", "1")].into_iter().collect::<ParamsMap>();
+        loader.prefetch(params.clone());
+        loader.prefetch(params.clone());
+
+        // still wanted by another link
+        loader.cancel_prefetch(&params);
+        loader.prefetch(params.clone());
+        assert_eq!(calls.load(Ordering::Relaxed), 1);
+
+        loader.cancel_prefetch(&params);
+        loader.cancel_prefetch(&params);
+        loader.prefetch(params.clone());
+        assert_eq!(calls.load(Ordering::Relaxed), 2);
+
+        // kept for a navigation
+        loader.release_prefetch(&params);
+        loader.prefetch(params);
+        assert_eq!(calls.load(Ordering::Relaxed), 2);
+    }
+
+    #[test]
+    fn keeps_prefetches_links_still_want() {
+        _ = Executor::init_futures_executor();
+
+        let (loader, calls) = counting_loader(true);
+        let params = |id: usize| {
+            [("id", id.to_string())].into_iter().collect::<ParamsMap>()
+        };
+        for id in 0..MAX_PREFETCHED {
+            loader.prefetch(params(id));
+        }
+
+        // every entry is still wanted, so the new one is not prefetched
+        loader.prefetch(params(MAX_PREFETCHED));
+        assert_eq!(calls.load(Ordering::Relaxed), MAX_PREFETCHED);
+        loader.prefetch(params(0));
+        assert_eq!(calls.load(Ordering::Relaxed), MAX_PREFETCHED);
+
+        // once one is released, it makes room for the new one
+        loader.release_prefetch(&params(3));
+        loader.prefetch(params(MAX_PREFETCHED));
+        assert_eq!(calls.load(Ordering::Relaxed), MAX_PREFETCHED + 1);
+        loader.prefetch(params(0));
+        assert_eq!(calls.load(Ordering::Relaxed), MAX_PREFETCHED + 1);
+    }
+}
@@ -0,0 +1,284 @@
+use super::{BrowserUrl, LocationChange, LocationProvider, Url};
+use crate::{
+    navigate::{NavigationDecision, NavigationGuards},
+    route_data::TrailingSlashRedirect,
+    scroll::{
+        enter_history_entry, push_entry_state, replace_entry_state,
+        ScrollRestorer,
+    },
+};
+use any_spawner::Executor;
+use core::fmt;
+use leptos::{ev, prelude::*};
+use reactive_graph::{
+    signal::ArcRwSignal,
+    traits::{ReadUntracked, Set},
+};
+use std::{borrow::Cow, cell::Cell, rc::Rc};
+use tachys::dom::{document, window};
+use wasm_bindgen::JsValue;
+
+/// A [`LocationProvider`] that stores the router's location in the hash fragment of the browser's
+/// URL, e.g., `https://example.com/#/posts/1?sort=asc`.
+///
+/// The server only ever sees requests for the page itself, so this can be used to deploy an app
+/// to a static host that cannot serve it for every path. Links created with
+/// [`<A/>`](crate::components::A) point to the hash fragment automatically; plain `<a>` tags need
+/// an `href` like `"#/posts/1"`. A fragment that does not start with `/`, like `#section`, is an
+/// anchor within the page and does not change the route.
+#[derive(Clone)]
+pub struct HashUrl {
+    url: ArcRwSignal<Url>,
+    path_stack: ArcStoredValue<Vec<Url>>,
+    is_back: ArcRwSignal<bool>,
+}
+
+impl fmt::Debug for HashUrl {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("HashUrl").finish_non_exhaustive()
+    }
+}
+
+impl HashUrl {
+    /// The route in the hash fragment of the browser's URL, or `None` if the fragment is an
+    /// anchor within the page.
+    fn route_in_hash() -> Result<Option<String>, JsValue> {
+        let hash = window().location().hash()?;
+        let hash = hash.strip_prefix('#').unwrap_or(&hash);
+        Ok(if hash.is_empty() {
+            Some("/".to_string())
+        } else if hash.starts_with('/') {
+            Some(hash.to_string())
+        } else {
+            None
+        })
+    }
+
+    fn scroll_to_el(&self, loc_scroll: bool) {
+        let hash = self.url.read_untracked().hash.clone();
+        if let Some(id) = hash.strip_prefix('#').filter(|id| !id.is_empty()) {
+            if let Some(el) = document().get_element_by_id(id) {
+                el.scroll_into_view();
+                return;
+            }
+        }
+
+        // scroll to top
+        if loc_scroll {
+            window().scroll_to_with_x_and_y(0.0, 0.0);
+        }
+    }
+}
+
+impl LocationProvider for HashUrl {
+    type Error = JsValue;
+
+    fn new() -> Result<Self, JsValue> {
+        let url = Self::current()?;
+        Ok(Self {
+            url: ArcRwSignal::new(url.clone()),
+            path_stack: ArcStoredValue::new(vec![url]),
+            is_back: Default::default(),
+        })
+    }
+
+    fn as_url(&self) -> &ArcRwSignal<Url> {
+        &self.url
+    }
+
+    fn current() -> Result<Url, Self::Error> {
+        // a page that is opened at an anchor shows the root route
+        let path = Self::route_in_hash()?.unwrap_or_else(|| "/".to_string());
+        Self::parse_with_base(&path, &window().location().origin()?)
+    }
+
+    fn parse(url: &str) -> Result<Url, Self::Error> {
+        BrowserUrl::parse(url)
+    }
+
+    fn parse_with_base(url: &str, base: &str) -> Result<Url, Self::Error> {
+        BrowserUrl::parse_with_base(url, base)
+    }
+
+    fn init(&self, _base: Option<Cow<'static, str>>) {
+        enter_history_entry();
+        // links to the hash fragment are followed by the browser, so only the resulting
+        // hashchange event (including forward/back navigation) needs to be handled
+        let hashchange_cb = {
+            let url = self.url.clone();
+            let path_stack = self.path_stack.clone();
+            let is_back = self.is_back.clone();
+            let this = self.clone();
+            let scroll = use_context::<ScrollRestorer>().unwrap_or_default();
+            move |new_url: Url| {
+                let mut stack = path_stack.write_value();
+                let is_navigating_back = stack.len() >= 2
+                    && stack.get(stack.len() - 2) == Some(&new_url);
+
+                if is_navigating_back {
+                    stack.pop();
+                } else if stack.last() != Some(&new_url) {
+                    stack.push(new_url.clone());
+                }
+
+                is_back.set(is_navigating_back);
+
+                let left_entry = enter_history_entry();
+                if scroll.is_enabled() {
+                    scroll.leave(left_entry);
+                    let scroll = scroll.clone();
+                    let this = this.clone();
+                    request_animation_frame(move || {
+                        scroll.restore(|| this.scroll_to_el(true))
+                    });
+                }
+
+                url.set(new_url);
+            }
+        };
+        let hashchange_cb = {
+            let this = self.clone();
+            let guards = use_context::<NavigationGuards>().unwrap_or_default();
+            let hashchange_cb = Rc::new(hashchange_cb);
+            // set while the browser returns to the current URL after a blocked navigation
+            let restoring = Rc::new(Cell::new(false));
+            // the history entry is redirected in place, before the guards are consulted
+            let trailing_slash_redirect =
+                use_context::<TrailingSlashRedirect>().unwrap_or_default();
+            let current = move || {
+                Self::current().map(|mut new_url| {
+                    if trailing_slash_redirect.apply(&mut new_url) {
+                        let hash_url = format!("#{}", new_url.to_full_path());
+                        if let Err(e) = window().history().and_then(|history| {
+                            history.replace_state_with_url(
+                                &history.state()?,
+                                "",
+                                Some(&hash_url),
+                            )
+                        }) {
+                            leptos::logging::error!(
+                                "Failed to replace URL: {e:?}"
+                            );
+                        }
+                    }
+                    new_url
+                })
+            };
+            move || match current() {
+                Ok(_) if restoring.replace(false) => {}
+                // the browser scrolls to anchors within the page by itself
+                Ok(_) if matches!(Self::route_in_hash(), Ok(None)) => {}
+                Ok(new_url) if guards.is_empty() => hashchange_cb(new_url),
+                Ok(new_url) => {
+                    let from = this.url.read_untracked().clone();
+                    let is_navigating_back = {
+                        let stack = this.path_stack.read_value();
+                        stack.len() >= 2
+                            && stack.get(stack.len() - 2) == Some(&new_url)
+                    };
+                    let this = this.clone();
+                    let guards = guards.clone();
+                    let hashchange_cb = Rc::clone(&hashchange_cb);
+                    let restoring = Rc::clone(&restoring);
+                    Executor::spawn_local(async move {
+                        match guards.check(&from, &new_url).await {
+                            NavigationDecision::Allow => hashchange_cb(new_url),
+                            NavigationDecision::Cancel => {
+                                restoring.set(true);
+                                let delta =
+                                    if is_navigating_back { 1 } else { -1 };
+                                if let Err(e) =
+                                    window().history().and_then(|history| {
+                                        history.go_with_delta(delta)
+                                    })
+                                {
+                                    restoring.set(false);
+                                    leptos::logging::error!(
+                                        "Failed to restore URL: {e:?}"
+                                    );
+                                }
+                            }
+                            NavigationDecision::Redirect(to) => {
+                                hashchange_cb(new_url);
+                                this.complete_navigation(&LocationChange {
+                                    value: to,
+                                    replace: true,
+                                    ..Default::default()
+                                });
+                                if let Ok(url) = Self::current() {
+                                    this.url.set(url);
+                                }
+                            }
+                        }
+                    });
+                }
+                Err(e) => {
+                    #[cfg(feature = "tracing")]
+                    tracing::error!("{e:?}");
+                    #[cfg(not(feature = "tracing"))]
+                    web_sys::console::error_1(&e);
+                }
+            }
+        };
+
+        let hashchange_handle =
+            window_event_listener(ev::hashchange, move |_| hashchange_cb());
+
+        on_cleanup(|| hashchange_handle.remove());
+    }
+
+    fn ready_to_complete(&self) {}
+
+    fn complete_navigation(&self, loc: &LocationChange) {
+        let history = window().history().unwrap();
+
+        let current_path = self
+            .path_stack
+            .read_value()
+            .last()
+            .map(|url| url.to_full_path());
+        let add_to_stack = current_path.as_ref() != Some(&loc.value);
+
+        // pushing a new hash does not fire hashchange, so the URL signal is not set twice
+        let hash_url = format!("#{}", loc.value);
+        let state = loc.state.to_js_value();
+        if loc.replace {
+            history
+                .replace_state_with_url(
+                    &replace_entry_state(&state),
+                    "",
+                    Some(&hash_url),
+                )
+                .unwrap();
+        } else if add_to_stack {
+            history
+                .push_state_with_url(
+                    &push_entry_state(&state),
+                    "",
+                    Some(&hash_url),
+                )
+                .unwrap();
+        }
+
+        if let Ok(url) = Self::current() {
+            let mut stack = self.path_stack.write_value();
+            if loc.replace {
+                stack.pop();
+                stack.push(url);
+            } else if add_to_stack {
+                stack.push(url);
+            }
+            self.is_back.set(false);
+        }
+
+        self.scroll_to_el(loc.scroll);
+    }
+
+    fn redirect(loc: &str) {
+        BrowserUrl::redirect(loc)
+    }
+
+    fn is_back(&self) -> ReadSignal<bool> {
+        self.is_back.read_only().into()
+    }
+}
@@ -1,7 +1,17 @@
 use super::{handle_anchor_click, LocationChange, LocationProvider, Url};
-use crate::{hooks::use_navigate, params::ParamsMap};
+use crate::{
+    hooks::use_navigate,
+    navigate::{NavigationDecision, NavigationGuards},
+    params::ParamsMap,
+    route_data::TrailingSlashRedirect,
+    scroll::{
+        current_entry, enter_history_entry, push_entry_state,
+        replace_entry_state, ScrollRestorer,
+    },
+};
+use any_spawner::Executor;
 use core::fmt;
-use futures::channel::oneshot;
+use futures::{channel::oneshot, future::Either};
 use js_sys::{try_iter, Array, JsString};
 use leptos::{ev, prelude::*};
 use or_poisoned::OrPoisoned;
@@ -11,6 +21,8 @@ use reactive_graph::{
 };
 use std::{
     borrow::Cow,
+    cell::Cell,
+    rc::Rc,
     string::String,
     sync::{Arc, Mutex},
 };
@@ -115,13 +127,20 @@ impl LocationProvider for BrowserUrl {
     }
 
     fn init(&self, base: Option<Cow<'static, str>>) {
-        let navigate = {
+        enter_history_entry();
+        let guards = use_context::<NavigationGuards>().unwrap_or_default();
+        let scroll = use_context::<ScrollRestorer>().unwrap_or_default();
+        let trailing_slash_redirect =
+            use_context::<TrailingSlashRedirect>().unwrap_or_default();
+        let commit = {
             let url = self.url.clone();
             let pending = Arc::clone(&self.pending_navigation);
             let this = self.clone();
-            move |new_url: Url, loc| {
+            let scroll = scroll.clone();
+            move |new_url: Url, loc: LocationChange| {
                 let same_path = {
                     let curr = url.read_untracked();
+                    scroll.leave(current_entry());
                     curr.origin() == new_url.origin()
                         && curr.path() == new_url.path()
                 };
@@ -154,9 +173,49 @@ impl LocationProvider for BrowserUrl {
                 }
             }
         };
+        let commit = Rc::new(commit);
+        let navigate = {
+            let url = self.url.clone();
+            let guards = guards.clone();
+            let commit = Rc::clone(&commit);
+            let trailing_slash_redirect = trailing_slash_redirect.clone();
+            move |mut new_url: Url, mut loc: LocationChange| {
+                if trailing_slash_redirect.apply(&mut new_url) {
+                    loc.value = new_url.to_full_path();
+                }
+                if guards.is_empty() {
+                    return Either::Left(commit(new_url, loc));
+                }
+                let from = url.read_untracked().clone();
+                let guards = guards.clone();
+                let commit = Rc::clone(&commit);
+                Either::Right(async move {
+                    match guards.check(&from, &new_url).await {
+                        NavigationDecision::Allow => commit(new_url, loc).await,
+                        NavigationDecision::Cancel => {}
+                        NavigationDecision::Redirect(to) => {
+                            match Self::parse(&to) {
+                                Ok(new_url) => {
+                                    let loc = LocationChange {
+                                        value: new_url.to_full_path(),
+                                        ..loc
+                                    };
+                                    commit(new_url, loc).await
+                                }
+                                Err(e) => {
+                                    leptos::logging::error!(
+                                        "Error parsing URL: {e:?}"
+                                    )
+                                }
+                            }
+                        }
+                    }
+                })
+            }
+        };
 
         let handle_anchor_click =
-            handle_anchor_click(base, Self::parse_with_base, navigate);
+            handle_anchor_click(base, Self::parse_with_base, navigate.clone());
 
         let click_handle = window_event_listener(ev::click, move |ev| {
             if let Err(e) = handle_anchor_click(ev) {
@@ -172,20 +231,106 @@ impl LocationProvider for BrowserUrl {
             let url = self.url.clone();
             let path_stack = self.path_stack.clone();
             let is_back = self.is_back.clone();
-            move || match Self::current() {
-                Ok(new_url) => {
-                    let mut stack = path_stack.write_value();
-                    let is_navigating_back = stack.len() == 1
-                        || (stack.len() >= 2
-                            && stack.get(stack.len() - 2) == Some(&new_url));
+            let pending = Arc::clone(&self.pending_navigation);
+            move |new_url: Url| {
+                let mut stack = path_stack.write_value();
+                let is_navigating_back = is_navigating_back(&stack, &new_url);
 
-                    if is_navigating_back {
-                        stack.pop();
-                    }
+                if is_navigating_back {
+                    stack.pop();
+                }
 
-                    is_back.set(is_navigating_back);
+                is_back.set(is_navigating_back);
 
-                    url.set(new_url);
+                let left_entry = enter_history_entry();
+                if scroll.is_enabled() {
+                    let curr = url.read_untracked();
+                    scroll.leave(left_entry);
+
+                    // restore the scroll position once the new route has loaded
+                    let ready = (curr.path() != new_url.path()).then(|| {
+                        let (tx, rx) = oneshot::channel::<()>();
+                        *pending.lock().or_poisoned() = Some(tx);
+                        rx
+                    });
+                    let scroll = scroll.clone();
+                    Executor::spawn_local(async move {
+                        if let Some(ready) = ready {
+                            // another navigation has started in the meantime
+                            if ready.await.is_err() {
+                                return;
+                            }
+                        }
+                        request_animation_frame(move || {
+                            scroll.restore(|| Self::scroll_to_el(true))
+                        });
+                    });
+                }
+
+                url.set(new_url);
+            }
+        };
+        let popstate_cb = {
+            let url = self.url.clone();
+            let path_stack = self.path_stack.clone();
+            let popstate_cb = Rc::new(popstate_cb);
+            // set while the browser returns to the current URL after a blocked navigation
+            let restoring = Rc::new(Cell::new(false));
+            // the history entry is redirected in place, before the guards are consulted
+            let current = move || {
+                Self::current().map(|mut new_url| {
+                    if trailing_slash_redirect.apply(&mut new_url) {
+                        replace_url(&new_url);
+                    }
+                    new_url
+                })
+            };
+            move || match current() {
+                Ok(_) if restoring.replace(false) => {}
+                Ok(new_url) if guards.is_empty() => popstate_cb(new_url),
+                Ok(new_url) => {
+                    let from = url.read_untracked().clone();
+                    let is_navigating_back =
+                        is_navigating_back(&path_stack.read_value(), &new_url);
+                    let guards = guards.clone();
+                    let popstate_cb = Rc::clone(&popstate_cb);
+                    let restoring = Rc::clone(&restoring);
+                    let commit = Rc::clone(&commit);
+                    Executor::spawn_local(async move {
+                        match guards.check(&from, &new_url).await {
+                            NavigationDecision::Allow => popstate_cb(new_url),
+                            NavigationDecision::Cancel => {
+                                restoring.set(true);
+                                let delta =
+                                    if is_navigating_back { 1 } else { -1 };
+                                if let Err(e) =
+                                    window().history().and_then(|history| {
+                                        history.go_with_delta(delta)
+                                    })
+                                {
+                                    restoring.set(false);
+                                    leptos::logging::error!(
+                                        "Failed to restore URL: {e:?}"
+                                    );
+                                }
+                            }
+                            // the guards are not consulted again for the redirect
+                            NavigationDecision::Redirect(to) => {
+                                popstate_cb(new_url);
+                                if let Ok(redirect_url) = Self::parse(&to) {
+                                    commit(
+                                        redirect_url,
+                                        LocationChange {
+                                            value: to,
+                                            replace: true,
+                                            ..Default::default()
+                                        },
+                                    )
+                                    .await;
+                                }
+                            }
+                        }
+                    });
                 }
                 Err(e) => {
                     #[cfg(feature = "tracing")]
@@ -224,14 +369,14 @@ impl LocationProvider for BrowserUrl {
         if loc.replace {
             history
                 .replace_state_with_url(
-                    &loc.state.to_js_value(),
+                    &replace_entry_state(&loc.state.to_js_value()),
                     "",
                     Some(&loc.value),
                 )
                 .unwrap();
         } else if add_to_stack {
             // push the "forward direction" marker
-            let state = &loc.state.to_js_value();
+            let state = &push_entry_state(&loc.state.to_js_value());
             history
                 .push_state_with_url(state, "", Some(&loc.value))
                 .unwrap();
@@ -273,6 +418,26 @@ impl LocationProvider for BrowserUrl {
     }
 }
 
+/// Replaces the URL of the current history entry, keeping its state.
+fn replace_url(url: &Url) {
+    if let Err(e) = window().history().and_then(|history| {
+        history.replace_state_with_url(
+            &history.state()?,
+            "",
+            Some(&url.to_full_path()),
+        )
+    }) {
+        leptos::logging::error!("Failed to replace URL: {e:?}");
+    }
+}
+
+/// Whether a popstate to `new_url` moves back through the stack of visited URLs. With only the
+/// first URL on the stack, any move goes back to a page that was left before the app started.
+fn is_navigating_back(stack: &[Url], new_url: &Url) -> bool {
+    stack.len() == 1
+        || (stack.len() >= 2 && stack.get(stack.len() - 2) == Some(new_url))
+}
+
 fn search_params_from_web_url(
     params: &web_sys::UrlSearchParams,
 ) -> Result<ParamsMap, JsValue> {
@@ -315,3 +480,22 @@ pub(crate) fn resolve_redirect_url(loc: &str) -> Option<web_sys::Url> {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::is_navigating_back;
+    use crate::location::Url;
+
+    #[test]
+    fn detects_back_navigations() {
+        let url = |path: &str| {
+            let mut url = Url::default();
+            url.set_path(path.to_string());
+            url
+        };
+        let stack = [url("/"), url("/a"), url("/b")];
+        assert!(is_navigating_back(&stack, &url("/a")));
+        assert!(!is_navigating_back(&stack, &url("/c")));
+        assert!(is_navigating_back(&stack[..1], &url("/c")));
+    }
+}
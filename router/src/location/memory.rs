@@ -0,0 +1,288 @@
+use super::{LocationChange, LocationProvider, Url, BASE};
+use crate::{hooks::use_navigate, params::ParamsMap};
+use core::fmt;
+use leptos::prelude::*;
+use reactive_graph::{
+    signal::ArcRwSignal,
+    traits::{ReadUntracked, Set},
+};
+use std::borrow::Cow;
+
+/// A [`LocationProvider`] that keeps its own history stack in memory, without reading or
+/// updating the browser's URL.
+///
+/// This allows the router to run outside of a browser, e.g., in native tests, and to be driven
+/// and inspected from code:
+///
+/// ```rust
+/// # use leptos_router::location::MemoryUrl;
+/// let history = MemoryUrl::with_path("/posts").unwrap();
+/// history.push("/posts/1?comments=true").unwrap();
+/// assert_eq!(history.path(), "/posts/1?comments=true");
+///
+/// history.back();
+/// assert_eq!(history.path(), "/posts");
+/// assert!(history.can_go_forward());
+/// ```
+///
+/// Pass it to a [`Router`](crate::components::Router) with `<Router location=history.clone()>`.
+#[derive(Clone)]
+pub struct MemoryUrl {
+    url: ArcRwSignal<Url>,
+    history: ArcStoredValue<MemoryHistory>,
+    is_back: ArcRwSignal<bool>,
+}
+
+#[derive(Debug)]
+struct MemoryHistory {
+    entries: Vec<Url>,
+    index: usize,
+}
+
+impl fmt::Debug for MemoryUrl {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("MemoryUrl")
+            .field("entries", &self.entries())
+            .field("index", &self.index())
+            .finish()
+    }
+}
+
+impl MemoryUrl {
+    /// Creates a history whose only entry is the given path.
+    pub fn with_path(path: &str) -> Result<Self, url::ParseError> {
+        let url = Self::parse(path)?;
+        Ok(Self {
+            url: ArcRwSignal::new(url.clone()),
+            history: ArcStoredValue::new(MemoryHistory {
+                entries: vec![url],
+                index: 0,
+            }),
+            is_back: Default::default(),
+        })
+    }
+
+    /// The path, query string and hash of the current entry.
+    pub fn path(&self) -> String {
+        self.url.read_untracked().to_full_path()
+    }
+
+    /// The path, query string and hash of every entry in the history, oldest first.
+    pub fn entries(&self) -> Vec<String> {
+        self.history
+            .read_value()
+            .entries
+            .iter()
+            .map(Url::to_full_path)
+            .collect()
+    }
+
+    /// The position of the current entry in [`entries`](Self::entries).
+    pub fn index(&self) -> usize {
+        self.history.read_value().index
+    }
+
+    /// Whether there is an entry before the current one.
+    pub fn can_go_back(&self) -> bool {
+        self.index() > 0
+    }
+
+    /// Whether there is an entry after the current one.
+    pub fn can_go_forward(&self) -> bool {
+        let history = self.history.read_value();
+        history.index + 1 < history.entries.len()
+    }
+
+    /// Navigates to a new entry, discarding any entries after the current one.
+    pub fn push(&self, path: &str) -> Result<(), url::ParseError> {
+        self.navigate(path, false)
+    }
+
+    /// Replaces the current entry.
+    pub fn replace(&self, path: &str) -> Result<(), url::ParseError> {
+        self.navigate(path, true)
+    }
+
+    /// Moves back one entry, like the browser's "back" button.
+    pub fn back(&self) {
+        self.go(-1);
+    }
+
+    /// Moves forward one entry, like the browser's "forward" button.
+    pub fn forward(&self) {
+        self.go(1);
+    }
+
+    /// Moves `delta` entries forward or (if negative) back. Does nothing if there is no such
+    /// entry.
+    pub fn go(&self, delta: isize) {
+        let url = {
+            let mut history = self.history.write_value();
+            let Some(index) = history
+                .index
+                .checked_add_signed(delta)
+                .filter(|index| *index < history.entries.len())
+            else {
+                return;
+            };
+            history.index = index;
+            history.entries[index].clone()
+        };
+        self.is_back.set(delta < 0);
+        self.url.set(url);
+    }
+
+    fn navigate(
+        &self,
+        path: &str,
+        replace: bool,
+    ) -> Result<(), url::ParseError> {
+        // check the path before completing the navigation, which only logs errors
+        let origin = self.url.read_untracked().origin.clone();
+        Self::parse_with_base(path, &origin)?;
+        self.complete_navigation(&LocationChange {
+            value: path.to_string(),
+            replace,
+            ..Default::default()
+        });
+        Ok(())
+    }
+}
+
+impl LocationProvider for MemoryUrl {
+    type Error = url::ParseError;
+
+    fn new() -> Result<Self, Self::Error> {
+        Self::with_path("/")
+    }
+
+    fn as_url(&self) -> &ArcRwSignal<Url> {
+        &self.url
+    }
+
+    /// The URL of a new, empty history.
+    fn current() -> Result<Url, Self::Error> {
+        Self::parse("/")
+    }
+
+    fn init(&self, _base: Option<Cow<'static, str>>) {}
+
+    fn ready_to_complete(&self) {}
+
+    fn complete_navigation(&self, loc: &LocationChange) {
+        let origin = self.url.read_untracked().origin.clone();
+        let url = match Self::parse_with_base(&loc.value, &origin) {
+            Ok(url) => url,
+            Err(e) => {
+                leptos::logging::error!("Error parsing URL: {e:?}");
+                return;
+            }
+        };
+
+        {
+            let mut history = self.history.write_value();
+            let index = history.index;
+            if loc.replace {
+                history.entries[index] = url.clone();
+            } else if history.entries[index] != url {
+                history.entries.truncate(index + 1);
+                history.entries.push(url.clone());
+                history.index += 1;
+            }
+        }
+        self.is_back.set(false);
+
+        if *self.url.read_untracked() != url {
+            self.url.set(url);
+        }
+    }
+
+    fn parse(url: &str) -> Result<Url, Self::Error> {
+        Self::parse_with_base(url, BASE)
+    }
+
+    fn parse_with_base(url: &str, base: &str) -> Result<Url, Self::Error> {
+        let base = url::Url::parse(base)?;
+        let url = url::Url::options().base_url(Some(&base)).parse(url)?;
+
+        let search_params = url
+            .query_pairs()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect::<ParamsMap>();
+
+        Ok(Url {
+            origin: url.origin().unicode_serialization(),
+            path: url.path().to_string(),
+            search: url.query().unwrap_or_default().to_string(),
+            search_params,
+            hash: url
+                .fragment()
+                .map(|hash| format!("#{hash}"))
+                .unwrap_or_default(),
+        })
+    }
+
+    fn redirect(loc: &str) {
+        let navigate = use_navigate();
+        navigate(loc, Default::default());
+    }
+
+    fn is_back(&self) -> ReadSignal<bool> {
+        self.is_back.read_only().into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::MemoryUrl;
+    use crate::location::{LocationChange, LocationProvider};
+    use reactive_graph::traits::ReadUntracked;
+
+    #[test]
+    fn pushes_and_moves_through_history() {
+        let history = MemoryUrl::with_path("/").unwrap();
+        history.push("/a").unwrap();
+        history.push("/b?x=1#top").unwrap();
+        assert_eq!(history.entries(), ["/", "/a", "/b?x=1#top"]);
+        assert_eq!(
+            history
+                .as_url()
+                .read_untracked()
+                .search_params()
+                .get_str("x"),
+            Some("1")
+        );
+
+        history.back();
+        history.back();
+        assert_eq!(history.path(), "/");
+        assert!(!history.can_go_back());
+        history.back();
+        assert_eq!(history.index(), 0);
+
+        history.forward();
+        assert_eq!(history.path(), "/a");
+
+        // navigating discards the forward entries
+        history.push("/c").unwrap();
+        assert_eq!(history.entries(), ["/", "/a", "/c"]);
+        assert!(!history.can_go_forward());
+    }
+
+    #[test]
+    fn completes_router_navigations() {
+        let history = MemoryUrl::new().unwrap();
+        history.complete_navigation(&LocationChange {
+            value: "/posts".into(),
+            replace: false,
+            ..Default::default()
+        });
+        history.complete_navigation(&LocationChange {
+            value: "/posts/1".into(),
+            replace: true,
+            ..Default::default()
+        });
+        assert_eq!(history.entries(), ["/", "/posts/1"]);
+        assert_eq!(history.path(), "/posts/1");
+    }
+}
@@ -8,7 +8,7 @@ use reactive_graph::{
     computed::Memo,
     owner::provide_context,
     signal::{ArcRwSignal, ReadSignal},
-    traits::With,
+    traits::{ReadUntracked, With},
 };
 use send_wrapper::SendWrapper;
 use std::{borrow::Cow, future::Future};
@@ -16,10 +16,14 @@ use tachys::dom::window;
 use wasm_bindgen::{JsCast, JsValue};
 use web_sys::{HtmlAnchorElement, MouseEvent};
 
+mod hash;
 mod history;
+mod memory;
 mod server;
 use crate::params::ParamsMap;
+pub use hash::*;
 pub use history::*;
+pub use memory::*;
 pub use server::*;
 
 pub(crate) const BASE: &str = "https://leptos.dev";
@@ -50,6 +54,10 @@ impl Url {
         &mut self.path
     }
 
+    pub(crate) fn set_path(&mut self, path: String) {
+        self.path = path;
+    }
+
     pub fn search(&self) -> &str {
         &self.search
     }
@@ -121,12 +129,14 @@ impl Url {
         path
     }
 
+    // outside the browser (on the server, or in native tests), the URL is (un)escaped without
+    // calling into JavaScript
     pub fn escape(s: &str) -> String {
-        #[cfg(not(feature = "ssr"))]
+        #[cfg(all(not(feature = "ssr"), target_arch = "wasm32"))]
         {
             js_sys::encode_uri_component(s).as_string().unwrap()
         }
-        #[cfg(feature = "ssr")]
+        #[cfg(any(feature = "ssr", not(target_arch = "wasm32")))]
         {
             percent_encoding::utf8_percent_encode(
                 s,
@@ -136,15 +146,34 @@ impl Url {
         }
     }
 
+    /// Percent-encodes a path segment, leaving only ASCII letters, digits and `-._~` alone.
+    ///
+    /// Unlike [`escape`](Self::escape), this gives the same result on the server and in the
+    /// browser, so that links rendered on the server match those rendered while hydrating.
+    pub fn escape_segment(s: &str) -> String {
+        const HEX: &[u8; 16] = b"0123456789ABCDEF";
+        let mut escaped = String::with_capacity(s.len());
+        for byte in s.bytes() {
+            if byte.is_ascii_alphanumeric() || b"-._~".contains(&byte) {
+                escaped.push(byte as char);
+            } else {
+                escaped.push('%');
+                escaped.push(HEX[usize::from(byte >> 4)] as char);
+                escaped.push(HEX[usize::from(byte & 0xf)] as char);
+            }
+        }
+        escaped
+    }
+
     pub fn unescape(s: &str) -> String {
-        #[cfg(feature = "ssr")]
+        #[cfg(any(feature = "ssr", not(target_arch = "wasm32")))]
         {
             percent_encoding::percent_decode_str(s)
                 .decode_utf8_lossy()
                 .to_string()
         }
 
-        #[cfg(not(feature = "ssr"))]
+        #[cfg(all(not(feature = "ssr"), target_arch = "wasm32"))]
         {
             match js_sys::decode_uri_component(s) {
                 Ok(v) => v.into(),
@@ -154,7 +183,7 @@ impl Url {
     }
 
     pub fn unescape_minimal(s: &str) -> String {
-        #[cfg(not(feature = "ssr"))]
+        #[cfg(all(not(feature = "ssr"), target_arch = "wasm32"))]
         {
             match js_sys::decode_uri(s) {
                 Ok(v) => v.into(),
@@ -162,7 +191,7 @@ impl Url {
             }
         }
 
-        #[cfg(feature = "ssr")]
+        #[cfg(any(feature = "ssr", not(target_arch = "wasm32")))]
         {
             Self::unescape(s)
         }
@@ -263,6 +292,121 @@ pub trait LocationProvider: Clone + 'static {
     fn is_back(&self) -> ReadSignal<bool>;
 }
 
+/// How a [`Router`](crate::components::Router) reads and updates its location.
+#[derive(Debug, Clone, Default)]
+pub enum RouterLocation {
+    /// The path of the browser's URL, updated with the History API. See [`BrowserUrl`].
+    #[default]
+    Browser,
+    /// The hash fragment of the browser's URL, for static hosts that cannot serve the app for
+    /// every path. See [`HashUrl`].
+    Hash,
+    /// An in-memory history, for tests and other environments without a browser. See
+    /// [`MemoryUrl`].
+    Memory(MemoryUrl),
+}
+
+impl From<MemoryUrl> for RouterLocation {
+    fn from(value: MemoryUrl) -> Self {
+        Self::Memory(value)
+    }
+}
+
+/// The location provider chosen with a [`RouterLocation`].
+///
+/// This does not implement [`LocationProvider`], whose associated functions cannot know the
+/// variant; its methods dispatch to the chosen provider instead.
+// on the server, only an in-memory history is used
+#[cfg_attr(feature = "ssr", allow(dead_code))]
+#[derive(Debug, Clone)]
+pub(crate) enum AnyLocation {
+    Browser(BrowserUrl),
+    Hash(HashUrl),
+    Memory(MemoryUrl),
+}
+
+impl AnyLocation {
+    #[cfg_attr(feature = "ssr", allow(dead_code))]
+    pub fn new(location: RouterLocation) -> Result<Self, JsValue> {
+        Ok(match location {
+            RouterLocation::Browser => Self::Browser(BrowserUrl::new()?),
+            RouterLocation::Hash => Self::Hash(HashUrl::new()?),
+            RouterLocation::Memory(memory) => Self::Memory(memory),
+        })
+    }
+
+    /// Provides both this and the underlying location provider as context.
+    pub fn provide_context(&self) {
+        match self {
+            Self::Browser(location) => provide_context(location.clone()),
+            Self::Hash(location) => provide_context(location.clone()),
+            Self::Memory(location) => provide_context(location.clone()),
+        }
+        provide_context(self.clone());
+    }
+
+    pub fn parse_url(&self, url: &str) -> Result<Url, String> {
+        match self {
+            Self::Browser(_) | Self::Hash(_) => {
+                BrowserUrl::parse(url).map_err(|e| format!("{e:?}"))
+            }
+            Self::Memory(location) => {
+                let origin = location.as_url().read_untracked().origin.clone();
+                MemoryUrl::parse_with_base(url, &origin)
+                    .map_err(|e| format!("{e:?}"))
+            }
+        }
+    }
+
+    pub fn redirect_to(&self, loc: &str) {
+        match self {
+            Self::Browser(_) => BrowserUrl::redirect(loc),
+            Self::Hash(_) => HashUrl::redirect(loc),
+            Self::Memory(_) => MemoryUrl::redirect(loc),
+        }
+    }
+
+    pub fn as_url(&self) -> &ArcRwSignal<Url> {
+        match self {
+            Self::Browser(location) => location.as_url(),
+            Self::Hash(location) => location.as_url(),
+            Self::Memory(location) => location.as_url(),
+        }
+    }
+
+    pub fn init(&self, base: Option<Cow<'static, str>>) {
+        match self {
+            Self::Browser(location) => location.init(base),
+            Self::Hash(location) => location.init(base),
+            Self::Memory(location) => location.init(base),
+        }
+    }
+
+    pub fn ready_to_complete(&self) {
+        match self {
+            Self::Browser(location) => location.ready_to_complete(),
+            Self::Hash(location) => location.ready_to_complete(),
+            Self::Memory(location) => location.ready_to_complete(),
+        }
+    }
+
+    pub fn complete_navigation(&self, loc: &LocationChange) {
+        match self {
+            Self::Browser(location) => location.complete_navigation(loc),
+            Self::Hash(location) => location.complete_navigation(loc),
+            Self::Memory(location) => location.complete_navigation(loc),
+        }
+    }
+
+    pub fn is_back(&self) -> ReadSignal<bool> {
+        match self {
+            Self::Browser(location) => location.is_back(),
+            Self::Hash(location) => location.is_back(),
+            Self::Memory(location) => location.is_back(),
+        }
+    }
+}
+
 #[derive(Debug, Clone, Default)]
 pub struct State(Option<SendWrapper<JsValue>>);
 
@@ -0,0 +1,292 @@
+use super::{ParamSegment, PartialPathMatch, PathSegment, PossibleRouteMatch};
+use crate::location::Url;
+use std::{
+    any::TypeId, borrow::Cow, fmt::Debug, marker::PhantomData, str::FromStr,
+};
+
+/// A condition on the value of a [`ConstrainedParamSegment`].
+pub trait ParamConstraint {
+    /// Whether the value of the param, percent-decoded, satisfies the constraint.
+    fn accepts(&self, value: &str) -> bool;
+
+    /// A regular expression that matches at least every value that is accepted, used for the
+    /// routes registered by the server integrations that support it.
+    fn pattern(&self) -> Option<Cow<'static, str>>;
+}
+
+/// A segment that captures a value from the url like a [`ParamSegment`], but only matches if the
+/// value satisfies a [`ParamConstraint`].
+///
+/// When the constraint is not satisfied, the route does not match, so the next route is tried.
+///
+/// ```rust
+/// # (|| -> Option<()> { // Option does not impl Terminate, so no main
+/// use leptos::prelude::*;
+/// use leptos_router::{
+///     path, OneOf, ParamSegment, ParsesAs, PossibleRouteMatch, StaticSegment,
+/// };
+///
+/// // Manual definition
+/// let manual = (StaticSegment("posts"), ParamSegment("id").parses::<u64>());
+/// assert!(manual.test("/posts/42").is_some());
+/// assert!(manual.test("/posts/new").is_none());
+///
+/// // Macro definition
+/// let using_macro = path!("/posts/:id<u64>");
+/// assert_eq!(using_macro, manual);
+///
+/// let tab = path!("/settings/:tab{profile,billing}");
+/// assert_eq!(
+///     tab,
+///     (
+///         StaticSegment("settings"),
+///         ParamSegment("tab").one_of(&["profile", "billing"])
+///     )
+/// );
+/// assert!(tab.test("/settings/billing").is_some());
+/// assert!(tab.test("/settings/admin").is_none());
+/// # Some(())
+/// # })().unwrap();
+/// ```
+///
+/// With the `regex` feature, `path!("/posts/:slug([a-z-]+)")` constrains the param with
+/// [`MatchesRegex`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct ConstrainedParamSegment<C>(pub &'static str, pub C);
+
+impl ParamSegment {
+    /// Only matches values that can be parsed as a `T`.
+    pub const fn parses<T>(self) -> ConstrainedParamSegment<ParsesAs<T>> {
+        ConstrainedParamSegment(self.0, ParsesAs::new())
+    }
+
+    /// Only matches one of the given values.
+    pub const fn one_of(
+        self,
+        values: &'static [&'static str],
+    ) -> ConstrainedParamSegment<OneOf> {
+        ConstrainedParamSegment(self.0, OneOf(values))
+    }
+
+    /// Only matches values that fully match the regular expression.
+    #[cfg(feature = "regex")]
+    pub const fn matches_regex(
+        self,
+        regex: &'static str,
+    ) -> ConstrainedParamSegment<MatchesRegex> {
+        ConstrainedParamSegment(self.0, MatchesRegex(regex))
+    }
+}
+
+impl<C> PossibleRouteMatch for ConstrainedParamSegment<C>
+where
+    C: ParamConstraint,
+{
+    fn optional(&self) -> bool {
+        false
+    }
+
+    fn test<'a>(&self, path: &'a str) -> Option<PartialPathMatch<'a>> {
+        let matched = ParamSegment(self.0).test(path)?;
+        let value = Url::unescape(matched.matched().trim_start_matches('/'));
+        self.1.accepts(&value).then_some(matched)
+    }
+
+    fn generate_path(&self, path: &mut Vec<PathSegment>) {
+        path.push(match self.1.pattern() {
+            Some(pattern) => {
+                PathSegment::ConstrainedParam(self.0.into(), pattern)
+            }
+            None => PathSegment::Param(self.0.into()),
+        });
+    }
+}
+
+/// A [`ParamConstraint`] that accepts values that can be parsed as a `T`.
+pub struct ParsesAs<T>(PhantomData<fn() -> T>);
+
+impl<T> ParsesAs<T> {
+    /// Creates the constraint.
+    pub const fn new() -> Self {
+        Self(PhantomData)
+    }
+}
+
+impl<T> Default for ParsesAs<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> Clone for ParsesAs<T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for ParsesAs<T> {}
+
+impl<T> PartialEq for ParsesAs<T> {
+    fn eq(&self, _other: &Self) -> bool {
+        true
+    }
+}
+
+impl<T> Eq for ParsesAs<T> {}
+
+impl<T> std::hash::Hash for ParsesAs<T> {
+    fn hash<H: std::hash::Hasher>(&self, _state: &mut H) {}
+}
+
+impl<T> Debug for ParsesAs<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "ParsesAs<{}>", std::any::type_name::<T>())
+    }
+}
+
+impl<T> ParamConstraint for ParsesAs<T>
+where
+    T: FromStr + 'static,
+{
+    fn accepts(&self, value: &str) -> bool {
+        value.parse::<T>().is_ok()
+    }
+
+    fn pattern(&self) -> Option<Cow<'static, str>> {
+        let ty = TypeId::of::<T>();
+        let unsigned = [
+            TypeId::of::<u8>(),
+            TypeId::of::<u16>(),
+            TypeId::of::<u32>(),
+            TypeId::of::<u64>(),
+            TypeId::of::<u128>(),
+            TypeId::of::<usize>(),
+        ];
+        let signed = [
+            TypeId::of::<i8>(),
+            TypeId::of::<i16>(),
+            TypeId::of::<i32>(),
+            TypeId::of::<i64>(),
+            TypeId::of::<i128>(),
+            TypeId::of::<isize>(),
+        ];
+        // `parse` accepts a leading `+`, and rejects values that are out of range when the
+        // app's routes are matched
+        if unsigned.contains(&ty) {
+            Some(r"\+?[0-9]+".into())
+        } else if signed.contains(&ty) {
+            Some("[+-]?[0-9]+".into())
+        } else {
+            None
+        }
+    }
+}
+
+/// A [`ParamConstraint`] that accepts one of a set of values.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct OneOf(pub &'static [&'static str]);
+
+impl ParamConstraint for OneOf {
+    fn accepts(&self, value: &str) -> bool {
+        self.0.contains(&value)
+    }
+
+    fn pattern(&self) -> Option<Cow<'static, str>> {
+        let escaped = self.0.iter().map(|value| {
+            value
+                .chars()
+                .flat_map(|c| {
+                    let escape = r"\.+*?()|[]{}^$#&-~".contains(c);
+                    escape.then_some('\\').into_iter().chain([c])
+                })
+                .collect::<String>()
+        });
+        Some(escaped.collect::<Vec<_>>().join("|").into())
+    }
+}
+
+/// A [`ParamConstraint`] that accepts values that fully match a regular expression.
+#[cfg(feature = "regex")]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct MatchesRegex(pub &'static str);
+
+#[cfg(feature = "regex")]
+impl ParamConstraint for MatchesRegex {
+    fn accepts(&self, value: &str) -> bool {
+        use or_poisoned::OrPoisoned;
+        use regex_lite::Regex;
+        use std::{
+            collections::HashMap,
+            sync::{Arc, LazyLock, Mutex},
+        };
+
+        // each regex is compiled once, the first time a path is matched against it
+        type Compiled = HashMap<&'static str, Option<Arc<Regex>>>;
+        static COMPILED: LazyLock<Mutex<Compiled>> =
+            LazyLock::new(Default::default);
+
+        let regex = COMPILED
+            .lock()
+            .or_poisoned()
+            .entry(self.0)
+            .or_insert_with(|| {
+                let regex = Regex::new(&format!("^(?:{})$", self.0));
+                #[cfg(feature = "tracing")]
+                if let Err(e) = &regex {
+                    tracing::error!("invalid regex in path segment: {e}");
+                }
+                regex.ok().map(Arc::new)
+            })
+            .clone();
+        regex.is_some_and(|regex| regex.is_match(value))
+    }
+
+    fn pattern(&self) -> Option<Cow<'static, str>> {
+        Some(self.0.into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{OneOf, ParamConstraint, ParsesAs};
+    use crate::{ParamSegment, PathSegment, PossibleRouteMatch};
+
+    #[test]
+    fn constrained_params_match_only_accepted_values() {
+        let id = ParamSegment("id").parses::<u64>();
+        let matched = id.test("/42/edit").expect("should match a number");
+        assert_eq!(matched.remaining(), "/edit");
+        assert_eq!(matched.params(), vec![("id".into(), "42".into())]);
+        assert!(id.test("/new").is_none());
+        assert!(id.test("/-1").is_none());
+        // the value is percent-decoded first
+        assert!(id.test("/%34%32").is_some());
+        assert!(id.test("/+5").is_some());
+
+        let tab = ParamSegment("tab").one_of(&["a", "b"]);
+        assert!(tab.test("/a").is_some());
+        assert!(tab.test("/c").is_none());
+    }
+
+    #[test]
+    fn constrained_params_generate_patterns() {
+        let mut path = Vec::new();
+        ParamSegment("id").parses::<i32>().generate_path(&mut path);
+        ParamSegment("name")
+            .parses::<String>()
+            .generate_path(&mut path);
+        assert_eq!(
+            path,
+            vec![
+                PathSegment::ConstrainedParam(
+                    "id".into(),
+                    "[+-]?[0-9]+".into()
+                ),
+                PathSegment::Param("name".into())
+            ]
+        );
+        assert_eq!(OneOf(&["a.b", "c"]).pattern().as_deref(), Some(r"a\.b|c"));
+        assert!(ParsesAs::<u8>::new().accepts("255"));
+        assert!(!ParsesAs::<u8>::new().accepts("256"));
+    }
+}
@@ -0,0 +1,63 @@
+use super::{
+    MatchContext, PartialPathMatch, PathSegment, PossibleRouteMatch,
+    StaticSegment,
+};
+
+/// A static segment that is translated into some locales, for a router that is localized with
+/// [`Locales`](crate::Locales).
+///
+/// The first field is the segment in the default locale, and in any locale without a
+/// translation. The second field lists the translations by locale.
+///
+/// ```rust
+/// use leptos_router::{LocalizedSegment, ParamSegment};
+///
+/// const PRODUCTS: LocalizedSegment =
+///     LocalizedSegment("products", &[("de", "produkte"), ("fr", "produits")]);
+///
+/// // matches `/products/1`, `/de/produkte/1` and `/fr/produits/1`
+/// let _path = (PRODUCTS, ParamSegment("id"));
+///
+/// assert_eq!(PRODUCTS.for_locale("de"), "produkte");
+/// assert_eq!(PRODUCTS.for_locale("en"), "products");
+/// ```
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct LocalizedSegment(
+    pub &'static str,
+    pub &'static [(&'static str, &'static str)],
+);
+
+impl LocalizedSegment {
+    /// The segment in the given locale.
+    pub fn for_locale(&self, locale: &str) -> &'static str {
+        self.1
+            .iter()
+            .find(|(l, _)| *l == locale)
+            .map(|(_, segment)| *segment)
+            .unwrap_or(self.0)
+    }
+}
+
+impl PossibleRouteMatch for LocalizedSegment {
+    fn optional(&self) -> bool {
+        false
+    }
+
+    fn test<'a>(&self, path: &'a str) -> Option<PartialPathMatch<'a>> {
+        StaticSegment(self.0).test(path)
+    }
+
+    fn test_with<'a>(
+        &self,
+        path: &'a str,
+        cx: &MatchContext<'_>,
+    ) -> Option<PartialPathMatch<'a>> {
+        let segment =
+            cx.locale().map_or(self.0, |locale| self.for_locale(locale));
+        StaticSegment(segment).test_with(path, cx)
+    }
+
+    fn generate_path(&self, path: &mut Vec<PathSegment>) {
+        path.push(PathSegment::Localized(*self));
+    }
+}
@@ -1,8 +1,12 @@
-use super::{PartialPathMatch, PathSegment};
+use super::{MatchContext, PartialPathMatch, PathSegment};
 use std::sync::Arc;
+mod constrained_segments;
+mod localized_segment;
 mod param_segments;
 mod static_segment;
 mod tuples;
+pub use constrained_segments::*;
+pub use localized_segment::*;
 pub use param_segments::*;
 pub use static_segment::*;
 
@@ -32,6 +36,19 @@ pub trait PossibleRouteMatch {
     ///    tested can detect wherever they are matching from the beginning of the given path segment.
     fn test<'a>(&self, path: &'a str) -> Option<PartialPathMatch<'a>>;
 
+    /// Checks if this segment matches the beginning of the path, like [`test`](Self::test), with
+    /// the state of the router that it is matched in.
+    ///
+    /// By default, this ignores the context. Segments that match differently depending on the
+    /// trailing slash mode or the locale, and tuples of segments, override this.
+    fn test_with<'a>(
+        &self,
+        path: &'a str,
+        _cx: &MatchContext<'_>,
+    ) -> Option<PartialPathMatch<'a>> {
+        self.test(path)
+    }
+
     fn generate_path(&self, path: &mut Vec<PathSegment>);
 }
 
@@ -44,6 +61,14 @@ impl PossibleRouteMatch for Box<dyn PossibleRouteMatch + Send + Sync> {
         (**self).test(path)
     }
 
+    fn test_with<'a>(
+        &self,
+        path: &'a str,
+        cx: &MatchContext<'_>,
+    ) -> Option<PartialPathMatch<'a>> {
+        (**self).test_with(path, cx)
+    }
+
     fn generate_path(&self, path: &mut Vec<PathSegment>) {
         (**self).generate_path(path);
     }
@@ -58,6 +83,14 @@ impl PossibleRouteMatch for Arc<dyn PossibleRouteMatch + Send + Sync> {
         (**self).test(path)
     }
 
+    fn test_with<'a>(
+        &self,
+        path: &'a str,
+        cx: &MatchContext<'_>,
+    ) -> Option<PartialPathMatch<'a>> {
+        (**self).test_with(path, cx)
+    }
+
     fn generate_path(&self, path: &mut Vec<PathSegment>) {
         (**self).generate_path(path);
     }
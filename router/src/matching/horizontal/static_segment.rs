@@ -1,4 +1,4 @@
-use super::{PartialPathMatch, PathSegment, PossibleRouteMatch};
+use super::{MatchContext, PartialPathMatch, PathSegment, PossibleRouteMatch};
 use std::fmt::Debug;
 
 impl PossibleRouteMatch for () {
@@ -62,6 +62,23 @@ impl<T: AsPath> PossibleRouteMatch for StaticSegment<T> {
         false
     }
 
+    fn test_with<'a>(
+        &self,
+        path: &'a str,
+        cx: &MatchContext<'_>,
+    ) -> Option<PartialPathMatch<'a>> {
+        // a trailing slash has to be in the path when matching it exactly, and is consumed so
+        // that the path is fully matched
+        if self.0.as_path() == "/" && cx.strict_trailing_slash() {
+            match path {
+                "" => return None,
+                "/" => return Some(PartialPathMatch::new("", vec![], "/")),
+                _ => {}
+            }
+        }
+        self.test(path)
+    }
+
     fn test<'a>(&self, path: &'a str) -> Option<PartialPathMatch<'a>> {
         let mut matched_len = 0;
         let mut test = path.chars().peekable();
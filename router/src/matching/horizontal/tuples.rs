@@ -1,4 +1,4 @@
-use super::{PartialPathMatch, PathSegment, PossibleRouteMatch};
+use super::{MatchContext, PartialPathMatch, PathSegment, PossibleRouteMatch};
 
 macro_rules! tuples {
     ($first:ident => $($ty:ident),*) => {
@@ -14,6 +14,14 @@ macro_rules! tuples {
             }
 
             fn test<'a>(&self, path: &'a str) -> Option<PartialPathMatch<'a>> {
+                self.test_with(path, &MatchContext::default())
+            }
+
+            fn test_with<'a>(
+                &self,
+                path: &'a str,
+                cx: &MatchContext<'_>,
+            ) -> Option<PartialPathMatch<'a>> {
                 #[allow(non_snake_case)]
                 let ($first, $($ty,)*) = &self;
 
@@ -34,7 +42,7 @@ macro_rules! tuples {
                         nth_field += 1;
                     }
                     if !$first.optional() || nth_field <= include_optionals {
-                        match $first.test(r) {
+                        match $first.test_with(r, cx) {
                             None => {
                                 return None;
                             },
@@ -56,7 +64,7 @@ macro_rules! tuples {
                                 remaining,
                                 matched,
                                 params
-                            } = match $ty.test(r) {
+                            } = match $ty.test_with(r, cx) {
                                 None => if $ty.optional() {
                                     return None;
                                 } else {
@@ -103,12 +111,20 @@ where
     }
 
     fn test<'a>(&self, path: &'a str) -> Option<PartialPathMatch<'a>> {
+        self.test_with(path, &MatchContext::default())
+    }
+
+    fn test_with<'a>(
+        &self,
+        path: &'a str,
+        cx: &MatchContext<'_>,
+    ) -> Option<PartialPathMatch<'a>> {
         let remaining = path;
         let PartialPathMatch {
             remaining,
             matched,
             params,
-        } = self.0.test(remaining)?;
+        } = self.0.test_with(remaining, cx)?;
         Some(PartialPathMatch {
             remaining,
             matched: &path[0..matched.len()],
@@ -0,0 +1,113 @@
+/// The locales that a [`<Router/>`](crate::components::Router) is localized into.
+///
+/// Each locale is a prefix of the paths of the routes, e.g. `/de/produkte`, and a path without
+/// a locale prefix falls back to the default locale. Static segments of the routes can be
+/// translated with a [`LocalizedSegment`](crate::LocalizedSegment), so that each route is only
+/// declared once.
+///
+/// ```rust
+/// # use leptos::prelude::*;
+/// # use leptos_router::{components::*, path, Locales, LocalizedSegment};
+/// # fn app() -> impl IntoView {
+/// view! {
+///     // serves `/en/products`, `/de/produkte`, and `/products` in English
+///     <Router locales=Locales::new(["en", "de"])>
+///         <Routes fallback=|| "Not found.">
+///             <Route
+///                 path=(LocalizedSegment("products", &[("de", "produkte")]),)
+///                 view=|| "Products"
+///             />
+///         </Routes>
+///     </Router>
+/// }
+/// # }
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Locales {
+    locales: Vec<&'static str>,
+    default: &'static str,
+    prefix_default: bool,
+}
+
+impl Locales {
+    /// Creates the set of locales, with the first one as the default locale.
+    ///
+    /// # Panics
+    /// Panics if there are no locales.
+    pub fn new(locales: impl IntoIterator<Item = &'static str>) -> Self {
+        let locales = locales.into_iter().collect::<Vec<_>>();
+        let default = *locales.first().expect("there should be a locale");
+        Self {
+            locales,
+            default,
+            prefix_default: true,
+        }
+    }
+
+    /// Sets the locale that is used for paths without a locale prefix.
+    ///
+    /// # Panics
+    /// Panics if the locale is not one of the locales.
+    pub fn with_default(mut self, locale: &str) -> Self {
+        self.default = self
+            .find(locale)
+            .expect("the default locale should be one of the locales");
+        self
+    }
+
+    /// Sets whether paths in the default locale also have a prefix. If they do (the default),
+    /// the default locale is served both with and without its prefix; if they do not, only the
+    /// paths without a prefix are served in the default locale.
+    pub fn with_prefixed_default(mut self, prefix_default: bool) -> Self {
+        self.prefix_default = prefix_default;
+        self
+    }
+
+    /// The locales, in the order they were given.
+    pub fn locales(&self) -> &[&'static str] {
+        &self.locales
+    }
+
+    /// The locale that is used for paths without a locale prefix.
+    pub fn default_locale(&self) -> &'static str {
+        self.default
+    }
+
+    /// The locale with the given code, if it is one of the locales.
+    pub fn find(&self, locale: &str) -> Option<&'static str> {
+        self.locales.iter().find(|l| **l == locale).copied()
+    }
+
+    /// Whether paths in the locale start with a prefix.
+    pub fn is_prefixed(&self, locale: &str) -> bool {
+        self.prefix_default || locale != self.default
+    }
+
+    /// The prefix of paths in the locale, e.g. `/de`, or an empty string if it has none.
+    pub fn prefix(&self, locale: &str) -> String {
+        if self.is_prefixed(locale) {
+            format!("/{locale}")
+        } else {
+            String::new()
+        }
+    }
+
+    /// Splits a path into its locale, its locale prefix, and the rest of the path.
+    pub(crate) fn split_path<'a>(
+        &self,
+        path: &'a str,
+    ) -> (&'static str, &'a str, &'a str) {
+        let first = path.trim_start_matches('/').split('/').next();
+        let locale = first
+            .and_then(|first| self.find(first))
+            .filter(|locale| self.is_prefixed(locale));
+        match locale {
+            Some(locale) => {
+                let len = path.find(locale).unwrap_or_default() + locale.len();
+                let (prefix, rest) = path.split_at(len);
+                (locale, prefix, if rest.is_empty() { "/" } else { rest })
+            }
+            None => (self.default, "", path),
+        }
+    }
+}
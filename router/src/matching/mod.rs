@@ -2,23 +2,128 @@
 
 mod any_choose_view;
 mod choose_view;
+mod locales;
 mod path_segment;
 pub(crate) mod resolve_path;
 pub use choose_view::*;
+pub use locales::*;
 pub use path_segment::*;
 mod horizontal;
 mod nested;
 mod vertical;
-use crate::{static_routes::RegenerationFn, Method, SsrMode};
+use crate::{
+    params::ParamsMap, static_routes::RegenerationFn, Method, RouteData,
+    RouteLoader, SsrMode,
+};
+use either_of::Either;
 pub use horizontal::*;
 pub use nested::*;
-use std::{borrow::Cow, collections::HashSet, sync::atomic::Ordering};
+use std::{
+    borrow::Cow,
+    collections::HashSet,
+    sync::{atomic::Ordering, OnceLock},
+};
 pub use vertical::*;
 
+/// How a trailing slash at the end of the URL is matched against the paths of routes.
+///
+/// This is set for all routes with the `trailing_slash` prop of the
+/// [`<Router/>`](crate::components::Router), and applies to matching routes in the browser,
+/// to the active state of [`<A/>`](crate::components::A) links, and to the routes registered by
+/// the server integrations.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum TrailingSlash {
+    /// Trailing slashes are ignored in the browser: `/about` and `/about/` both match a route
+    /// defined as either of them. The server integrations only register each path as it is
+    /// written.
+    #[default]
+    Drop,
+    /// Routes only match the URL with the path as it is written: a route defined as `/about/`
+    /// does not match `/about`, and a route defined as `/about` does not match `/about/`.
+    Exact,
+    /// Like [`TrailingSlash::Exact`], but the other form of each path redirects to the path as
+    /// it is written, with a `308 Permanent Redirect` on the server.
+    Redirect,
+}
+
+impl TrailingSlash {
+    /// Whether the trailing slash of the URL must match the path of the route.
+    pub fn is_strict(&self) -> bool {
+        !matches!(self, TrailingSlash::Drop)
+    }
+}
+
+/// The state of the router that routes are matched with, in addition to the path.
+///
+/// This is passed down to each route by [`MatchNestedRoutes::match_nested_with`] and
+/// [`PossibleRouteMatch::test_with`]. The default context matches trailing slashes loosely and
+/// has no locale.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct MatchContext<'a> {
+    strict_trailing_slash: bool,
+    locale: Option<&'a str>,
+    outlet_paths: &'a [(&'static str, String)],
+    target_outlet: Option<&'static str>,
+}
+
+impl MatchContext<'_> {
+    /// Whether the trailing slash of the URL must match the paths of the routes.
+    pub fn strict_trailing_slash(&self) -> bool {
+        self.strict_trailing_slash
+    }
+
+    /// The locale of the path that is being matched, if the router is localized.
+    pub fn locale(&self) -> Option<&str> {
+        self.locale
+    }
+
+    /// The named outlet that routes are being matched for, or `None` for the default outlet.
+    pub fn target_outlet(&self) -> Option<&'static str> {
+        self.target_outlet
+    }
+
+    /// Whether the URL has the path of any named outlet.
+    pub fn has_outlet_paths(&self) -> bool {
+        !self.outlet_paths.is_empty()
+    }
+
+    /// The path of a named outlet in the URL, if it has one.
+    pub fn outlet_path(&self, name: &str) -> Option<&str> {
+        self.outlet_paths
+            .iter()
+            .find(|(outlet, _)| *outlet == name)
+            .map(|(_, path)| path.as_str())
+    }
+}
+
+/// The path with the router's base removed, or `None` if it does not start with the base.
+pub(crate) fn strip_base<'a>(
+    base: Option<&str>,
+    path: &'a str,
+) -> Option<&'a str> {
+    match base {
+        None => Some(path),
+        Some(base) => {
+            let (base, path) = if base.starts_with('/') {
+                (base.trim_start_matches('/'), path.trim_start_matches('/'))
+            } else {
+                (base, path)
+            };
+            path.strip_prefix(base)
+        }
+    }
+}
+
 #[derive(Debug)]
 pub struct RouteDefs<Children> {
     base: Option<Cow<'static, str>>,
+    trailing_slash: TrailingSlash,
+    locales: Option<Locales>,
     children: Children,
+    /// The segments of each route, generated once when a path is first localized.
+    segments: OnceLock<Vec<Vec<PathSegment>>>,
+    /// The names of the named outlets of the routes, found once when they are first needed.
+    outlet_names: OnceLock<Vec<&'static str>>,
 }
 
 impl<Children> Clone for RouteDefs<Children>
@@ -28,7 +133,11 @@ where
     fn clone(&self) -> Self {
         Self {
             base: self.base.clone(),
+            trailing_slash: self.trailing_slash,
+            locales: self.locales.clone(),
             children: self.children.clone(),
+            segments: self.segments.clone(),
+            outlet_names: self.outlet_names.clone(),
         }
     }
 }
@@ -37,7 +146,11 @@ impl<Children> RouteDefs<Children> {
     pub fn new(children: Children) -> Self {
         Self {
             base: None,
+            trailing_slash: TrailingSlash::default(),
+            locales: None,
             children,
+            segments: OnceLock::new(),
+            outlet_names: OnceLock::new(),
         }
     }
 
@@ -47,9 +160,38 @@ impl<Children> RouteDefs<Children> {
     ) -> Self {
         Self {
             base: Some(base.into()),
+            trailing_slash: TrailingSlash::default(),
+            locales: None,
             children,
+            segments: OnceLock::new(),
+            outlet_names: OnceLock::new(),
         }
     }
+
+    /// Sets how a trailing slash in the URL is matched against the paths of the routes.
+    pub fn with_trailing_slash(
+        mut self,
+        trailing_slash: TrailingSlash,
+    ) -> Self {
+        self.trailing_slash = trailing_slash;
+        self
+    }
+
+    /// How a trailing slash in the URL is matched against the paths of the routes.
+    pub fn trailing_slash(&self) -> TrailingSlash {
+        self.trailing_slash
+    }
+
+    /// Sets the locales that prefix the paths of the routes, if they are localized.
+    pub fn with_locales(mut self, locales: Option<Locales>) -> Self {
+        self.locales = locales;
+        self
+    }
+
+    /// The locales that prefix the paths of the routes, if they are localized.
+    pub fn locales(&self) -> Option<&Locales> {
+        self.locales.as_ref()
+    }
 }
 
 impl<Children> RouteDefs<Children>
@@ -57,36 +199,265 @@ where
     Children: MatchNestedRoutes,
 {
     pub fn match_route(&self, path: &str) -> Option<Children::Match> {
-        let path = match &self.base {
-            None => path,
-            Some(base) => {
-                let (base, path) = if base.starts_with('/') {
-                    (base.trim_start_matches('/'), path.trim_start_matches('/'))
-                } else {
-                    (base.as_ref(), path)
-                };
-                path.strip_prefix(base)?
+        self.match_route_in(path, &[])
+    }
+
+    /// Matches a path like [`match_route`](Self::match_route), along with the branches of the
+    /// named outlets of the matched routes. The path of each named outlet is read from the query
+    /// param with its name, e.g. `/dashboard?sidebar=/users/1`.
+    pub fn match_route_with_outlets(
+        &self,
+        path: &str,
+        query: &ParamsMap,
+    ) -> Option<Children::Match> {
+        self.match_route_in(path, &self.outlet_paths(query))
+    }
+
+    /// The paths of the named outlets in the query, by the name of the outlet.
+    ///
+    /// Only the query params named after one of the [named outlets](Self::outlet_names) are
+    /// read, and only if their value is a path, i.e., starts with `/`.
+    pub fn outlet_paths(
+        &self,
+        query: &ParamsMap,
+    ) -> Vec<(&'static str, String)> {
+        self.outlet_names()
+            .iter()
+            .filter_map(|&name| {
+                let path = query.get(name)?;
+                path.starts_with('/').then_some((name, path))
+            })
+            .collect()
+    }
+
+    /// The names of the named outlets that any of the routes are rendered in.
+    pub fn outlet_names(&self) -> &[&'static str] {
+        self.outlet_names.get_or_init(|| {
+            let mut names = Vec::new();
+            self.children.outlet_names(&mut names);
+            names
+        })
+    }
+
+    fn match_route_in(
+        &self,
+        path: &str,
+        outlet_paths: &[(&'static str, String)],
+    ) -> Option<Children::Match> {
+        let path = strip_base(self.base.as_deref(), path)?;
+        let (locale, path) = match &self.locales {
+            None => (None, path),
+            Some(locales) => {
+                let (locale, _, path) = locales.split_path(path);
+                (Some(locale), path)
             }
         };
 
-        let (matched, remaining) = self.children.match_nested(path);
+        let cx = MatchContext {
+            strict_trailing_slash: self.trailing_slash.is_strict(),
+            locale,
+            outlet_paths,
+            target_outlet: None,
+        };
+        self.match_children(path, &cx).or_else(|| {
+            // a route written with a trailing slash also matches the path without it
+            (!cx.strict_trailing_slash
+                && !path.is_empty()
+                && !path.ends_with('/'))
+            .then(|| self.match_children(&format!("{path}/"), &cx))
+            .flatten()
+        })
+    }
+
+    fn match_children(
+        &self,
+        path: &str,
+        cx: &MatchContext<'_>,
+    ) -> Option<Children::Match> {
+        let (matched, remaining) = self.children.match_nested_with(path, cx);
         let matched = matched?;
 
-        if !(remaining.is_empty() || remaining == "/") {
-            None
-        } else {
+        if remaining.is_empty()
+            || (remaining == "/" && (!cx.strict_trailing_slash || path == "/"))
+        {
             Some(matched.1)
+        } else {
+            None
+        }
+    }
+
+    /// The path that a URL with a trailing slash that does not match its route should redirect
+    /// to, when using [`TrailingSlash::Redirect`].
+    pub fn redirect_trailing_slash(&self, path: &str) -> Option<String> {
+        if self.trailing_slash != TrailingSlash::Redirect
+            || path == "/"
+            || self.match_route(path).is_some()
+        {
+            return None;
+        }
+        let redirect = match path.strip_suffix('/') {
+            Some(path) => path.to_string(),
+            None => format!("{path}/"),
+        };
+        self.match_route(&redirect).map(|_| redirect)
+    }
+
+    /// The part of the path after the router's base that is its locale prefix, e.g. `/de`, or an
+    /// empty string if it has none.
+    pub fn locale_prefix<'a>(&self, path: &'a str) -> &'a str {
+        match (&self.locales, strip_base(self.base.as_deref(), path)) {
+            (Some(locales), Some(path)) => locales.split_path(path).1,
+            _ => "",
         }
     }
 
+    /// Generates the routes, with one route for each locale that the router is localized into.
+    ///
+    /// The routes of the default locale are also generated without a prefix, as a path without
+    /// a locale prefix falls back to the default locale.
     pub fn generate_routes(
         &self,
     ) -> (
         Option<&str>,
         impl IntoIterator<Item = GeneratedRouteData> + '_,
     ) {
-        (self.base.as_deref(), self.children.generate_routes())
+        let generated = self.children.generate_routes().into_iter();
+        let routes = match &self.locales {
+            None => Either::Left(generated.map(|mut route| {
+                route.segments = localized_segments(&route.segments, None);
+                route
+            })),
+            Some(locales) => {
+                let generated = generated.collect::<Vec<_>>();
+                let mut routes = Vec::new();
+                for &locale in locales.locales() {
+                    let prefixed = locales
+                        .is_prefixed(locale)
+                        .then(|| Some(PathSegment::Static(locale.into())));
+                    let fallback =
+                        (locale == locales.default_locale()).then_some(None);
+                    for prefix in prefixed.into_iter().chain(fallback) {
+                        routes.extend(generated.iter().map(|route| {
+                            let segments = localized_segments(
+                                &route.segments,
+                                Some(locale),
+                            );
+                            GeneratedRouteData {
+                                segments: prefix
+                                    .clone()
+                                    .into_iter()
+                                    .chain(segments)
+                                    .collect(),
+                                ..route.clone()
+                            }
+                        }));
+                    }
+                }
+                Either::Right(routes.into_iter())
+            }
+        };
+        (self.base.as_deref(), routes)
+    }
+
+    /// Translates a path that matches one of the routes into another locale, given the params
+    /// it matched. With no locale, the path is translated into the default locale without a
+    /// prefix, which is the `x-default` alternate of the path.
+    pub fn localize_path(
+        &self,
+        path: &str,
+        params: &ParamsMap,
+        locale: Option<&str>,
+    ) -> Option<String> {
+        let locales = self.locales.as_ref()?;
+        let to = match locale {
+            Some(locale) => locales.find(locale)?,
+            None => locales.default_locale(),
+        };
+        let rest = strip_base(self.base.as_deref(), path)?;
+        let base = &path[..path.len() - rest.len()];
+        let (from, _, rest) = locales.split_path(rest);
+        let (rest, suffix) =
+            rest.split_at(rest.find(['?', '#']).unwrap_or(rest.len()));
+
+        let routes = self.segments.get_or_init(|| {
+            self.children
+                .generate_routes()
+                .into_iter()
+                .map(|route| route.segments)
+                .collect()
+        });
+        let route = routes.iter().find(|segments| {
+            fill_path(segments, params, from).is_some_and(|filled| {
+                filled.trim_end_matches('/') == rest.trim_end_matches('/')
+            })
+        })?;
+        let prefix = match locale {
+            Some(_) => locales.prefix(to),
+            None => String::new(),
+        };
+        let filled = fill_path(route, params, to)?;
+        // the root of a locale is its prefix, e.g. `/de` rather than `/de/`
+        let path = match (prefix.is_empty(), filled.as_str()) {
+            (true, "") => "/".to_string(),
+            (false, "/") => prefix,
+            _ => format!("{prefix}{filled}"),
+        };
+        Some(format!("{base}{path}{suffix}"))
+    }
+}
+
+/// The segments with each localized segment replaced by the static segment in the given locale,
+/// or in its default locale.
+fn localized_segments(
+    segments: &[PathSegment],
+    locale: Option<&str>,
+) -> Vec<PathSegment> {
+    segments
+        .iter()
+        .map(|segment| match segment {
+            PathSegment::Localized(localized) => PathSegment::Static(
+                locale
+                    .map_or(localized.0, |locale| localized.for_locale(locale))
+                    .into(),
+            ),
+            segment => segment.clone(),
+        })
+        .collect()
+}
+
+/// The path of a route in the given locale with its params filled in, or `None` if a param is
+/// missing.
+fn fill_path(
+    segments: &[PathSegment],
+    params: &ParamsMap,
+    locale: &str,
+) -> Option<String> {
+    let mut path = String::new();
+    for segment in localized_segments(segments, Some(locale)) {
+        match &segment {
+            PathSegment::Unit => {}
+            PathSegment::Static(_) | PathSegment::Localized(_) => {
+                let s = segment.as_raw_str();
+                if !s.is_empty() && !s.starts_with('/') {
+                    path.push('/');
+                }
+                path.push_str(s);
+            }
+            PathSegment::Param(name)
+            | PathSegment::ConstrainedParam(name, _)
+            | PathSegment::Splat(name) => {
+                path.push('/');
+                path.push_str(params.get_str(name)?);
+            }
+            PathSegment::OptionalParam(name) => {
+                if let Some(value) = params.get_str(name) {
+                    path.push('/');
+                    path.push_str(value);
+                }
+            }
+        }
     }
+    Some(path)
 }
 
 #[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
@@ -109,7 +480,23 @@ pub trait MatchInterface {
 
     fn as_matched(&self) -> &str;
 
+    /// The loader of the matched route, if it has one.
+    fn as_loader(&self) -> Option<&RouteLoader> {
+        None
+    }
+
+    /// The data attached to the matched route, if it has any.
+    fn as_route_data(&self) -> Option<&RouteData> {
+        None
+    }
+
     fn into_view_and_child(self) -> (impl ChooseView, Option<Self::Child>);
+
+    /// Takes the matches of the named outlets of the matched route, with the names of the
+    /// outlets.
+    fn take_named_children(&mut self) -> Vec<(&'static str, Self::Child)> {
+        Vec::new()
+    }
 }
 
 pub trait MatchParams {
@@ -139,27 +526,62 @@ pub trait MatchNestedRoutes {
         path: &'a str,
     ) -> (Option<(RouteMatchId, Self::Match)>, &'a str);
 
+    /// Matches nested routes like [`match_nested`](Self::match_nested), with the state of the
+    /// router that they are matched in.
+    ///
+    /// By default, this ignores the context. Routes that contain other routes or path segments
+    /// override this to pass it on to them.
+    fn match_nested_with<'a>(
+        &'a self,
+        path: &'a str,
+        _cx: &MatchContext<'_>,
+    ) -> (Option<(RouteMatchId, Self::Match)>, &'a str) {
+        self.match_nested(path)
+    }
+
     fn generate_routes(
         &self,
     ) -> impl IntoIterator<Item = GeneratedRouteData> + '_;
 
     fn optional(&self) -> bool;
+
+    /// Adds the names of the named outlets that these routes, or any of their children, are
+    /// rendered in.
+    fn outlet_names(&self, _names: &mut Vec<&'static str>) {}
 }
 
-#[derive(Default, Debug, PartialEq)]
+#[derive(Default, Debug, Clone, PartialEq)]
 pub struct GeneratedRouteData {
     pub segments: Vec<PathSegment>,
     pub ssr_mode: SsrMode,
     pub methods: HashSet<Method>,
     pub regenerate: Vec<RegenerationFn>,
+    pub(crate) data: Vec<RouteData>,
+    pub(crate) ids: Vec<RouteMatchId>,
+}
+
+impl GeneratedRouteData {
+    /// The [data](RouteData) attached to the route and its parents, outermost first.
+    pub fn data(&self) -> &[RouteData] {
+        &self.data
+    }
+
+    /// The ids of the route and its parents, outermost first, which are the ids of their matches
+    /// when the route matches a path.
+    pub fn ids(&self) -> &[RouteMatchId] {
+        &self.ids
+    }
 }
 
 #[cfg(test)]
 mod tests {
-    use super::{NestedRoute, ParamSegment, RouteDefs};
+    use super::{
+        Locales, LocalizedSegment, NestedRoute, ParamSegment, RouteDefs,
+        TrailingSlash,
+    };
     use crate::{
-        matching::MatchParams, MatchInterface, PathSegment, StaticSegment,
-        WildcardSegment,
+        matching::MatchParams, params::ParamsMap, MatchInterface, PathSegment,
+        RouteLoader, StaticSegment, WildcardSegment,
     };
     use either_of::{Either, EitherOf4};
 
@@ -360,6 +782,236 @@ mod tests {
         let matched = routes.match_route("/usersid");
         assert!(matches!(matched, Some(EitherOf4::D(..))));
     }
+
+    #[test]
+    pub fn matches_carry_route_loaders() {
+        let loader = RouteLoader::new(|_| async { 0 });
+        let routes: RouteDefs<_> = RouteDefs::new(
+            NestedRoute::new(StaticSegment("users"), || ())
+                .loader(loader.clone())
+                .child((
+                    NestedRoute::new(StaticSegment(""), || ()),
+                    NestedRoute::new(ParamSegment("id"), || ())
+                        .loader(|_| async { String::new() }),
+                )),
+        );
+
+        let matched = routes.match_route("/users").unwrap();
+        assert_eq!(matched.as_loader(), Some(&loader));
+        let (_, child) = matched.into_view_and_child();
+        assert!(child.unwrap().as_loader().is_none());
+
+        let matched = routes.match_route("/users/1").unwrap();
+        let (_, child) = matched.into_view_and_child();
+        let child_loader = child.unwrap().as_loader().cloned();
+        assert!(child_loader.is_some_and(|child| child != loader));
+    }
+
+    #[test]
+    pub fn constrained_params_fall_through_to_next_route() {
+        let routes: RouteDefs<_> = RouteDefs::new((
+            NestedRoute::new(
+                (StaticSegment("posts"), ParamSegment("id").parses::<u64>()),
+                || (),
+            ),
+            NestedRoute::new(
+                (StaticSegment("posts"), ParamSegment("slug")),
+                || (),
+            ),
+        ));
+
+        let matched = routes.match_route("/posts/42");
+        assert!(matches!(matched, Some(Either::Left(..))));
+        let matched = routes.match_route("/posts/new");
+        assert!(matches!(matched, Some(Either::Right(..))));
+    }
+
+    #[test]
+    pub fn trailing_slash_modes() {
+        let routes = |mode| {
+            RouteDefs::<_>::new((
+                NestedRoute::new((), || ()),
+                NestedRoute::new(StaticSegment("about"), || ()),
+                NestedRoute::new(
+                    (StaticSegment("docs"), StaticSegment("/")),
+                    || (),
+                ),
+            ))
+            .with_trailing_slash(mode)
+        };
+
+        let drop = routes(TrailingSlash::Drop);
+        for path in ["/about", "/about/", "/docs", "/docs/"] {
+            assert!(drop.match_route(path).is_some(), "{path}");
+            assert_eq!(drop.redirect_trailing_slash(path), None);
+        }
+
+        let exact = routes(TrailingSlash::Exact);
+        assert!(exact.match_route("/").is_some());
+        assert!(exact.match_route("/about").is_some());
+        assert!(exact.match_route("/about/").is_none());
+        assert!(exact.match_route("/docs/").is_some());
+        assert!(exact.match_route("/docs").is_none());
+        assert_eq!(exact.redirect_trailing_slash("/about/"), None);
+
+        let redirect = routes(TrailingSlash::Redirect);
+        assert!(redirect.match_route("/about/").is_none());
+        assert_eq!(
+            redirect.redirect_trailing_slash("/about/").as_deref(),
+            Some("/about")
+        );
+        assert_eq!(
+            redirect.redirect_trailing_slash("/docs").as_deref(),
+            Some("/docs/")
+        );
+        assert_eq!(redirect.redirect_trailing_slash("/docs/"), None);
+        assert_eq!(redirect.redirect_trailing_slash("/missing"), None);
+    }
+
+    #[test]
+    pub fn named_outlets_match_paths_from_the_query() {
+        let routes = RouteDefs::<_>::new(
+            NestedRoute::new(StaticSegment("dashboard"), || ()).child((
+                NestedRoute::new(StaticSegment(""), || ()),
+                NestedRoute::new(StaticSegment("stats"), || ()),
+                NestedRoute::new(
+                    (StaticSegment("users"), ParamSegment("id")),
+                    || (),
+                )
+                .outlet("sidebar"),
+                NestedRoute::new(StaticSegment("help"), || ()).outlet("modal"),
+            )),
+        );
+        assert_eq!(routes.outlet_names(), ["sidebar", "modal"]);
+
+        // routes in a named outlet are not matched against the path
+        assert!(routes.match_route("/dashboard/users/1").is_none());
+
+        let query = ParamsMap::from_iter([
+            ("sidebar".to_string(), "/users/1".to_string()),
+            ("modal".to_string(), "/missing".to_string()),
+            ("page".to_string(), "/help".to_string()),
+        ]);
+        let mut matched = routes
+            .match_route_with_outlets("/dashboard/stats", &query)
+            .unwrap();
+        let named = matched.take_named_children();
+        assert_eq!(named.len(), 1);
+        let (name, sidebar) = &named[0];
+        assert_eq!(*name, "sidebar");
+        assert_eq!(sidebar.as_matched(), "/users/1");
+        assert_eq!(sidebar.to_params(), vec![("id".into(), "1".into())]);
+        let (_, child) = matched.into_view_and_child();
+        assert_eq!(child.unwrap().as_matched(), "/stats");
+
+        // without the query, only the default outlet is matched
+        let mut matched = routes.match_route("/dashboard").unwrap();
+        assert!(matched.take_named_children().is_empty());
+
+        // only the declared outlets are read from the query, and only if their value is a path
+        let query = ParamsMap::from_iter([
+            ("modal".to_string(), "help".to_string()),
+            ("page".to_string(), "/help".to_string()),
+        ]);
+        assert!(routes.outlet_paths(&query).is_empty());
+        let mut matched = routes
+            .match_route_with_outlets("/dashboard", &query)
+            .unwrap();
+        assert!(matched.take_named_children().is_empty());
+
+        let (_, generated) = routes.generate_routes();
+        let paths = generated
+            .into_iter()
+            .map(|route| route.segments)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            paths,
+            [
+                vec![
+                    PathSegment::Static("dashboard".into()),
+                    PathSegment::Static("".into())
+                ],
+                vec![
+                    PathSegment::Static("dashboard".into()),
+                    PathSegment::Static("stats".into())
+                ],
+            ]
+        );
+    }
+
+    #[test]
+    pub fn localized_routes() {
+        const PRODUCTS: LocalizedSegment =
+            LocalizedSegment("products", &[("de", "produkte")]);
+        let routes = RouteDefs::<_>::new((
+            NestedRoute::new(StaticSegment("/"), || ()),
+            NestedRoute::new((PRODUCTS, ParamSegment("id")), || ()),
+        ))
+        .with_locales(Some(Locales::new(["en", "de"])));
+
+        for path in ["/", "/de", "/en/", "/en/products/1", "/de/produkte/1"] {
+            assert!(routes.match_route(path).is_some(), "{path}");
+        }
+        assert!(routes.match_route("/products/1").is_some());
+        assert!(routes.match_route("/de/products/1").is_none());
+        assert!(routes.match_route("/en/produkte/1").is_none());
+        assert_eq!(routes.locale_prefix("/de/produkte/1"), "/de");
+        assert_eq!(routes.locale_prefix("/products/1"), "");
+
+        let (_, generated) = routes.generate_routes();
+        let paths = generated
+            .into_iter()
+            .map(|route| route.segments)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            paths,
+            [
+                vec![
+                    PathSegment::Static("en".into()),
+                    PathSegment::Static("/".into())
+                ],
+                vec![
+                    PathSegment::Static("en".into()),
+                    PathSegment::Static("products".into()),
+                    PathSegment::Param("id".into())
+                ],
+                vec![PathSegment::Static("/".into())],
+                vec![
+                    PathSegment::Static("products".into()),
+                    PathSegment::Param("id".into())
+                ],
+                vec![
+                    PathSegment::Static("de".into()),
+                    PathSegment::Static("/".into())
+                ],
+                vec![
+                    PathSegment::Static("de".into()),
+                    PathSegment::Static("produkte".into()),
+                    PathSegment::Param("id".into())
+                ],
+            ]
+        );
+
+        let params =
+            ParamsMap::from_iter([("id".to_string(), "5".to_string())]);
+        let localize =
+            |path, locale| routes.localize_path(path, &params, locale);
+        assert_eq!(
+            localize("/de/produkte/5?tab=1", Some("en")).as_deref(),
+            Some("/en/products/5?tab=1")
+        );
+        assert_eq!(
+            localize("/products/5", Some("de")).as_deref(),
+            Some("/de/produkte/5")
+        );
+        assert_eq!(
+            localize("/de/produkte/5", None).as_deref(),
+            Some("/products/5")
+        );
+        assert_eq!(localize("/de", Some("en")).as_deref(), Some("/en"));
+        assert_eq!(localize("/en", None).as_deref(), Some("/"));
+        assert_eq!(localize("/de/produkte/5", Some("fr")), None);
+    }
 }
 
 /// Successful result of [testing](PossibleRouteMatch::test) a single segment in the route path
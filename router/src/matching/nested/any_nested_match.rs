@@ -1,7 +1,7 @@
 #![allow(clippy::type_complexity)]
 use crate::{
     matching::any_choose_view::AnyChooseView, ChooseView, MatchInterface,
-    MatchParams, RouteMatchId,
+    MatchParams, RouteData, RouteLoader, RouteMatchId,
 };
 use std::{borrow::Cow, fmt::Debug};
 use tachys::erased::ErasedLocal;
@@ -12,8 +12,12 @@ pub struct AnyNestedMatch {
     to_params: fn(&ErasedLocal) -> Vec<(Cow<'static, str>, String)>,
     as_id: fn(&ErasedLocal) -> RouteMatchId,
     as_matched: for<'a> fn(&'a ErasedLocal) -> &'a str,
+    as_loader: for<'a> fn(&'a ErasedLocal) -> Option<&'a RouteLoader>,
+    as_route_data: for<'a> fn(&'a ErasedLocal) -> Option<&'a RouteData>,
     into_view_and_child:
         fn(ErasedLocal) -> (AnyChooseView, Option<AnyNestedMatch>),
+    take_named_children:
+        fn(&mut ErasedLocal) -> Vec<(&'static str, AnyNestedMatch)>,
 }
 
 impl Debug for AnyNestedMatch {
@@ -56,6 +60,20 @@ where
             value.as_matched()
         }
 
+        fn as_loader<T: MatchInterface + 'static>(
+            value: &ErasedLocal,
+        ) -> Option<&RouteLoader> {
+            let value = value.get_ref::<T>();
+            value.as_loader()
+        }
+
+        fn as_route_data<T: MatchInterface + 'static>(
+            value: &ErasedLocal,
+        ) -> Option<&RouteData> {
+            let value = value.get_ref::<T>();
+            value.as_route_data()
+        }
+
         fn into_view_and_child<T: MatchInterface + 'static>(
             value: ErasedLocal,
         ) -> (AnyChooseView, Option<AnyNestedMatch>) {
@@ -67,12 +85,26 @@ where
             )
         }
 
+        fn take_named_children<T: MatchInterface + 'static>(
+            value: &mut ErasedLocal,
+        ) -> Vec<(&'static str, AnyNestedMatch)> {
+            let value = value.get_mut::<T>();
+            value
+                .take_named_children()
+                .into_iter()
+                .map(|(name, child)| (name, child.into_any_nested_match()))
+                .collect()
+        }
+
         AnyNestedMatch {
             value,
             to_params: to_params::<T>,
             as_id: as_id::<T>,
             as_matched: as_matched::<T>,
+            as_loader: as_loader::<T>,
+            as_route_data: as_route_data::<T>,
             into_view_and_child: into_view_and_child::<T>,
+            take_named_children: take_named_children::<T>,
         }
     }
 }
@@ -94,7 +126,19 @@ impl MatchInterface for AnyNestedMatch {
         (self.as_matched)(&self.value)
     }
 
+    fn as_loader(&self) -> Option<&RouteLoader> {
+        (self.as_loader)(&self.value)
+    }
+
+    fn as_route_data(&self) -> Option<&RouteData> {
+        (self.as_route_data)(&self.value)
+    }
+
     fn into_view_and_child(self) -> (impl ChooseView, Option<Self::Child>) {
         (self.into_view_and_child)(self.value)
     }
+
+    fn take_named_children(&mut self) -> Vec<(&'static str, Self::Child)> {
+        (self.take_named_children)(&mut self.value)
+    }
 }
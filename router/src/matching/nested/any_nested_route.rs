@@ -1,7 +1,7 @@
 #![allow(clippy::type_complexity)]
 use crate::{
     matching::nested::any_nested_match::{AnyNestedMatch, IntoAnyNestedMatch},
-    GeneratedRouteData, MatchNestedRoutes, RouteMatchId,
+    GeneratedRouteData, MatchContext, MatchNestedRoutes, RouteMatchId,
 };
 use std::fmt::Debug;
 use tachys::{erased::Erased, prelude::IntoMaybeErased};
@@ -14,10 +14,12 @@ pub struct AnyNestedRoute {
         for<'a> fn(
             &'a Erased,
             &'a str,
+            &MatchContext<'_>,
         )
             -> (Option<(RouteMatchId, AnyNestedMatch)>, &'a str),
     generate_routes: fn(&Erased) -> Vec<GeneratedRouteData>,
     optional: fn(&Erased) -> bool,
+    outlet_names: fn(&Erased, &mut Vec<&'static str>),
 }
 
 impl Clone for AnyNestedRoute {
@@ -60,8 +62,10 @@ where
         fn match_nested<'a, T: MatchNestedRoutes + Send + Clone + 'static>(
             value: &'a Erased,
             path: &'a str,
+            cx: &MatchContext<'_>,
         ) -> (Option<(RouteMatchId, AnyNestedMatch)>, &'a str) {
-            let (maybe_match, path) = value.get_ref::<T>().match_nested(path);
+            let (maybe_match, path) =
+                value.get_ref::<T>().match_nested_with(path, cx);
             (
                 maybe_match
                     .map(|(id, matched)| (id, matched.into_any_nested_match())),
@@ -81,12 +85,20 @@ where
             value.get_ref::<T>().optional()
         }
 
+        fn outlet_names<T: MatchNestedRoutes + Send + Clone + 'static>(
+            value: &Erased,
+            names: &mut Vec<&'static str>,
+        ) {
+            value.get_ref::<T>().outlet_names(names)
+        }
+
         AnyNestedRoute {
             value: Erased::new(self),
             clone: clone::<T>,
             match_nested: match_nested::<T>,
             generate_routes: generate_routes::<T>,
             optional: optional::<T>,
+            outlet_names: outlet_names::<T>,
         }
     }
 }
@@ -99,7 +111,15 @@ impl MatchNestedRoutes for AnyNestedRoute {
         &'a self,
         path: &'a str,
     ) -> (Option<(RouteMatchId, Self::Match)>, &'a str) {
-        (self.match_nested)(&self.value, path)
+        self.match_nested_with(path, &MatchContext::default())
+    }
+
+    fn match_nested_with<'a>(
+        &'a self,
+        path: &'a str,
+        cx: &MatchContext<'_>,
+    ) -> (Option<(RouteMatchId, Self::Match)>, &'a str) {
+        (self.match_nested)(&self.value, path, cx)
     }
 
     fn generate_routes(&self) -> impl IntoIterator<Item = GeneratedRouteData> {
@@ -109,4 +129,8 @@ impl MatchNestedRoutes for AnyNestedRoute {
     fn optional(&self) -> bool {
         (self.optional)(&self.value)
     }
+
+    fn outlet_names(&self, names: &mut Vec<&'static str>) {
+        (self.outlet_names)(&self.value, names)
+    }
 }
@@ -1,13 +1,17 @@
 use super::{
-    IntoChooseViewMaybeErased, MatchInterface, MatchNestedRoutes, PathSegment,
-    PossibleRouteMatch, RouteMatchId,
+    IntoChooseViewMaybeErased, MatchContext, MatchInterface, MatchNestedRoutes,
+    PathSegment, PossibleRouteMatch, RouteMatchId,
+};
+use crate::{
+    ChooseView, GeneratedRouteData, MatchParams, Method, RouteData,
+    RouteLoader, SsrMode,
 };
-use crate::{ChooseView, GeneratedRouteData, MatchParams, Method, SsrMode};
 use core::{fmt, iter};
 use either_of::Either;
 use std::{
     borrow::Cow,
     collections::HashSet,
+    mem,
     sync::atomic::{AtomicU16, Ordering},
 };
 use tachys::prelude::IntoMaybeErased;
@@ -27,6 +31,9 @@ pub struct NestedRoute<Segments, Children, Data, View> {
     view: View,
     methods: HashSet<Method>,
     ssr_mode: SsrMode,
+    loader: Option<RouteLoader>,
+    route_data: Option<RouteData>,
+    outlet: Option<&'static str>,
 }
 
 impl<Segments, Children, Data, View> IntoMaybeErased
@@ -71,6 +78,9 @@ where
             view: self.view.clone(),
             methods: self.methods.clone(),
             ssr_mode: self.ssr_mode.clone(),
+            loader: self.loader.clone(),
+            route_data: self.route_data.clone(),
+            outlet: self.outlet,
         }
     }
 }
@@ -96,6 +106,9 @@ impl<Segments, View> NestedRoute<Segments, (), (), View> {
             view: view.into_maybe_erased(),
             methods: [Method::Get].into(),
             ssr_mode: Default::default(),
+            loader: None,
+            route_data: None,
+            outlet: None,
         }
     }
 }
@@ -112,6 +125,9 @@ impl<Segments, Data, View> NestedRoute<Segments, (), Data, View> {
             view,
             ssr_mode,
             methods,
+            loader,
+            route_data,
+            outlet,
             ..
         } = self;
         NestedRoute {
@@ -122,13 +138,41 @@ impl<Segments, Data, View> NestedRoute<Segments, (), Data, View> {
             view,
             ssr_mode,
             methods,
+            loader,
+            route_data,
+            outlet,
         }
     }
+}
 
+impl<Segments, Children, Data, View>
+    NestedRoute<Segments, Children, Data, View>
+{
     pub fn ssr_mode(mut self, ssr_mode: SsrMode) -> Self {
         self.ssr_mode = ssr_mode;
         self
     }
+
+    /// Sets a function that loads data for this route from its params.
+    pub fn loader(mut self, loader: impl Into<RouteLoader>) -> Self {
+        self.loader = Some(loader.into());
+        self
+    }
+
+    /// Attaches data to this route, which can be read from its matches and its route listings.
+    pub fn data(mut self, data: RouteData) -> Self {
+        self.route_data = Some(data);
+        self
+    }
+
+    /// Renders this route in the named outlet of its parent, rather than in its default outlet.
+    ///
+    /// The route is matched against the path in the query param with the outlet's name, e.g.
+    /// `?sidebar=/users/1`, rather than against the rest of the URL's path.
+    pub fn outlet(mut self, name: &'static str) -> Self {
+        self.outlet = Some(name);
+        self
+    }
 }
 
 #[derive(PartialEq, Eq)]
@@ -141,6 +185,10 @@ pub struct NestedMatch<Child, View> {
     /// The nested route.
     child: Option<Child>,
     view_fn: View,
+    loader: Option<RouteLoader>,
+    route_data: Option<RouteData>,
+    /// The matches of the named outlets of this route.
+    named: Vec<(&'static str, Child)>,
 }
 
 impl<Child, View> fmt::Debug for NestedMatch<Child, View>
@@ -152,6 +200,7 @@ where
             .field("matched", &self.matched)
             .field("params", &self.params)
             .field("child", &self.child)
+            .field("named", &self.named)
             .finish()
     }
 }
@@ -178,9 +227,21 @@ where
         &self.matched
     }
 
+    fn as_loader(&self) -> Option<&RouteLoader> {
+        self.loader.as_ref()
+    }
+
+    fn as_route_data(&self) -> Option<&RouteData> {
+        self.route_data.as_ref()
+    }
+
     fn into_view_and_child(self) -> (impl ChooseView, Option<Self::Child>) {
         (self.view_fn, self.child)
     }
+
+    fn take_named_children(&mut self) -> Vec<(&'static str, Self::Child)> {
+        mem::take(&mut self.named)
+    }
 }
 
 impl<Segments, Children, Data, View> MatchNestedRoutes
@@ -203,12 +264,71 @@ where
         &'a self,
         path: &'a str,
     ) -> (Option<(RouteMatchId, Self::Match)>, &'a str) {
+        self.match_nested_with(path, &MatchContext::default())
+    }
+
+    fn match_nested_with<'a>(
+        &'a self,
+        path: &'a str,
+        cx: &MatchContext<'_>,
+    ) -> (Option<(RouteMatchId, Self::Match)>, &'a str) {
+        // a route in a named outlet only matches the path of that outlet, and the routes in the
+        // default outlet only match the rest of the URL's path
+        if self.outlet != cx.target_outlet() {
+            return (None, path);
+        }
+        // the children of this route are in its own default outlet
+        let cx = MatchContext {
+            target_outlet: None,
+            ..*cx
+        };
+        self.match_segments(path, &cx)
+    }
+
+    fn generate_routes(
+        &self,
+    ) -> impl IntoIterator<Item = GeneratedRouteData> + '_ {
+        // routes in a named outlet are matched against the query, so they have no paths of their
+        // own
+        let in_named_outlet = self.outlet.is_some();
+        self.generate_own_routes()
+            .into_iter()
+            .filter(move |_| !in_named_outlet)
+    }
+
+    fn outlet_names(&self, names: &mut Vec<&'static str>) {
+        if let Some(outlet) = self.outlet {
+            if !names.contains(&outlet) {
+                names.push(outlet);
+            }
+        }
+        if let Some(children) = &self.children {
+            children.outlet_names(names);
+        }
+    }
+}
+
+impl<Segments, Children, Data, View> NestedRoute<Segments, Children, Data, View>
+where
+    Self: 'static,
+    Segments: PossibleRouteMatch,
+    Children: MatchNestedRoutes,
+    View: ChooseView,
+{
+    fn match_segments<'a>(
+        &'a self,
+        path: &'a str,
+        cx: &MatchContext<'_>,
+    ) -> (
+        Option<(RouteMatchId, <Self as MatchNestedRoutes>::Match)>,
+        &'a str,
+    ) {
         // if this was optional (for example, this whole nested route definition consisted of an optional param),
         // then we'll need to retest the inner value against the starting path, if this one succeeds and the inner one fails
         let this_was_optional = self.segments.optional();
 
         self.segments
-            .test(path)
+            .test_with(path, cx)
             .and_then({
                 type Params = Vec<(Cow<'static, str>, String)>;
 
@@ -220,6 +340,7 @@ where
                     segments: &dyn PossibleRouteMatch,
                     children: &'a Option<Children>,
                     mut params: Params,
+                    cx: &MatchContext<'_>,
                 ) -> Option<(Option<Children::Match>, &'a str, Params)>
                 where
                     Children: MatchNestedRoutes,
@@ -230,7 +351,7 @@ where
                         None => (None, remaining),
                         Some(children) => {
                             let (inner, remaining) =
-                                children.match_nested(remaining);
+                                children.match_nested_with(remaining, cx);
 
                             if let Some((_, child)) = inner {
                                 (Some(child), remaining)
@@ -238,7 +359,7 @@ where
                                 // if the parent route was optional, re-match children against full path
                                 was_optional_fallback = true;
                                 let (inner, remaining) =
-                                    children.match_nested(path);
+                                    children.match_nested_with(path, cx);
                                 inner.map(|(_, child)| {
                                     (Some(child), remaining)
                                 })?
@@ -248,7 +369,11 @@ where
                         }
                     };
 
-                    if remaining.is_empty() || remaining == "/" {
+                    // a route that consumes nothing matches the root path in any case
+                    if remaining.is_empty()
+                        || (remaining == "/"
+                            && (!cx.strict_trailing_slash() || path == "/"))
+                    {
                         // if this was an optional route, re-parse its params
                         if was_optional_fallback {
                             // new params are based on the path it matched (up to the point where the matched child begins)
@@ -260,7 +385,8 @@ where
                             let rematch = path.trim_end_matches(&format!(
                                 "{matched}{remaining}"
                             ));
-                            let new_partial = segments.test(rematch).unwrap();
+                            let new_partial =
+                                segments.test_with(rematch, cx).unwrap();
                             params = new_partial.params;
                         }
 
@@ -283,8 +409,14 @@ where
                         &self.segments,
                         &self.children,
                         partial_match.params,
+                        cx,
                     )?;
                     let id = RouteMatchId(self.id);
+                    let named = self
+                        .children
+                        .as_ref()
+                        .map(|children| match_named_outlets(children, cx))
+                        .unwrap_or_default();
 
                     Some((
                         Some((
@@ -295,6 +427,9 @@ where
                                 params,
                                 child,
                                 view_fn: self.view.clone(),
+                                loader: self.loader.clone(),
+                                route_data: self.route_data.clone(),
+                                named,
                             },
                         )),
                         remaining,
@@ -304,7 +439,7 @@ where
             .unwrap_or((None, path))
     }
 
-    fn generate_routes(
+    fn generate_own_routes(
         &self,
     ) -> impl IntoIterator<Item = GeneratedRouteData> + '_ {
         let mut segment_routes = Vec::new();
@@ -312,6 +447,8 @@ where
         let children = self.children.as_ref();
         let ssr_mode = self.ssr_mode.clone();
         let methods = self.methods.clone();
+        let data = self.route_data.iter().cloned().collect::<Vec<_>>();
+        let id = RouteMatchId(self.id);
         let regenerate = match &ssr_mode {
             SsrMode::Static(data) => match data.regenerate.as_ref() {
                 None => vec![],
@@ -326,6 +463,8 @@ where
                 ssr_mode,
                 methods,
                 regenerate,
+                data,
+                ids: vec![id],
             })),
             Some(children) => {
                 Either::Right(children.generate_routes().into_iter().map(
@@ -343,12 +482,19 @@ where
                         let mut regenerate = regenerate.clone();
                         regenerate.extend(child.regenerate);
 
+                        let mut data = data.clone();
+                        data.extend(child.data);
+
+                        let ids = iter::once(id).chain(child.ids).collect();
+
                         if child.ssr_mode > ssr_mode {
                             GeneratedRouteData {
                                 segments,
                                 ssr_mode: child.ssr_mode,
                                 methods,
                                 regenerate,
+                                data,
+                                ids,
                             }
                         } else {
                             GeneratedRouteData {
@@ -356,6 +502,8 @@ where
                                 ssr_mode: ssr_mode.clone(),
                                 methods,
                                 regenerate,
+                                data,
+                                ids,
                             }
                         }
                     },
@@ -364,3 +512,30 @@ where
         }
     }
 }
+
+/// Matches the children of a route against the paths of the named outlets they are rendered in.
+fn match_named_outlets<Children>(
+    children: &Children,
+    cx: &MatchContext<'_>,
+) -> Vec<(&'static str, Children::Match)>
+where
+    Children: MatchNestedRoutes,
+{
+    if !cx.has_outlet_paths() {
+        return Vec::new();
+    }
+    let mut names = Vec::new();
+    children.outlet_names(&mut names);
+    names
+        .into_iter()
+        .filter_map(|name| {
+            let path = cx.outlet_path(name)?;
+            let cx = MatchContext {
+                target_outlet: Some(name),
+                ..*cx
+            };
+            let (matched, _) = children.match_nested_with(path, &cx);
+            matched.map(|(_, matched)| (name, matched))
+        })
+        .collect()
+}
@@ -1,5 +1,9 @@
-use super::{MatchInterface, MatchNestedRoutes, PathSegment, RouteMatchId};
-use crate::{ChooseView, GeneratedRouteData, MatchParams};
+use super::{
+    MatchContext, MatchInterface, MatchNestedRoutes, PathSegment, RouteMatchId,
+};
+use crate::{
+    ChooseView, GeneratedRouteData, MatchParams, RouteData, RouteLoader,
+};
 use core::iter;
 use either_of::*;
 use std::borrow::Cow;
@@ -47,6 +51,7 @@ impl MatchNestedRoutes for () {
     ) -> impl IntoIterator<Item = GeneratedRouteData> + '_ {
         iter::once(GeneratedRouteData {
             segments: vec![PathSegment::Unit],
+            ids: vec![RouteMatchId(0)],
             ..Default::default()
         })
     }
@@ -75,9 +80,21 @@ where
         self.0.as_matched()
     }
 
+    fn as_loader(&self) -> Option<&RouteLoader> {
+        self.0.as_loader()
+    }
+
+    fn as_route_data(&self) -> Option<&RouteData> {
+        self.0.as_route_data()
+    }
+
     fn into_view_and_child(self) -> (impl ChooseView, Option<Self::Child>) {
         self.0.into_view_and_child()
     }
+
+    fn take_named_children(&mut self) -> Vec<(&'static str, Self::Child)> {
+        self.0.take_named_children()
+    }
 }
 
 impl<A> MatchNestedRoutes for (A,)
@@ -94,6 +111,14 @@ where
         self.0.match_nested(path)
     }
 
+    fn match_nested_with<'a>(
+        &'a self,
+        path: &'a str,
+        cx: &MatchContext<'_>,
+    ) -> (Option<(RouteMatchId, Self::Match)>, &'a str) {
+        self.0.match_nested_with(path, cx)
+    }
+
     fn generate_routes(
         &self,
     ) -> impl IntoIterator<Item = GeneratedRouteData> + '_ {
@@ -103,6 +128,10 @@ where
     fn optional(&self) -> bool {
         self.0.optional()
     }
+
+    fn outlet_names(&self, names: &mut Vec<&'static str>) {
+        self.0.outlet_names(names);
+    }
 }
 
 impl<A, B> MatchParams for Either<A, B>
@@ -139,6 +168,20 @@ where
         }
     }
 
+    fn as_loader(&self) -> Option<&RouteLoader> {
+        match self {
+            Either::Left(i) => i.as_loader(),
+            Either::Right(i) => i.as_loader(),
+        }
+    }
+
+    fn as_route_data(&self) -> Option<&RouteData> {
+        match self {
+            Either::Left(i) => i.as_route_data(),
+            Either::Right(i) => i.as_route_data(),
+        }
+    }
+
     fn into_view_and_child(self) -> (impl ChooseView, Option<Self::Child>) {
         match self {
             Either::Left(i) => {
@@ -151,6 +194,21 @@ where
             }
         }
     }
+
+    fn take_named_children(&mut self) -> Vec<(&'static str, Self::Child)> {
+        match self {
+            Either::Left(i) => i
+                .take_named_children()
+                .into_iter()
+                .map(|(name, child)| (name, Either::Left(child)))
+                .collect(),
+            Either::Right(i) => i
+                .take_named_children()
+                .into_iter()
+                .map(|(name, child)| (name, Either::Right(child)))
+                .collect(),
+        }
+    }
 }
 
 impl<A, B> MatchNestedRoutes for (A, B)
@@ -164,13 +222,23 @@ where
     fn match_nested<'a>(
         &'a self,
         path: &'a str,
+    ) -> (Option<(RouteMatchId, Self::Match)>, &'a str) {
+        self.match_nested_with(path, &MatchContext::default())
+    }
+
+    fn match_nested_with<'a>(
+        &'a self,
+        path: &'a str,
+        cx: &MatchContext<'_>,
     ) -> (Option<(RouteMatchId, Self::Match)>, &'a str) {
         #[allow(non_snake_case)]
         let (A, B) = &self;
-        if let (Some((id, matched)), remaining) = A.match_nested(path) {
+        if let (Some((id, matched)), remaining) = A.match_nested_with(path, cx)
+        {
             return (Some((id, Either::Left(matched))), remaining);
         }
-        if let (Some((id, matched)), remaining) = B.match_nested(path) {
+        if let (Some((id, matched)), remaining) = B.match_nested_with(path, cx)
+        {
             return (Some((id, Either::Right(matched))), remaining);
         }
         (None, path)
@@ -192,6 +260,11 @@ where
     fn optional(&self) -> bool {
         self.0.optional() && self.1.optional()
     }
+
+    fn outlet_names(&self, names: &mut Vec<&'static str>) {
+        self.0.outlet_names(names);
+        self.1.outlet_names(names);
+    }
 }
 
 impl<T> MatchNestedRoutes for StaticVec<T>
@@ -204,9 +277,19 @@ where
     fn match_nested<'a>(
         &'a self,
         path: &'a str,
+    ) -> (Option<(RouteMatchId, Self::Match)>, &'a str) {
+        self.match_nested_with(path, &MatchContext::default())
+    }
+
+    fn match_nested_with<'a>(
+        &'a self,
+        path: &'a str,
+        cx: &MatchContext<'_>,
     ) -> (Option<(RouteMatchId, Self::Match)>, &'a str) {
         for item in self.iter() {
-            if let (Some((id, matched)), remaining) = item.match_nested(path) {
+            if let (Some((id, matched)), remaining) =
+                item.match_nested_with(path, cx)
+            {
                 return (Some((id, matched)), remaining);
             }
         }
@@ -222,6 +305,12 @@ where
     fn optional(&self) -> bool {
         self.iter().all(|n| n.optional())
     }
+
+    fn outlet_names(&self, names: &mut Vec<&'static str>) {
+        for item in self.iter() {
+            item.outlet_names(names);
+        }
+    }
 }
 
 macro_rules! chain_generated {
@@ -267,6 +356,18 @@ macro_rules! tuples {
                 }
             }
 
+            fn as_loader(&self) -> Option<&RouteLoader> {
+                match self {
+                    $($either::$ty(i) => i.as_loader(),)*
+                }
+            }
+
+            fn as_route_data(&self) -> Option<&RouteData> {
+                match self {
+                    $($either::$ty(i) => i.as_route_data(),)*
+                }
+            }
+
             fn into_view_and_child(
                 self,
             ) -> (
@@ -280,6 +381,18 @@ macro_rules! tuples {
                     })*
                 }
             }
+
+            fn take_named_children(
+                &mut self,
+            ) -> Vec<(&'static str, Self::Child)> {
+                match self {
+                    $($either::$ty(i) => i
+                        .take_named_children()
+                        .into_iter()
+                        .map(|(name, child)| (name, $either::$ty(child)))
+                        .collect(),)*
+                }
+            }
         }
 
         impl<$($ty),*> MatchNestedRoutes for ($($ty,)*)
@@ -297,10 +410,14 @@ macro_rules! tuples {
             }
 
             fn match_nested<'a>(&'a self, path: &'a str) -> (Option<(RouteMatchId, Self::Match)>, &'a str) {
+                self.match_nested_with(path, &MatchContext::default())
+            }
+
+            fn match_nested_with<'a>(&'a self, path: &'a str, cx: &MatchContext<'_>) -> (Option<(RouteMatchId, Self::Match)>, &'a str) {
                 #[allow(non_snake_case)]
 
                 let ($($ty,)*) = &self;
-                $(if let (Some((_, matched)), remaining) = $ty.match_nested(path) {
+                $(if let (Some((_, matched)), remaining) = $ty.match_nested_with(path, cx) {
                     return (Some((RouteMatchId($count), $either::$ty(matched))), remaining);
                 })*
                 (None, path)
@@ -315,6 +432,12 @@ macro_rules! tuples {
                 $(let $ty = $ty.generate_routes().into_iter();)*
                 chain_generated!($($ty,)*)
             }
+
+            fn outlet_names(&self, names: &mut Vec<&'static str>) {
+                #[allow(non_snake_case)]
+                let ($($ty,)*) = &self;
+                $($ty.outlet_names(names);)*
+            }
         }
     }
 }
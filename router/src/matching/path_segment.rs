@@ -1,12 +1,20 @@
+use crate::LocalizedSegment;
 use std::borrow::Cow;
 
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
 pub enum PathSegment {
     Unit,
     Static(Cow<'static, str>),
     Param(Cow<'static, str>),
+    /// A param with its name, and a regular expression that its value matches.
+    ConstrainedParam(Cow<'static, str>, Cow<'static, str>),
     OptionalParam(Cow<'static, str>),
     Splat(Cow<'static, str>),
+    /// A static segment that is translated into some locales. The routes generated by
+    /// [`RouteDefs`](crate::RouteDefs) have the segment in their locale as a
+    /// [`PathSegment::Static`] instead.
+    Localized(LocalizedSegment),
 }
 
 impl PathSegment {
@@ -15,8 +23,10 @@ impl PathSegment {
             PathSegment::Unit => "",
             PathSegment::Static(i) => i,
             PathSegment::Param(i) => i,
+            PathSegment::ConstrainedParam(i, _) => i,
             PathSegment::OptionalParam(i) => i,
             PathSegment::Splat(i) => i,
+            PathSegment::Localized(i) => i.0,
         }
     }
 }
@@ -1,4 +1,14 @@
-use crate::location::State;
+use crate::location::{State, Url};
+use or_poisoned::OrPoisoned;
+use std::{
+    fmt::Debug,
+    future::{ready, Future, IntoFuture, Ready},
+    pin::Pin,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc, Mutex,
+    },
+};
 
 /// Options that can be used to configure a navigation. Used with [use_navigate](crate::hooks::use_navigate).
 #[derive(Clone, Debug)]
@@ -13,6 +23,8 @@ pub struct NavigateOptions {
     pub scroll: bool,
     /// [State](https://developer.mozilla.org/en-US/docs/Web/API/History/state) that should be pushed
     /// onto the history stack during navigation.
+    ///
+    /// The router keeps its own data in `history.state`, so this is found in its `state` property.
     pub state: State,
 }
 
@@ -26,3 +38,125 @@ impl Default for NavigateOptions {
         }
     }
 }
+
+/// What a navigation guard registered with
+/// [`use_before_navigate`](crate::hooks::use_before_navigate) decides to do with a navigation.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub enum NavigationDecision {
+    /// Let the navigation continue.
+    #[default]
+    Allow,
+    /// Stay on the current page.
+    Cancel,
+    /// Navigate to another path instead. The guards are not consulted again for the redirect.
+    Redirect(String),
+}
+
+/// Allows a guard to return a decision directly, rather than a `Future`.
+impl IntoFuture for NavigationDecision {
+    type Output = Self;
+    type IntoFuture = Ready<Self>;
+
+    fn into_future(self) -> Self::IntoFuture {
+        ready(self)
+    }
+}
+
+type Guard = Arc<
+    dyn Fn(&Url, &Url) -> Pin<Box<dyn Future<Output = NavigationDecision>>>
+        + Send
+        + Sync,
+>;
+
+/// The navigation guards registered for a router.
+#[derive(Clone, Default)]
+pub(crate) struct NavigationGuards {
+    next_id: Arc<AtomicUsize>,
+    guards: Arc<Mutex<Vec<(usize, Guard)>>>,
+}
+
+impl Debug for NavigationGuards {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("NavigationGuards")
+            .field("len", &self.guards.lock().or_poisoned().len())
+            .finish()
+    }
+}
+
+impl NavigationGuards {
+    /// Adds a guard, returning an ID that can be used to remove it.
+    pub fn add(
+        &self,
+        guard: impl Fn(&Url, &Url) -> Pin<Box<dyn Future<Output = NavigationDecision>>>
+            + Send
+            + Sync
+            + 'static,
+    ) -> usize {
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+        self.guards.lock().or_poisoned().push((id, Arc::new(guard)));
+        id
+    }
+
+    pub fn remove(&self, id: usize) {
+        self.guards
+            .lock()
+            .or_poisoned()
+            .retain(|(guard, _)| *guard != id);
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.guards.lock().or_poisoned().is_empty()
+    }
+
+    /// Consults each guard in the order they were added, until one of them does not allow the
+    /// navigation.
+    pub async fn check(&self, from: &Url, to: &Url) -> NavigationDecision {
+        let guards = self
+            .guards
+            .lock()
+            .or_poisoned()
+            .iter()
+            .map(|(_, guard)| Arc::clone(guard))
+            .collect::<Vec<_>>();
+        for guard in guards {
+            let decision = guard(from, to).await;
+            if decision != NavigationDecision::Allow {
+                return decision;
+            }
+        }
+        NavigationDecision::Allow
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{NavigationDecision, NavigationGuards};
+    use crate::location::Url;
+    use futures::executor::block_on;
+
+    #[test]
+    fn guards_decide_in_order() {
+        let guards = NavigationGuards::default();
+        let (from, to) = (Url::default(), Url::default());
+        assert_eq!(
+            block_on(guards.check(&from, &to)),
+            NavigationDecision::Allow
+        );
+
+        guards.add(|_, _| Box::pin(async { NavigationDecision::Allow }));
+        let redirect = guards.add(|_, _| {
+            Box::pin(async { NavigationDecision::Redirect("/login".into()) })
+        });
+        guards.add(|_, _| Box::pin(async { NavigationDecision::Cancel }));
+        assert_eq!(
+            block_on(guards.check(&from, &to)),
+            NavigationDecision::Redirect("/login".into())
+        );
+
+        guards.remove(redirect);
+        assert_eq!(
+            block_on(guards.check(&from, &to)),
+            NavigationDecision::Cancel
+        );
+    }
+}
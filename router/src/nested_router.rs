@@ -1,12 +1,13 @@
 use crate::{
     flat_router::MatchedRoute,
     hooks::Matched,
-    location::{LocationProvider, Url},
+    loader::RouteLoaders,
+    location::{AnyLocation, Url},
     matching::RouteDefs,
     params::ParamsMap,
     view_transition::start_view_transition,
     ChooseView, MatchInterface, MatchNestedRoutes, MatchParams, PathSegment,
-    RouteList, RouteListing, RouteMatchId,
+    RouteList, RouteListing, RouteMatchId, RouteMatcher,
 };
 use any_spawner::Executor;
 use either_of::{Either, EitherOf3};
@@ -52,8 +53,8 @@ use tachys::{
     },
 };
 
-pub(crate) struct NestedRoutesView<Loc, Defs, FalFn> {
-    pub location: Option<Loc>,
+pub(crate) struct NestedRoutesView<Defs, FalFn> {
+    pub location: Option<AnyLocation>,
     pub routes: RouteDefs<Defs>,
     pub outer_owner: Owner,
     pub current_url: ArcRwSignal<Url>,
@@ -69,6 +70,8 @@ where
     Fal: Render,
 {
     path: String,
+    /// the paths of the named outlets, from the query
+    outlet_paths: Vec<(&'static str, String)>,
     current_url: ArcRwSignal<Url>,
     outlets: Vec<RouteContext>,
     // TODO loading fallback
@@ -80,9 +83,8 @@ where
     abort_navigation: ArcStoredValue<Option<AbortHandle>>,
 }
 
-impl<Loc, Defs, FalFn, Fal> Render for NestedRoutesView<Loc, Defs, FalFn>
+impl<Defs, FalFn, Fal> Render for NestedRoutesView<Defs, FalFn>
 where
-    Loc: LocationProvider,
     Defs: MatchNestedRoutes,
     FalFn: FnOnce() -> Fal,
     Fal: Render + 'static,
@@ -104,9 +106,11 @@ where
         let mut outlets = Vec::new();
         let url = current_url.read_untracked();
         let path = url.path().to_string();
+        let outlet_paths = outlet_paths(&routes, &url);
 
         // match the route
-        let new_match = routes.match_route(url.path());
+        let new_match =
+            routes.match_route_with_outlets(url.path(), url.search_params());
 
         // start with an empty view because we'll be loading routes async
         let view = EitherOf3::A(()).build();
@@ -141,6 +145,7 @@ where
 
         NestedRouteViewState {
             path,
+            outlet_paths,
             current_url,
             outlets,
             view,
@@ -152,9 +157,12 @@ where
     fn rebuild(self, state: &mut Self::State) {
         let url_snapshot = self.current_url.get_untracked();
 
-        // if the path is the same, we do not need to re-route
-        // we can just update the search query and go about our day
-        if url_snapshot.path() == state.path {
+        // if the path and the paths of the named outlets are the same, we do not need to
+        // re-route; we can just update the search query and go about our day
+        let outlet_paths = outlet_paths(&self.routes, &url_snapshot);
+        if url_snapshot.path() == state.path
+            && outlet_paths == state.outlet_paths
+        {
             for outlet in &state.outlets {
                 outlet.url.set(url_snapshot.to_owned());
             }
@@ -164,8 +172,12 @@ where
         // since the path didn't match, we'll update the retained path for future diffing
         state.path.clear();
         state.path.push_str(url_snapshot.path());
+        state.outlet_paths = outlet_paths;
 
-        let new_match = self.routes.match_route(url_snapshot.path());
+        let new_match = self.routes.match_route_with_outlets(
+            url_snapshot.path(),
+            url_snapshot.search_params(),
+        );
 
         *state.current_url.write_untracked() = url_snapshot;
 
@@ -260,15 +272,14 @@ where
     }
 }
 
-impl<Loc, Defs, Fal, FalFn> AddAnyAttr for NestedRoutesView<Loc, Defs, FalFn>
+impl<Defs, Fal, FalFn> AddAnyAttr for NestedRoutesView<Defs, FalFn>
 where
-    Loc: LocationProvider + Send,
     Defs: MatchNestedRoutes + Send + 'static,
     FalFn: FnOnce() -> Fal + Send + 'static,
     Fal: RenderHtml + 'static,
 {
     type Output<SomeNewAttr: leptos::attr::Attribute> =
-        NestedRoutesView<Loc, Defs, FalFn>;
+        NestedRoutesView<Defs, FalFn>;
 
     fn add_any_attr<NewAttr: leptos::attr::Attribute>(
         self,
@@ -281,9 +292,8 @@ where
     }
 }
 
-impl<Loc, Defs, FalFn, Fal> RenderHtml for NestedRoutesView<Loc, Defs, FalFn>
+impl<Defs, FalFn, Fal> RenderHtml for NestedRoutesView<Defs, FalFn>
 where
-    Loc: LocationProvider + Send,
     Defs: MatchNestedRoutes + Send + 'static,
     FalFn: FnOnce() -> Fal + Send + 'static,
     Fal: RenderHtml + 'static,
@@ -310,6 +320,7 @@ where
         // if this is being run on the server for the first time, generating all possible routes
         if RouteList::is_generating() {
             // add routes
+            let trailing_slash = self.routes.trailing_slash();
             let (base, routes) = self.routes.generate_routes();
             let routes = routes
                 .into_iter()
@@ -329,6 +340,9 @@ where
                         data.methods,
                         data.regenerate,
                     )
+                    .with_data(data.data)
+                    .with_trailing_slash(trailing_slash)
+                    .with_route_ids(data.ids)
                 })
                 .collect::<Vec<_>>();
 
@@ -349,7 +363,10 @@ where
                 None,
             ));*/
 
-            RouteList::register(RouteList::from(routes));
+            RouteList::register(
+                RouteList::from(routes)
+                    .with_matcher(RouteMatcher::new(self.routes)),
+            );
         } else {
             let NestedRoutesView {
                 routes,
@@ -362,7 +379,10 @@ where
             let current_url = current_url.read_untracked();
 
             let mut outlets = Vec::new();
-            let new_match = routes.match_route(current_url.path());
+            let new_match = routes.match_route_with_outlets(
+                current_url.path(),
+                current_url.search_params(),
+            );
             let view = match new_match {
                 None => Either::Left(fallback()),
                 Some(route) => {
@@ -416,7 +436,10 @@ where
         let current_url = current_url.read_untracked();
 
         let mut outlets = Vec::new();
-        let new_match = routes.match_route(current_url.path());
+        let new_match = routes.match_route_with_outlets(
+            current_url.path(),
+            current_url.search_params(),
+        );
         let view = match new_match {
             None => Either::Left(fallback()),
             Some(route) => {
@@ -429,10 +452,7 @@ where
                     &outer_owner,
                 );
 
-                let preload_owners = outlets
-                    .iter()
-                    .map(|o| o.preload_owner.clone())
-                    .collect::<Vec<_>>();
+                let preload_owners = preload_owners(&outlets, 0);
                 outer_owner
                     .with(|| Owner::on_cleanup(move || drop(preload_owners)));
 
@@ -473,9 +493,11 @@ where
         let mut outlets = Vec::new();
         let url = current_url.read_untracked();
         let path = url.path().to_string();
+        let outlet_paths = outlet_paths(&routes, &url);
 
         // match the route
-        let new_match = routes.match_route(url.path());
+        let new_match =
+            routes.match_route_with_outlets(url.path(), url.search_params());
 
         // start with an empty view because we'll be loading routes async
         let view = Rc::new(RefCell::new(
@@ -503,6 +525,7 @@ where
 
         NestedRouteViewState {
             path,
+            outlet_paths,
             current_url,
             outlets,
             view,
@@ -529,9 +552,11 @@ where
         let mut outlets = Vec::new();
         let url = current_url.read_untracked();
         let path = url.path().to_string();
+        let outlet_paths = outlet_paths(&routes, &url);
 
         // match the route
-        let new_match = routes.match_route(url.path());
+        let new_match =
+            routes.match_route_with_outlets(url.path(), url.search_params());
 
         // start with an empty view because we'll be loading routes async
         let view = Rc::new(RefCell::new(
@@ -556,6 +581,7 @@ where
 
         NestedRouteViewState {
             path,
+            outlet_paths,
             current_url,
             outlets,
             view,
@@ -581,12 +607,27 @@ pub(crate) struct RouteContext {
     view_fn: Arc<Mutex<OutletViewFn>>,
     owner: Arc<Mutex<Option<Owner>>>,
     preload_owner: Owner,
+    loader_data: RouteLoaders,
     child: ChildRoute,
+    named: NamedOutlets,
 }
 
-#[derive(Clone)]
+#[derive(Clone, Default)]
 pub(crate) struct ChildRoute(Arc<Mutex<Option<RouteContext>>>);
 
+/// The branches of matched routes rendered in the named outlets of a route, by name.
+#[derive(Clone, Default)]
+pub(crate) struct NamedOutlets(Arc<Mutex<Vec<(&'static str, NamedOutlet)>>>);
+
+#[derive(Clone, Default)]
+pub(crate) struct NamedOutlet {
+    /// notified when a branch is added to or removed from the outlet
+    trigger: ArcTrigger,
+    child: ChildRoute,
+    /// the parents of the branch, followed by the routes of the branch
+    outlets: Arc<Mutex<Vec<RouteContext>>>,
+}
+
 impl Debug for RouteContext {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("RouteContext")
@@ -613,6 +654,8 @@ impl Clone for RouteContext {
             owner: Arc::clone(&self.owner),
             child: self.child.clone(),
             preload_owner: self.preload_owner.clone(),
+            loader_data: self.loader_data.clone(),
+            named: self.named.clone(),
         }
     }
 }
@@ -647,7 +690,7 @@ where
     Match: MatchInterface + MatchParams,
 {
     fn build_nested_route(
-        self,
+        mut self,
         url: &Url,
         base: Option<Oco<'static, str>>,
         loaders: &mut Vec<Pin<Box<dyn Future<Output = ArcTrigger>>>>,
@@ -655,6 +698,7 @@ where
         outer_owner: &Owner,
     ) {
         let orig_url = url;
+        let named = self.take_named_children();
 
         // the params signal can be updated to allow the same outlet to update to changes in the
         // params, even if there's not a route match change
@@ -707,6 +751,18 @@ where
         // require that we can clone it out
         let trigger = ArcTrigger::new();
 
+        // start loading this route's data right away, so that the loaders of all nested matches
+        // run in parallel, before any of their views are rendered
+        let preload_owner = outer_owner.child();
+        let loader_data = outlets
+            .last()
+            .map(|parent| parent.loader_data.clone())
+            .unwrap_or_default()
+            .with(self.as_loader().map(|loader| {
+                preload_owner
+                    .with(|| loader.load(params_including_parents.clone()))
+            }));
+
         // add this outlet to the end of the outlet stack used for diffing
         let outlet = RouteContext {
             id: self.as_id(),
@@ -720,7 +776,9 @@ where
             base: base.clone(),
             child: ChildRoute(Arc::new(Mutex::new(None))),
             owner: Arc::new(Mutex::new(None)),
-            preload_owner: outer_owner.child(),
+            preload_owner,
+            loader_data,
+            named: NamedOutlets::default(),
         };
         if !outlets.is_empty() {
             let prev_index = outlets.len().saturating_sub(1);
@@ -729,6 +787,16 @@ where
         }
         outlets.push(outlet.clone());
 
+        // build the branches of the named outlets, which hang off this route
+        outlet.named.build(
+            named,
+            orig_url,
+            base.clone(),
+            loaders,
+            outlets,
+            outer_owner,
+        );
+
         // send the initial view through the channel, and recurse through the children
         let (view, child) = self.into_view_and_child();
 
@@ -741,6 +809,7 @@ where
             let params = params_including_parents.clone();
             let url = url.clone();
             let matched = matched.clone();
+            let loader_data = outlet.loader_data.clone();
             async move {
                 provide_context(params.clone());
                 provide_context(url.clone());
@@ -751,28 +820,34 @@ where
                         provide_context(params.clone());
                         provide_context(url.clone());
                         provide_context(matched.clone());
+                        provide_context(loader_data.clone());
                         ScopedFuture::new(view.preload())
                     })
                     .await;
                 let child = outlet.child.clone();
+                let named = outlet.named.clone();
                 *view_fn.lock().or_poisoned() =
                     Box::new(move |owner_where_used| {
                         *route_owner.lock().or_poisoned() =
                             Some(owner_where_used.clone());
                         let view = view.clone();
                         let child = child.clone();
+                        let named = named.clone();
                         let params = params.clone();
                         let url = url.clone();
                         let matched = matched.clone();
+                        let loader_data = loader_data.clone();
                         owner_where_used.with({
                             let matched = matched.clone();
                             || {
                                 let child = child.clone();
                                 Suspend::new(Box::pin(async move {
                                     provide_context(child.clone());
+                                    provide_context(named.clone());
                                     provide_context(params.clone());
                                     provide_context(url.clone());
                                     provide_context(matched.clone());
+                                    provide_context(loader_data.clone());
                                     let view = SendWrapper::new(
                                         ScopedFuture::new(view.choose()),
                                     );
@@ -812,7 +887,7 @@ where
 
     #[allow(clippy::too_many_arguments)]
     fn rebuild_nested_route(
-        self,
+        mut self,
         url: &Url,
         base: Option<Oco<'static, str>>,
         items: &mut usize,
@@ -828,6 +903,11 @@ where
             .take(*items)
             .map(|route| (route.params.clone(), route.matched.clone()))
             .unzip();
+        let parent_loader_data = items
+            .checked_sub(1)
+            .and_then(|parent| outlets.get(parent))
+            .map(|parent| parent.loader_data.clone())
+            .unwrap_or_default();
 
         if outlets.get(*items).is_some() && *items > 0 {
             *outlets[*items - 1].child.0.lock().or_poisoned() =
@@ -857,6 +937,8 @@ where
                 let new_params =
                     self.to_params().into_iter().collect::<ParamsMap>();
                 let new_match = self.as_matched().to_owned();
+                let loader = self.as_loader().cloned();
+                let named = self.take_named_children();
 
                 let (view, child) = self.into_view_and_child();
 
@@ -913,6 +995,16 @@ where
                         })
                     };
 
+                    current.loader_data =
+                        parent_loader_data.with(loader.map(|loader| {
+                            current.preload_owner.with(|| {
+                                loader.load(params_including_parents.clone())
+                            })
+                        }));
+
+                    // the named outlets of the new view start out empty
+                    current.named = NamedOutlets::default();
+
                     let (full_tx, full_rx) = oneshot::channel();
                     let full_tx = Mutex::new(Some(full_tx));
                     full_loaders.push(full_rx);
@@ -928,6 +1020,8 @@ where
                         let view_fn = Arc::clone(&current.view_fn);
                         let route_owner = Arc::clone(&current.owner);
                         let child = outlet.child.clone();
+                        let named = outlet.named.clone();
+                        let loader_data = current.loader_data.clone();
                         async move {
                             let child = child.clone();
                             outlet
@@ -938,6 +1032,7 @@ where
                                     );
                                     provide_context(url.clone());
                                     provide_context(matched.clone());
+                                    provide_context(loader_data.clone());
                                     ScopedFuture::new(async {
                                         if set_is_routing {
                                             AsyncTransition::run(|| {
@@ -960,17 +1055,21 @@ where
                                     let full_tx =
                                         full_tx.lock().or_poisoned().take();
                                     let child = child.clone();
+                                    let named = named.clone();
                                     let params =
                                         params_including_parents.clone();
                                     let url = url.clone();
                                     let matched = matched.clone();
+                                    let loader_data = loader_data.clone();
                                     Suspend::new(Box::pin(async move {
                                         let view = SendWrapper::new(
                                             owner_where_used.with(|| {
                                                 provide_context(child.clone());
+                                                provide_context(named);
                                                 provide_context(params);
                                                 provide_context(url);
                                                 provide_context(matched);
+                                                provide_context(loader_data);
                                                 ScopedFuture::new(async move {
                                                     if set_is_routing {
                                                         AsyncTransition::run(
@@ -1007,6 +1106,16 @@ where
                     // if this match is different, all its children will also be different
                     outlets.truncate(*items + 1);
 
+                    let named_outlets = outlets[*items].named.clone();
+                    named_outlets.build(
+                        named,
+                        url,
+                        base.clone(),
+                        preloaders,
+                        outlets,
+                        outer_owner,
+                    );
+
                     // if this children has matches, then rebuild the lower section of the tree
                     if let Some(child) = child {
                         child.build_nested_route(
@@ -1028,6 +1137,17 @@ where
                 current.matched.set(new_match);
                 current.params.set(new_params);
                 current.url.set(url.to_owned());
+                let named_outlets = current.named.clone();
+                named_outlets.rebuild(
+                    named,
+                    url,
+                    base.clone(),
+                    preloaders,
+                    full_loaders,
+                    &outlets[..=*items],
+                    set_is_routing,
+                    outer_owner,
+                );
                 if let Some(child) = child {
                     *items += 1;
                     child.rebuild_nested_route(
@@ -1042,7 +1162,7 @@ where
                         outer_owner,
                     )
                 } else {
-                    *current.child.0.lock().or_poisoned() = None;
+                    *outlets[*items].child.0.lock().or_poisoned() = None;
                     level
                 }
             }
@@ -1050,6 +1170,146 @@ where
     }
 }
 
+impl NamedOutlets {
+    /// The named outlet with the given name, which is created if it does not exist yet.
+    fn get(&self, name: &'static str) -> NamedOutlet {
+        let mut outlets = self.0.lock().or_poisoned();
+        match outlets.iter().find(|(n, _)| *n == name) {
+            Some((_, outlet)) => outlet.clone(),
+            None => {
+                let outlet = NamedOutlet::default();
+                outlets.push((name, outlet.clone()));
+                outlet
+            }
+        }
+    }
+
+    /// The routes of the branches in the named outlets, each after the parents of the branch.
+    fn branches(&self) -> Vec<Vec<RouteContext>> {
+        self.0
+            .lock()
+            .or_poisoned()
+            .iter()
+            .map(|(_, outlet)| outlet.outlets.lock().or_poisoned().clone())
+            .collect()
+    }
+
+    fn build<Match>(
+        &self,
+        named: Vec<(&'static str, Match)>,
+        url: &Url,
+        base: Option<Oco<'static, str>>,
+        loaders: &mut Vec<Pin<Box<dyn Future<Output = ArcTrigger>>>>,
+        parents: &[RouteContext],
+        outer_owner: &Owner,
+    ) where
+        Match: MatchInterface + MatchParams,
+    {
+        for (name, matched) in named {
+            let outlet = self.get(name);
+            let mut branch = parents.to_vec();
+            // the branch is rendered in the named outlet, rather than the default outlet of its
+            // parent
+            if let Some(parent) = branch.last_mut() {
+                parent.child = outlet.child.clone();
+            }
+            matched.build_nested_route(
+                url,
+                base.clone(),
+                loaders,
+                &mut branch,
+                outer_owner,
+            );
+            *outlet.outlets.lock().or_poisoned() = branch;
+            outlet.trigger.notify();
+        }
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn rebuild<Match>(
+        &self,
+        named: Vec<(&'static str, Match)>,
+        url: &Url,
+        base: Option<Oco<'static, str>>,
+        preloaders: &mut Vec<Pin<Box<dyn Future<Output = ArcTrigger>>>>,
+        full_loaders: &mut Vec<oneshot::Receiver<Option<Owner>>>,
+        parents: &[RouteContext],
+        set_is_routing: bool,
+        outer_owner: &Owner,
+    ) where
+        Match: MatchInterface + MatchParams,
+    {
+        // remove the branches of outlets that are no longer matched
+        let current = self.0.lock().or_poisoned().clone();
+        for (name, outlet) in current {
+            if !named.iter().any(|(n, _)| *n == name) {
+                outlet.outlets.lock().or_poisoned().clear();
+                if outlet.child.0.lock().or_poisoned().take().is_some() {
+                    outlet.trigger.notify();
+                }
+            }
+        }
+
+        for (name, matched) in named {
+            let outlet = self.get(name);
+            let mut branch =
+                mem::take(&mut *outlet.outlets.lock().or_poisoned());
+            if branch.len() > parents.len() {
+                // the branch is diffed like the routes in the default outlet
+                matched.rebuild_nested_route(
+                    url,
+                    base.clone(),
+                    &mut parents.len(),
+                    preloaders,
+                    full_loaders,
+                    &mut branch,
+                    set_is_routing,
+                    0,
+                    outer_owner,
+                );
+                *outlet.outlets.lock().or_poisoned() = branch;
+            } else {
+                self.build(
+                    vec![(name, matched)],
+                    url,
+                    base.clone(),
+                    preloaders,
+                    parents,
+                    outer_owner,
+                );
+            }
+        }
+    }
+}
+
+/// The paths of the named outlets of the routes in the URL's query.
+fn outlet_paths<Defs>(
+    routes: &RouteDefs<Defs>,
+    url: &Url,
+) -> Vec<(&'static str, String)>
+where
+    Defs: MatchNestedRoutes,
+{
+    routes.outlet_paths(url.search_params())
+}
+
+/// The owners that preload the matched routes, including the routes in named outlets.
+fn preload_owners(outlets: &[RouteContext], from: usize) -> Vec<Owner> {
+    outlets
+        .iter()
+        .enumerate()
+        .skip(from)
+        .flat_map(|(index, outlet)| {
+            let branches = outlet.named.branches();
+            iter::once(outlet.preload_owner.clone()).chain(
+                branches
+                    .into_iter()
+                    .flat_map(move |branch| preload_owners(&branch, index + 1)),
+            )
+        })
+        .collect()
+}
+
 impl<Fal> Mountable for NestedRouteViewState<Fal>
 where
     Fal: Render,
@@ -1094,19 +1354,75 @@ fn top_level_outlet(outlets: &[RouteContext], outer_owner: &Owner) -> AnyView {
 
 /// Displays the child route nested in a parent route, allowing you to control exactly where
 /// that child route is displayed. Renders nothing if there is no nested child.
+///
+/// A named outlet displays the child route that is rendered in the outlet with that name,
+/// instead of the default child route. The path of its child route is read from the query param
+/// with its name, so that several panes of a layout can each be driven by the URL.
+///
+/// ```rust
+/// # use leptos::prelude::*;
+/// # use leptos_router::{components::*, path};
+/// #[component]
+/// fn Dashboard() -> impl IntoView {
+///     view! {
+///         <aside><Outlet name="sidebar"/></aside>
+///         <main><Outlet/></main>
+///         // links to `/dashboard/stats` with user 1 in the sidebar
+///         <A href="/dashboard/stats?sidebar=/users/1">"User 1"</A>
+///     }
+/// }
+///
+/// # fn app() -> impl IntoView {
+/// view! {
+///     <Router>
+///         <Routes fallback=|| "Not found.">
+///             <ParentRoute path=path!("/dashboard") view=Dashboard>
+///                 <Route path=path!("") view=|| "Overview"/>
+///                 <Route path=path!("stats") view=|| "Stats"/>
+///                 <Route path=path!("users/:id") view=|| "User" outlet="sidebar"/>
+///             </ParentRoute>
+///         </Routes>
+///     </Router>
+/// }
+/// # }
+/// ```
 #[component]
-pub fn Outlet() -> impl RenderHtml
+pub fn Outlet(
+    /// The name of the outlet, for a named outlet that displays the child routes with a matching
+    /// `outlet`.
+    #[prop(optional)]
+    name: Option<&'static str>,
+) -> impl RenderHtml
 where
 {
-    let ChildRoute(child) = use_context()
-        .expect("<Outlet/> used without RouteContext being provided.");
-    let child = child.lock().or_poisoned().clone();
     let outer_owner = Owner::current().unwrap();
-    child.map(|child| {
-        move || {
-            child.trigger.track();
-            let mut view_fn = child.view_fn.lock().or_poisoned();
-            view_fn(outer_owner.child())
+    match name {
+        None => {
+            let ChildRoute(child) = use_context()
+                .expect("<Outlet/> used without RouteContext being provided.");
+            let child = child.lock().or_poisoned().clone();
+            Either::Left(child.map(|child| {
+                move || {
+                    child.trigger.track();
+                    let mut view_fn = child.view_fn.lock().or_poisoned();
+                    view_fn(outer_owner.child())
+                }
+            }))
         }
-    })
+        Some(name) => {
+            let named = use_context::<NamedOutlets>().expect(
+                "<Outlet name=.../> used without RouteContext being provided.",
+            );
+            let outlet = named.get(name);
+            Either::Right(move || {
+                outlet.trigger.track();
+                let child = outlet.child.0.lock().or_poisoned().clone();
+                child.map(|child| {
+                    child.trigger.track();
+                    let mut view_fn = child.view_fn.lock().or_poisoned();
+                    view_fn(outer_owner.child())
+                })
+            })
+        }
+    }
 }
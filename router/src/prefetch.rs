@@ -0,0 +1,204 @@
+use crate::{
+    params::ParamsMap, route_data::RouteMatcher, ChooseView, MatchInterface,
+    MatchParams, RouteLoader,
+};
+use any_spawner::Executor;
+use or_poisoned::OrPoisoned;
+use reactive_graph::{
+    computed::{ArcMemo, ScopedFuture},
+    owner::{provide_context, Owner},
+};
+use std::{
+    fmt,
+    future::Future,
+    pin::Pin,
+    sync::{Arc, Mutex},
+};
+
+/// The most paths whose views are kept preloaded.
+const MAX_PRELOADED: usize = 8;
+
+/// Starts loading the views and data of the routes that match a path, before navigating to it.
+///
+/// This is registered in the router context by `<Routes>` and `<FlatRoutes>`, and used by
+/// [`<A prefetch=...>`](crate::components::A).
+#[derive(Clone)]
+pub(crate) struct Prefetcher {
+    routes: RouteMatcher,
+    owner: Owner,
+    flat: bool,
+    // the owners hold any data created while preloading the views, until they are used
+    preloaded: Arc<Mutex<Vec<(String, Owner)>>>,
+}
+
+impl fmt::Debug for Prefetcher {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Prefetcher").finish_non_exhaustive()
+    }
+}
+
+impl Prefetcher {
+    /// Creates a prefetcher for the given routes. `flat` should be `true` for `<FlatRoutes>`,
+    /// which collect the params of a match differently.
+    pub fn new(routes: RouteMatcher, owner: Owner, flat: bool) -> Self {
+        Self {
+            routes,
+            owner,
+            flat,
+            preloaded: Default::default(),
+        }
+    }
+
+    /// Starts loading the views and data for a path, if it matches any route.
+    pub fn prefetch(&self, path: &str) -> Option<PrefetchedRoute> {
+        let preload_owner = self.owner.child();
+        let PrefetchedMatch { route, views } =
+            preload_owner.with(|| self.routes.prefetch(path, self.flat))?;
+
+        let mut preloaded = self.preloaded.lock().or_poisoned();
+        if !preloaded.iter().any(|(preloaded, _)| preloaded == path) {
+            // views are only preloaded once; their data is kept for when they are chosen
+            for view in views {
+                Executor::spawn_local(view);
+            }
+            if preloaded.len() >= MAX_PRELOADED {
+                drop(preloaded.remove(0));
+            }
+            preloaded.push((path.to_string(), preload_owner));
+        }
+        Some(route)
+    }
+}
+
+/// The loaders started for the routes that match a path, and the views that can be preloaded.
+pub(crate) struct PrefetchedMatch {
+    route: PrefetchedRoute,
+    views: Vec<Pin<Box<dyn Future<Output = ()>>>>,
+}
+
+impl PrefetchedMatch {
+    /// Starts the loaders of a match and its children. This should be called in the owner that
+    /// holds the preloaded views.
+    pub fn new<Match>(matched: Match, flat: bool) -> Self
+    where
+        Match: MatchInterface + MatchParams,
+    {
+        let mut route = PrefetchedRoute::default();
+        let mut views = Vec::new();
+        route.add_match(matched, &mut Vec::new(), &mut views, flat);
+        Self { route, views }
+    }
+}
+
+/// The loaders started by a [`Prefetcher`] for a path.
+#[derive(Debug, Default)]
+pub(crate) struct PrefetchedRoute {
+    loaders: Vec<(RouteLoader, ParamsMap)>,
+}
+
+impl PrefetchedRoute {
+    fn add_match<Match>(
+        &mut self,
+        matched: Match,
+        parent_params: &mut Vec<ParamsMap>,
+        views: &mut Vec<Pin<Box<dyn Future<Output = ()>>>>,
+        flat: bool,
+    ) where
+        Match: MatchInterface + MatchParams,
+    {
+        // the params are collected the same way the router does, so that the loaders can find
+        // the prefetched data when the route is matched
+        parent_params.push(matched.to_params().into_iter().collect());
+        let params = if flat {
+            matched.to_params().into_iter().collect()
+        } else {
+            parent_params
+                .iter()
+                .flat_map(|params| params.clone().into_iter())
+                .collect::<ParamsMap>()
+        };
+
+        if let Some(loader) = matched.as_loader() {
+            loader.prefetch(params.clone());
+            self.loaders.push((loader.clone(), params.clone()));
+        }
+
+        let (view, child) = matched.into_view_and_child();
+        provide_context(ArcMemo::new(move |_| params.clone()));
+        views.push(Box::pin(ScopedFuture::new(
+            async move { view.preload().await },
+        )));
+
+        if let Some(child) = child {
+            self.add_match(child, parent_params, views, flat);
+        }
+    }
+
+    /// Stops loading, and drops, any data that is not wanted by another link.
+    pub fn cancel(self) {
+        for (loader, params) in self.loaders {
+            loader.cancel_prefetch(&params);
+        }
+    }
+
+    /// Keeps the data for the navigation to the path, without holding on to it for this link.
+    pub fn keep(self) {
+        for (loader, params) in self.loaders {
+            loader.release_prefetch(&params);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Prefetcher;
+    use crate::{
+        params::ParamsMap, route_data::RouteMatcher, NestedRoute, ParamSegment,
+        RouteDefs, StaticSegment,
+    };
+    use any_spawner::Executor;
+    use reactive_graph::owner::Owner;
+    use std::sync::{Arc, Mutex};
+
+    #[test]
+    fn prefetches_loaders_of_nested_matches() {
+        _ = Executor::init_futures_executor();
+        let loaded = Arc::new(Mutex::new(Vec::new()));
+        let loader = |name: &'static str| {
+            let loaded = Arc::clone(&loaded);
+            move |params: ParamsMap| {
+                loaded.lock().unwrap().push((name, params.get("id")));
+                async {}
+            }
+        };
+        let routes = RouteDefs::<_>::new(
+            NestedRoute::new(StaticSegment("users"), || ())
+                .loader(loader("users"))
+                .child((
+                    NestedRoute::new(StaticSegment(""), || ()),
+                    NestedRoute::new(ParamSegment("id"), || ())
+                        .loader(loader("user")),
+                )),
+        );
+        let prefetcher =
+            Prefetcher::new(RouteMatcher::new(routes), Owner::new(), false);
+
+        assert!(prefetcher.prefetch("/posts").is_none());
+        let route = prefetcher.prefetch("/users/1").unwrap();
+        assert_eq!(
+            *loaded.lock().unwrap(),
+            [("users", Some("1".into())), ("user", Some("1".into()))]
+        );
+
+        // already prefetched, and kept for the navigation once the link is followed
+        prefetcher.prefetch("/users/1").unwrap().cancel();
+        route.keep();
+        let route = prefetcher.prefetch("/users/1").unwrap();
+        assert_eq!(loaded.lock().unwrap().len(), 2);
+
+        // no longer wanted by any link
+        route.cancel();
+        prefetcher.prefetch("/users/1").unwrap();
+        assert_eq!(loaded.lock().unwrap().len(), 4);
+    }
+}
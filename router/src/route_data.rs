@@ -0,0 +1,384 @@
+use crate::{
+    location::Url, params::ParamsMap, prefetch::PrefetchedMatch,
+    MatchInterface, MatchNestedRoutes, MatchParams, RouteDefs, RouteMatchId,
+    TrailingSlash,
+};
+use or_poisoned::OrPoisoned;
+use reactive_graph::{signal::ArcRwSignal, traits::ReadUntracked};
+use std::{
+    any::Any,
+    fmt,
+    sync::{Arc, Mutex},
+};
+
+/// Typed data attached to a route definition, such as its title, its breadcrumb, or the role
+/// required to view it.
+///
+/// Data is set with the `data` prop of a [`Route`](crate::components::Route) or
+/// [`ParentRoute`](crate::components::ParentRoute). It can be read for the routes that match the
+/// current URL with [`use_matched_routes`](crate::hooks::use_matched_routes), and on the server
+/// from each [`RouteListing`](crate::RouteListing).
+///
+/// ```rust
+/// # use leptos::prelude::*;
+/// # use leptos_router::{components::*, hooks::use_matched_routes, path, RouteData};
+/// struct RouteMeta {
+///     title: &'static str,
+/// }
+///
+/// #[component]
+/// fn Breadcrumbs() -> impl IntoView {
+///     let routes = use_matched_routes();
+///     move || {
+///         routes
+///             .get()
+///             .into_iter()
+///             .filter_map(|route| {
+///                 let title = route.data::<RouteMeta>()?.title;
+///                 Some(view! { <a href=route.path>{title}</a> })
+///             })
+///             .collect_view()
+///     }
+/// }
+///
+/// # fn app() -> impl IntoView {
+/// view! {
+///     <Router>
+///         <Routes fallback=|| "Not found.">
+///             <ParentRoute
+///                 path=path!("/users")
+///                 view=|| view! { <Breadcrumbs/> <Outlet/> }
+///                 data=RouteData::new(RouteMeta { title: "Users" })
+///             >
+///                 <Route path=path!("") view=|| "All users"/>
+///                 <Route
+///                     path=path!(":id")
+///                     view=|| "User"
+///                     data=RouteData::new(RouteMeta { title: "User" })
+///                 />
+///             </ParentRoute>
+///         </Routes>
+///     </Router>
+/// }
+/// # }
+/// ```
+#[derive(Clone)]
+pub struct RouteData(Vec<Arc<dyn Any + Send + Sync>>);
+
+impl RouteData {
+    /// Wraps a value to attach it to a route.
+    pub fn new<T: Send + Sync + 'static>(data: T) -> Self {
+        Self(vec![Arc::new(data)])
+    }
+
+    /// Adds a value of another type, e.g., the route's breadcrumb next to its title.
+    pub fn with<T: Send + Sync + 'static>(mut self, data: T) -> Self {
+        self.0.push(Arc::new(data));
+        self
+    }
+
+    /// The attached value of type `T`, if there is one.
+    pub fn get<T: 'static>(&self) -> Option<&T> {
+        self.0.iter().find_map(|data| data.downcast_ref())
+    }
+}
+
+impl fmt::Debug for RouteData {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RouteData").finish_non_exhaustive()
+    }
+}
+
+impl PartialEq for RouteData {
+    fn eq(&self, other: &Self) -> bool {
+        self.0.len() == other.0.len()
+            && self.0.iter().zip(&other.0).all(|(a, b)| Arc::ptr_eq(a, b))
+    }
+}
+
+impl Eq for RouteData {}
+
+/// A route that matches the current URL, returned by
+/// [`use_matched_routes`](crate::hooks::use_matched_routes).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MatchedRoute {
+    /// The part of the URL matched by this route and its parents, after the router's base, e.g.,
+    /// `/users/1`.
+    pub path: String,
+    /// The params matched by this route and its parents.
+    pub params: ParamsMap,
+    route_data: Option<RouteData>,
+}
+
+impl MatchedRoute {
+    /// The data of type `T` attached to this route, if there is any.
+    pub fn data<T: 'static>(&self) -> Option<&T> {
+        self.route_data.as_ref()?.get()
+    }
+
+    /// The data attached to this route, if it has any.
+    pub fn route_data(&self) -> Option<&RouteData> {
+        self.route_data.as_ref()
+    }
+}
+
+/// Matches a path against the route definitions of a `<Routes>` or `<FlatRoutes>`, for
+/// [`use_matched_routes`](crate::hooks::use_matched_routes), for redirecting trailing slashes,
+/// for translating paths into other locales, for prefetching, and for the
+/// [`RouteListMatcher`](crate::RouteListMatcher) of the generated routes.
+#[derive(Clone)]
+pub(crate) struct RouteMatcher(Arc<dyn ErasedRoutes>);
+
+/// The route definitions, with their type erased.
+trait ErasedRoutes: Send + Sync {
+    fn match_path(&self, path: &str) -> Vec<MatchedRoute>;
+
+    fn route_ids(&self, path: &str) -> Vec<RouteMatchId>;
+
+    fn prefetch(&self, path: &str, flat: bool) -> Option<PrefetchedMatch>;
+
+    fn redirect_trailing_slash(&self, path: &str) -> Option<String>;
+
+    fn localize(&self, path: &str, locale: Option<&str>) -> Option<String>;
+}
+
+impl<Defs> ErasedRoutes for Mutex<RouteDefs<Defs>>
+where
+    Defs: MatchNestedRoutes + Send + 'static,
+{
+    fn match_path(&self, path: &str) -> Vec<MatchedRoute> {
+        let routes = self.lock().or_poisoned();
+        let mut matched_routes = Vec::new();
+        if let Some(matched) = routes.match_route(path) {
+            add_matched_route(
+                matched,
+                // the matched paths keep the locale prefix of the URL
+                &mut routes.locale_prefix(path).to_string(),
+                &mut ParamsMap::new(),
+                &mut matched_routes,
+            );
+        }
+        matched_routes
+    }
+
+    fn route_ids(&self, path: &str) -> Vec<RouteMatchId> {
+        let mut ids = Vec::new();
+        if let Some(matched) = self.lock().or_poisoned().match_route(path) {
+            add_route_id(matched, &mut ids);
+        }
+        ids
+    }
+
+    fn prefetch(&self, path: &str, flat: bool) -> Option<PrefetchedMatch> {
+        let matched = self.lock().or_poisoned().match_route(path)?;
+        Some(PrefetchedMatch::new(matched, flat))
+    }
+
+    fn redirect_trailing_slash(&self, path: &str) -> Option<String> {
+        self.lock().or_poisoned().redirect_trailing_slash(path)
+    }
+
+    fn localize(&self, path: &str, locale: Option<&str>) -> Option<String> {
+        let params = self.match_path(path).pop()?.params;
+        self.lock()
+            .or_poisoned()
+            .localize_path(path, &params, locale)
+    }
+}
+
+impl fmt::Debug for RouteMatcher {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RouteMatcher").finish_non_exhaustive()
+    }
+}
+
+impl RouteMatcher {
+    pub fn new<Defs>(routes: RouteDefs<Defs>) -> Self
+    where
+        Defs: MatchNestedRoutes + Send + 'static,
+    {
+        Self(Arc::new(Mutex::new(routes)))
+    }
+
+    /// The chain of routes that match a path, outermost first.
+    pub fn match_path(&self, path: &str) -> Vec<MatchedRoute> {
+        self.0.match_path(path)
+    }
+
+    /// The ids of the chain of routes that match a path, outermost first.
+    pub fn route_ids(&self, path: &str) -> Vec<RouteMatchId> {
+        self.0.route_ids(path)
+    }
+
+    /// Starts the loaders of the routes that match a path, and returns the views to preload.
+    /// `flat` should be `true` for `<FlatRoutes>`, which collect the params of a match
+    /// differently.
+    pub fn prefetch(&self, path: &str, flat: bool) -> Option<PrefetchedMatch> {
+        self.0.prefetch(path, flat)
+    }
+
+    /// The path to redirect to, if the path only matches a route with its trailing slash added
+    /// or removed, when using [`TrailingSlash::Redirect`](crate::TrailingSlash::Redirect).
+    pub fn redirect_trailing_slash(&self, path: &str) -> Option<String> {
+        self.0.redirect_trailing_slash(path)
+    }
+
+    /// The path translated into another locale, or into the default locale without a prefix if
+    /// there is no locale, if the path matches a route of a localized router.
+    pub fn localize(&self, path: &str, locale: Option<&str>) -> Option<String> {
+        self.0.localize(path, locale)
+    }
+}
+
+/// Writes the path of each URL the router commits to the way its route defines it, when using
+/// [`TrailingSlash::Redirect`]. The `<Router/>` provides this to its location provider, so that
+/// link clicks and history navigations are redirected as well as calls to `navigate`.
+#[derive(Clone, Debug, Default)]
+pub(crate) struct TrailingSlashRedirect(
+    Option<ArcRwSignal<Option<RouteMatcher>>>,
+);
+
+impl TrailingSlashRedirect {
+    pub fn new(
+        trailing_slash: TrailingSlash,
+        route_matcher: ArcRwSignal<Option<RouteMatcher>>,
+    ) -> Self {
+        Self(
+            (trailing_slash == TrailingSlash::Redirect)
+                .then_some(route_matcher),
+        )
+    }
+
+    /// Redirects the path of the URL, and returns whether it was changed.
+    pub fn apply(&self, url: &mut Url) -> bool {
+        let Some(route_matcher) = &self.0 else {
+            return false;
+        };
+        let redirect = route_matcher
+            .read_untracked()
+            .as_ref()
+            .and_then(|matcher| matcher.redirect_trailing_slash(url.path()));
+        match redirect {
+            Some(path) => {
+                url.set_path(path);
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+fn add_matched_route<Match>(
+    matched: Match,
+    path: &mut String,
+    params: &mut ParamsMap,
+    matched_routes: &mut Vec<MatchedRoute>,
+) where
+    Match: MatchInterface + MatchParams,
+{
+    path.push_str(matched.as_matched());
+    for (key, value) in matched.to_params() {
+        params.insert(key, value);
+    }
+    matched_routes.push(MatchedRoute {
+        path: path.clone(),
+        params: params.clone(),
+        route_data: matched.as_route_data().cloned(),
+    });
+
+    let (_, child) = matched.into_view_and_child();
+    if let Some(child) = child {
+        add_matched_route(child, path, params, matched_routes);
+    }
+}
+
+fn add_route_id<Match>(matched: Match, ids: &mut Vec<RouteMatchId>)
+where
+    Match: MatchInterface,
+{
+    ids.push(matched.as_id());
+    let (_, child) = matched.into_view_and_child();
+    if let Some(child) = child {
+        add_route_id(child, ids);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{RouteData, RouteMatcher};
+    use crate::{NestedRoute, ParamSegment, RouteDefs, StaticSegment};
+
+    #[test]
+    fn matches_routes_with_their_data() {
+        let routes = RouteDefs::<_>::new(
+            NestedRoute::new(StaticSegment("users"), || ())
+                .data(RouteData::new("Users"))
+                .child((
+                    NestedRoute::new(StaticSegment(""), || ()),
+                    NestedRoute::new(ParamSegment("id"), || ())
+                        .data(RouteData::new(42).with(true)),
+                )),
+        );
+        let matcher = RouteMatcher::new(routes);
+
+        assert!(matcher.match_path("/posts").is_empty());
+
+        let matched = matcher.match_path("/users/7");
+        assert_eq!(
+            matched.iter().map(|route| &route.path).collect::<Vec<_>>(),
+            ["/users", "/users/7"]
+        );
+        assert_eq!(matched[0].data::<&str>(), Some(&"Users"));
+        assert_eq!(matched[1].data::<i32>(), Some(&42));
+        assert_eq!(matched[1].data::<bool>(), Some(&true));
+        assert_eq!(matched[1].data::<&str>(), None);
+        assert_eq!(matched[1].params.get("id"), Some("7".into()));
+
+        let matched = matcher.match_path("/users");
+        assert_eq!(matched.len(), 2);
+        assert_eq!(matched[1].route_data(), None);
+    }
+
+    #[test]
+    fn generated_routes_carry_the_data_of_their_parents() {
+        let users = RouteData::new("Users");
+        let user = RouteData::new(42);
+        let routes = RouteDefs::<_>::new(
+            NestedRoute::new(StaticSegment("users"), || ())
+                .data(users.clone())
+                .child((
+                    NestedRoute::new(StaticSegment(""), || ()),
+                    NestedRoute::new(ParamSegment("id"), || ())
+                        .data(user.clone()),
+                )),
+        );
+
+        let (_, generated) = routes.generate_routes();
+        let data = generated
+            .into_iter()
+            .map(|route| route.data().to_vec())
+            .collect::<Vec<_>>();
+        assert_eq!(data, [vec![users.clone()], vec![users, user]]);
+    }
+
+    #[test]
+    fn matches_paths_to_the_ids_of_generated_routes() {
+        let routes = RouteDefs::<_>::new(
+            NestedRoute::new(StaticSegment("posts"), || ()).child((
+                NestedRoute::new(ParamSegment("id").parses::<u64>(), || ()),
+                NestedRoute::new(ParamSegment("slug"), || ()),
+            )),
+        );
+        let (_, generated) = routes.generate_routes();
+        let ids = generated
+            .into_iter()
+            .map(|route| route.ids().to_vec())
+            .collect::<Vec<_>>();
+        let matcher = RouteMatcher::new(routes);
+
+        assert_eq!(ids.len(), 2);
+        assert_eq!(matcher.route_ids("/posts/7"), ids[0]);
+        assert_eq!(matcher.route_ids("/posts/hello"), ids[1]);
+        assert!(matcher.route_ids("/users").is_empty());
+    }
+}
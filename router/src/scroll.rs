@@ -0,0 +1,422 @@
+use leptos::{component, ev, prelude::*};
+use or_poisoned::OrPoisoned;
+use serde::{Deserialize, Serialize};
+use std::{
+    cell::RefCell,
+    sync::{Arc, Mutex},
+};
+use tachys::dom::{document, window};
+use wasm_bindgen::JsValue;
+use web_sys::ScrollRestoration as BrowserScrollRestoration;
+
+/// How long to keep trying to restore a scroll position, while the page is not yet long enough
+/// to reach it.
+const RESTORE_TIMEOUT_MS: f64 = 3000.0;
+
+/// The number of history entries whose scroll positions are kept.
+const MAX_ENTRIES: usize = 100;
+
+/// The `sessionStorage` key under which the scroll positions are kept across reloads.
+const STORAGE_KEY: &str = "leptos_router_scroll_positions";
+
+/// The property of `history.state` that holds the id of the history entry.
+const ENTRY_ID: &str = "leptosEntryId";
+
+/// The property of `history.state` that holds the state set with
+/// [`NavigateOptions::state`](crate::NavigateOptions::state).
+const ENTRY_STATE: &str = "state";
+
+/// Restores the scroll position of the window, and of any other scrollable containers, when
+/// going back or forward in the history.
+///
+/// Browsers restore the window's scroll position as soon as the URL changes, which is usually
+/// before the route being returned to has loaded its data. When this component is used inside a
+/// [`<Router/>`](crate::components::Router), the router records the scroll positions of each
+/// history entry when navigating away from it, and restores them once the routes matched for the
+/// entry have loaded. New entries are still scrolled to the top, or to the element targeted by
+/// the hash.
+///
+/// Each history entry is identified by an id that the router stores in its `history.state`,
+/// next to the state set with [`NavigateOptions::state`](crate::NavigateOptions::state). The
+/// positions are kept in `sessionStorage`, so that they are also restored after the page has
+/// been reloaded, or left and returned to.
+///
+/// ```rust
+/// # use leptos::prelude::*;
+/// # use leptos_router::{components::*, path};
+/// # fn app() -> impl IntoView {
+/// view! {
+///     <Router>
+///         <ScrollRestoration containers=vec!["#sidebar".to_string()]/>
+///         <Routes fallback=|| "Not found.">
+///             <Route path=path!("/") view=|| "Home"/>
+///         </Routes>
+///     </Router>
+/// }
+/// # }
+/// ```
+#[component]
+pub fn ScrollRestoration(
+    /// CSS selectors for scrollable containers whose scroll positions should be restored along
+    /// with the window's.
+    #[prop(optional, into)]
+    containers: Vec<String>,
+) -> impl IntoView {
+    let restorer = use_context::<ScrollRestorer>()
+        .expect("<ScrollRestoration/> should be used inside a <Router/>");
+
+    Effect::new(move |_| {
+        restorer.enable(containers.clone());
+        set_browser_restoration(BrowserScrollRestoration::Manual);
+        // the page was reloaded, or returned to from another site
+        restorer.restore(|| {});
+
+        // the positions of the current entry are kept when the page is left
+        let pagehide = window_event_listener(ev::pagehide, {
+            let restorer = restorer.clone();
+            move |_| restorer.leave(current_entry())
+        });
+        let restorer = restorer.clone();
+        on_cleanup(move || {
+            pagehide.remove();
+            restorer.disable();
+            set_browser_restoration(BrowserScrollRestoration::Auto);
+        });
+    });
+}
+
+fn set_browser_restoration(mode: BrowserScrollRestoration) {
+    if let Err(e) = window()
+        .history()
+        .and_then(|history| history.set_scroll_restoration(mode))
+    {
+        leptos::logging::error!("Failed to set scroll restoration: {e:?}");
+    }
+}
+
+thread_local! {
+    /// The id of the history entry that is shown, which is no longer in `history.state` once the
+    /// browser has moved to another entry.
+    static CURRENT_ENTRY: RefCell<Option<String>> = const { RefCell::new(None) };
+}
+
+/// The id of the history entry that is shown.
+pub(crate) fn current_entry() -> Option<String> {
+    CURRENT_ENTRY.with(|current| current.borrow().clone())
+}
+
+/// The `history.state` for a new history entry with the given state, which becomes the current
+/// entry.
+pub(crate) fn push_entry_state(state: &JsValue) -> JsValue {
+    let id = format!(
+        "{:x}-{:x}",
+        js_sys::Date::now() as u64,
+        (js_sys::Math::random() * f64::from(u32::MAX)) as u32
+    );
+    let entry = entry_state(&id, state);
+    CURRENT_ENTRY.with(|current| *current.borrow_mut() = Some(id));
+    entry
+}
+
+/// The `history.state` for replacing the state of the current history entry, which keeps its id.
+pub(crate) fn replace_entry_state(state: &JsValue) -> JsValue {
+    match current_entry() {
+        Some(id) => entry_state(&id, state),
+        None => push_entry_state(state),
+    }
+}
+
+/// Makes the history entry the browser has moved to the current entry, and returns the id of the
+/// entry that was left.
+///
+/// An entry that was not added by the router, like the first one or one added by following a
+/// link to an anchor, is given an id by replacing its state.
+pub(crate) fn enter_history_entry() -> Option<String> {
+    let id = window().history().and_then(|history| {
+        let state = history.state()?;
+        let id = js_sys::Reflect::get(&state, &ENTRY_ID.into())
+            .ok()
+            .and_then(|id| id.as_string());
+        match id {
+            Some(id) => Ok(id),
+            None => {
+                let entry = push_entry_state(&state);
+                history.replace_state(&entry, "")?;
+                Ok(current_entry().unwrap_or_default())
+            }
+        }
+    });
+    match id {
+        Ok(id) => {
+            CURRENT_ENTRY.with(|current| current.borrow_mut().replace(id))
+        }
+        Err(e) => {
+            leptos::logging::error!("Failed to read the history state: {e:?}");
+            None
+        }
+    }
+}
+
+fn entry_state(id: &str, state: &JsValue) -> JsValue {
+    let entry = js_sys::Object::new();
+    _ = js_sys::Reflect::set(&entry, &ENTRY_ID.into(), &id.into());
+    _ = js_sys::Reflect::set(&entry, &ENTRY_STATE.into(), state);
+    entry.into()
+}
+
+/// Records and restores the scroll positions of history entries, while a [`ScrollRestoration`]
+/// is mounted.
+///
+/// This is provided by the `<Router/>`, and used by the location providers that are backed by the
+/// browser's history.
+#[derive(Clone, Debug, Default)]
+pub(crate) struct ScrollRestorer(Arc<Mutex<RestorerState>>);
+
+#[derive(Debug, Default)]
+struct RestorerState {
+    /// The selectors of the restored containers, or `None` if scroll restoration is disabled.
+    containers: Option<Vec<String>>,
+    /// The recorded positions, which are read from `sessionStorage` when first needed.
+    positions: Option<ScrollPositions>,
+    /// Incremented on every navigation, so that restoring an entry stops when it is left.
+    generation: usize,
+}
+
+impl ScrollRestorer {
+    fn enable(&self, containers: Vec<String>) {
+        self.0.lock().or_poisoned().containers = Some(containers);
+    }
+
+    fn disable(&self) {
+        let mut state = self.0.lock().or_poisoned();
+        state.containers = None;
+        state.generation += 1;
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.0.lock().or_poisoned().containers.is_some()
+    }
+
+    /// Records the scroll positions of the history entry with the given id, before it is left.
+    pub fn leave(&self, entry: Option<String>) {
+        let mut state = self.0.lock().or_poisoned();
+        let Some(containers) = &state.containers else {
+            return;
+        };
+        let scroll = EntryScroll::current(containers);
+        state.generation += 1;
+        if let Some(entry) = entry {
+            let positions = state.positions.get_or_insert_with(load_positions);
+            positions.record(entry, scroll);
+            save_positions(positions);
+        }
+    }
+
+    /// Restores the scroll positions recorded for the current entry, or calls `fallback` if none
+    /// were recorded.
+    pub fn restore(&self, fallback: impl FnOnce()) {
+        let mut state = self.0.lock().or_poisoned();
+        if state.containers.is_none() {
+            return;
+        }
+        let scroll = current_entry().and_then(|entry| {
+            state
+                .positions
+                .get_or_insert_with(load_positions)
+                .get(&entry)
+                .cloned()
+        });
+        match scroll {
+            Some(scroll) => {
+                let generation = state.generation;
+                drop(state);
+                self.scroll_until_restored(
+                    scroll,
+                    generation,
+                    js_sys::Date::now() + RESTORE_TIMEOUT_MS,
+                );
+            }
+            None => {
+                drop(state);
+                fallback();
+            }
+        }
+    }
+
+    fn scroll_until_restored(
+        &self,
+        scroll: EntryScroll,
+        generation: usize,
+        deadline: f64,
+    ) {
+        if self.0.lock().or_poisoned().generation != generation {
+            return;
+        }
+        // parts of the page may still be loading, so it can be too short to scroll all the way
+        if !scroll.apply() && js_sys::Date::now() < deadline {
+            let this = self.clone();
+            request_animation_frame(move || {
+                this.scroll_until_restored(scroll, generation, deadline)
+            });
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+struct ScrollPosition {
+    x: f64,
+    y: f64,
+}
+
+impl ScrollPosition {
+    fn reached(&self, x: f64, y: f64) -> bool {
+        (self.x - x).abs() < 1.0 && (self.y - y).abs() < 1.0
+    }
+}
+
+/// The scroll positions of the window and the restored containers.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+struct EntryScroll {
+    window: ScrollPosition,
+    containers: Vec<(String, ScrollPosition)>,
+}
+
+impl EntryScroll {
+    fn current(containers: &[String]) -> Self {
+        let window = window();
+        Self {
+            window: ScrollPosition {
+                x: window.scroll_x().unwrap_or_default(),
+                y: window.scroll_y().unwrap_or_default(),
+            },
+            containers: containers
+                .iter()
+                .filter_map(|selector| {
+                    let el = document().query_selector(selector).ok()??;
+                    Some((
+                        selector.clone(),
+                        ScrollPosition {
+                            x: el.scroll_left().into(),
+                            y: el.scroll_top().into(),
+                        },
+                    ))
+                })
+                .collect(),
+        }
+    }
+
+    /// Scrolls to the recorded positions, returning whether all of them were reached.
+    fn apply(&self) -> bool {
+        let window = window();
+        window.scroll_to_with_x_and_y(self.window.x, self.window.y);
+        let mut reached = self.window.reached(
+            window.scroll_x().unwrap_or_default(),
+            window.scroll_y().unwrap_or_default(),
+        );
+        for (selector, position) in &self.containers {
+            match document().query_selector(selector) {
+                Ok(Some(el)) => {
+                    el.scroll_to_with_x_and_y(position.x, position.y);
+                    reached &= position.reached(
+                        el.scroll_left().into(),
+                        el.scroll_top().into(),
+                    );
+                }
+                // the container may not have been rendered yet
+                _ => reached = false,
+            }
+        }
+        reached
+    }
+}
+
+/// The scroll positions recorded for history entries, by the id stored in their `history.state`,
+/// from the least to the most recently recorded.
+#[derive(Debug, Default, PartialEq, Serialize, Deserialize)]
+struct ScrollPositions(Vec<(String, EntryScroll)>);
+
+impl ScrollPositions {
+    fn record(&mut self, entry: String, scroll: EntryScroll) {
+        self.0.retain(|(id, _)| *id != entry);
+        if self.0.len() >= MAX_ENTRIES {
+            self.0.remove(0);
+        }
+        self.0.push((entry, scroll));
+    }
+
+    fn get(&self, entry: &str) -> Option<&EntryScroll> {
+        self.0
+            .iter()
+            .find_map(|(id, scroll)| (id == entry).then_some(scroll))
+    }
+}
+
+fn load_positions() -> ScrollPositions {
+    window()
+        .session_storage()
+        .ok()
+        .flatten()
+        .and_then(|storage| storage.get_item(STORAGE_KEY).ok().flatten())
+        .and_then(|json| serde_json::from_str(&json).ok())
+        .unwrap_or_default()
+}
+
+fn save_positions(positions: &ScrollPositions) {
+    let Ok(Some(storage)) = window().session_storage() else {
+        return;
+    };
+    if let Ok(json) = serde_json::to_string(positions) {
+        // the storage may be full, or disabled
+        _ = storage.set_item(STORAGE_KEY, &json);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{EntryScroll, ScrollPosition, ScrollPositions, MAX_ENTRIES};
+
+    fn scrolled_to(y: f64) -> EntryScroll {
+        EntryScroll {
+            window: ScrollPosition { x: 0.0, y },
+            containers: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn positions_are_kept_per_history_entry() {
+        let mut positions = ScrollPositions::default();
+        positions.record("a".into(), scrolled_to(800.0));
+        positions.record("b".into(), scrolled_to(50.0));
+        positions.record("a".into(), scrolled_to(900.0));
+        assert_eq!(positions.get("a"), Some(&scrolled_to(900.0)));
+        assert_eq!(positions.get("b"), Some(&scrolled_to(50.0)));
+        assert_eq!(positions.get("c"), None);
+
+        // the least recently recorded entries are dropped first
+        for i in 0..MAX_ENTRIES - 1 {
+            positions.record(i.to_string(), scrolled_to(i as f64));
+        }
+        assert_eq!(positions.get("b"), None);
+        assert_eq!(positions.get("a"), Some(&scrolled_to(900.0)));
+    }
+
+    #[test]
+    fn positions_round_trip_through_json() {
+        let mut positions = ScrollPositions::default();
+        positions.record(
+            "a".into(),
+            EntryScroll {
+                window: ScrollPosition { x: 0.0, y: 120.5 },
+                containers: vec![(
+                    "#sidebar".into(),
+                    ScrollPosition { x: 0.0, y: 40.0 },
+                )],
+            },
+        );
+        let json = serde_json::to_string(&positions).unwrap();
+        assert_eq!(
+            serde_json::from_str::<ScrollPositions>(&json).unwrap(),
+            positions
+        );
+    }
+}
@@ -0,0 +1,414 @@
+use crate::{
+    location::Url,
+    static_routes::{join_segments, StaticParamsMap, StaticPath},
+    ExpandOptionals, Method, PathSegment, RouteList, RouteListing,
+};
+use std::{borrow::Cow, collections::HashSet, fmt, fmt::Write, sync::Arc};
+
+/// How often the page at a URL is likely to change.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ChangeFrequency {
+    /// The page changes every time it is accessed.
+    Always,
+    /// The page changes about every hour.
+    Hourly,
+    /// The page changes about every day.
+    Daily,
+    /// The page changes about every week.
+    Weekly,
+    /// The page changes about every month.
+    Monthly,
+    /// The page changes about every year.
+    Yearly,
+    /// The page is archived, and does not change.
+    Never,
+}
+
+impl ChangeFrequency {
+    /// The value of the `<changefreq>` tag.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ChangeFrequency::Always => "always",
+            ChangeFrequency::Hourly => "hourly",
+            ChangeFrequency::Daily => "daily",
+            ChangeFrequency::Weekly => "weekly",
+            ChangeFrequency::Monthly => "monthly",
+            ChangeFrequency::Yearly => "yearly",
+            ChangeFrequency::Never => "never",
+        }
+    }
+}
+
+/// How the URLs of a route are listed in the sitemap.
+///
+/// These are attached to a route as [`RouteData`](crate::RouteData), and also apply to its child
+/// routes unless they attach their own.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SitemapOptions {
+    /// When the page was last modified, in the
+    /// [W3C Datetime](https://www.w3.org/TR/NOTE-datetime) format, e.g., `2024-05-01`.
+    pub lastmod: Option<String>,
+    /// How often the page is likely to change.
+    pub changefreq: Option<ChangeFrequency>,
+    /// The priority of the page relative to the other pages of the site, from `0.0` to `1.0`.
+    pub priority: Option<f32>,
+    /// Leaves the route out of the sitemap.
+    pub exclude: bool,
+    /// Leaves the route out of the sitemap, and disallows crawling it in `robots.txt`.
+    pub disallow: bool,
+}
+
+/// A URL listed in the sitemap.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SitemapEntry {
+    /// The full URL of the page.
+    pub loc: String,
+    /// When the page was last modified.
+    pub lastmod: Option<String>,
+    /// How often the page is likely to change.
+    pub changefreq: Option<ChangeFrequency>,
+    /// The priority of the page relative to the other pages of the site.
+    pub priority: Option<f32>,
+}
+
+/// Generates a `sitemap.xml` and a `robots.txt` from the routes of an app.
+///
+/// The routes are read from a [`RouteList`], which can be generated on the server with
+/// [`RouteList::generate`], or with the `generate_route_list` function of an integration. Routes
+/// without params are always listed. Routes with params are listed once for each set of params
+/// returned by the [`prerender_params`](crate::static_routes::StaticRoute::prerender_params) of
+/// their [`SsrMode::Static`](crate::SsrMode::Static) mode, and left out otherwise.
+///
+/// The `lastmod`, `changefreq` and `priority` of each URL are taken from the [`SitemapOptions`]
+/// attached to its route as [`RouteData`](crate::RouteData):
+///
+/// ```rust
+/// # use leptos::prelude::*;
+/// # use leptos_router::{components::*, path, sitemap::{ChangeFrequency, SitemapOptions}, RouteData};
+/// # fn app() -> impl IntoView {
+/// view! {
+///     <Router>
+///         <Routes fallback=|| "Not found.">
+///             <Route
+///                 path=path!("/")
+///                 view=|| "Home"
+///                 data=RouteData::new(SitemapOptions {
+///                     changefreq: Some(ChangeFrequency::Daily),
+///                     priority: Some(1.0),
+///                     ..Default::default()
+///                 })
+///             />
+///             <Route
+///                 path=path!("/admin")
+///                 view=|| "Admin"
+///                 data=RouteData::new(SitemapOptions {
+///                     disallow: true,
+///                     ..Default::default()
+///                 })
+///             />
+///         </Routes>
+///     </Router>
+/// }
+/// # }
+/// ```
+///
+/// The files are then generated on the server from the app's route list:
+///
+/// ```rust
+/// # use leptos_router::{sitemap::Sitemap, RouteList};
+/// # async fn sitemap(routes: RouteList) {
+/// let sitemap = Sitemap::new("https://example.com");
+/// let xml = sitemap.generate(&routes).await;
+/// let robots = sitemap.robots_txt(&routes);
+/// # }
+/// ```
+#[derive(Clone)]
+#[allow(clippy::type_complexity)]
+pub struct Sitemap {
+    base_url: String,
+    options: Arc<dyn Fn(&RouteListing) -> SitemapOptions + Send + Sync>,
+}
+
+impl fmt::Debug for Sitemap {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Sitemap")
+            .field("base_url", &self.base_url)
+            .finish_non_exhaustive()
+    }
+}
+
+impl Sitemap {
+    /// Creates a sitemap for the site at the given URL, e.g., `https://example.com`. The
+    /// [`SitemapOptions`] of each route are read from its [`RouteData`](crate::RouteData).
+    pub fn new(base_url: impl Into<String>) -> Self {
+        let mut base_url = base_url.into();
+        while base_url.ends_with('/') {
+            base_url.pop();
+        }
+        Self {
+            base_url,
+            options: Arc::new(|route| {
+                route
+                    .find_data::<SitemapOptions>()
+                    .cloned()
+                    .unwrap_or_default()
+            }),
+        }
+    }
+
+    /// Sets how the options of each route are found, e.g., to read them from another type of
+    /// route data.
+    pub fn with_options(
+        mut self,
+        options: impl Fn(&RouteListing) -> SitemapOptions + Send + Sync + 'static,
+    ) -> Self {
+        self.options = Arc::new(options);
+        self
+    }
+
+    /// Lists the URLs of the routes, loading the prerendered params of those that have any.
+    pub async fn entries(&self, routes: &RouteList) -> Vec<SitemapEntry> {
+        let mut seen = HashSet::new();
+        let mut entries = Vec::new();
+        for route in routes.iter() {
+            if !route.methods().any(|method| method == Method::Get) {
+                continue;
+            }
+            let options = (self.options)(route);
+            if options.exclude || options.disallow {
+                continue;
+            }
+
+            let params = match route.static_route() {
+                Some(static_route) => static_route
+                    .to_prerendered_params()
+                    .await
+                    .map(encode_params),
+                None => None,
+            };
+            for path in route.path().to_vec().expand_optionals() {
+                for path in StaticPath::new(path).into_paths(params.clone()) {
+                    let path = path.as_ref();
+                    let loc = format!(
+                        "{}{}",
+                        self.base_url,
+                        if path.is_empty() { "/" } else { path }
+                    );
+                    if seen.insert(loc.clone()) {
+                        entries.push(SitemapEntry {
+                            loc,
+                            lastmod: options.lastmod.clone(),
+                            changefreq: options.changefreq,
+                            priority: options.priority,
+                        });
+                    }
+                }
+            }
+        }
+        entries
+    }
+
+    /// Generates the contents of `sitemap.xml`.
+    pub async fn generate(&self, routes: &RouteList) -> String {
+        let mut xml = String::from(
+            "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<urlset \
+             xmlns=\"http://www.sitemaps.org/schemas/sitemap/0.9\">\n",
+        );
+        for entry in self.entries(routes).await {
+            _ = write!(xml, "  <url>\n    <loc>{}</loc>\n", escape(&entry.loc));
+            if let Some(lastmod) = &entry.lastmod {
+                _ = writeln!(xml, "    <lastmod>{}</lastmod>", escape(lastmod));
+            }
+            if let Some(changefreq) = entry.changefreq {
+                _ = writeln!(
+                    xml,
+                    "    <changefreq>{}</changefreq>",
+                    changefreq.as_str()
+                );
+            }
+            if let Some(priority) = entry.priority {
+                _ = writeln!(
+                    xml,
+                    "    <priority>{:.1}</priority>",
+                    priority.clamp(0.0, 1.0)
+                );
+            }
+            xml.push_str("  </url>\n");
+        }
+        xml.push_str("</urlset>\n");
+        xml
+    }
+
+    /// Generates the contents of `robots.txt`, which disallows the routes whose
+    /// [`SitemapOptions`] set `disallow`, and points to `sitemap.xml` at the root of the site.
+    pub fn robots_txt(&self, routes: &RouteList) -> String {
+        let mut robots = String::from("User-agent: *\n");
+        let mut seen = HashSet::new();
+        for route in routes.iter() {
+            if !(self.options)(route).disallow {
+                continue;
+            }
+            for path in route.path().to_vec().expand_optionals() {
+                let pattern = robots_pattern(&path);
+                if seen.insert(pattern.clone()) {
+                    _ = writeln!(robots, "Disallow: {pattern}");
+                }
+            }
+        }
+        if seen.is_empty() {
+            robots.push_str("Allow: /\n");
+        }
+        _ = write!(robots, "\nSitemap: {}/sitemap.xml\n", self.base_url);
+        robots
+    }
+}
+
+/// The path of a route, with a `*` wildcard for each param.
+fn robots_pattern(segments: &[PathSegment]) -> String {
+    join_segments(segments, |_| Cow::Borrowed("*"))
+}
+
+/// Percent-encodes the prerendered param values, keeping the `/` between the segments of a
+/// splat.
+fn encode_params(params: StaticParamsMap) -> StaticParamsMap {
+    params
+        .into_iter()
+        .map(|(key, values)| {
+            let values = values
+                .iter()
+                .map(|value| {
+                    value
+                        .split('/')
+                        .map(Url::escape_segment)
+                        .collect::<Vec<_>>()
+                        .join("/")
+                })
+                .collect();
+            (key, values)
+        })
+        .collect()
+}
+
+fn escape(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ChangeFrequency, Sitemap, SitemapOptions};
+    use crate::{
+        static_routes::{StaticParamsMap, StaticRoute},
+        Method, PathSegment, RouteData, RouteList, RouteListing, SsrMode,
+    };
+    use futures::executor::block_on;
+
+    fn listing(
+        path: impl IntoIterator<Item = PathSegment>,
+        mode: SsrMode,
+        options: Option<SitemapOptions>,
+    ) -> RouteListing {
+        RouteListing::new(path, mode, [Method::Get], [])
+            .with_data(options.map(RouteData::new))
+    }
+
+    fn routes() -> RouteList {
+        let posts = StaticRoute::new().prerender_params(|| async {
+            let mut params = StaticParamsMap::new();
+            params.insert(
+                "id",
+                vec!["1".into(), "a&b c".into(), "hello-world.v2".into()],
+            );
+            params
+        });
+        RouteList::from(vec![
+            listing(
+                [PathSegment::Unit],
+                SsrMode::Async,
+                Some(SitemapOptions {
+                    changefreq: Some(ChangeFrequency::Daily),
+                    priority: Some(1.0),
+                    ..Default::default()
+                }),
+            ),
+            listing(
+                [
+                    PathSegment::Static("posts".into()),
+                    PathSegment::Param("id".into()),
+                ],
+                SsrMode::Static(posts),
+                Some(SitemapOptions {
+                    lastmod: Some("2024-05-01".into()),
+                    ..Default::default()
+                }),
+            ),
+            // dynamic, without any prerendered params
+            listing(
+                [
+                    PathSegment::Static("users".into()),
+                    PathSegment::Param("id".into()),
+                ],
+                SsrMode::Async,
+                None,
+            ),
+            listing(
+                [
+                    PathSegment::Static("admin".into()),
+                    PathSegment::OptionalParam("section".into()),
+                ],
+                SsrMode::Async,
+                Some(SitemapOptions {
+                    disallow: true,
+                    ..Default::default()
+                }),
+            ),
+        ])
+    }
+
+    #[test]
+    fn lists_static_paths_and_prerendered_params() {
+        let sitemap = Sitemap::new("https://example.com/");
+        let xml = block_on(sitemap.generate(&routes()));
+        assert_eq!(
+            xml,
+            "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n\
+             <urlset xmlns=\"http://www.sitemaps.org/schemas/sitemap/0.9\">\n  \
+             <url>\n    <loc>https://example.com/</loc>\n    \
+             <changefreq>daily</changefreq>\n    <priority>1.0</priority>\n  \
+             </url>\n  \
+             <url>\n    <loc>https://example.com/posts/1</loc>\n    \
+             <lastmod>2024-05-01</lastmod>\n  </url>\n  \
+             <url>\n    <loc>https://example.com/posts/a%26b%20c</loc>\n    \
+             <lastmod>2024-05-01</lastmod>\n  </url>\n  \
+             <url>\n    <loc>https://example.com/posts/hello-world.v2</loc>\n    \
+             <lastmod>2024-05-01</lastmod>\n  </url>\n\
+             </urlset>\n"
+        );
+    }
+
+    #[test]
+    fn disallows_routes_in_robots_txt() {
+        let sitemap = Sitemap::new("https://example.com");
+        let robots = sitemap.robots_txt(&routes());
+        let disallowed = robots
+            .lines()
+            .filter(|line| line.starts_with("Disallow"))
+            .collect::<Vec<_>>();
+        assert_eq!(disallowed.len(), 2);
+        assert!(disallowed.contains(&"Disallow: /admin"));
+        assert!(disallowed.contains(&"Disallow: /admin/*"));
+        assert!(
+            robots.ends_with("\nSitemap: https://example.com/sitemap.xml\n")
+        );
+    }
+}
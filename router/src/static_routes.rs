@@ -1,11 +1,16 @@
-use crate::{hooks::RawParamsMap, params::ParamsMap, PathSegment};
+use crate::{
+    hooks::RawParamsMap, params::ParamsMap, ExpandOptionals, Method,
+    PathSegment, RouteList,
+};
 use futures::{channel::oneshot, stream, Stream, StreamExt};
 use leptos::task::spawn;
 use reactive_graph::{owner::Owner, traits::GetUntracked};
 use std::{
+    borrow::Cow,
     fmt::{Debug, Display},
     future::Future,
     ops::Deref,
+    path::{Path, PathBuf},
     pin::Pin,
     sync::Arc,
 };
@@ -216,7 +221,8 @@ impl StaticPath {
         for segment in &self.segments {
             match segment {
                 Unit => {}
-                Static(s) => {
+                Static(_) | Localized(_) => {
+                    let s = segment.as_raw_str();
                     paths = paths
                         .into_iter()
                         .map(|p| {
@@ -232,7 +238,7 @@ impl StaticPath {
                         })
                         .collect::<Vec<_>>();
                 }
-                Param(name) | Splat(name) => {
+                Param(name) | ConstrainedParam(name, _) | Splat(name) => {
                     let mut new_paths = vec![];
                     if let Some(params) = params.as_ref() {
                         for path in paths {
@@ -378,6 +384,258 @@ impl ResolvedStaticPath {
     }
 }
 
+/// Exports every page of an app to static files, so that it can be deployed without a server.
+///
+/// This is used by the `export_static_site` function of an integration. Unlike the
+/// [`SsrMode::Static`](crate::SsrMode::Static) routes generated when the server starts, an export
+/// renders every route in the [`RouteList`]: routes without params are rendered once, and routes
+/// with params are rendered for each set of params given with [`StaticExport::params`], or
+/// returned by their `prerender_params`.
+///
+/// ```rust
+/// # use leptos_router::static_routes::{StaticExport, StaticParamsMap};
+/// let mut posts = StaticParamsMap::new();
+/// posts.insert("id", vec!["1".to_string(), "2".to_string()]);
+/// let export = StaticExport::new("dist").params("/posts/:id", posts);
+/// ```
+#[derive(Debug, Clone)]
+pub struct StaticExport {
+    out_dir: PathBuf,
+    params: Vec<(String, StaticParamsMap)>,
+}
+
+impl StaticExport {
+    /// Creates an export into the given directory.
+    pub fn new(out_dir: impl Into<PathBuf>) -> Self {
+        Self {
+            out_dir: out_dir.into(),
+            params: Vec::new(),
+        }
+    }
+
+    /// Sets the params to render a route with, which is identified by its path as it is defined,
+    /// e.g., `/posts/:id`. These are used instead of the params returned by the route's
+    /// `prerender_params`.
+    pub fn params(
+        mut self,
+        route: impl Into<String>,
+        params: StaticParamsMap,
+    ) -> Self {
+        self.params.push((route.into(), params));
+        self
+    }
+
+    /// The directory the pages are written to.
+    pub fn out_dir(&self) -> &Path {
+        &self.out_dir
+    }
+
+    /// Lists the paths of the pages to render, and the routes that cannot be exported.
+    pub async fn plan(&self, routes: &RouteList) -> ExportPlan {
+        let mut plan = ExportPlan::default();
+        for route in routes.iter() {
+            let pattern = route_pattern(route.path());
+            if !route.methods().any(|method| method == Method::Get) {
+                plan.unsupported.push(UnsupportedPage {
+                    path: pattern,
+                    reason: UnsupportedReason::NotGet,
+                });
+                continue;
+            }
+
+            let params =
+                match self.params.iter().find(|(route, _)| *route == pattern) {
+                    Some((_, params)) => Some(params.clone()),
+                    None => match route.static_route() {
+                        Some(static_route) => {
+                            static_route.to_prerendered_params().await
+                        }
+                        None => None,
+                    },
+                };
+            let params = params.map(|params| {
+                checked_params(route.path(), params, |value| {
+                    plan.unsupported.push(UnsupportedPage {
+                        path: pattern.clone(),
+                        reason: UnsupportedReason::InvalidParam(value),
+                    })
+                })
+            });
+            for path in route.path().to_vec().expand_optionals() {
+                let has_params = path.iter().any(|segment| {
+                    matches!(
+                        segment,
+                        PathSegment::Param(_)
+                            | PathSegment::ConstrainedParam(..)
+                            | PathSegment::Splat(_)
+                    )
+                });
+                let paths =
+                    StaticPath::new(path.clone()).into_paths(params.clone());
+                if has_params && paths.is_empty() {
+                    plan.unsupported.push(UnsupportedPage {
+                        path: route_pattern(&path),
+                        reason: UnsupportedReason::MissingParams,
+                    });
+                }
+                for path in paths {
+                    if !plan.paths.contains(&path) {
+                        plan.paths.push(path);
+                    }
+                }
+            }
+        }
+        plan
+    }
+}
+
+/// Leaves out the param values that cannot be written to a file inside the output directory: a
+/// `.` or `..` segment, a `\`, or a `/` in a param that is not a splat.
+fn checked_params(
+    segments: &[PathSegment],
+    params: StaticParamsMap,
+    mut invalid: impl FnMut(String),
+) -> StaticParamsMap {
+    params
+        .into_iter()
+        .map(|(key, values)| {
+            let is_splat = segments.iter().any(|segment| {
+                matches!(segment, PathSegment::Splat(name) if *name == key)
+            });
+            let values = values
+                .into_iter()
+                .filter(|value| {
+                    let valid = !value.contains('\\')
+                        && (is_splat || !value.contains('/'))
+                        && value
+                            .split('/')
+                            .all(|segment| segment != "." && segment != "..");
+                    if !valid {
+                        invalid(value.clone());
+                    }
+                    valid
+                })
+                .collect();
+            (key, values)
+        })
+        .collect()
+}
+
+/// The path of a route as it is defined, e.g., `/posts/:id`.
+fn route_pattern(segments: &[PathSegment]) -> String {
+    join_segments(segments, |segment| match segment {
+        PathSegment::OptionalParam(s) => format!(":{s}?").into(),
+        PathSegment::Splat(s) => format!("*{s}").into(),
+        PathSegment::Param(s) | PathSegment::ConstrainedParam(s, _) => {
+            format!(":{s}").into()
+        }
+        _ => Cow::Borrowed(""),
+    })
+}
+
+/// Joins the segments of a route into a path, writing each param, optional param or splat with
+/// `param`.
+pub(crate) fn join_segments<'a>(
+    segments: &'a [PathSegment],
+    param: impl Fn(&'a PathSegment) -> Cow<'a, str>,
+) -> String {
+    let mut path = String::new();
+    for segment in segments {
+        let segment = match segment {
+            PathSegment::Unit => continue,
+            PathSegment::Static(s) if s.is_empty() => continue,
+            PathSegment::Static(s) => Cow::Borrowed(s.as_ref()),
+            segment => param(segment),
+        };
+        if !segment.starts_with('/') {
+            path.push('/');
+        }
+        path.push_str(&segment);
+    }
+    if path.is_empty() {
+        path.push('/');
+    }
+    path
+}
+
+/// The pages to render in a [`StaticExport`].
+#[derive(Debug, Clone, Default)]
+pub struct ExportPlan {
+    /// The paths of the pages to render.
+    pub paths: Vec<ResolvedStaticPath>,
+    /// The routes that cannot be exported.
+    pub unsupported: Vec<UnsupportedPage>,
+}
+
+/// The pages written by a [`StaticExport`], and the ones that were left out.
+#[derive(Debug, Clone, Default)]
+pub struct ExportReport {
+    /// The paths of the pages that were written.
+    pub pages: Vec<String>,
+    /// The routes and pages that were left out, because they need a server.
+    pub unsupported: Vec<UnsupportedPage>,
+}
+
+impl Display for ExportReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "Exported {} pages.", self.pages.len())?;
+        if !self.unsupported.is_empty() {
+            writeln!(f, "Unsupported pages:")?;
+            for page in &self.unsupported {
+                writeln!(f, "  {}: {}", page.path, page.reason)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// A route or page that a [`StaticExport`] left out.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnsupportedPage {
+    /// The path of the page, or the path of the route as it is defined if it was not rendered.
+    pub path: String,
+    /// Why the page was left out.
+    pub reason: UnsupportedReason,
+}
+
+/// Why a [`StaticExport`] left out a page.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum UnsupportedReason {
+    /// The route does not handle `GET` requests.
+    NotGet,
+    /// The route has params, but no params were given for it.
+    MissingParams,
+    /// This param value would be written outside of the output directory, or over another page,
+    /// e.g., `..`, or a `/` in a param that is not a splat.
+    InvalidParam(String),
+    /// The page links to the server function at this path, e.g., as the `action` of a form.
+    ServerFunction(String),
+    /// Rendering the page set a status other than `200 OK`, e.g., for a redirect or a 404.
+    Status(u16),
+}
+
+impl Display for UnsupportedReason {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            UnsupportedReason::NotGet => {
+                write!(f, "the route does not handle GET requests")
+            }
+            UnsupportedReason::MissingParams => {
+                write!(f, "no params were given for the route")
+            }
+            UnsupportedReason::InvalidParam(value) => {
+                write!(f, "the param value {value:?} is not a valid file name")
+            }
+            UnsupportedReason::ServerFunction(path) => {
+                write!(f, "the page links to the server function at {path}")
+            }
+            UnsupportedReason::Status(status) => {
+                write!(f, "rendering the page set status {status}")
+            }
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -440,4 +698,79 @@ mod tests {
             ]
         );
     }
+
+    #[test]
+    fn export_plans_every_route_with_params() {
+        use crate::{RouteListing, SsrMode};
+
+        let mut posts = StaticParamsMap::new();
+        posts.insert(
+            "id",
+            vec!["1".into(), "2".into(), "..".into(), "a/b".into()],
+        );
+        let export = StaticExport::new("dist").params("/posts/:id", posts);
+        let routes = RouteList::from(vec![
+            RouteListing::new(
+                [PathSegment::Unit],
+                SsrMode::Async,
+                [Method::Get],
+                [],
+            ),
+            RouteListing::new(
+                [
+                    PathSegment::Static("posts".into()),
+                    PathSegment::Param("id".into()),
+                ],
+                SsrMode::OutOfOrder,
+                [Method::Get],
+                [],
+            ),
+            RouteListing::new(
+                [
+                    PathSegment::Static("users".into()),
+                    PathSegment::Param("id".into()),
+                ],
+                SsrMode::Async,
+                [Method::Get],
+                [],
+            ),
+            RouteListing::new(
+                [PathSegment::Static("login".into())],
+                SsrMode::Async,
+                [Method::Post],
+                [],
+            ),
+        ]);
+
+        let plan = futures::executor::block_on(export.plan(&routes));
+        assert_eq!(
+            plan.paths,
+            vec![
+                ResolvedStaticPath::new(""),
+                ResolvedStaticPath::new("/posts/1"),
+                ResolvedStaticPath::new("/posts/2"),
+            ]
+        );
+        assert_eq!(
+            plan.unsupported,
+            vec![
+                UnsupportedPage {
+                    path: "/posts/:id".into(),
+                    reason: UnsupportedReason::InvalidParam("..".into()),
+                },
+                UnsupportedPage {
+                    path: "/posts/:id".into(),
+                    reason: UnsupportedReason::InvalidParam("a/b".into()),
+                },
+                UnsupportedPage {
+                    path: "/users/:id".into(),
+                    reason: UnsupportedReason::MissingParams,
+                },
+                UnsupportedPage {
+                    path: "/login".into(),
+                    reason: UnsupportedReason::NotGet,
+                },
+            ]
+        );
+    }
 }
@@ -9,9 +9,12 @@ use proc_macro2::Span;
 use proc_macro_error2::{abort, proc_macro_error, set_dummy};
 use quote::{format_ident, quote, ToTokens};
 use syn::{
-    spanned::Spanned, FnArg, Ident, ImplItem, ItemImpl, Path, Type, TypePath,
+    spanned::Spanned, FnArg, Ident, ImplItem, ItemImpl, LitStr, Path, Type,
+    TypePath,
 };
 
+mod routes;
+
 const RFC3986_UNRESERVED: [char; 4] = ['-', '.', '_', '~'];
 const RFC3986_PCHAR_OTHER: [char; 1] = ['@'];
 
@@ -38,7 +41,14 @@ const RFC3986_PCHAR_OTHER: [char; 1] = ['@'];
 ///
 /// assert_eq!(path, output);
 /// ```
+///
+/// A param can be constrained, so that the route only matches some values: `:id<u64>` only
+/// matches values that can be parsed as a `u64`, `:tab{profile,billing}` matches one of the
+/// listed values, and `:slug([a-z-]+)` matches a regular expression (with the `regex` feature of
+/// `leptos_router`). See [`ConstrainedParamSegment`].
+///
 /// [`Route`]: https://docs.rs/leptos_router/latest/leptos_router/components/fn.Route.html
+/// [`ConstrainedParamSegment`]: https://docs.rs/leptos_router/latest/leptos_router/struct.ConstrainedParamSegment.html
 #[proc_macro_error2::proc_macro_error]
 #[proc_macro]
 pub fn path(tokens: TokenStream) -> TokenStream {
@@ -48,6 +58,51 @@ pub fn path(tokens: TokenStream) -> TokenStream {
     segments.into_token_stream().into()
 }
 
+/// Derives typed routes for an enum with one variant per route.
+///
+/// Each variant is given its path with a `#[route("...")]` attribute, using the syntax of
+/// [`path`]. Its named fields are the params of the path, and can be of any type that implements
+/// [`Display`] and [`FromStr`] (wrapped in an [`Option`] for optional params).
+///
+/// ```rust
+/// use leptos_router::Routes;
+///
+/// #[derive(Routes, Debug, Clone, PartialEq)]
+/// enum AppRoute {
+///     #[route("/")]
+///     Home,
+///     #[route("/users/:id")]
+///     User { id: u64 },
+///     #[route("/users/:id/posts/:post?")]
+///     UserPosts { id: u64, post: Option<String> },
+/// }
+///
+/// // a constant with the path segments of each route, for use in a `<Route/>`
+/// # use leptos_router::{ParamSegment, StaticSegment};
+/// assert_eq!(AppRoute::USER, (StaticSegment("users"), ParamSegment("id")));
+///
+/// // the URL of a route, which is also used by `ToHref` for `<A href=.../>`
+/// assert_eq!(AppRoute::User { id: 42 }.to_string(), "/users/42");
+///
+/// // and the route for a URL
+/// assert_eq!(
+///     AppRoute::from_path("/users/42/posts"),
+///     Some(AppRoute::UserPosts { id: 42, post: None })
+/// );
+/// assert_eq!(AppRoute::from_path("/users/me"), None);
+/// ```
+///
+/// The constants are named after the variants in `SCREAMING_SNAKE_CASE`. When a URL matches the
+/// paths of several routes, `from_path` returns the first route whose params can be parsed.
+///
+/// [`Display`]: std::fmt::Display
+/// [`FromStr`]: std::str::FromStr
+#[proc_macro_error2::proc_macro_error]
+#[proc_macro_derive(Routes, attributes(route))]
+pub fn routes_derive(tokens: TokenStream) -> TokenStream {
+    routes::routes_impl(tokens)
+}
+
 #[derive(Debug, PartialEq)]
 struct Segments(pub Vec<Segment>);
 
@@ -55,10 +110,21 @@ struct Segments(pub Vec<Segment>);
 enum Segment {
     Static(String),
     Param(String),
+    ConstrainedParam(String, Constraint),
     OptionalParam(String),
     Wildcard(String),
 }
 
+#[derive(Debug, PartialEq)]
+enum Constraint {
+    /// `:id<u64>`
+    Parses(String),
+    /// `:slug([a-z-]+)`
+    Regex(String),
+    /// `:tab{profile,billing}`
+    OneOf(Vec<String>),
+}
+
 struct SegmentParser {
     input: proc_macro::token_stream::IntoIter,
     segments: Vec<Segment>,
@@ -79,20 +145,11 @@ impl SegmentParser {
             match input {
                 TokenTree::Literal(lit) => {
                     let lit = lit.to_string();
-                    if lit.contains("//") {
-                        abort!(
-                            proc_macro2::Span::call_site(),
-                            "Consecutive '/' is not allowed"
-                        );
-                    }
-                    Self::parse_str(
-                        &mut self.segments,
-                        lit.trim_start_matches(['"', '/'])
-                            .trim_end_matches(['"', '/']),
-                    );
-                    if lit.ends_with(r#"/""#) && lit != r#""/""# {
-                        self.segments.push(Segment::Static("/".to_string()));
-                    }
+                    // unescaped, so that a regex constraint can contain `\`
+                    let path = syn::parse_str::<LitStr>(&lit)
+                        .map(|lit| lit.value())
+                        .unwrap_or_else(|_| lit.trim_matches('"').to_string());
+                    Self::parse_path(&mut self.segments, &path);
                 }
                 TokenTree::Group(_) => unimplemented!(),
                 TokenTree::Ident(_) => unimplemented!(),
@@ -101,6 +158,19 @@ impl SegmentParser {
         }
     }
 
+    pub fn parse_path(segments: &mut Vec<Segment>, path: &str) {
+        if path.contains("//") {
+            abort!(
+                proc_macro2::Span::call_site(),
+                "Consecutive '/' is not allowed"
+            );
+        }
+        Self::parse_str(segments, path.trim_matches('/'));
+        if path.ends_with('/') && path != "/" {
+            segments.push(Segment::Static("/".to_string()));
+        }
+    }
+
     pub fn parse_str(segments: &mut Vec<Segment>, current_str: &str) {
         if ["", "*"].contains(&current_str) {
             return;
@@ -109,7 +179,18 @@ impl SegmentParser {
         for segment in current_str.split('/') {
             if let Some(segment) = segment.strip_prefix(':') {
                 if let Some(segment) = segment.strip_suffix('?') {
+                    if Self::parse_constraint(segment).is_some() {
+                        abort!(
+                            Span::call_site(),
+                            "Optional params cannot be constrained: {}",
+                            segment
+                        );
+                    }
                     segments.push(Segment::OptionalParam(segment.to_string()));
+                } else if let Some((name, constraint)) =
+                    Self::parse_constraint(segment)
+                {
+                    segments.push(Segment::ConstrainedParam(name, constraint));
                 } else {
                     segments.push(Segment::Param(segment.to_string()));
                 }
@@ -122,6 +203,35 @@ impl SegmentParser {
     }
 }
 
+impl SegmentParser {
+    /// Splits a param like `id<u64>` into its name and constraint.
+    fn parse_constraint(segment: &str) -> Option<(String, Constraint)> {
+        let start = segment.find(['<', '(', '{'])?;
+        let (name, constraint) = segment.split_at(start);
+        let constraint = match constraint.split_at(1) {
+            ("<", ty) => ty
+                .strip_suffix('>')
+                .map(|ty| Constraint::Parses(ty.to_string())),
+            ("(", regex) => regex
+                .strip_suffix(')')
+                .map(|regex| Constraint::Regex(regex.to_string())),
+            (_, values) => values.strip_suffix('}').map(|values| {
+                Constraint::OneOf(
+                    values.split(',').map(|v| v.trim().to_string()).collect(),
+                )
+            }),
+        };
+        match constraint {
+            Some(constraint) => Some((name.to_string(), constraint)),
+            None => abort!(
+                Span::call_site(),
+                "Invalid param constraint: {}",
+                segment
+            ),
+        }
+    }
+}
+
 impl Segment {
     fn is_valid(segment: &str) -> bool {
         segment == "/"
@@ -142,7 +252,9 @@ impl Segment {
             Self::Static(s) if !Self::is_valid(s) => {
                 abort!(Span::call_site(), "Invalid static segment: {}", s)
             }
-            Self::Param(s) if !Self::is_valid(s) => {
+            Self::Param(s) | Self::ConstrainedParam(s, _)
+                if !Self::is_valid(s) =>
+            {
                 abort!(Span::call_site(), "Invalid param segment: {}", s)
             }
             _ => (),
@@ -162,6 +274,55 @@ impl Segments {
     }
 }
 
+impl Segment {
+    /// The type of the segment, for use in a type definition.
+    fn ty(&self) -> proc_macro2::TokenStream {
+        match self {
+            Segment::Wildcard(_) => quote! { leptos_router::WildcardSegment },
+            Segment::Static(_) => {
+                quote! { leptos_router::StaticSegment<&'static str> }
+            }
+            Segment::Param(_) => quote! { leptos_router::ParamSegment },
+            Segment::ConstrainedParam(_, constraint) => {
+                let constraint = match constraint {
+                    Constraint::Parses(ty) => {
+                        let ty = Constraint::parse_type(ty);
+                        quote! { leptos_router::ParsesAs<#ty> }
+                    }
+                    Constraint::Regex(_) => {
+                        quote! { leptos_router::MatchesRegex }
+                    }
+                    Constraint::OneOf(_) => quote! { leptos_router::OneOf },
+                };
+                quote! { leptos_router::ConstrainedParamSegment<#constraint> }
+            }
+            Segment::OptionalParam(_) => {
+                quote! { leptos_router::OptionalParamSegment }
+            }
+        }
+    }
+}
+
+impl Constraint {
+    fn parse_type(ty: &str) -> Type {
+        syn::parse_str(ty).unwrap_or_else(|_| {
+            abort!(Span::call_site(), "Invalid param type: {}", ty)
+        })
+    }
+}
+
+impl Segments {
+    /// The type of the tuple of segments, for use in a type definition.
+    fn ty(&self) -> proc_macro2::TokenStream {
+        let types = self.0.iter().map(Segment::ty);
+        match self.0.as_slice() {
+            [] => quote! { () },
+            [_] => quote! { (#(#types,)*) },
+            _ => quote! { (#(#types),*) },
+        }
+    }
+}
+
 impl ToTokens for Segment {
     fn to_tokens(&self, tokens: &mut proc_macro2::TokenStream) {
         self.ensure_valid();
@@ -175,6 +336,23 @@ impl ToTokens for Segment {
             Segment::Param(p) => {
                 tokens.extend(quote! { leptos_router::ParamSegment(#p) });
             }
+            Segment::ConstrainedParam(p, constraint) => {
+                let constraint = match constraint {
+                    Constraint::Parses(ty) => {
+                        let ty = Constraint::parse_type(ty);
+                        quote! { parses::<#ty>() }
+                    }
+                    Constraint::Regex(regex) => {
+                        quote! { matches_regex(#regex) }
+                    }
+                    Constraint::OneOf(values) => {
+                        quote! { one_of(&[#(#values),*]) }
+                    }
+                };
+                tokens.extend(
+                    quote! { leptos_router::ParamSegment(#p).#constraint },
+                );
+            }
             Segment::OptionalParam(p) => {
                 tokens
                     .extend(quote! { leptos_router::OptionalParamSegment(#p) });
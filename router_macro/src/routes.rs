@@ -0,0 +1,274 @@
+use crate::{Segment, SegmentParser, Segments};
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use proc_macro_error2::abort;
+use quote::{format_ident, quote};
+use syn::{
+    spanned::Spanned, Data, DeriveInput, Fields, Ident, LitStr, Variant,
+};
+
+pub fn routes_impl(tokens: TokenStream) -> TokenStream {
+    let input = syn::parse::<DeriveInput>(tokens).unwrap_or_else(|e| {
+        abort!(e.span(), "`Routes` can only be derived for an enum")
+    });
+    let Data::Enum(data) = &input.data else {
+        abort!(input.span(), "`Routes` can only be derived for an enum")
+    };
+    let routes = data.variants.iter().map(Route::new).collect::<Vec<_>>();
+
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) =
+        input.generics.split_for_impl();
+    let consts = routes.iter().map(Route::path_const);
+    let display_arms = routes.iter().map(Route::display_arm);
+    let path_matches = routes.iter().map(Route::path_match);
+
+    quote! {
+        impl #impl_generics #name #ty_generics #where_clause {
+            #(#consts)*
+
+            /// Returns the route matched by the given path, if any.
+            pub fn from_path(path: &str) -> ::core::option::Option<Self> {
+                #[allow(unused_imports)]
+                use leptos_router::params::macro_helpers::Fallback as _;
+
+                #(#path_matches)*
+                ::core::option::Option::None
+            }
+        }
+
+        impl #impl_generics ::core::fmt::Display for #name #ty_generics #where_clause {
+            fn fmt(&self, __f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+                let mut __href = ::std::string::String::new();
+                match self {
+                    #(#display_arms)*
+                }
+                if __href.is_empty() {
+                    __href.push('/');
+                }
+                __f.write_str(&__href)
+            }
+        }
+
+        impl #impl_generics leptos_router::components::ToHref for #name #ty_generics #where_clause {
+            fn to_href(&self) -> ::std::boxed::Box<dyn Fn() -> ::std::string::String + '_> {
+                let href = self.to_string();
+                ::std::boxed::Box::new(move || href.clone())
+            }
+        }
+    }
+    .into()
+}
+
+struct Route<'a> {
+    variant: &'a Variant,
+    path: String,
+    segments: Segments,
+}
+
+impl<'a> Route<'a> {
+    fn new(variant: &'a Variant) -> Self {
+        let attr = variant
+            .attrs
+            .iter()
+            .find(|attr| attr.path().is_ident("route"))
+            .unwrap_or_else(|| {
+                abort!(
+                    variant.span(),
+                    "each variant needs a path, like \
+                     `#[route(\"/users/:id\")]`"
+                )
+            });
+        let path = attr
+            .parse_args::<LitStr>()
+            .unwrap_or_else(|e| abort!(e.span(), "expected a path string"))
+            .value();
+        let mut segments = Vec::new();
+        SegmentParser::parse_path(&mut segments, &path);
+        let route = Self {
+            variant,
+            path,
+            segments: Segments(segments),
+        };
+        route.check_fields();
+        route
+    }
+
+    fn params(&self) -> impl Iterator<Item = &str> {
+        self.segments.0.iter().filter_map(|segment| match segment {
+            Segment::Param(name)
+            | Segment::ConstrainedParam(name, _)
+            | Segment::OptionalParam(name)
+            | Segment::Wildcard(name) => Some(name.as_str()),
+            Segment::Static(_) => None,
+        })
+    }
+
+    /// Ensures that the fields of the variant are exactly the params of the path.
+    fn check_fields(&self) {
+        let fields = match &self.variant.fields {
+            Fields::Named(fields) => fields
+                .named
+                .iter()
+                .filter_map(|field| field.ident.as_ref())
+                .collect::<Vec<_>>(),
+            Fields::Unit => Vec::new(),
+            Fields::Unnamed(fields) => abort!(
+                fields.span(),
+                "route params must be named fields, like `User {{ id: u64 }}`"
+            ),
+        };
+        for param in self.params() {
+            if !fields.iter().any(|field| *field == param) {
+                abort!(
+                    self.variant.span(),
+                    "the param `{}` of `{}` has no field",
+                    param,
+                    self.path
+                );
+            }
+        }
+        for field in fields {
+            if !self.params().any(|param| field == param) {
+                abort!(
+                    field.span(),
+                    "`{}` is not a param of `{}`",
+                    field,
+                    self.path
+                );
+            }
+        }
+    }
+
+    fn const_name(&self) -> Ident {
+        // a run of capitals is one word, which ends before a capital that starts the next
+        // one: `HTTPPage` is `HTTP_PAGE`
+        let ident = self.variant.ident.to_string();
+        let chars = ident.chars().collect::<Vec<_>>();
+        let mut name = String::new();
+        for (i, &c) in chars.iter().enumerate() {
+            if c.is_uppercase() && i > 0 {
+                let prev_is_upper = chars[i - 1].is_uppercase();
+                let next_is_lower =
+                    chars.get(i + 1).is_some_and(|c| c.is_lowercase());
+                if !prev_is_upper || next_is_lower {
+                    name.push('_');
+                }
+            }
+            name.push(c.to_ascii_uppercase());
+        }
+        format_ident!("{name}", span = self.variant.ident.span())
+    }
+
+    fn path_const(&self) -> TokenStream2 {
+        let name = self.const_name();
+        let ty = self.segments.ty();
+        let segments = &self.segments;
+        let doc = format!(
+            "The path segments of [`{}`](Self::{}), `{}`.",
+            self.variant.ident, self.variant.ident, self.path
+        );
+        quote! {
+            #[doc = #doc]
+            pub const #name: #ty = #segments;
+        }
+    }
+
+    fn fields_pattern(&self) -> TokenStream2 {
+        let variant = &self.variant.ident;
+        match &self.variant.fields {
+            Fields::Named(fields) => {
+                let fields = fields.named.iter().map(|field| &field.ident);
+                quote! { Self::#variant { #(#fields),* } }
+            }
+            _ => quote! { Self::#variant },
+        }
+    }
+
+    fn display_arm(&self) -> TokenStream2 {
+        let pattern = self.fields_pattern();
+        let escape = quote! { leptos_router::location::Url::escape_segment };
+        let pushes = self.segments.0.iter().map(|segment| match segment {
+            Segment::Static(s) if s == "/" => quote! { __href.push('/'); },
+            Segment::Static(s) => quote! {
+                __href.push('/');
+                __href.push_str(#s);
+            },
+            Segment::Param(name) | Segment::ConstrainedParam(name, _) => {
+                let ident = format_ident!("{name}");
+                quote! {
+                    __href.push('/');
+                    __href.push_str(&#escape(&#ident.to_string()));
+                }
+            }
+            Segment::OptionalParam(name) => {
+                let ident = format_ident!("{name}");
+                quote! {
+                    if let ::core::option::Option::Some(__value) = #ident {
+                        __href.push('/');
+                        __href.push_str(&#escape(&__value.to_string()));
+                    }
+                }
+            }
+            Segment::Wildcard(name) => {
+                let ident = format_ident!("{name}");
+                quote! {
+                    __href.push('/');
+                    let __value = #ident.to_string();
+                    let mut __parts = __value.split('/').map(#escape);
+                    if let ::core::option::Option::Some(__first) = __parts.next() {
+                        __href.push_str(&__first);
+                    }
+                    for __part in __parts {
+                        __href.push('/');
+                        __href.push_str(&__part);
+                    }
+                }
+            }
+        });
+        quote! {
+            #pattern => {
+                #(#pushes)*
+            }
+        }
+    }
+
+    fn path_match(&self) -> TokenStream2 {
+        let name = self.const_name();
+        let variant = &self.variant.ident;
+        let route = match &self.variant.fields {
+            Fields::Named(fields) => {
+                let fields = fields.named.iter().map(|field| {
+                    let ident = &field.ident;
+                    let ty = &field.ty;
+                    let param = ident.as_ref().map(ToString::to_string);
+                    quote! {
+                        #ident: leptos_router::params::macro_helpers::Wrapper::<#ty>::__into_param(
+                            map.get_str(#param),
+                            #param
+                        ).ok()?
+                    }
+                });
+                quote! {
+                    .and_then(|matched| {
+                        let map = matched
+                            .params()
+                            .into_iter()
+                            .collect::<leptos_router::params::ParamsMap>();
+                        ::core::option::Option::Some(Self::#variant { #(#fields),* })
+                    })
+                }
+            }
+            _ => quote! { .map(|_| Self::#variant) },
+        };
+        quote! {
+            if let ::core::option::Option::Some(route) =
+                leptos_router::PossibleRouteMatch::test(&Self::#name, path)
+                    .filter(|matched| matched.is_complete())
+                    #route
+            {
+                return ::core::option::Option::Some(route);
+            }
+        }
+    }
+}
@@ -1,5 +1,6 @@
 use leptos_router::{
-    OptionalParamSegment, ParamSegment, StaticSegment, WildcardSegment,
+    OptionalParamSegment, ParamSegment, PossibleRouteMatch, StaticSegment,
+    WildcardSegment,
 };
 use leptos_router_macro::path;
 
@@ -181,6 +182,24 @@ fn parses_complex() {
     );
 }
 
+#[test]
+fn parses_constrained_params() {
+    let output = path!("/posts/:id<u64>/:tab{comments, likes}/:slug([a-z]\\d)");
+    assert_eq!(
+        output,
+        (
+            StaticSegment("posts"),
+            ParamSegment("id").parses::<u64>(),
+            ParamSegment("tab").one_of(&["comments", "likes"]),
+            ParamSegment("slug").matches_regex(r"[a-z]\d"),
+        )
+    );
+    assert!(output.test("/posts/1/likes/a1").is_some());
+    assert!(output.test("/posts/one/likes/a1").is_none());
+    assert!(output.test("/posts/1/shares/a1").is_none());
+    assert!(output.test("/posts/1/likes/ab").is_none());
+}
+
 // #[test]
 // fn deny_consecutive_slashes() {
 //     let _ = path!("/////foo///bar/////baz/");
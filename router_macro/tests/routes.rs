@@ -0,0 +1,134 @@
+use leptos_router::{
+    components::ToHref, OptionalParamSegment, ParamSegment, StaticSegment,
+    WildcardSegment,
+};
+use leptos_router_macro::Routes;
+
+#[derive(Routes, Debug, Clone, PartialEq)]
+enum AppRoute {
+    #[route("/")]
+    Home,
+    #[route("/users/:id")]
+    User { id: u64 },
+    #[route("/users/:name")]
+    UserByName { name: String },
+    #[route("/posts/:id/comments/:comment?")]
+    PostComments { id: u32, comment: Option<u32> },
+    #[route("/files/*path")]
+    File { path: String },
+    #[route("/about/")]
+    About,
+    #[route("/http")]
+    HTTPPage,
+}
+
+#[test]
+fn generates_segments() {
+    assert_eq!(AppRoute::HOME, ());
+    assert_eq!(AppRoute::USER, (StaticSegment("users"), ParamSegment("id")));
+    assert_eq!(
+        AppRoute::POST_COMMENTS,
+        (
+            StaticSegment("posts"),
+            ParamSegment("id"),
+            StaticSegment("comments"),
+            OptionalParamSegment("comment")
+        )
+    );
+    assert_eq!(
+        AppRoute::FILE,
+        (StaticSegment("files"), WildcardSegment("path"))
+    );
+    assert_eq!(
+        AppRoute::ABOUT,
+        (StaticSegment("about"), StaticSegment("/"))
+    );
+    // a run of capitals is one word
+    assert_eq!(AppRoute::HTTP_PAGE, (StaticSegment("http"),));
+}
+
+#[test]
+fn generates_hrefs() {
+    assert_eq!(AppRoute::Home.to_string(), "/");
+    assert_eq!(AppRoute::User { id: 42 }.to_href()(), "/users/42");
+    assert_eq!(
+        AppRoute::UserByName {
+            name: "a b".to_string()
+        }
+        .to_string(),
+        "/users/a%20b"
+    );
+    assert_eq!(
+        AppRoute::PostComments {
+            id: 1,
+            comment: None
+        }
+        .to_string(),
+        "/posts/1/comments"
+    );
+    assert_eq!(
+        AppRoute::PostComments {
+            id: 1,
+            comment: Some(2)
+        }
+        .to_string(),
+        "/posts/1/comments/2"
+    );
+    assert_eq!(
+        AppRoute::File {
+            path: "docs/read me~1.md".to_string()
+        }
+        .to_string(),
+        "/files/docs/read%20me~1.md"
+    );
+    assert_eq!(AppRoute::About.to_string(), "/about/");
+}
+
+#[test]
+fn parses_paths() {
+    assert_eq!(AppRoute::from_path("/"), Some(AppRoute::Home));
+    assert_eq!(
+        AppRoute::from_path("/users/42"),
+        Some(AppRoute::User { id: 42 })
+    );
+    // falls through to the next route whose params can be parsed
+    assert_eq!(
+        AppRoute::from_path("/users/a%20b"),
+        Some(AppRoute::UserByName {
+            name: "a b".to_string()
+        })
+    );
+    assert_eq!(
+        AppRoute::from_path("/posts/1/comments/2"),
+        Some(AppRoute::PostComments {
+            id: 1,
+            comment: Some(2)
+        })
+    );
+    assert_eq!(
+        AppRoute::from_path("/files/docs/readme.md"),
+        Some(AppRoute::File {
+            path: "docs/readme.md".to_string()
+        })
+    );
+    assert_eq!(AppRoute::from_path("/posts/x/comments"), None);
+    assert_eq!(AppRoute::from_path("/nowhere"), None);
+}
+
+#[test]
+fn roundtrips() {
+    let routes = [
+        AppRoute::Home,
+        AppRoute::User { id: 7 },
+        AppRoute::PostComments {
+            id: 3,
+            comment: None,
+        },
+        AppRoute::File {
+            path: "a/b/c".to_string(),
+        },
+    ];
+    for route in routes {
+        assert_eq!(AppRoute::from_path(&route.to_string()), Some(route));
+    }
+}
@@ -0,0 +1,550 @@
+//! HTTP caching for server functions that use `GET` requests.
+//!
+//! The response of a server function that is called with a `GET` request (e.g., one that uses
+//! the `GetUrl` input encoding) can be cached by browsers and CDNs. The `Cache-Control` header
+//! for its successful responses can be declared on the function:
+//!
+//! ```rust,ignore
+//! #[server(input = GetUrl, cache = "public, max-age=60")]
+//! pub async fn list_posts() -> Result<Vec<Post>, ServerFnError> {
+//!     // ...
+//! }
+//! ```
+//!
+//! or set while it runs, which overrides the declared value:
+//!
+//! ```rust,ignore
+//! #[server(input = GetUrl)]
+//! pub async fn get_post(id: u32) -> Result<Post, ServerFnError> {
+//!     let post = load_post(id).await?;
+//!     if post.draft {
+//!         server_fn::cache::set_cache_control("private, no-cache");
+//!         server_fn::cache::set_vary("Cookie");
+//!     }
+//!     Ok(post)
+//! }
+//! ```
+//!
+//! When caching is enabled for a response, the server also computes an `ETag` from the encoded
+//! response body, and answers requests whose `If-None-Match` header matches it with an empty
+//! `304 Not Modified` response. Streaming responses are not given an `ETag`.
+//!
+//! In the browser, revalidation is handled by the browser’s own HTTP cache. The
+//! [`reqwest`](crate::client::reqwest) and [`in_process`](crate::client::in_process) clients can
+//! keep responses that have an `ETag` in memory, send `If-None-Match` when the same URL is
+//! requested again, and transparently reuse the cached response, with its original headers, when
+//! the server answers with `304 Not Modified`. A response is only reused for requests that have
+//! the same values for the headers named by its `Vary` header, and responses with `Vary: *` are
+//! not kept. How many responses are kept is bounded by [`ClientCacheLimits`].
+//!
+//! The `reqwest` client only keeps responses once its cache has been enabled with
+//! [`enable_cache`](crate::client::reqwest::enable_cache). Its cache is shared by every request
+//! made by the process, which may be made on behalf of different users, so it does not keep
+//! responses with `Cache-Control: private`, or responses to requests with an `Authorization`
+//! header unless they are explicitly `public`, and it does not replay `Set-Cookie` headers. The
+//! `in_process` client keeps responses for as long as its context, which can be configured with
+//! [`with_cache_limits`](crate::client::in_process::InProcessContext::with_cache_limits).
+
+#[cfg(any(feature = "reqwest", feature = "axum"))]
+use bytes::Bytes;
+#[cfg(any(feature = "reqwest", feature = "axum"))]
+use http::{
+    header::{
+        AUTHORIZATION, CACHE_CONTROL, CONNECTION, ETAG, IF_NONE_MATCH,
+        SET_COOKIE, TRANSFER_ENCODING, VARY,
+    },
+    HeaderMap, HeaderName, HeaderValue, StatusCode,
+};
+use or_poisoned::OrPoisoned;
+use pin_project_lite::pin_project;
+#[cfg(any(feature = "reqwest", feature = "axum"))]
+use std::collections::{HashMap, VecDeque};
+use std::{
+    cell::RefCell,
+    future::Future,
+    pin::Pin,
+    sync::{Arc, Mutex},
+    task::{Context, Poll},
+};
+
+/// The cache headers for the response of a server function.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct CacheDirectives {
+    /// The value of the `Cache-Control` header.
+    pub cache_control: Option<String>,
+    /// The value of the `Vary` header.
+    pub vary: Option<String>,
+}
+
+impl CacheDirectives {
+    /// Whether any cache headers have been set.
+    pub fn is_empty(&self) -> bool {
+        self.cache_control.is_none() && self.vary.is_none()
+    }
+}
+
+thread_local! {
+    static CURRENT: RefCell<Option<Arc<Mutex<CacheDirectives>>>> =
+        const { RefCell::new(None) };
+}
+
+/// Sets the `Cache-Control` header for the response of the server function that is currently
+/// running, overriding the value declared with `#[server(cache = "...")]`.
+///
+/// This has no effect outside of a server function, or if the server function does not use a
+/// `GET` request.
+pub fn set_cache_control(value: impl Into<String>) {
+    with_current(|directives| directives.cache_control = Some(value.into()));
+}
+
+/// Sets the `Vary` header for the response of the server function that is currently running.
+///
+/// This has no effect outside of a server function, or if the server function does not use a
+/// `GET` request.
+pub fn set_vary(value: impl Into<String>) {
+    with_current(|directives| directives.vary = Some(value.into()));
+}
+
+fn with_current(f: impl FnOnce(&mut CacheDirectives)) {
+    CURRENT.with(|current| {
+        if let Some(directives) = &*current.borrow() {
+            f(&mut directives.lock().or_poisoned());
+        }
+    });
+}
+
+pin_project! {
+    /// Runs a future with cache directives that can be set by [`set_cache_control`] and
+    /// [`set_vary`].
+    pub(crate) struct CacheScope<Fut> {
+        directives: Arc<Mutex<CacheDirectives>>,
+        #[pin]
+        inner: Fut,
+    }
+}
+
+impl<Fut> CacheScope<Fut> {
+    pub(crate) fn new(cache_control: Option<&str>, inner: Fut) -> Self {
+        Self {
+            directives: Arc::new(Mutex::new(CacheDirectives {
+                cache_control: cache_control.map(ToOwned::to_owned),
+                vary: None,
+            })),
+            inner,
+        }
+    }
+}
+
+impl<Fut: Future> Future for CacheScope<Fut> {
+    type Output = (Fut::Output, CacheDirectives);
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = self.project();
+        let prev = CURRENT
+            .with(|current| current.replace(Some(this.directives.clone())));
+        let poll = this.inner.poll(cx);
+        CURRENT.with(|current| *current.borrow_mut() = prev);
+        poll.map(|output| {
+            (output, this.directives.lock().or_poisoned().clone())
+        })
+    }
+}
+
+/// Computes a strong `ETag` for a response body.
+pub fn etag(body: &[u8]) -> String {
+    format!("\"{:016x}\"", xxhash_rust::const_xxh64::xxh64(body, 0))
+}
+
+/// Whether the value of an `If-None-Match` header matches the given `ETag`, using the weak
+/// comparison that applies to `GET` requests.
+pub fn if_none_match(header: &str, etag: &str) -> bool {
+    let opaque = |tag: &str| tag.trim().trim_start_matches("W/").to_owned();
+    let etag = opaque(etag);
+    header
+        .split(',')
+        .any(|tag| tag.trim() == "*" || opaque(tag) == etag)
+}
+
+/// A response kept by a client, to be reused when the server answers `304 Not Modified`.
+#[cfg(any(feature = "reqwest", feature = "axum"))]
+#[derive(Debug, Clone)]
+pub(crate) struct CachedResponse {
+    etag: HeaderValue,
+    /// The headers of the original response.
+    headers: HeaderMap,
+    body: Bytes,
+    /// The values that the request had for the headers named by the response’s `Vary` header.
+    vary: Vec<(HeaderName, Vec<HeaderValue>)>,
+}
+
+#[cfg(any(feature = "reqwest", feature = "axum"))]
+impl CachedResponse {
+    /// Rebuilds the original response from the cached headers and body.
+    pub fn to_response<B: From<Bytes>>(&self) -> http::Response<B> {
+        let mut res = http::Response::new(B::from(self.body.clone()));
+        *res.headers_mut() = self.headers.clone();
+        res
+    }
+
+    /// Whether the response can be reused for a request with the given headers.
+    fn matches(&self, request_headers: &HeaderMap) -> bool {
+        self.vary.iter().all(|(name, values)| {
+            header_values(request_headers, name) == *values
+        })
+    }
+}
+
+#[cfg(any(feature = "reqwest", feature = "axum"))]
+fn header_values(headers: &HeaderMap, name: &HeaderName) -> Vec<HeaderValue> {
+    headers.get_all(name).iter().cloned().collect()
+}
+
+/// The names of the comma-separated directives or header names in the values of a header,
+/// lowercased and without arguments.
+#[cfg(any(feature = "reqwest", feature = "axum"))]
+fn header_list(headers: &HeaderMap, name: HeaderName) -> Vec<String> {
+    headers
+        .get_all(name)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(','))
+        .map(|item| {
+            let name = item.split('=').next().unwrap_or_default();
+            name.trim().to_ascii_lowercase()
+        })
+        .filter(|item| !item.is_empty())
+        .collect()
+}
+
+/// The limits of the responses kept in memory by a client, see the [module documentation](self).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ClientCacheLimits {
+    /// The number of URLs for which responses are kept. The oldest URL is evicted first.
+    pub urls: usize,
+    /// The number of responses kept for each URL, for requests that differ in the headers named
+    /// by `Vary`.
+    pub variants: usize,
+    /// The total size of the bodies of the responses that are kept. Larger responses are not
+    /// kept at all.
+    pub bytes: usize,
+}
+
+impl Default for ClientCacheLimits {
+    /// 256 URLs, 8 variants per URL and 16 MiB of response bodies.
+    fn default() -> Self {
+        Self {
+            urls: 256,
+            variants: 8,
+            bytes: 16 * 1024 * 1024,
+        }
+    }
+}
+
+/// The responses kept for a URL.
+#[cfg(any(feature = "reqwest", feature = "axum"))]
+type Variants = Vec<CachedResponse>;
+
+/// The responses kept by a client, by URL, evicting the oldest URL when full.
+#[cfg(any(feature = "reqwest", feature = "axum"))]
+#[derive(Debug, Default)]
+pub(crate) struct ClientCache {
+    /// Whether the cache is shared by requests made on behalf of different users.
+    shared: bool,
+    limits: ClientCacheLimits,
+    entries: Mutex<CacheEntries>,
+}
+
+#[cfg(any(feature = "reqwest", feature = "axum"))]
+#[derive(Debug, Default)]
+struct CacheEntries {
+    by_url: HashMap<String, Variants>,
+    /// The URLs, from oldest to newest.
+    order: VecDeque<String>,
+    /// The total size of the cached bodies.
+    bytes: usize,
+}
+
+#[cfg(any(feature = "reqwest", feature = "axum"))]
+impl ClientCache {
+    /// Creates a cache that is only used by requests made on behalf of the same user.
+    #[cfg(feature = "axum")]
+    pub fn new(limits: ClientCacheLimits) -> Self {
+        Self {
+            shared: false,
+            limits,
+            entries: Default::default(),
+        }
+    }
+
+    /// Creates a cache that is shared by requests made on behalf of different users, which does
+    /// not keep private responses.
+    #[cfg(any(feature = "reqwest", test))]
+    pub fn shared(limits: ClientCacheLimits) -> Self {
+        Self {
+            shared: true,
+            limits,
+            entries: Default::default(),
+        }
+    }
+
+    /// Looks up the cached response for a `GET` request, and asks the server to revalidate it.
+    pub fn revalidate(
+        &self,
+        url: &str,
+        headers: &mut HeaderMap,
+    ) -> Option<CachedResponse> {
+        let cached = self
+            .entries
+            .lock()
+            .or_poisoned()
+            .by_url
+            .get(url)?
+            .iter()
+            .find(|cached| cached.matches(headers))
+            .cloned()?;
+        headers.insert(IF_NONE_MATCH, cached.etag.clone());
+        Some(cached)
+    }
+
+    /// Whether a response should be stored, i.e., it is a successful response with an `ETag`,
+    /// without `Cache-Control: no-store` or `Vary: *`, and, for a shared cache, it is not
+    /// private.
+    pub fn should_store(
+        &self,
+        request_headers: &HeaderMap,
+        status: StatusCode,
+        headers: &HeaderMap,
+    ) -> bool {
+        let directives = header_list(headers, CACHE_CONTROL);
+        let has = |directive: &str| directives.iter().any(|d| d == directive);
+        let storable = status == StatusCode::OK
+            && headers.contains_key(ETAG)
+            && !has("no-store")
+            && !header_list(headers, VARY).iter().any(|name| name == "*");
+        if !storable || !self.shared {
+            return storable;
+        }
+        // a shared cache may only reuse an authorized response if it is marked as shareable
+        let authorized = request_headers.contains_key(AUTHORIZATION)
+            && !(has("public") || has("s-maxage") || has("must-revalidate"));
+        !has("private") && !authorized
+    }
+
+    /// Stores the body of a response for which [`should_store`](Self::should_store) is true.
+    pub fn store(
+        &self,
+        url: String,
+        request_headers: &HeaderMap,
+        headers: &HeaderMap,
+        body: Bytes,
+    ) {
+        let Some(etag) = headers.get(ETAG).cloned() else {
+            return;
+        };
+        let vary = header_list(headers, VARY)
+            .into_iter()
+            .filter_map(|name| HeaderName::from_bytes(name.as_bytes()).ok())
+            .map(|name| {
+                let values = header_values(request_headers, &name);
+                (name, values)
+            })
+            .collect();
+        let mut headers = headers.clone();
+        headers.remove(CONNECTION);
+        headers.remove(TRANSFER_ENCODING);
+        if self.shared {
+            headers.remove(SET_COOKIE);
+        }
+        let cached = CachedResponse {
+            etag,
+            headers,
+            body,
+            vary,
+        };
+        let ClientCacheLimits {
+            urls,
+            variants: max_variants,
+            bytes: max_bytes,
+        } = self.limits;
+        if urls == 0 || max_variants == 0 || cached.body.len() > max_bytes {
+            return;
+        }
+        let mut entries = self.entries.lock().or_poisoned();
+        let CacheEntries {
+            by_url,
+            order,
+            bytes,
+        } = &mut *entries;
+        let variants = by_url.entry(url.clone()).or_insert_with(|| {
+            order.push_back(url.clone());
+            Vec::new()
+        });
+        variants.retain(|variant| {
+            let keep = !variant.matches(request_headers);
+            if !keep {
+                *bytes -= variant.body.len();
+            }
+            keep
+        });
+        if variants.len() >= max_variants {
+            *bytes -= variants.remove(0).body.len();
+        }
+        *bytes += cached.body.len();
+        variants.push(cached);
+        while order.len() > urls || *bytes > max_bytes {
+            let Some(oldest) = order.pop_front() else {
+                break;
+            };
+            if oldest == url {
+                // the newest response fits on its own, so only its older variants can go
+                order.push_back(oldest);
+                let variants = by_url.get_mut(&url).expect("just inserted");
+                while *bytes > max_bytes && variants.len() > 1 {
+                    *bytes -= variants.remove(0).body.len();
+                }
+                break;
+            }
+            for variant in by_url.remove(&oldest).unwrap_or_default() {
+                *bytes -= variant.body.len();
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::executor::block_on;
+    #[cfg(any(feature = "reqwest", feature = "axum"))]
+    use http::header::ACCEPT_LANGUAGE;
+
+    #[test]
+    fn matches_if_none_match() {
+        let tag = etag(b"hello");
+        assert!(if_none_match(&tag, &tag));
+        assert!(if_none_match(&format!("\"other\", W/{tag}"), &tag));
+        assert!(if_none_match("*", &tag));
+        assert!(!if_none_match("\"other\"", &tag));
+    }
+
+    #[test]
+    fn directives_can_be_set_while_running() {
+        let (_, directives) =
+            block_on(CacheScope::new(Some("max-age=60"), async {
+                set_vary("Cookie");
+            }));
+        assert_eq!(directives.cache_control.as_deref(), Some("max-age=60"));
+        assert_eq!(directives.vary.as_deref(), Some("Cookie"));
+
+        // outside of a server function, nothing happens
+        set_cache_control("no-store");
+    }
+
+    #[cfg(any(feature = "reqwest", feature = "axum"))]
+    #[test]
+    fn client_caches_respect_private_and_vary() {
+        let url = "/api/posts".to_string();
+        let headers = |pairs: &[(HeaderName, &'static str)]| {
+            pairs
+                .iter()
+                .map(|(name, value)| {
+                    (name.clone(), HeaderValue::from_static(value))
+                })
+                .collect::<HeaderMap>()
+        };
+        let private =
+            headers(&[(ETAG, "\"a\""), (CACHE_CONTROL, "private, max-age=60")]);
+        let none = HeaderMap::new();
+        assert!(ClientCache::default().should_store(
+            &none,
+            StatusCode::OK,
+            &private
+        ));
+        assert!(!ClientCache::shared(ClientCacheLimits::default())
+            .should_store(&none, StatusCode::OK, &private));
+        let authorized = headers(&[(AUTHORIZATION, "Bearer token")]);
+        let public = headers(&[(ETAG, "\"a\""), (CACHE_CONTROL, "public")]);
+        let unmarked = headers(&[(ETAG, "\"a\"")]);
+        assert!(ClientCache::shared(ClientCacheLimits::default())
+            .should_store(&authorized, StatusCode::OK, &public));
+        assert!(!ClientCache::shared(ClientCacheLimits::default())
+            .should_store(&authorized, StatusCode::OK, &unmarked));
+        let any = headers(&[(ETAG, "\"a\""), (VARY, "*")]);
+        assert!(!ClientCache::default().should_store(
+            &none,
+            StatusCode::OK,
+            &any
+        ));
+
+        // responses are only reused for requests with the same values for the varying headers
+        let cache = ClientCache::default();
+        let vary = headers(&[(ETAG, "\"a\""), (VARY, "Accept-Language")]);
+        let english = headers(&[(ACCEPT_LANGUAGE, "en")]);
+        let german = headers(&[(ACCEPT_LANGUAGE, "de")]);
+        cache.store(url.clone(), &english, &vary, Bytes::from_static(b"en"));
+        assert!(cache.revalidate(&url, &mut german.clone()).is_none());
+        cache.store(url.clone(), &german, &vary, Bytes::from_static(b"de"));
+        let cached = cache.revalidate(&url, &mut english.clone()).unwrap();
+        assert_eq!(cached.body, "en");
+        let cached = cache.revalidate(&url, &mut german.clone()).unwrap();
+        assert_eq!(cached.body, "de");
+    }
+
+    #[cfg(feature = "axum")]
+    #[test]
+    fn client_caches_are_bounded_and_replay_headers() {
+        let headers = HeaderMap::from_iter([
+            (ETAG, HeaderValue::from_static("\"a\"")),
+            (
+                HeaderName::from_static("x-total-count"),
+                HeaderValue::from_static("3"),
+            ),
+            (TRANSFER_ENCODING, HeaderValue::from_static("chunked")),
+        ]);
+        let none = HeaderMap::new();
+        let cache = ClientCache::new(ClientCacheLimits {
+            urls: 2,
+            variants: 1,
+            bytes: 8,
+        });
+        cache.store("/a".into(), &none, &headers, Bytes::from_static(b"aaa"));
+        let res = cache
+            .revalidate("/a", &mut HeaderMap::new())
+            .unwrap()
+            .to_response::<Bytes>();
+        assert_eq!(res.headers()["x-total-count"], "3");
+        assert!(!res.headers().contains_key(TRANSFER_ENCODING));
+        assert_eq!(res.into_body(), "aaa");
+
+        // too large on its own
+        cache.store(
+            "/b".into(),
+            &none,
+            &headers,
+            Bytes::from_static(b"123456789"),
+        );
+        assert!(cache.revalidate("/b", &mut HeaderMap::new()).is_none());
+
+        // evicts the oldest URL to stay below the size and count limits
+        cache.store(
+            "/c".into(),
+            &none,
+            &headers,
+            Bytes::from_static(b"cccccc"),
+        );
+        assert!(cache.revalidate("/a", &mut HeaderMap::new()).is_none());
+        assert!(cache.revalidate("/c", &mut HeaderMap::new()).is_some());
+        cache.store("/d".into(), &none, &headers, Bytes::from_static(b"d"));
+        cache.store("/e".into(), &none, &headers, Bytes::from_static(b"e"));
+        assert!(cache.revalidate("/c", &mut HeaderMap::new()).is_none());
+        assert!(cache.revalidate("/d", &mut HeaderMap::new()).is_some());
+        assert!(cache.revalidate("/e", &mut HeaderMap::new()).is_some());
+
+        let disabled = ClientCache::new(ClientCacheLimits {
+            urls: 0,
+            ..Default::default()
+        });
+        disabled.store("/a".into(), &none, &headers, Bytes::from_static(b"a"));
+        assert!(disabled.revalidate("/a", &mut HeaderMap::new()).is_none());
+    }
+}
@@ -108,6 +108,9 @@ pub mod browser {
                     request,
                     mut abort_ctrl,
                 } = req;
+                if let Some(token) = crate::csrf::token_from_document() {
+                    request.headers().set(crate::csrf::CSRF_HEADER, &token);
+                }
                 let res = request
                     .send()
                     .await
@@ -247,13 +250,31 @@ pub mod browser {
 pub mod reqwest {
     use super::{get_server_url, Client};
     use crate::{
+        cache::{ClientCache, ClientCacheLimits},
         error::{FromServerFnError, IntoAppError, ServerFnErrorErr},
         request::reqwest::CLIENT,
     };
     use bytes::Bytes;
-    use futures::{SinkExt, StreamExt, TryFutureExt};
+    use futures::{SinkExt, StreamExt};
+    use http::{Method, StatusCode};
     use reqwest::{Request, Response};
-    use std::future::Future;
+    use std::{future::Future, sync::OnceLock};
+
+    /// Responses kept to be revalidated, once enabled with [`enable_cache`]. This is shared by
+    /// every request made by the process.
+    static CACHE: OnceLock<ClientCache> = OnceLock::new();
+
+    /// Keeps the responses to `GET` requests that have an `ETag` in memory, within the given
+    /// limits, and revalidates them when the same URL is requested again. See
+    /// [`cache`](crate::cache) for which responses are kept.
+    ///
+    /// The cache is shared by every request made by the process. Returns `Err` with the given
+    /// limits if the cache has already been enabled.
+    pub fn enable_cache(
+        limits: ClientCacheLimits,
+    ) -> Result<(), ClientCacheLimits> {
+        CACHE.set(ClientCache::shared(limits)).map_err(|_| limits)
+    }
 
     /// Implements [`Client`] for a request made by [`reqwest`].
     pub struct ReqwestClient;
@@ -267,13 +288,49 @@ pub mod reqwest {
         type Request = Request;
         type Response = Response;
 
-        fn send(
-            req: Self::Request,
-        ) -> impl Future<Output = Result<Self::Response, Error>> + Send
-        {
-            CLIENT.execute(req).map_err(|e| {
+        async fn send(mut req: Self::Request) -> Result<Self::Response, Error> {
+            let cache = CACHE.get().filter(|_| req.method() == Method::GET);
+            let cache_key = cache.map(|_| req.url().to_string());
+            let cached =
+                cache.zip(cache_key.as_deref()).and_then(|(cache, url)| {
+                    cache.revalidate(url, req.headers_mut())
+                });
+            let request_headers = req.headers().clone();
+
+            let res = CLIENT.execute(req).await.map_err(|e| {
                 ServerFnErrorErr::Request(e.to_string()).into_app_error()
-            })
+            })?;
+
+            let (Some(cache), Some(cache_key)) = (cache, cache_key) else {
+                return Ok(res);
+            };
+            match cached {
+                Some(cached) if res.status() == StatusCode::NOT_MODIFIED => {
+                    Ok(Response::from(cached.to_response::<Bytes>()))
+                }
+                _ if cache.should_store(
+                    &request_headers,
+                    res.status(),
+                    res.headers(),
+                ) =>
+                {
+                    let headers = res.headers().clone();
+                    let body = res.bytes().await.map_err(|e| {
+                        ServerFnErrorErr::Request(e.to_string())
+                            .into_app_error()
+                    })?;
+                    cache.store(
+                        cache_key,
+                        &request_headers,
+                        &headers,
+                        body.clone(),
+                    );
+                    let mut res = http::Response::new(body);
+                    *res.headers_mut() = headers;
+                    Ok(Response::from(res))
+                }
+                _ => Ok(res),
+            }
         }
 
         async fn open_websocket(
@@ -328,3 +385,414 @@ pub mod reqwest {
         }
     }
 }
+
+#[cfg(feature = "axum")]
+/// Implements [`Client`] by passing requests directly to the server function handler, for
+/// testing server functions without starting an HTTP server.
+///
+/// Requests are still encoded, routed, passed through middleware and decoded exactly as they
+/// would be over the network, so tests also catch problems with the encodings and middleware.
+/// To test a server function, call it through [`ServerFn::run_on_client`](crate::ServerFn::run_on_client)
+/// with `client = InProcessClient`:
+///
+/// ```rust,ignore
+/// #[server(client = server_fn::client::in_process::InProcessClient)]
+/// pub async fn whoami() -> Result<String, ServerFnError> {
+///     // ...
+/// }
+///
+/// #[tokio::test]
+/// async fn returns_the_user() {
+///     let context = InProcessContext::new()
+///         .with_header(COOKIE, HeaderValue::from_static("session=abc"));
+///     let name = context.run(Whoami {}.run_on_client()).await.unwrap();
+///     assert_eq!(name, "alice");
+///     assert_eq!(context.last_response().unwrap().status, StatusCode::OK);
+/// }
+/// ```
+///
+/// By default, requests are handled by [`handle_server_fn`](crate::axum::handle_server_fn).
+/// Integrations that provide their own handler, like `leptos_axum::handle_server_fns`, can be
+/// used instead with [`InProcessContext::with_handler`](in_process::InProcessContext::with_handler), so that everything the handler provides
+/// to the server function, and anything it adds to the response, is included in the test. For
+/// example, headers and status codes set through `leptos_axum::ResponseOptions` can then be read
+/// from the recorded responses:
+///
+/// ```rust,ignore
+/// let context = InProcessContext::new().with_handler(|req| async move {
+///     leptos_axum::handle_server_fns(req).await.into_response()
+/// });
+/// context.run(Login { name: "alice".into() }.run_on_client()).await?;
+/// assert!(context.last_response().unwrap().headers.contains_key(SET_COOKIE));
+/// ```
+pub mod in_process {
+    use super::Client;
+    use crate::{
+        cache::{ClientCache, ClientCacheLimits},
+        error::{FromServerFnError, IntoAppError, ServerFnErrorErr},
+        request::in_process::InProcessRequest,
+        response::in_process::InProcessResponse,
+    };
+    use axum::body::Body;
+    use bytes::Bytes;
+    use http::{
+        request::Parts, HeaderMap, HeaderName, HeaderValue, Method, Request,
+        Response, StatusCode,
+    };
+    use or_poisoned::OrPoisoned;
+    use std::{
+        fmt::{self, Debug},
+        future::Future,
+        pin::Pin,
+        sync::{Arc, Mutex},
+    };
+
+    type Handler = Arc<
+        dyn Fn(
+                Request<Body>,
+            )
+                -> Pin<Box<dyn Future<Output = Response<Body>> + Send>>
+            + Send
+            + Sync,
+    >;
+    type PartsHook = Arc<dyn Fn(&mut Parts) + Send + Sync>;
+
+    tokio::task_local! {
+        static CONTEXT: InProcessContext;
+    }
+
+    /// Implements [`Client`] by passing requests directly to a server function handler in the
+    /// same process.
+    pub struct InProcessClient;
+
+    /// The status and headers of a response received by the [`InProcessClient`].
+    #[derive(Debug, Clone)]
+    pub struct RecordedResponse {
+        /// The path of the server function that was called.
+        pub path: String,
+        /// The status of the response.
+        pub status: StatusCode,
+        /// The headers of the response.
+        pub headers: HeaderMap,
+    }
+
+    /// Configures how the [`InProcessClient`] sends requests while running a future with
+    /// [`run`](InProcessContext::run), and records the responses it receives.
+    ///
+    /// Outside of `run`, requests are sent to the default handler unchanged, and responses are
+    /// not recorded. Cacheable responses to `GET` requests are kept for as long as the context,
+    /// within the [`ClientCacheLimits`] set with
+    /// [`with_cache_limits`](InProcessContext::with_cache_limits), see [`cache`](crate::cache).
+    #[derive(Clone, Default)]
+    pub struct InProcessContext {
+        handler: Option<Handler>,
+        request_parts: Vec<PartsHook>,
+        responses: Arc<Mutex<Vec<RecordedResponse>>>,
+        cache: Arc<ClientCache>,
+    }
+
+    impl Debug for InProcessContext {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            f.debug_struct("InProcessContext")
+                .field("responses", &self.responses)
+                .finish_non_exhaustive()
+        }
+    }
+
+    impl InProcessContext {
+        /// Creates a context that uses the default handler.
+        pub fn new() -> Self {
+            Self::default()
+        }
+
+        /// Sets the handler that requests are passed to.
+        pub fn with_handler<F, Fut>(mut self, handler: F) -> Self
+        where
+            F: Fn(Request<Body>) -> Fut + Send + Sync + 'static,
+            Fut: Future<Output = Response<Body>> + Send + 'static,
+        {
+            self.handler = Some(Arc::new(move |req| Box::pin(handler(req))));
+            self
+        }
+
+        /// Modifies the parts of each request before it is handled, e.g., to add headers or
+        /// extensions that would usually be added by the HTTP server or by other layers.
+        pub fn with_request_parts(
+            mut self,
+            modify: impl Fn(&mut Parts) + Send + Sync + 'static,
+        ) -> Self {
+            self.request_parts.push(Arc::new(modify));
+            self
+        }
+
+        /// Sets the limits of the responses that are kept for this context. Limiting the number
+        /// of URLs to 0 turns the cache off.
+        ///
+        /// This clears the responses kept so far.
+        pub fn with_cache_limits(mut self, limits: ClientCacheLimits) -> Self {
+            self.cache = Arc::new(ClientCache::new(limits));
+            self
+        }
+
+        /// Adds a header to each request.
+        pub fn with_header(self, name: HeaderName, value: HeaderValue) -> Self {
+            self.with_request_parts(move |parts| {
+                parts.headers.append(name.clone(), value.clone());
+            })
+        }
+
+        /// Runs the future, sending all requests made by the [`InProcessClient`] while it runs
+        /// according to this context.
+        pub async fn run<Fut: Future>(&self, fut: Fut) -> Fut::Output {
+            CONTEXT.scope(self.clone(), fut).await
+        }
+
+        /// Returns the responses that have been received within this context, in order.
+        pub fn responses(&self) -> Vec<RecordedResponse> {
+            self.responses.lock().or_poisoned().clone()
+        }
+
+        /// Returns the most recent response received within this context.
+        pub fn last_response(&self) -> Option<RecordedResponse> {
+            self.responses.lock().or_poisoned().last().cloned()
+        }
+
+        async fn send(
+            &self,
+            req: Request<Body>,
+        ) -> Result<InProcessResponse, axum::Error> {
+            let (mut parts, body) = req.into_parts();
+            for modify in &self.request_parts {
+                modify(&mut parts);
+            }
+            let path = parts.uri.path().to_string();
+            let cache_key =
+                (parts.method == Method::GET).then(|| parts.uri.to_string());
+            let cached = cache_key
+                .as_deref()
+                .and_then(|url| self.cache.revalidate(url, &mut parts.headers));
+            let request_headers = parts.headers.clone();
+            let req = Request::from_parts(parts, body);
+            let mut res = match &self.handler {
+                Some(handler) => handler(req).await,
+                None => crate::axum::handle_server_fn(req).await,
+            };
+            self.responses.lock().or_poisoned().push(RecordedResponse {
+                path: path.clone(),
+                status: res.status(),
+                headers: res.headers().clone(),
+            });
+
+            if let Some(cache_key) = cache_key {
+                match cached {
+                    Some(cached)
+                        if res.status() == StatusCode::NOT_MODIFIED =>
+                    {
+                        res = cached.to_response();
+                    }
+                    _ if self.cache.should_store(
+                        &request_headers,
+                        res.status(),
+                        res.headers(),
+                    ) =>
+                    {
+                        let (parts, body) = res.into_parts();
+                        let body =
+                            axum::body::to_bytes(body, usize::MAX).await?;
+                        self.cache.store(
+                            cache_key,
+                            &request_headers,
+                            &parts.headers,
+                            body.clone(),
+                        );
+                        res = Response::from_parts(parts, Body::from(body));
+                    }
+                    _ => {}
+                }
+            }
+            Ok(InProcessResponse { path, res })
+        }
+    }
+
+    impl<
+            Error: FromServerFnError,
+            InputStreamError: FromServerFnError,
+            OutputStreamError: FromServerFnError,
+        > Client<Error, InputStreamError, OutputStreamError>
+        for InProcessClient
+    {
+        type Request = InProcessRequest;
+        type Response = InProcessResponse;
+
+        async fn send(req: Self::Request) -> Result<Self::Response, Error> {
+            let context = CONTEXT.try_with(Clone::clone).unwrap_or_default();
+            context.send(req.0).await.map_err(|e| {
+                ServerFnErrorErr::Request(e.to_string()).into_app_error()
+            })
+        }
+
+        async fn open_websocket(
+            _path: &str,
+        ) -> Result<
+            (
+                impl futures::Stream<Item = Result<Bytes, Bytes>> + Send + 'static,
+                impl futures::Sink<Bytes> + Send + 'static,
+            ),
+            Error,
+        > {
+            Err::<
+                (
+                    futures::stream::Empty<Result<Bytes, Bytes>>,
+                    futures::sink::Drain<Bytes>,
+                ),
+                _,
+            >(
+                ServerFnErrorErr::Request(
+                    "Websockets are not supported by the in-process client."
+                        .into(),
+                )
+                .into_app_error(),
+            )
+        }
+
+        fn spawn(future: impl Future<Output = ()> + Send + 'static) {
+            tokio::spawn(future);
+        }
+    }
+}
+
+#[cfg(feature = "ipc")]
+/// Implements [`Client`] for server functions called over an IPC connection, see
+/// [`ipc`](crate::ipc).
+pub mod ipc {
+    use super::Client;
+    use crate::{
+        error::{FromServerFnError, IntoAppError, ServerFnErrorErr},
+        request::ipc::IpcRequest,
+        response::ipc::IpcResponse,
+    };
+    use bytes::Bytes;
+    use std::future::Future;
+
+    /// Implements [`Client`] for server functions called over an
+    /// [`IpcConnection`](crate::ipc::IpcConnection): the one the call is
+    /// [`run`](crate::ipc::IpcConnection::run) with, or otherwise the default connection.
+    pub struct IpcClient;
+
+    impl<
+            Error: FromServerFnError,
+            InputStreamError: FromServerFnError,
+            OutputStreamError: FromServerFnError,
+        > Client<Error, InputStreamError, OutputStreamError> for IpcClient
+    {
+        type Request = IpcRequest;
+        type Response = IpcResponse;
+
+        async fn send(req: Self::Request) -> Result<Self::Response, Error> {
+            let connection = crate::ipc::connection().ok_or_else(|| {
+                ServerFnErrorErr::Request(
+                    "No IPC connection is in scope, and no default connection \
+                     has been set up."
+                        .into(),
+                )
+                .into_app_error()
+            })?;
+            let path = req.path.clone();
+            let reply = connection
+                .call(req.method, req.path, req.headers, req.body)
+                .await?;
+            Ok(IpcResponse {
+                path,
+                status: reply.status,
+                headers: reply.headers,
+                body: reply.body,
+            })
+        }
+
+        async fn open_websocket(
+            _path: &str,
+        ) -> Result<
+            (
+                impl futures::Stream<Item = Result<Bytes, Bytes>> + Send + 'static,
+                impl futures::Sink<Bytes> + Send + 'static,
+            ),
+            Error,
+        > {
+            Err::<
+                (
+                    futures::stream::Empty<Result<Bytes, Bytes>>,
+                    futures::sink::Drain<Bytes>,
+                ),
+                _,
+            >(
+                ServerFnErrorErr::Request(
+                    "Websockets are not supported over IPC; use a streaming \
+                     encoding instead."
+                        .into(),
+                )
+                .into_app_error(),
+            )
+        }
+
+        fn spawn(future: impl Future<Output = ()> + Send + 'static) {
+            if let Some(connection) = crate::ipc::connection() {
+                connection.spawn(Box::pin(future));
+            }
+        }
+    }
+}
+
+#[cfg(all(test, feature = "axum"))]
+mod tests {
+    use super::{
+        in_process::{InProcessClient, InProcessContext},
+        Client,
+    };
+    use crate::{
+        request::{in_process::InProcessRequest, ClientReq},
+        response::ClientRes,
+        ServerFnError,
+    };
+    use axum::body::Body;
+    use futures::executor::block_on;
+    use http::{HeaderName, HeaderValue, Method, Response, StatusCode};
+
+    #[test]
+    fn in_process_context_modifies_requests_and_records_responses() {
+        let context = InProcessContext::new()
+            .with_header(
+                HeaderName::from_static("x-user"),
+                HeaderValue::from_static("alice"),
+            )
+            .with_handler(|req| async move {
+                let user = req.headers()["x-user"].to_str().unwrap().to_owned();
+                Response::builder()
+                    .status(StatusCode::CREATED)
+                    .body(Body::from(format!("{} {user}", req.uri())))
+                    .unwrap()
+            });
+
+        let req: InProcessRequest =
+            ClientReq::<ServerFnError>::try_new_req_text(
+                "/api/whoami",
+                "text/plain",
+                "text/plain",
+                String::new(),
+                Method::POST,
+            )
+            .unwrap();
+        let body = block_on(context.run(async {
+            let res = <InProcessClient as Client<ServerFnError>>::send(req)
+                .await
+                .unwrap();
+            ClientRes::<ServerFnError>::try_into_string(res)
+                .await
+                .unwrap()
+        }));
+
+        assert_eq!(body, "/api/whoami alice");
+        let res = context.last_response().unwrap();
+        assert_eq!(res.path, "/api/whoami");
+        assert_eq!(res.status, StatusCode::CREATED);
+    }
+}
@@ -40,6 +40,9 @@ mod multipart;
 #[cfg(feature = "multipart")]
 pub use multipart::*;
 
+#[cfg(feature = "multipart")]
+pub mod upload;
+
 #[cfg(feature = "msgpack")]
 mod msgpack;
 #[cfg(feature = "msgpack")]
@@ -50,6 +53,11 @@ mod postcard;
 #[cfg(feature = "postcard")]
 pub use postcard::*;
 
+#[cfg(feature = "prost")]
+mod prost;
+#[cfg(feature = "prost")]
+pub use prost::*;
+
 #[cfg(feature = "bitcode")]
 mod bitcode;
 #[cfg(feature = "bitcode")]
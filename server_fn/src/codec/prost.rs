@@ -0,0 +1,60 @@
+use crate::{
+    codec::{Patch, Post, Put},
+    ContentType, Decodes, Encodes, Format, FormatType,
+};
+use bytes::Bytes;
+use prost::Message;
+
+/// A codec for Protocol Buffers, using [`prost`].
+///
+/// Any type that implements [`prost::Message`] can be used as the output of a server function,
+/// or as the item type of a streaming encoding such as
+/// [`StreamingFramed`](super::StreamingFramed), so that message types generated for gRPC
+/// services can be shared with server functions.
+///
+/// A server function that uses this encoding for its input must take exactly one argument,
+/// which is the request message.
+pub struct ProtobufEncoding;
+
+impl ContentType for ProtobufEncoding {
+    const CONTENT_TYPE: &'static str = "application/x-protobuf";
+}
+
+impl FormatType for ProtobufEncoding {
+    const FORMAT_TYPE: Format = Format::Binary;
+}
+
+impl<T> Encodes<T> for ProtobufEncoding
+where
+    T: Message,
+{
+    type Error = prost::EncodeError;
+
+    fn encode(value: &T) -> Result<Bytes, Self::Error> {
+        Ok(Bytes::from(value.encode_to_vec()))
+    }
+}
+
+impl<T> Decodes<T> for ProtobufEncoding
+where
+    T: Message + Default,
+{
+    type Error = prost::DecodeError;
+
+    fn decode(bytes: Bytes) -> Result<T, Self::Error> {
+        T::decode(bytes)
+    }
+}
+
+/// Pass arguments and receive responses as Protocol Buffers in a `POST` request.
+pub type Protobuf = Post<ProtobufEncoding>;
+
+/// Pass arguments and receive responses as Protocol Buffers in a `PATCH` request.
+/// **Note**: Browser support for `PATCH` requests without JS/WASM may be poor.
+/// Consider using a `POST` request if functionality without JS/WASM is required.
+pub type PatchProtobuf = Patch<ProtobufEncoding>;
+
+/// Pass arguments and receive responses as Protocol Buffers in a `PUT` request.
+/// **Note**: Browser support for `PUT` requests without JS/WASM may be poor.
+/// Consider using a `POST` request if functionality without JS/WASM is required.
+pub type PutProtobuf = Put<ProtobufEncoding>;
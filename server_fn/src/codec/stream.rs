@@ -1,14 +1,22 @@
 use super::{Encoding, FromReq, FromRes, IntoReq};
 use crate::{
-    error::{FromServerFnError, ServerFnErrorErr},
+    error::{FromServerFnError, IntoAppError, ServerFnErrorErr},
     request::{ClientReq, Req},
     response::{ClientRes, TryRes},
-    ContentType, IntoRes, ServerFnError,
+    ContentType, Decodes, Encodes, IntoRes, ServerFnError,
 };
-use bytes::Bytes;
-use futures::{Stream, StreamExt, TryStreamExt};
+use base64::{engine::general_purpose::STANDARD, Engine as _};
+use bytes::{Buf, Bytes, BytesMut};
+use futures::{stream, Stream, StreamExt, TryStreamExt};
 use http::Method;
-use std::{fmt::Debug, pin::Pin};
+use serde::{de::DeserializeOwned, Serialize};
+use std::{
+    fmt::Debug,
+    marker::PhantomData,
+    ops::Deref,
+    pin::Pin,
+    sync::atomic::{AtomicUsize, Ordering},
+};
 
 /// An encoding that represents a stream of bytes.
 ///
@@ -65,14 +73,8 @@ where
 ///
 /// A server function can return this type if its output encoding is [`Streaming`].
 ///
-/// ## Browser Support for Streaming Input
-///
-/// Browser fetch requests do not currently support full request duplexing, which
-/// means that that they do begin handling responses until the full request has been sent.
-/// This means that if you use a streaming input encoding, the input stream needs to
-/// end before the output will begin.
-///
-/// Streaming requests are only allowed over HTTP2 or HTTP3.
+/// See [`Streaming`](Streaming#browser-support-for-streaming-input) for browser support for
+/// streaming input.
 pub struct ByteStream<E = ServerFnError>(
     Pin<Box<dyn Stream<Item = Result<Bytes, E>> + Send>>,
 );
@@ -140,14 +142,8 @@ where
 ///
 /// A server function that uses this as its output encoding should return [`TextStream`].
 ///
-/// ## Browser Support for Streaming Input
-///
-/// Browser fetch requests do not currently support full request duplexing, which
-/// means that that they do begin handling responses until the full request has been sent.
-/// This means that if you use a streaming input encoding, the input stream needs to
-/// end before the output will begin.
-///
-/// Streaming requests are only allowed over HTTP2 or HTTP3.
+/// See [`Streaming`](Streaming#browser-support-for-streaming-input) for browser support for
+/// streaming input.
 pub struct StreamingText;
 
 impl ContentType for StreamingText {
@@ -162,14 +158,8 @@ impl Encoding for StreamingText {
 ///
 /// A server function can return this type if its output encoding is [`StreamingText`].
 ///
-/// ## Browser Support for Streaming Input
-///
-/// Browser fetch requests do not currently support full request duplexing, which
-/// means that that they do begin handling responses until the full request has been sent.
-/// This means that if you use a streaming input encoding, the input stream needs to
-/// end before the output will begin.
-///
-/// Streaming requests are only allowed over HTTP2 or HTTP3.
+/// See [`Streaming`](Streaming#browser-support-for-streaming-input) for browser support for
+/// streaming input.
 pub struct TextStream<E = ServerFnError>(
     Pin<Box<dyn Stream<Item = Result<String, E>> + Send>>,
 );
@@ -280,3 +270,653 @@ where
         }))))
     }
 }
+
+static MAX_FRAME_LEN: AtomicUsize = AtomicUsize::new(16 * 1024 * 1024);
+
+/// Sets the largest value, in bytes, that is accepted from a [`StreamingJson`] or
+/// [`StreamingFramed`] stream.
+///
+/// A longer line or frame is yielded as an error and ends the stream, so that a peer cannot make
+/// the receiver buffer an unbounded amount of data. Defaults to 16 MiB.
+pub fn set_max_frame_len(bytes: usize) {
+    MAX_FRAME_LEN.store(bytes, Ordering::Relaxed);
+}
+
+/// The largest value, in bytes, that is accepted from a [`StreamingJson`] or [`StreamingFramed`]
+/// stream.
+pub fn max_frame_len() -> usize {
+    MAX_FRAME_LEN.load(Ordering::Relaxed)
+}
+
+/// A frame split from a [`StreamingJson`] or [`StreamingFramed`] stream.
+#[derive(Debug, PartialEq)]
+enum Frame {
+    /// An encoded value.
+    Value(Bytes),
+    /// A serialized error, sent in place of a value.
+    Error(Bytes),
+}
+
+/// Splits a stream of chunks into a stream of frames, reassembling frames that were split
+/// across chunk boundaries.
+///
+/// `next_frame` removes the next complete frame from the front of the buffer, if there is one,
+/// and fails if the frame would be longer than the maximum length. When the stream ends,
+/// `last_frame` is called with whatever is left in the buffer.
+fn split_frames<E>(
+    chunks: impl Stream<Item = Result<Bytes, E>> + Send + 'static,
+    next_frame: fn(&mut BytesMut, usize) -> Result<Option<Frame>, &'static str>,
+    last_frame: fn(BytesMut) -> Result<Option<Frame>, &'static str>,
+) -> impl Stream<Item = Result<Frame, E>> + Send + 'static
+where
+    E: FromServerFnError,
+{
+    let max_len = max_frame_len();
+    stream::unfold(
+        (Box::pin(chunks), BytesMut::new(), false),
+        move |(mut chunks, mut buf, done)| async move {
+            loop {
+                match next_frame(&mut buf, max_len) {
+                    Ok(Some(frame)) => {
+                        return Some((Ok(frame), (chunks, buf, done)))
+                    }
+                    Ok(None) => {}
+                    // the rest of the stream cannot be split reliably
+                    Err(e) => {
+                        return Some((
+                            Err(deserialization_error(e)),
+                            (chunks, BytesMut::new(), true),
+                        ))
+                    }
+                }
+                if done {
+                    return None;
+                }
+                match chunks.next().await {
+                    Some(Ok(chunk)) => buf.extend_from_slice(&chunk),
+                    Some(Err(e)) => return Some((Err(e), (chunks, buf, done))),
+                    None => {
+                        let last = match last_frame(buf.split()) {
+                            Ok(Some(frame)) => Ok(frame),
+                            Ok(None) => return None,
+                            Err(e) => Err(deserialization_error(e)),
+                        };
+                        return Some((last, (chunks, buf, true)));
+                    }
+                }
+            }
+        },
+    )
+}
+
+fn deserialization_error<E: FromServerFnError>(message: &str) -> E {
+    E::from_server_fn_error(ServerFnErrorErr::Deserialization(message.into()))
+}
+
+/// Converts a stream of items into body chunks.
+///
+/// Errors, including values that cannot be encoded, are serialized and sent in place of the
+/// value with `encode_error`, so that the receiver gets them as errors.
+fn encode_items<T, E>(
+    items: impl Stream<Item = Result<T, E>> + Send + 'static,
+    encode: fn(&T) -> Result<Bytes, String>,
+    encode_error: fn(Bytes) -> Bytes,
+) -> impl Stream<Item = Bytes> + Send + 'static
+where
+    T: 'static,
+    E: FromServerFnError,
+{
+    items.map(move |item| {
+        item.and_then(|item| {
+            encode(&item).map_err(|e| {
+                ServerFnErrorErr::Serialization(e).into_app_error()
+            })
+        })
+        .unwrap_or_else(|e: E| encode_error(e.ser()))
+    })
+}
+
+/// Decodes each frame of a stream into an item, yielding decode errors and errors sent by the
+/// other side as items.
+fn decode_frames<T, E>(
+    frames: impl Stream<Item = Result<Frame, E>> + Send + 'static,
+    decode: fn(Bytes) -> Result<T, String>,
+) -> impl Stream<Item = Result<T, E>> + Send + 'static
+where
+    T: 'static,
+    E: FromServerFnError,
+{
+    frames.map(move |frame| match frame? {
+        Frame::Value(value) => decode(value)
+            .map_err(|e| ServerFnErrorErr::Deserialization(e).into_app_error()),
+        Frame::Error(error) => Err(E::de(error)),
+    })
+}
+
+/// An encoding that represents a stream of JSON values, one per line
+/// ([newline-delimited JSON](https://github.com/ndjson/ndjson-spec)).
+///
+/// A server function that uses this as its output encoding should return [`JsonStream`]. As an
+/// input encoding, the server function should take a single [`JsonStream`] argument.
+///
+/// Values may be split across chunks of the underlying stream, and are reassembled before being
+/// deserialized. A value that cannot be deserialized is yielded as an error, without ending the
+/// stream. A line longer than [`max_frame_len`] is yielded as an error and ends the stream.
+///
+/// An error in the stream being sent is yielded as an error by the receiver. It is sent as a line
+/// starting with `!`, followed by the serialized error encoded as base64.
+///
+/// See [`Streaming`](Streaming#browser-support-for-streaming-input) for browser support for
+/// streaming input.
+pub struct StreamingJson;
+
+impl ContentType for StreamingJson {
+    const CONTENT_TYPE: &'static str = "application/x-ndjson";
+}
+
+impl Encoding for StreamingJson {
+    const METHOD: Method = Method::POST;
+}
+
+/// A stream of values, sent as newline-delimited JSON.
+///
+/// A server function can return this type if its output encoding is [`StreamingJson`].
+///
+/// See [`Streaming`](Streaming#browser-support-for-streaming-input) for browser support for
+/// streaming input.
+pub struct JsonStream<T, E = ServerFnError>(
+    Pin<Box<dyn Stream<Item = Result<T, E>> + Send>>,
+);
+
+impl<T, E> Debug for JsonStream<T, E> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("JsonStream").finish()
+    }
+}
+
+impl<T, E> JsonStream<T, E> {
+    /// Creates a new `JsonStream` from the given stream.
+    pub fn new(
+        value: impl Stream<Item = Result<T, E>> + Send + 'static,
+    ) -> Self {
+        Self(Box::pin(value))
+    }
+
+    /// Consumes the wrapper, returning a stream of values.
+    pub fn into_inner(self) -> impl Stream<Item = Result<T, E>> + Send {
+        self.0
+    }
+}
+
+impl<T, E, S> From<S> for JsonStream<T, E>
+where
+    S: Stream<Item = T> + Send + 'static,
+    T: 'static,
+    E: 'static,
+{
+    fn from(value: S) -> Self {
+        Self(Box::pin(value.map(Ok)))
+    }
+}
+
+fn encode_json_line<T: Serialize>(value: &T) -> Result<Bytes, String> {
+    let mut line = serde_json::to_vec(value).map_err(|e| e.to_string())?;
+    line.push(b'\n');
+    Ok(Bytes::from(line))
+}
+
+fn decode_json_line<T: DeserializeOwned>(line: Bytes) -> Result<T, String> {
+    serde_json::from_slice(&line).map_err(|e| e.to_string())
+}
+
+/// Marks a line that carries an error rather than a value, as no JSON value starts with it.
+const JSON_ERROR_MARKER: u8 = b'!';
+
+fn encode_json_error(error: Bytes) -> Bytes {
+    let mut line = vec![JSON_ERROR_MARKER];
+    line.extend_from_slice(STANDARD.encode(error).as_bytes());
+    line.push(b'\n');
+    Bytes::from(line)
+}
+
+fn json_frame(line: Bytes) -> Result<Frame, &'static str> {
+    match line.strip_prefix(&[JSON_ERROR_MARKER]) {
+        Some(error) => STANDARD
+            .decode(error.trim_ascii())
+            .map(|error| Frame::Error(error.into()))
+            .map_err(|_| "invalid error line"),
+        None => Ok(Frame::Value(line)),
+    }
+}
+
+fn next_json_line(
+    buf: &mut BytesMut,
+    max_len: usize,
+) -> Result<Option<Frame>, &'static str> {
+    const TOO_LONG: &str = "a line is longer than the maximum frame length";
+    loop {
+        let Some(end) = buf.iter().position(|byte| *byte == b'\n') else {
+            return if buf.len() > max_len {
+                Err(TOO_LONG)
+            } else {
+                Ok(None)
+            };
+        };
+        if end > max_len {
+            return Err(TOO_LONG);
+        }
+        let line = buf.split_to(end + 1).freeze().slice(..end);
+        if !line.trim_ascii().is_empty() {
+            return json_frame(line).map(Some);
+        }
+    }
+}
+
+fn last_json_line(buf: BytesMut) -> Result<Option<Frame>, &'static str> {
+    // the final value may not be followed by a newline
+    if buf.trim_ascii().is_empty() {
+        Ok(None)
+    } else {
+        json_frame(buf.freeze()).map(Some)
+    }
+}
+
+impl<E, T, Item, Request> IntoReq<StreamingJson, Request, E> for T
+where
+    Request: ClientReq<E>,
+    T: Deref<Target = JsonStream<Item, E>> + Into<JsonStream<Item, E>>,
+    Item: Serialize + Send + 'static,
+    E: FromServerFnError,
+{
+    fn into_req(self, path: &str, accepts: &str) -> Result<Request, E> {
+        let data = self.into();
+        Request::try_new_post_streaming(
+            path,
+            accepts,
+            StreamingJson::CONTENT_TYPE,
+            encode_items(data.0, encode_json_line, encode_json_error),
+        )
+    }
+}
+
+impl<E, T, Item, Request> FromReq<StreamingJson, Request, E> for T
+where
+    Request: Req<E> + Send + 'static,
+    T: Deref<Target = JsonStream<Item, E>>
+        + From<JsonStream<Item, E>>
+        + 'static,
+    Item: DeserializeOwned + Send + 'static,
+    E: FromServerFnError,
+{
+    async fn from_req(req: Request) -> Result<Self, E> {
+        let data = req.try_into_stream()?.map_err(|e| E::de(e));
+        let lines = split_frames(data, next_json_line, last_json_line);
+        Ok(JsonStream::new(decode_frames(lines, decode_json_line)).into())
+    }
+}
+
+impl<E, T, Response> IntoRes<StreamingJson, Response, E> for JsonStream<T, E>
+where
+    Response: TryRes<E>,
+    T: Serialize + Send + 'static,
+    E: FromServerFnError,
+{
+    async fn into_res(self) -> Result<Response, E> {
+        Response::try_from_stream(
+            StreamingJson::CONTENT_TYPE,
+            encode_items(self.0, encode_json_line, encode_json_error).map(Ok),
+        )
+    }
+}
+
+impl<E, T, Response> FromRes<StreamingJson, Response, E> for JsonStream<T, E>
+where
+    Response: ClientRes<E> + Send,
+    T: DeserializeOwned + Send + 'static,
+    E: FromServerFnError,
+{
+    async fn from_res(res: Response) -> Result<Self, E> {
+        let data = res.try_into_stream()?.map_err(|e| E::de(e));
+        let lines = split_frames(data, next_json_line, last_json_line);
+        Ok(JsonStream::new(decode_frames(lines, decode_json_line)))
+    }
+}
+
+/// An encoding that represents a stream of values, each encoded with `Codec` and prefixed with
+/// its length.
+///
+/// Any codec that implements [`Encodes`] and [`Decodes`] can be used, such as
+/// [`CborEncoding`](super::CborEncoding), [`PostcardEncoding`](super::PostcardEncoding) or
+/// [`MsgPackEncoding`](super::MsgPackEncoding). Each value is sent as a 4-byte big-endian length,
+/// followed by that many bytes of encoded data.
+///
+/// A server function that uses this as its output encoding should return [`FramedStream`]. As an
+/// input encoding, the server function should take a single [`FramedStream`] argument.
+///
+/// Values may be split across chunks of the underlying stream, and are reassembled before being
+/// decoded. A value that cannot be decoded is yielded as an error, without ending the stream. A
+/// frame longer than [`max_frame_len`] is yielded as an error and ends the stream.
+///
+/// An error in the stream being sent is yielded as an error by the receiver. It is sent with the
+/// reserved length `0xFFFFFFFF`, followed by the length of the serialized error and the error
+/// itself.
+///
+/// See [`Streaming`](Streaming#browser-support-for-streaming-input) for browser support for
+/// streaming input.
+pub struct StreamingFramed<Codec>(PhantomData<Codec>);
+
+impl<Codec> ContentType for StreamingFramed<Codec> {
+    const CONTENT_TYPE: &'static str = "application/octet-stream";
+}
+
+impl<Codec> Encoding for StreamingFramed<Codec> {
+    const METHOD: Method = Method::POST;
+}
+
+/// A stream of values, each encoded with a codec and prefixed with its length.
+///
+/// A server function can return this type if its output encoding is [`StreamingFramed`].
+///
+/// See [`Streaming`](Streaming#browser-support-for-streaming-input) for browser support for
+/// streaming input.
+pub struct FramedStream<T, E = ServerFnError>(
+    Pin<Box<dyn Stream<Item = Result<T, E>> + Send>>,
+);
+
+impl<T, E> Debug for FramedStream<T, E> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("FramedStream").finish()
+    }
+}
+
+impl<T, E> FramedStream<T, E> {
+    /// Creates a new `FramedStream` from the given stream.
+    pub fn new(
+        value: impl Stream<Item = Result<T, E>> + Send + 'static,
+    ) -> Self {
+        Self(Box::pin(value))
+    }
+
+    /// Consumes the wrapper, returning a stream of values.
+    pub fn into_inner(self) -> impl Stream<Item = Result<T, E>> + Send {
+        self.0
+    }
+}
+
+impl<T, E, S> From<S> for FramedStream<T, E>
+where
+    S: Stream<Item = T> + Send + 'static,
+    T: 'static,
+    E: 'static,
+{
+    fn from(value: S) -> Self {
+        Self(Box::pin(value.map(Ok)))
+    }
+}
+
+/// A length prefix that marks a frame carrying an error rather than a value. It is followed by
+/// the length of the serialized error and the error itself.
+const ERROR_FRAME: u32 = u32::MAX;
+
+fn encode_frame<Codec: Encodes<T>, T>(value: &T) -> Result<Bytes, String> {
+    let data = Codec::encode(value).map_err(|e| e.to_string())?;
+    let len = u32::try_from(data.len())
+        .ok()
+        .filter(|len| *len != ERROR_FRAME)
+        .ok_or_else(|| "value is too large to be framed".to_string())?;
+    let mut frame = BytesMut::with_capacity(4 + data.len());
+    frame.extend_from_slice(&len.to_be_bytes());
+    frame.extend_from_slice(&data);
+    Ok(frame.freeze())
+}
+
+fn encode_error_frame(error: Bytes) -> Bytes {
+    // an error too long to be framed is cut short rather than dropped
+    let len = error.len().min(ERROR_FRAME as usize - 1);
+    let mut frame = BytesMut::with_capacity(8 + len);
+    frame.extend_from_slice(&ERROR_FRAME.to_be_bytes());
+    frame.extend_from_slice(&(len as u32).to_be_bytes());
+    frame.extend_from_slice(&error[..len]);
+    frame.freeze()
+}
+
+fn decode_frame<Codec: Decodes<T>, T>(frame: Bytes) -> Result<T, String> {
+    Codec::decode(frame).map_err(|e| e.to_string())
+}
+
+fn read_len(buf: &[u8], at: usize) -> Option<u32> {
+    Some(u32::from_be_bytes(buf.get(at..at + 4)?.try_into().ok()?))
+}
+
+fn next_length_prefixed(
+    buf: &mut BytesMut,
+    max_len: usize,
+) -> Result<Option<Frame>, &'static str> {
+    let Some(len) = read_len(buf, 0) else {
+        return Ok(None);
+    };
+    let (is_error, len) = if len == ERROR_FRAME {
+        match read_len(buf, 4) {
+            Some(len) => (true, len),
+            None => return Ok(None),
+        }
+    } else {
+        (false, len)
+    };
+    let len = len as usize;
+    if len > max_len {
+        return Err("a frame is longer than the maximum frame length");
+    }
+    let header = if is_error { 8 } else { 4 };
+    if buf.len() < header + len {
+        return Ok(None);
+    }
+    buf.advance(header);
+    let data = buf.split_to(len).freeze();
+    Ok(Some(if is_error {
+        Frame::Error(data)
+    } else {
+        Frame::Value(data)
+    }))
+}
+
+fn last_length_prefixed(buf: BytesMut) -> Result<Option<Frame>, &'static str> {
+    if buf.is_empty() {
+        Ok(None)
+    } else {
+        Err("the stream ended in the middle of a frame")
+    }
+}
+
+impl<E, T, Item, Codec, Request> IntoReq<StreamingFramed<Codec>, Request, E>
+    for T
+where
+    Request: ClientReq<E>,
+    T: Deref<Target = FramedStream<Item, E>> + Into<FramedStream<Item, E>>,
+    Codec: Encodes<Item>,
+    Item: Send + 'static,
+    E: FromServerFnError,
+{
+    fn into_req(self, path: &str, accepts: &str) -> Result<Request, E> {
+        let data = self.into();
+        Request::try_new_post_streaming(
+            path,
+            accepts,
+            StreamingFramed::<Codec>::CONTENT_TYPE,
+            encode_items(
+                data.0,
+                encode_frame::<Codec, Item>,
+                encode_error_frame,
+            ),
+        )
+    }
+}
+
+impl<E, T, Item, Codec, Request> FromReq<StreamingFramed<Codec>, Request, E>
+    for T
+where
+    Request: Req<E> + Send + 'static,
+    T: Deref<Target = FramedStream<Item, E>>
+        + From<FramedStream<Item, E>>
+        + 'static,
+    Codec: Decodes<Item>,
+    Item: Send + 'static,
+    E: FromServerFnError,
+{
+    async fn from_req(req: Request) -> Result<Self, E> {
+        let data = req.try_into_stream()?.map_err(|e| E::de(e));
+        let frames =
+            split_frames(data, next_length_prefixed, last_length_prefixed);
+        Ok(FramedStream::new(decode_frames(
+            frames,
+            decode_frame::<Codec, Item>,
+        ))
+        .into())
+    }
+}
+
+impl<E, T, Codec, Response> IntoRes<StreamingFramed<Codec>, Response, E>
+    for FramedStream<T, E>
+where
+    Response: TryRes<E>,
+    Codec: Encodes<T>,
+    T: Send + 'static,
+    E: FromServerFnError,
+{
+    async fn into_res(self) -> Result<Response, E> {
+        Response::try_from_stream(
+            StreamingFramed::<Codec>::CONTENT_TYPE,
+            encode_items(self.0, encode_frame::<Codec, T>, encode_error_frame)
+                .map(Ok),
+        )
+    }
+}
+
+impl<E, T, Codec, Response> FromRes<StreamingFramed<Codec>, Response, E>
+    for FramedStream<T, E>
+where
+    Response: ClientRes<E> + Send,
+    Codec: Decodes<T>,
+    T: Send + 'static,
+    E: FromServerFnError,
+{
+    async fn from_res(res: Response) -> Result<Self, E> {
+        let data = res.try_into_stream()?.map_err(|e| E::de(e));
+        let frames =
+            split_frames(data, next_length_prefixed, last_length_prefixed);
+        Ok(FramedStream::new(decode_frames(
+            frames,
+            decode_frame::<Codec, T>,
+        )))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::executor::block_on;
+
+    fn chunks(
+        chunks: &[&'static [u8]],
+    ) -> impl Stream<Item = Result<Bytes, ServerFnError>> + Send + 'static {
+        stream::iter(
+            chunks
+                .iter()
+                .map(|chunk| Ok(Bytes::from_static(chunk)))
+                .collect::<Vec<_>>(),
+        )
+    }
+
+    #[test]
+    fn json_lines_are_reassembled_across_chunks() {
+        let lines = split_frames(
+            chunks(&[b"{\"a\":", b"1}\n{\"a\"", b":2}\n\nnot json\n{\"a\":3}"]),
+            next_json_line,
+            last_json_line,
+        );
+        let items = block_on(
+            decode_frames(lines, decode_json_line::<serde_json::Value>)
+                .collect::<Vec<_>>(),
+        );
+        assert_eq!(items.len(), 4);
+        assert_eq!(items[0].as_ref().unwrap()["a"], 1);
+        assert_eq!(items[1].as_ref().unwrap()["a"], 2);
+        assert!(items[2].is_err());
+        assert_eq!(items[3].as_ref().unwrap()["a"], 3);
+    }
+
+    #[test]
+    fn length_prefixed_frames_are_reassembled_across_chunks() {
+        let frames = split_frames(
+            chunks(&[b"\0\0", b"\0\x03ab", b"c\0\0\0\0\0\0\0\x02x"]),
+            next_length_prefixed,
+            last_length_prefixed,
+        );
+        let frames = block_on(frames.collect::<Vec<_>>());
+        assert_eq!(frames.len(), 3);
+        assert_eq!(
+            frames[0].as_ref().unwrap(),
+            &Frame::Value(Bytes::from_static(b"abc"))
+        );
+        assert_eq!(frames[1].as_ref().unwrap(), &Frame::Value(Bytes::new()));
+        // the stream ended before the last frame was complete
+        assert!(frames[2].is_err());
+    }
+
+    #[test]
+    fn frames_longer_than_the_maximum_are_rejected() {
+        let mut buf = BytesMut::from(&b"\0\0\0\x05ab"[..]);
+        assert!(next_length_prefixed(&mut buf, 4).is_err());
+        let mut buf = BytesMut::from(&b"\0\0\0\x02ab"[..]);
+        assert!(matches!(next_length_prefixed(&mut buf, 4), Ok(Some(_))));
+
+        // a line is rejected once it is too long, even before it ends
+        let mut buf = BytesMut::from(&b"[1,2,3"[..]);
+        assert!(next_json_line(&mut buf, 4).is_err());
+        let mut buf = BytesMut::from(&b"[1]\n"[..]);
+        assert!(matches!(next_json_line(&mut buf, 4), Ok(Some(_))));
+    }
+
+    fn round_trip<T: Send + 'static>(
+        items: Vec<Result<T, ServerFnError>>,
+        encode: fn(&T) -> Result<Bytes, String>,
+        encode_error: fn(Bytes) -> Bytes,
+        next_frame: fn(
+            &mut BytesMut,
+            usize,
+        ) -> Result<Option<Frame>, &'static str>,
+        last_frame: fn(BytesMut) -> Result<Option<Frame>, &'static str>,
+        decode: fn(Bytes) -> Result<T, String>,
+    ) -> Vec<Result<T, ServerFnError>> {
+        let body = encode_items(stream::iter(items), encode, encode_error);
+        let frames = split_frames(body.map(Ok), next_frame, last_frame);
+        block_on(decode_frames(frames, decode).collect())
+    }
+
+    #[test]
+    fn errors_are_sent_to_the_receiver() {
+        let items = vec![
+            Ok(1),
+            Err(ServerFnError::ServerError("failed".into())),
+            Ok(2),
+        ];
+        let json = round_trip(
+            items.clone(),
+            encode_json_line,
+            encode_json_error,
+            next_json_line,
+            last_json_line,
+            decode_json_line::<i32>,
+        );
+        let framed = round_trip(
+            items.clone(),
+            encode_frame::<crate::codec::JsonEncoding, i32>,
+            encode_error_frame,
+            next_length_prefixed,
+            last_length_prefixed,
+            decode_frame::<crate::codec::JsonEncoding, i32>,
+        );
+        assert_eq!(json, items);
+        assert_eq!(framed, items);
+    }
+}
@@ -0,0 +1,736 @@
+//! Resumable, chunked file uploads with progress reporting.
+//!
+//! [`MultipartFormData`](super::MultipartFormData) sends a whole form in a single request, so
+//! the client cannot report how far an upload has got, and an interrupted upload has to start
+//! again from the beginning. The [`ChunkedUpload`] protocol instead splits a file into chunks and
+//! sends each one in its own request. The server function is called once for each chunk, and
+//! passes it on to an [`UploadSink`], which stores the data under the upload’s id:
+//!
+//! ```rust,ignore
+//! use server_fn::codec::upload::{ChunkedUpload, FileUpload, UploadProgress};
+//!
+//! #[server(protocol = ChunkedUpload)]
+//! pub async fn upload_file(file: FileUpload) -> Result<UploadProgress, ServerFnError> {
+//!     file.write_to(&*UPLOADS).await
+//! }
+//! ```
+//!
+//! On the client, a [`FileUpload`] is created from a browser `File` (or from bytes), and calling
+//! the server function once uploads the whole file, reporting progress after every chunk:
+//!
+//! ```rust,ignore
+//! let upload = FileUpload::from_file(&file)
+//!     .on_progress(|progress| log!("{:.0}%", progress.fraction() * 100.0));
+//! upload_file(upload).await?;
+//! ```
+//!
+//! Before sending any data, the client asks the server to start the upload, and the server
+//! issues a random id for it. The id is reported with the upload’s [`UploadProgress`], and can be
+//! passed to [`FileUpload::with_id`] to resume the upload after an interruption, continuing from
+//! however much of it the server has received.
+
+use crate::{
+    error::{FromServerFnError, IntoAppError, ServerFnErrorErr},
+    request::{ClientReq, Req},
+    response::{ClientRes, TryRes},
+    Protocol, ServerFnError,
+};
+use bytes::{Bytes, BytesMut};
+use http::Method;
+use or_poisoned::OrPoisoned;
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::HashMap,
+    fmt::{self, Debug},
+    future::Future,
+    ops::Deref,
+    pin::Pin,
+    sync::{Arc, Mutex},
+};
+
+/// The default size of each chunk of a [`FileUpload`]: 1 MiB.
+pub const DEFAULT_CHUNK_SIZE: u64 = 1024 * 1024;
+
+const CHUNK_CONTENT_TYPE: &str = "application/octet-stream";
+const PROGRESS_CONTENT_TYPE: &str = "application/json";
+
+/// How much of an upload the server has received.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct UploadProgress {
+    /// The id of the upload.
+    pub id: String,
+    /// The number of bytes received so far.
+    pub received: u64,
+    /// The total size of the upload, in bytes.
+    pub total: u64,
+}
+
+impl UploadProgress {
+    /// Whether the whole upload has been received.
+    pub fn is_complete(&self) -> bool {
+        self.received >= self.total
+    }
+
+    /// The fraction of the upload that has been received, between `0.0` and `1.0`.
+    pub fn fraction(&self) -> f64 {
+        if self.total == 0 {
+            1.0
+        } else {
+            self.received as f64 / self.total as f64
+        }
+    }
+}
+
+/// Identifies an upload for an [`UploadSink`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct UploadInfo {
+    /// The id of the upload, issued by the server when the upload was started.
+    pub id: String,
+    /// The name of the file being uploaded.
+    pub name: String,
+    /// The total size of the upload, in bytes.
+    pub size: u64,
+}
+
+/// A place where the server stores the chunks of uploads.
+///
+/// Chunks are always written in order, and never extend past the size the upload was started
+/// with, so an implementation only needs to append data and remember the upload and how much it
+/// has received for it.
+pub trait UploadSink: Send + Sync {
+    /// Starts a new upload, with an id that has just been issued for it.
+    ///
+    /// This can fail to refuse the upload, for example if it is too large.
+    fn create(
+        &self,
+        upload: &UploadInfo,
+    ) -> impl Future<Output = Result<(), ServerFnError>> + Send;
+
+    /// Returns the upload with the given id and the number of bytes that have been received for
+    /// it so far, or `None` if there is no such upload.
+    fn received(
+        &self,
+        id: &str,
+    ) -> impl Future<Output = Result<Option<(UploadInfo, u64)>, ServerFnError>> + Send;
+
+    /// Appends a chunk of data to the upload. `offset` is always the number of bytes returned by
+    /// [`received`](UploadSink::received).
+    fn write(
+        &self,
+        upload: &UploadInfo,
+        offset: u64,
+        data: Bytes,
+    ) -> impl Future<Output = Result<(), ServerFnError>> + Send;
+
+    /// Called when the last chunk of the upload has been written.
+    ///
+    /// For an empty file, this may be called more than once.
+    fn finish(
+        &self,
+        upload: &UploadInfo,
+    ) -> impl Future<Output = Result<(), ServerFnError>> + Send {
+        let _ = upload;
+        async { Ok(()) }
+    }
+}
+
+/// An [`UploadSink`] that keeps uploads in memory.
+///
+/// This is useful for small files and for testing. Completed uploads can be removed with
+/// [`take`](InMemoryUploadSink::take).
+///
+/// Memory for the whole of an upload is reserved when it is started, and released when it is
+/// taken. An upload is refused if it is larger than
+/// [`with_max_upload_size`](InMemoryUploadSink::with_max_upload_size) (64 MiB by default), or if
+/// the sink would then hold more than [`with_max_memory`](InMemoryUploadSink::with_max_memory)
+/// (256 MiB by default).
+#[derive(Debug)]
+pub struct InMemoryUploadSink {
+    uploads: Mutex<HashMap<String, (UploadInfo, BytesMut)>>,
+    max_upload_size: u64,
+    max_memory: u64,
+}
+
+impl Default for InMemoryUploadSink {
+    fn default() -> Self {
+        Self {
+            uploads: Default::default(),
+            max_upload_size: 64 * 1024 * 1024,
+            max_memory: 256 * 1024 * 1024,
+        }
+    }
+}
+
+impl InMemoryUploadSink {
+    /// Creates a new, empty sink.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the largest upload, in bytes, that the sink accepts.
+    pub fn with_max_upload_size(mut self, bytes: u64) -> Self {
+        self.max_upload_size = bytes;
+        self
+    }
+
+    /// Sets the number of bytes that the sink may hold for all of its uploads together.
+    pub fn with_max_memory(mut self, bytes: u64) -> Self {
+        self.max_memory = bytes;
+        self
+    }
+
+    /// Removes an upload from the sink, returning the data received for it.
+    pub fn take(&self, id: &str) -> Option<Bytes> {
+        self.uploads
+            .lock()
+            .or_poisoned()
+            .remove(id)
+            .map(|(_, data)| data.freeze())
+    }
+}
+
+impl UploadSink for InMemoryUploadSink {
+    async fn create(&self, upload: &UploadInfo) -> Result<(), ServerFnError> {
+        if upload.size > self.max_upload_size {
+            return Err(ServerFnError::ServerError(format!(
+                "the upload is larger than the maximum of {} bytes",
+                self.max_upload_size
+            )));
+        }
+        let mut uploads = self.uploads.lock().or_poisoned();
+        let reserved: u64 = uploads.values().map(|(info, _)| info.size).sum();
+        if reserved.saturating_add(upload.size) > self.max_memory {
+            return Err(ServerFnError::ServerError(
+                "there is not enough memory for the upload".into(),
+            ));
+        }
+        uploads.insert(upload.id.clone(), (upload.clone(), BytesMut::new()));
+        Ok(())
+    }
+
+    async fn received(
+        &self,
+        id: &str,
+    ) -> Result<Option<(UploadInfo, u64)>, ServerFnError> {
+        Ok(self
+            .uploads
+            .lock()
+            .or_poisoned()
+            .get(id)
+            .map(|(info, data)| (info.clone(), data.len() as u64)))
+    }
+
+    async fn write(
+        &self,
+        upload: &UploadInfo,
+        offset: u64,
+        data: Bytes,
+    ) -> Result<(), ServerFnError> {
+        let mut uploads = self.uploads.lock().or_poisoned();
+        let Some((info, buf)) = uploads.get_mut(&upload.id) else {
+            return Err(ServerFnError::ServerError(
+                "the upload has been removed".into(),
+            ));
+        };
+        if offset != buf.len() as u64 || offset + data.len() as u64 > info.size
+        {
+            return Err(ServerFnError::ServerError(
+                "the chunk does not fit the upload".into(),
+            ));
+        }
+        buf.extend_from_slice(&data);
+        Ok(())
+    }
+}
+
+type ReadChunk = Pin<Box<dyn Future<Output = Result<Bytes, String>> + Send>>;
+
+#[derive(Clone)]
+enum Source {
+    Bytes(Bytes),
+    #[cfg(feature = "browser")]
+    Blob(send_wrapper::SendWrapper<web_sys::Blob>),
+    Chunk {
+        offset: u64,
+        data: Bytes,
+    },
+}
+
+impl Source {
+    fn read(&self, offset: u64, len: u64) -> ReadChunk {
+        match self {
+            Source::Bytes(data) => {
+                let start = (offset as usize).min(data.len());
+                let end = (offset.saturating_add(len) as usize).min(data.len());
+                let chunk = data.slice(start..end);
+                Box::pin(async move { Ok(chunk) })
+            }
+            #[cfg(feature = "browser")]
+            Source::Blob(blob) => {
+                let blob = blob.clone();
+                Box::pin(send_wrapper::SendWrapper::new(async move {
+                    let slice = blob
+                        .slice_with_f64_and_f64(
+                            offset as f64,
+                            offset.saturating_add(len) as f64,
+                        )
+                        .map_err(|e| format!("{e:?}"))?;
+                    let buffer = wasm_bindgen_futures::JsFuture::from(
+                        slice.array_buffer(),
+                    )
+                    .await
+                    .map_err(|e| format!("{e:?}"))?;
+                    Ok(Bytes::from(js_sys::Uint8Array::new(&buffer).to_vec()))
+                }))
+            }
+            Source::Chunk { .. } => Box::pin(async {
+                Err("a received chunk cannot be uploaded again".to_string())
+            }),
+        }
+    }
+}
+
+/// A file to be uploaded in chunks with the [`ChunkedUpload`] protocol.
+///
+/// On the client, this is the whole file. On the server, each call to the server function
+/// receives one chunk of it, which should be passed on to an [`UploadSink`] with
+/// [`write_to`](FileUpload::write_to).
+#[derive(Clone)]
+pub struct FileUpload {
+    id: Option<String>,
+    name: String,
+    size: u64,
+    source: Source,
+    chunk_size: u64,
+    on_progress: Option<Arc<dyn Fn(UploadProgress) + Send + Sync>>,
+}
+
+impl Debug for FileUpload {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("FileUpload")
+            .field("id", &self.id)
+            .field("name", &self.name)
+            .field("size", &self.size)
+            .field("chunk_size", &self.chunk_size)
+            .finish_non_exhaustive()
+    }
+}
+
+impl FileUpload {
+    fn new(
+        id: Option<String>,
+        name: String,
+        size: u64,
+        source: Source,
+    ) -> Self {
+        Self {
+            id,
+            name,
+            size,
+            source,
+            chunk_size: DEFAULT_CHUNK_SIZE,
+            on_progress: None,
+        }
+    }
+
+    /// Creates an upload of the given bytes.
+    pub fn from_bytes(name: impl Into<String>, data: impl Into<Bytes>) -> Self {
+        let data = data.into();
+        let size = data.len() as u64;
+        Self::new(None, name.into(), size, Source::Bytes(data))
+    }
+
+    /// Creates an upload of a file selected in the browser.
+    #[cfg(feature = "browser")]
+    pub fn from_file(file: &web_sys::File) -> Self {
+        let blob: &web_sys::Blob = file;
+        Self::new(
+            None,
+            file.name(),
+            file.size() as u64,
+            Source::Blob(send_wrapper::SendWrapper::new(blob.clone())),
+        )
+    }
+
+    /// Resumes an upload that the server has already started, using the id from its
+    /// [`UploadProgress`]. The upload continues from however much of it the server has received.
+    ///
+    /// Without an id, the server starts a new upload.
+    pub fn with_id(mut self, id: impl Into<String>) -> Self {
+        self.id = Some(id.into());
+        self
+    }
+
+    /// Sets the size of each chunk, in bytes. Defaults to [`DEFAULT_CHUNK_SIZE`].
+    pub fn with_chunk_size(mut self, chunk_size: u64) -> Self {
+        self.chunk_size = chunk_size.max(1);
+        self
+    }
+
+    /// Sets a function that is called with the progress of the upload after each chunk.
+    pub fn on_progress(
+        mut self,
+        on_progress: impl Fn(UploadProgress) + Send + Sync + 'static,
+    ) -> Self {
+        self.on_progress = Some(Arc::new(on_progress));
+        self
+    }
+
+    /// The id of the upload, if the server has issued one.
+    pub fn id(&self) -> Option<&str> {
+        self.id.as_deref()
+    }
+
+    /// The name of the file being uploaded.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// The total size of the upload, in bytes, as declared by the client.
+    pub fn size(&self) -> u64 {
+        self.size
+    }
+
+    /// On the server, the offset and data of the chunk that has been received.
+    pub fn chunk(&self) -> Option<(u64, &Bytes)> {
+        match &self.source {
+            Source::Chunk { offset, data } => Some((*offset, data)),
+            _ => None,
+        }
+    }
+
+    /// On the server, writes the chunk that has been received to the sink, and returns the
+    /// progress of the upload, which should be returned from the server function.
+    ///
+    /// If the upload has no id, a new one is issued and the upload is started in the sink.
+    /// Otherwise, the upload must already exist in the sink, and its size is the one it was
+    /// started with; a chunk that extends past that size is rejected.
+    ///
+    /// Chunks that the sink has already received are skipped, so a client can safely send a
+    /// chunk again after an interruption. If the server function was called directly on the
+    /// server with an upload created by [`from_bytes`](FileUpload::from_bytes), all of its data is
+    /// written at once.
+    pub async fn write_to(
+        self,
+        sink: &impl UploadSink,
+    ) -> Result<UploadProgress, ServerFnError> {
+        let (offset, data) = match self.source {
+            Source::Chunk { offset, data } => (offset, data),
+            // the server function was called directly on the server
+            Source::Bytes(data) => (0, data),
+            #[cfg(feature = "browser")]
+            Source::Blob(_) => {
+                return Err(ServerFnError::ServerError(
+                    "a browser file can only be written by the server".into(),
+                ))
+            }
+        };
+        let (info, mut received) = match self.id {
+            Some(id) => match sink.received(&id).await? {
+                Some(upload) => upload,
+                None => {
+                    return Err(ServerFnError::ServerError(format!(
+                        "unknown upload {id:?}"
+                    )))
+                }
+            },
+            None => {
+                let info = UploadInfo {
+                    id: new_upload_id()?,
+                    name: self.name,
+                    size: self.size,
+                };
+                sink.create(&info).await?;
+                (info, 0)
+            }
+        };
+        let end = offset.saturating_add(data.len() as u64);
+        if end > info.size {
+            return Err(ServerFnError::ServerError(format!(
+                "the chunk extends past the size of the upload, {} bytes",
+                info.size
+            )));
+        }
+        // only write the part of the chunk the sink doesn't have yet; chunks that start after
+        // the end of the received data are ignored, and the client resumes from `received`
+        if !data.is_empty() && offset <= received && end > received {
+            let data = data.slice((received - offset) as usize..);
+            sink.write(&info, received, data).await?;
+            received = end;
+            if received >= info.size {
+                sink.finish(&info).await?;
+            }
+        } else if info.size == 0 {
+            sink.finish(&info).await?;
+        }
+        Ok(UploadProgress {
+            id: info.id,
+            received,
+            total: info.size,
+        })
+    }
+}
+
+/// Issues an id for a new upload, which is random so that other clients cannot guess it.
+#[cfg(feature = "ssr")]
+fn new_upload_id() -> Result<String, ServerFnError> {
+    use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine as _};
+    use rand::RngCore as _;
+
+    let mut bytes = [0; 16];
+    rand::rng().fill_bytes(&mut bytes);
+    Ok(URL_SAFE_NO_PAD.encode(bytes))
+}
+
+#[cfg(not(feature = "ssr"))]
+fn new_upload_id() -> Result<String, ServerFnError> {
+    Err(ServerFnError::ServerError(
+        "uploads can only be started on the server".into(),
+    ))
+}
+
+#[derive(Serialize, Deserialize)]
+struct ChunkHeader {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    id: Option<String>,
+    name: String,
+    size: u64,
+    offset: u64,
+}
+
+/// A protocol that uploads a [`FileUpload`] in chunks, one request per chunk.
+///
+/// A server function that uses this protocol takes a single [`FileUpload`] argument and returns
+/// [`UploadProgress`]. Calling it uploads the whole file; on the server, it is called once per
+/// chunk. See the [module documentation](self) for an example.
+pub struct ChunkedUpload;
+
+impl<Input, Client, Server, E>
+    Protocol<Input, UploadProgress, Client, Server, E> for ChunkedUpload
+where
+    Input:
+        Deref<Target = FileUpload> + From<FileUpload> + Into<FileUpload> + Send,
+    E: FromServerFnError,
+    Client: crate::Client<E>,
+    Server: crate::Server<E>,
+{
+    const METHOD: Method = Method::POST;
+
+    async fn run_server<F, Fut>(
+        request: Server::Request,
+        server_fn: F,
+    ) -> Result<Server::Response, E>
+    where
+        F: Fn(Input) -> Fut + Send,
+        Fut: Future<Output = Result<UploadProgress, E>> + Send,
+    {
+        let header: ChunkHeader =
+            serde_qs::from_str(request.as_query().unwrap_or_default())
+                .map_err(|e| {
+                    ServerFnErrorErr::Args(e.to_string()).into_app_error()
+                })?;
+        let data = request.try_into_bytes().await?;
+        let upload = FileUpload::new(
+            header.id,
+            header.name,
+            header.size,
+            Source::Chunk {
+                offset: header.offset,
+                data,
+            },
+        );
+
+        let progress = server_fn(upload.into()).await?;
+
+        let json = serde_json::to_string(&progress).map_err(|e| {
+            ServerFnErrorErr::Serialization(e.to_string()).into_app_error()
+        })?;
+        Server::Response::try_from_string(PROGRESS_CONTENT_TYPE, json)
+    }
+
+    async fn run_client(path: &str, input: Input) -> Result<UploadProgress, E> {
+        let upload: FileUpload = input.into();
+        let report = |progress: &UploadProgress| {
+            if let Some(on_progress) = &upload.on_progress {
+                on_progress(progress.clone());
+            }
+        };
+
+        // an empty chunk starts the upload, or asks the server how much of it it already has
+        let mut progress = send_chunk::<Client, E>(
+            path,
+            &upload,
+            upload.id.as_deref(),
+            0,
+            Bytes::new(),
+        )
+        .await?;
+        report(&progress);
+
+        while !progress.is_complete() {
+            let offset = progress.received;
+            let data = upload
+                .source
+                .read(offset, upload.chunk_size)
+                .await
+                .map_err(|e| ServerFnErrorErr::Request(e).into_app_error())?;
+            if data.is_empty() {
+                return Err(ServerFnErrorErr::Request(format!(
+                    "the file ended after {offset} of {} bytes",
+                    upload.size
+                ))
+                .into_app_error());
+            }
+            progress = send_chunk::<Client, E>(
+                path,
+                &upload,
+                Some(&progress.id),
+                offset,
+                data,
+            )
+            .await?;
+            report(&progress);
+        }
+
+        Ok(progress)
+    }
+}
+
+async fn send_chunk<Client, E>(
+    path: &str,
+    upload: &FileUpload,
+    id: Option<&str>,
+    offset: u64,
+    data: Bytes,
+) -> Result<UploadProgress, E>
+where
+    Client: crate::Client<E>,
+    E: FromServerFnError,
+{
+    let header = ChunkHeader {
+        id: id.map(str::to_string),
+        name: upload.name.clone(),
+        size: upload.size,
+        offset,
+    };
+    let query = serde_qs::to_string(&header).map_err(|e| {
+        ServerFnErrorErr::Serialization(e.to_string()).into_app_error()
+    })?;
+    let req = Client::Request::try_new_post_bytes(
+        &format!("{path}?{query}"),
+        CHUNK_CONTENT_TYPE,
+        PROGRESS_CONTENT_TYPE,
+        data,
+    )?;
+    let res = Client::send(req).await?;
+
+    let status = res.status();
+    if (400..=599).contains(&status) {
+        return Err(E::de(res.try_into_bytes().await?));
+    }
+    let json = res.try_into_string().await?;
+    serde_json::from_str(&json).map_err(|e| {
+        ServerFnErrorErr::Deserialization(e.to_string()).into_app_error()
+    })
+}
+
+#[cfg(all(test, feature = "ssr"))]
+mod tests {
+    use super::*;
+    use futures::executor::block_on;
+
+    fn chunk(
+        upload: &FileUpload,
+        id: &str,
+        offset: u64,
+        len: u64,
+    ) -> FileUpload {
+        let data = block_on(upload.source.read(offset, len)).unwrap();
+        FileUpload::new(
+            Some(id.to_string()),
+            upload.name.clone(),
+            upload.size,
+            Source::Chunk { offset, data },
+        )
+    }
+
+    fn start(upload: &FileUpload, sink: &InMemoryUploadSink) -> String {
+        let start = FileUpload::new(
+            None,
+            upload.name.clone(),
+            upload.size,
+            Source::Chunk {
+                offset: 0,
+                data: Bytes::new(),
+            },
+        );
+        block_on(start.write_to(sink)).unwrap().id
+    }
+
+    #[test]
+    fn chunks_are_written_in_order_and_resent_chunks_are_skipped() {
+        let sink = InMemoryUploadSink::new();
+        let upload = FileUpload::from_bytes("hello.txt", "hello, world");
+        let id = start(&upload, &sink);
+
+        let progress =
+            block_on(chunk(&upload, &id, 0, 5).write_to(&sink)).unwrap();
+        assert_eq!(progress.received, 5);
+
+        // a chunk sent again after an interruption is not written twice
+        let progress =
+            block_on(chunk(&upload, &id, 0, 8).write_to(&sink)).unwrap();
+        assert_eq!(progress.received, 8);
+
+        // a chunk that starts after the received data is ignored
+        let progress =
+            block_on(chunk(&upload, &id, 10, 2).write_to(&sink)).unwrap();
+        assert_eq!(progress.received, 8);
+
+        let progress =
+            block_on(chunk(&upload, &id, 8, 4).write_to(&sink)).unwrap();
+        assert!(progress.is_complete());
+        assert_eq!(sink.take(&id).unwrap(), "hello, world");
+    }
+
+    #[test]
+    fn ids_are_issued_by_the_server() {
+        let sink = InMemoryUploadSink::new();
+        let upload = FileUpload::from_bytes("hello.txt", "hello, world");
+        let first = start(&upload, &sink);
+        let second = start(&upload, &sink);
+        assert_ne!(first, second);
+
+        // an id the server did not issue is rejected
+        assert!(
+            block_on(chunk(&upload, "guess", 0, 5).write_to(&sink)).is_err()
+        );
+    }
+
+    #[test]
+    fn chunks_past_the_declared_size_are_rejected() {
+        let sink = InMemoryUploadSink::new();
+        let id = start(&FileUpload::from_bytes("a.txt", "abc"), &sink);
+        let larger = FileUpload::from_bytes("a.txt", "abcdef");
+        assert!(block_on(chunk(&larger, &id, 0, 6).write_to(&sink)).is_err());
+        assert!(block_on(chunk(&larger, &id, 0, 3).write_to(&sink)).is_ok());
+    }
+
+    #[test]
+    fn in_memory_uploads_are_capped() {
+        let sink = InMemoryUploadSink::new()
+            .with_max_upload_size(4)
+            .with_max_memory(6);
+        let info = |id: &str, size| UploadInfo {
+            id: id.into(),
+            name: "a.txt".into(),
+            size,
+        };
+        assert!(block_on(sink.create(&info("a", 5))).is_err());
+        assert!(block_on(sink.create(&info("a", 4))).is_ok());
+        // the first upload's memory is reserved until it is taken
+        assert!(block_on(sink.create(&info("b", 4))).is_err());
+        sink.take("a");
+        assert!(block_on(sink.create(&info("b", 4))).is_ok());
+    }
+}
@@ -0,0 +1,397 @@
+//! Response compression for server functions.
+//!
+//! With any of the `gzip`, `brotli` or `zstd` features enabled, the responses of server
+//! functions are compressed with the best encoding that the client accepts in its
+//! `Accept-Encoding` header. The encodings are preferred in the order `br`, `zstd`, `gzip`
+//! when the client accepts several of them equally.
+//!
+//! Compression is applied in [`ServerFn::run_on_server`](crate::ServerFn), so every server
+//! integration gets the same behavior without any extra middleware:
+//!
+//! - Responses whose body is smaller than [`min_size`] (1 KiB by default) are sent as they are,
+//!   because compressing them does not pay off.
+//! - Streaming responses are always compressed, and each chunk is flushed as soon as it has been
+//!   compressed, so that the client receives it without waiting for the rest of the stream.
+//! - Only successful responses that are not already encoded are compressed. Their `ETag`, if
+//!   any, is made weak, and `Accept-Encoding` is added to their `Vary` header.
+//!
+//! Compression can be turned off for a single server function, e.g., one that returns data that
+//! is already compressed:
+//!
+//! ```rust,ignore
+//! #[server(compress = false)]
+//! pub async fn thumbnail(id: u32) -> Result<Vec<u8>, ServerFnError> {
+//!     // ...
+//! }
+//! ```
+//!
+//! The [`reqwest`](crate::client::reqwest) client decompresses responses when the same features
+//! are enabled, and browsers always do.
+
+// the compressors are only used by the server integrations
+#![cfg_attr(not(any(feature = "ssr", feature = "generic")), allow(dead_code))]
+
+use bytes::Bytes;
+use futures::{Stream, StreamExt};
+use std::{
+    io::{self, Write},
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+/// A content encoding that responses can be compressed with.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ContentEncoding {
+    /// Brotli (`br`).
+    #[cfg(feature = "brotli")]
+    Brotli,
+    /// Zstandard (`zstd`).
+    #[cfg(feature = "zstd")]
+    Zstd,
+    /// Gzip (`gzip`).
+    #[cfg(feature = "gzip")]
+    Gzip,
+}
+
+impl ContentEncoding {
+    /// The encodings that are enabled, in order of preference.
+    const ENABLED: &'static [Self] = &[
+        #[cfg(feature = "brotli")]
+        Self::Brotli,
+        #[cfg(feature = "zstd")]
+        Self::Zstd,
+        #[cfg(feature = "gzip")]
+        Self::Gzip,
+    ];
+
+    /// The value of the `Content-Encoding` header for this encoding.
+    pub const fn as_str(&self) -> &'static str {
+        match self {
+            #[cfg(feature = "brotli")]
+            Self::Brotli => "br",
+            #[cfg(feature = "zstd")]
+            Self::Zstd => "zstd",
+            #[cfg(feature = "gzip")]
+            Self::Gzip => "gzip",
+        }
+    }
+}
+
+/// Chooses the encoding for a response from the value of the request's `Accept-Encoding`
+/// header, or `None` if the client does not accept any of the enabled encodings.
+pub fn negotiate(accept_encoding: &str) -> Option<ContentEncoding> {
+    let mut wildcard = None;
+    let mut qualities = Vec::new();
+    for item in accept_encoding.split(',') {
+        let mut parts = item.split(';');
+        let name = parts.next().unwrap_or_default().trim();
+        let quality = parts
+            .find_map(|param| {
+                let (key, value) = param.split_once('=')?;
+                (key.trim() == "q").then(|| value.trim().parse::<f32>().ok())?
+            })
+            .unwrap_or(1.0);
+        if name == "*" {
+            wildcard = Some(quality);
+        } else if !name.is_empty() {
+            qualities.push((name.to_ascii_lowercase(), quality));
+        }
+    }
+
+    let mut best: Option<(ContentEncoding, f32)> = None;
+    for &encoding in ContentEncoding::ENABLED {
+        let quality = qualities
+            .iter()
+            .find(|(name, _)| {
+                name == encoding.as_str()
+                    // some older clients still send `x-gzip`
+                    || (encoding.as_str() == "gzip" && name == "x-gzip")
+            })
+            .map(|(_, quality)| *quality)
+            .or(wildcard)
+            .unwrap_or(0.0);
+        if quality > 0.0 && best.is_none_or(|(_, best)| quality > best) {
+            best = Some((encoding, quality));
+        }
+    }
+    best.map(|(encoding, _)| encoding)
+}
+
+static MIN_SIZE: AtomicUsize = AtomicUsize::new(1024);
+
+/// Sets the size, in bytes, below which response bodies are not compressed.
+pub fn set_min_size(bytes: usize) {
+    MIN_SIZE.store(bytes, Ordering::Relaxed);
+}
+
+/// The size, in bytes, below which response bodies are not compressed.
+pub fn min_size() -> usize {
+    MIN_SIZE.load(Ordering::Relaxed)
+}
+
+/// Returns the value of an `ETag` header for a compressed response, which is weak because the
+/// compressed body is not byte-for-byte identical to the one the tag was computed from.
+pub(crate) fn weak_etag(etag: &str) -> String {
+    if etag.starts_with("W/") {
+        etag.to_owned()
+    } else {
+        format!("W/{etag}")
+    }
+}
+
+/// Returns the value of a `Vary` header for a compressed response.
+pub(crate) fn vary(existing: Option<&str>) -> String {
+    match existing {
+        Some(vary)
+            if vary.split(',').any(|name| {
+                let name = name.trim();
+                name == "*" || name.eq_ignore_ascii_case("accept-encoding")
+            }) =>
+        {
+            vary.to_owned()
+        }
+        Some(vary) if !vary.trim().is_empty() => {
+            format!("{vary}, Accept-Encoding")
+        }
+        _ => "Accept-Encoding".to_owned(),
+    }
+}
+
+/// Updates the headers of a response whose body has been compressed.
+#[cfg(any(feature = "axum-no-default", feature = "generic"))]
+pub(crate) fn update_headers(
+    headers: &mut http::HeaderMap,
+    encoding: ContentEncoding,
+) {
+    use http::{
+        header::{CONTENT_ENCODING, CONTENT_LENGTH, ETAG, VARY},
+        HeaderValue,
+    };
+
+    headers.insert(
+        CONTENT_ENCODING,
+        HeaderValue::from_static(encoding.as_str()),
+    );
+    headers.remove(CONTENT_LENGTH);
+    let vary = vary(headers.get(VARY).and_then(|value| value.to_str().ok()));
+    if let Ok(vary) = HeaderValue::from_str(&vary) {
+        headers.insert(VARY, vary);
+    }
+    if let Some(etag) = headers
+        .get(ETAG)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|etag| HeaderValue::from_str(&weak_etag(etag)).ok())
+    {
+        headers.insert(ETAG, etag);
+    }
+}
+
+/// A streaming compressor, which writes into an in-memory buffer.
+pub(crate) enum Encoder {
+    #[cfg(feature = "brotli")]
+    Brotli(Box<brotli::CompressorWriter<Vec<u8>>>),
+    #[cfg(feature = "zstd")]
+    Zstd(zstd::stream::write::Encoder<'static, Vec<u8>>),
+    #[cfg(feature = "gzip")]
+    Gzip(flate2::write::GzEncoder<Vec<u8>>),
+}
+
+impl Encoder {
+    /// Creates a new compressor for the given encoding.
+    pub fn new(encoding: ContentEncoding) -> io::Result<Self> {
+        Ok(match encoding {
+            // the default quality of 11 is meant for static assets, and is too slow for
+            // responses that are compressed on every request
+            #[cfg(feature = "brotli")]
+            ContentEncoding::Brotli => Self::Brotli(Box::new(
+                brotli::CompressorWriter::new(Vec::new(), 4096, 5, 22),
+            )),
+            #[cfg(feature = "zstd")]
+            ContentEncoding::Zstd => {
+                Self::Zstd(zstd::stream::write::Encoder::new(Vec::new(), 3)?)
+            }
+            #[cfg(feature = "gzip")]
+            ContentEncoding::Gzip => Self::Gzip(flate2::write::GzEncoder::new(
+                Vec::new(),
+                flate2::Compression::default(),
+            )),
+        })
+    }
+
+    fn writer(&mut self) -> &mut dyn Write {
+        match self {
+            #[cfg(feature = "brotli")]
+            Self::Brotli(encoder) => encoder.as_mut(),
+            #[cfg(feature = "zstd")]
+            Self::Zstd(encoder) => encoder,
+            #[cfg(feature = "gzip")]
+            Self::Gzip(encoder) => encoder,
+        }
+    }
+
+    fn buffer(&mut self) -> &mut Vec<u8> {
+        match self {
+            #[cfg(feature = "brotli")]
+            Self::Brotli(encoder) => encoder.get_mut(),
+            #[cfg(feature = "zstd")]
+            Self::Zstd(encoder) => encoder.get_mut(),
+            #[cfg(feature = "gzip")]
+            Self::Gzip(encoder) => encoder.get_mut(),
+        }
+    }
+
+    /// Compresses a chunk and flushes it, returning the compressed data produced so far.
+    pub fn chunk(&mut self, chunk: &[u8]) -> io::Result<Bytes> {
+        let writer = self.writer();
+        writer.write_all(chunk)?;
+        writer.flush()?;
+        Ok(Bytes::from(std::mem::take(self.buffer())))
+    }
+
+    /// Ends the compressed stream, returning the remaining compressed data.
+    pub fn finish(self) -> io::Result<Bytes> {
+        let rest = match self {
+            #[cfg(feature = "brotli")]
+            Self::Brotli(encoder) => encoder.into_inner(),
+            #[cfg(feature = "zstd")]
+            Self::Zstd(encoder) => encoder.finish()?,
+            #[cfg(feature = "gzip")]
+            Self::Gzip(encoder) => encoder.finish()?,
+        };
+        Ok(Bytes::from(rest))
+    }
+}
+
+/// Compresses a complete body.
+pub(crate) fn compress(
+    encoding: ContentEncoding,
+    body: &[u8],
+) -> io::Result<Bytes> {
+    let mut encoder = Encoder::new(encoding)?;
+    let mut compressed = Vec::from(encoder.chunk(body)?);
+    compressed.extend_from_slice(&encoder.finish()?);
+    Ok(Bytes::from(compressed))
+}
+
+/// Compresses a streamed body, flushing the compressed data after each chunk.
+pub(crate) fn compress_stream<E: Send + 'static>(
+    encoding: ContentEncoding,
+    stream: impl Stream<Item = Result<Bytes, E>> + Send + 'static,
+    map_err: fn(io::Error) -> E,
+) -> impl Stream<Item = Result<Bytes, E>> + Send + 'static {
+    let encoder = Encoder::new(encoding).map_err(map_err);
+    futures::stream::unfold(
+        (stream.boxed(), Some(encoder)),
+        move |(mut stream, encoder)| async move {
+            let mut encoder = match encoder? {
+                Ok(encoder) => encoder,
+                Err(e) => return Some((Err(e), (stream, None))),
+            };
+            let item = match stream.next().await {
+                Some(Ok(chunk)) => encoder.chunk(&chunk).map_err(map_err),
+                Some(Err(e)) => Err(e),
+                None => {
+                    return Some((
+                        encoder.finish().map_err(map_err),
+                        (stream, None),
+                    ))
+                }
+            };
+            Some((item, (stream, Some(Ok(encoder)))))
+        },
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn negotiates_accept_encoding() {
+        let first = ContentEncoding::ENABLED[0];
+        assert_eq!(negotiate("gzip, deflate, br, zstd"), Some(first));
+        assert_eq!(negotiate("*"), Some(first));
+        assert_eq!(negotiate("identity"), None);
+        assert_eq!(negotiate("*;q=0"), None);
+        assert_eq!(negotiate(""), None);
+        #[cfg(feature = "gzip")]
+        {
+            assert_eq!(negotiate("gzip"), Some(ContentEncoding::Gzip));
+            assert_eq!(
+                negotiate("br;q=0.5, zstd;q=0.5, gzip"),
+                Some(ContentEncoding::Gzip)
+            );
+            assert_ne!(negotiate("*, gzip;q=0"), Some(ContentEncoding::Gzip));
+        }
+    }
+
+    #[test]
+    fn adds_accept_encoding_to_vary() {
+        assert_eq!(vary(None), "Accept-Encoding");
+        assert_eq!(vary(Some("Cookie")), "Cookie, Accept-Encoding");
+        assert_eq!(vary(Some("accept-encoding")), "accept-encoding");
+        assert_eq!(weak_etag("\"abc\""), "W/\"abc\"");
+        assert_eq!(weak_etag("W/\"abc\""), "W/\"abc\"");
+    }
+
+    #[test]
+    fn streamed_chunks_are_flushed() {
+        use futures::executor::block_on;
+
+        for &encoding in ContentEncoding::ENABLED {
+            let mut encoder = Encoder::new(encoding).unwrap();
+            // each chunk produces output as soon as it has been compressed
+            assert!(!encoder.chunk(b"hello, ").unwrap().is_empty());
+            assert!(!encoder.chunk(b"world").unwrap().is_empty());
+
+            let chunks = block_on(
+                compress_stream(
+                    encoding,
+                    futures::stream::iter([
+                        Ok::<_, io::Error>(Bytes::from_static(b"hello, ")),
+                        Ok(Bytes::from_static(b"world")),
+                    ]),
+                    |e| e,
+                )
+                .collect::<Vec<_>>(),
+            );
+            assert_eq!(chunks.len(), 3);
+            let streamed = chunks
+                .into_iter()
+                .flat_map(|chunk| chunk.unwrap())
+                .collect::<Vec<_>>();
+            assert_eq!(decompress(encoding, &streamed), b"hello, world");
+
+            let body = b"server functions ".repeat(100);
+            let compressed = compress(encoding, &body).unwrap();
+            assert!(compressed.len() < body.len());
+            assert_eq!(decompress(encoding, &compressed), body);
+        }
+    }
+
+    fn decompress(encoding: ContentEncoding, data: &[u8]) -> Vec<u8> {
+        match encoding {
+            #[cfg(feature = "brotli")]
+            ContentEncoding::Brotli => {
+                use std::io::Read;
+
+                let mut out = Vec::new();
+                brotli::Decompressor::new(data, 4096)
+                    .read_to_end(&mut out)
+                    .unwrap();
+                out
+            }
+            #[cfg(feature = "zstd")]
+            ContentEncoding::Zstd => zstd::decode_all(data).unwrap(),
+            #[cfg(feature = "gzip")]
+            ContentEncoding::Gzip => {
+                use std::io::Read;
+
+                let mut out = Vec::new();
+                flate2::read::GzDecoder::new(data)
+                    .read_to_end(&mut out)
+                    .unwrap();
+                out
+            }
+        }
+    }
+}
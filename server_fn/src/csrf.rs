@@ -0,0 +1,517 @@
+//! Protection against cross-site request forgery (CSRF) for server functions.
+//!
+//! Server functions are ordinary HTTP endpoints, so without protection another site can make a
+//! user’s browser submit a form to them, with the user’s cookies attached. Protection is turned
+//! off by default, and can be enabled for all server functions with [`set_csrf_mode`]:
+//!
+//! - [`CsrfMode::Token`] uses a *double-submit cookie*. The server integration issues a random
+//!   token in the [`CSRF_COOKIE`] cookie while rendering a page, and every unsafe request must
+//!   repeat that token in the [`CSRF_HEADER`] header or, for URL-encoded forms, in the
+//!   [`CSRF_FIELD`] form field. Another site can trigger a request that carries the cookie, but
+//!   it cannot read the cookie to repeat it. The browser client sends the header automatically,
+//!   and `<ActionForm/>` adds a hidden field so that forms also work before WASM has loaded.
+//!   Use [`set_csrf_session`] to tie tokens to the user’s session, so that a token planted by
+//!   an attacker who can set cookies for the site is not accepted.
+//! - [`CsrfMode::Origin`] checks the `Sec-Fetch-Site` and `Origin` headers that browsers attach
+//!   to requests, and rejects requests that come from another site. It needs no token, but relies
+//!   on the browser sending those headers. Browsers that do not send `Sec-Fetch-Site` are only
+//!   allowed if their `Origin` is one of the [trusted origins](set_trusted_origins).
+//!
+//! The check runs before the request body is decoded, and rejected requests receive a
+//! `403 Forbidden` response. Requests using safe methods (`GET`, `HEAD` and `OPTIONS`) are never
+//! checked. Individual server functions can opt out with `#[server(csrf = false)]`, e.g., for
+//! webhooks that are called by other services.
+
+use crate::{error::ServerFnErrorErr, limits::Rejection, request::Req};
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine as _};
+use bytes::Bytes;
+use futures::{stream, Stream, TryStreamExt};
+use hmac::{Hmac, Mac};
+use http::{Method, StatusCode};
+use or_poisoned::OrPoisoned;
+use sha2::Sha256;
+use std::sync::{
+    atomic::{AtomicBool, Ordering},
+    Arc, RwLock,
+};
+use subtle::ConstantTimeEq;
+
+/// The name of the cookie in which the CSRF token is stored.
+pub const CSRF_COOKIE: &str = "leptos_csrf";
+
+/// The name of the header in which clients send the CSRF token.
+pub const CSRF_HEADER: &str = "x-csrf-token";
+
+/// The name of the hidden field in which HTML forms send the CSRF token.
+pub const CSRF_FIELD: &str = "__csrf";
+
+/// How server functions are protected against cross-site request forgery.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum CsrfMode {
+    /// Requests are not checked.
+    #[default]
+    Disabled,
+    /// Requests must repeat the token from the [`CSRF_COOKIE`] cookie in the [`CSRF_HEADER`]
+    /// header or the [`CSRF_FIELD`] form field.
+    Token,
+    /// Requests are rejected if the `Sec-Fetch-Site` or `Origin` header shows that they were
+    /// sent from another site.
+    Origin,
+}
+
+static CSRF_MODE: RwLock<CsrfMode> = RwLock::new(CsrfMode::Disabled);
+
+/// Sets how all server functions are protected against cross-site request forgery.
+pub fn set_csrf_mode(mode: CsrfMode) {
+    *CSRF_MODE.write().or_poisoned() = mode;
+}
+
+/// Returns how server functions are currently protected against cross-site request forgery.
+pub fn csrf_mode() -> CsrfMode {
+    *CSRF_MODE.read().or_poisoned()
+}
+
+#[derive(Debug, Clone)]
+struct SessionBinding {
+    cookie: &'static str,
+    secret: Arc<[u8]>,
+}
+
+impl SessionBinding {
+    /// Signs the nonce together with the session of a request with the given cookies.
+    fn sign(&self, nonce: &str, cookies: &str) -> String {
+        let session = cookie(cookies, self.cookie).unwrap_or_default();
+
+        // HMAC-SHA256, with the session and the nonce as the message
+        let mut mac = Hmac::<Sha256>::new_from_slice(&self.secret)
+            .expect("HMAC accepts keys of any length");
+        mac.update(session.as_bytes());
+        mac.update(&[0]);
+        mac.update(nonce.as_bytes());
+        let mac = mac.finalize().into_bytes();
+        format!("{nonce}.{}", URL_SAFE_NO_PAD.encode(mac))
+    }
+
+    /// Whether the token was issued for the session of a request with the given cookies.
+    fn verify(&self, token: &str, cookies: &str) -> bool {
+        let Some((nonce, _)) = token.split_once('.') else {
+            return false;
+        };
+        tokens_match(token, &self.sign(nonce, cookies))
+    }
+}
+
+static SESSION: RwLock<Option<SessionBinding>> = RwLock::new(None);
+
+/// Ties CSRF tokens to the user’s session, which is identified by the value of the `cookie`
+/// cookie, e.g., the session ID set by your session middleware.
+///
+/// Tokens are then signed with `secret` together with the session, and a token is only accepted
+/// from the session it was issued to. Without this, an attacker who can set cookies for your
+/// site, e.g., from a sibling subdomain, can plant a token of their own choosing and repeat it.
+///
+/// When the session changes, e.g., after logging in, the old token is no longer accepted, and a
+/// new one is issued the next time a page is rendered on the server.
+pub fn set_csrf_session(cookie: &'static str, secret: impl Into<Arc<[u8]>>) {
+    *SESSION.write().or_poisoned() = Some(SessionBinding {
+        cookie,
+        secret: secret.into(),
+    });
+}
+
+/// Turns a random nonce into the CSRF token for the session of a request with the given `Cookie`
+/// header.
+///
+/// Without [`set_csrf_session`], the nonce itself is used as the token.
+pub fn token_for_session(nonce: &str, cookies: Option<&str>) -> String {
+    match &*SESSION.read().or_poisoned() {
+        Some(session) => session.sign(nonce, cookies.unwrap_or_default()),
+        None => nonce.to_owned(),
+    }
+}
+
+static TRUSTED_ORIGINS: RwLock<Vec<String>> = RwLock::new(Vec::new());
+
+/// Sets the origins, like `https://example.com`, that [`CsrfMode::Origin`] accepts requests from
+/// if the browser does not send a `Sec-Fetch-Site` header.
+///
+/// The `Host` header is not used to find the server’s own origin, because it is set by the
+/// client. If no origins are set, requests with an `Origin` but without `Sec-Fetch-Site` are
+/// rejected.
+pub fn set_trusted_origins<I>(origins: I) -> Result<(), url::ParseError>
+where
+    I: IntoIterator,
+    I::Item: AsRef<str>,
+{
+    let origins = origins
+        .into_iter()
+        .map(|origin| {
+            url::Url::parse(origin.as_ref())
+                .map(|url| url.origin().ascii_serialization())
+        })
+        .collect::<Result<_, _>>()?;
+    *TRUSTED_ORIGINS.write().or_poisoned() = origins;
+    Ok(())
+}
+
+fn cookie<'a>(cookies: &'a str, name: &str) -> Option<&'a str> {
+    cookies.split(';').find_map(|cookie| {
+        let (key, value) = cookie.trim().split_once('=')?;
+        (key == name && !value.is_empty()).then_some(value)
+    })
+}
+
+/// Finds the CSRF token in the value of a `Cookie` header.
+pub fn token_from_cookies(cookies: &str) -> Option<&str> {
+    cookie(cookies, CSRF_COOKIE)
+}
+
+/// Finds the CSRF token in the value of a `Cookie` header, if it was issued for the session
+/// that the cookies belong to.
+///
+/// Without [`set_csrf_session`], this is the same as [`token_from_cookies`].
+pub fn session_token_from_cookies(cookies: &str) -> Option<&str> {
+    let token = token_from_cookies(cookies)?;
+    match &*SESSION.read().or_poisoned() {
+        Some(session) => session.verify(token, cookies).then_some(token),
+        None => Some(token),
+    }
+}
+
+/// Builds the value of a `Set-Cookie` header that stores the given CSRF token.
+///
+/// The cookie cannot be `HttpOnly`, because the client needs to read the token to send it back.
+pub fn token_cookie(token: &str) -> String {
+    format!("{CSRF_COOKIE}={token}; Path=/; SameSite=Lax")
+}
+
+/// Reads the CSRF token from the document’s cookies, if one has been issued.
+#[cfg(feature = "browser")]
+pub fn token_from_document() -> Option<String> {
+    use wasm_bindgen::JsCast;
+
+    let cookies = web_sys::window()?
+        .document()?
+        .dyn_into::<web_sys::HtmlDocument>()
+        .ok()?
+        .cookie()
+        .ok()?;
+    token_from_cookies(&cookies).map(ToOwned::to_owned)
+}
+
+/// Compares two tokens in constant time, to avoid leaking their contents through timing.
+fn tokens_match(a: &str, b: &str) -> bool {
+    a.as_bytes().ct_eq(b.as_bytes()).into()
+}
+
+/// A CSRF token that must be repeated in the [`CSRF_FIELD`] field of a URL-encoded form body,
+/// because the request did not send it in the [`CSRF_HEADER`] header.
+///
+/// Server integrations check it in [`Req::require_form_token`].
+#[derive(Debug, Clone)]
+pub struct FormToken {
+    expected: Arc<str>,
+    rejected: Arc<AtomicBool>,
+}
+
+impl FormToken {
+    fn new(expected: &str) -> Self {
+        Self {
+            expected: expected.into(),
+            rejected: Arc::new(AtomicBool::new(false)),
+        }
+    }
+
+    /// Whether the request has been rejected because its form did not repeat the token.
+    pub fn rejected(&self) -> bool {
+        self.rejected.load(Ordering::Relaxed)
+    }
+
+    /// Marks the request as rejected, and returns the error to report.
+    pub fn reject(&self, reason: &str) -> ServerFnErrorErr {
+        self.rejected.store(true, Ordering::Relaxed);
+        ServerFnErrorErr::Forbidden(reason.to_owned())
+    }
+
+    /// Checks that the URL-encoded form body repeats the token.
+    pub fn check(&self, body: &[u8]) -> Result<(), ServerFnErrorErr> {
+        let provided = url::form_urlencoded::parse(body)
+            .find(|(key, _)| key == CSRF_FIELD)
+            .map(|(_, value)| value);
+        match provided {
+            Some(provided) if tokens_match(&provided, &self.expected) => Ok(()),
+            Some(_) => Err(self.reject("invalid CSRF token")),
+            None => Err(self.reject("missing CSRF token")),
+        }
+    }
+
+    /// Wraps a body stream so that nothing is passed on until the whole body has been read and
+    /// [checked](Self::check).
+    ///
+    /// `into_error` converts the [`Forbidden`](ServerFnErrorErr::Forbidden) error into the
+    /// error type of the stream.
+    pub fn guard_stream<S, E>(
+        &self,
+        body: S,
+        into_error: impl FnOnce(ServerFnErrorErr) -> E,
+    ) -> impl Stream<Item = Result<Bytes, E>>
+    where
+        S: Stream<Item = Result<Bytes, E>>,
+    {
+        let token = self.clone();
+        stream::once(async move {
+            let body = body.try_collect::<Vec<_>>().await?.concat();
+            token.check(&body).map_err(into_error)?;
+            Ok(Bytes::from(body))
+        })
+    }
+}
+
+fn check_token<Request, E, IS, OS>(
+    req: &Request,
+    session: Option<&SessionBinding>,
+) -> Result<Option<FormToken>, &'static str>
+where
+    Request: Req<E, IS, OS>,
+{
+    let cookies = req.header("cookie");
+    let cookies = cookies.as_deref().unwrap_or_default();
+    let Some(expected) = token_from_cookies(cookies) else {
+        return Err("missing CSRF cookie");
+    };
+    if session.is_some_and(|session| !session.verify(expected, cookies)) {
+        return Err("CSRF token was issued for another session");
+    }
+    match req.header(CSRF_HEADER) {
+        Some(provided) if tokens_match(&provided, expected) => Ok(None),
+        Some(_) => Err("invalid CSRF token"),
+        // forms that work without WASM cannot set headers, and send the token in the body
+        None if req.to_content_type().is_some_and(|content_type| {
+            content_type.starts_with("application/x-www-form-urlencoded")
+        }) =>
+        {
+            Ok(Some(FormToken::new(expected)))
+        }
+        None => Err("missing CSRF token"),
+    }
+}
+
+fn check_origin<Request, E, IS, OS>(
+    req: &Request,
+    trusted_origins: &[String],
+) -> Result<(), &'static str>
+where
+    Request: Req<E, IS, OS>,
+{
+    // browsers that send Sec-Fetch-Site tell us directly where the request came from
+    if let Some(site) = req.header("sec-fetch-site") {
+        return match site.as_ref() {
+            "same-origin" | "none" => Ok(()),
+            _ => Err("cross-site request"),
+        };
+    }
+
+    // otherwise, compare the Origin with the trusted origins; requests without an Origin are
+    // not from a browser that supports CORS, and are allowed
+    let Some(origin) = req.header("origin") else {
+        return Ok(());
+    };
+    let origin = url::Url::parse(&origin)
+        .map_err(|_| "invalid Origin")?
+        .origin()
+        .ascii_serialization();
+    if trusted_origins.contains(&origin) {
+        Ok(())
+    } else {
+        Err("cross-site request")
+    }
+}
+
+/// Checks the request against the configured [`CsrfMode`].
+///
+/// Returns the [`FormToken`] that the body of the request must repeat, if the token was not
+/// sent in a header.
+pub(crate) fn check<Request, E, IS, OS>(
+    req: &Request,
+    method: Method,
+    enabled: bool,
+) -> Result<Option<FormToken>, Rejection>
+where
+    Request: Req<E, IS, OS>,
+{
+    if !enabled || method.is_safe() {
+        return Ok(None);
+    }
+    let result = match csrf_mode() {
+        CsrfMode::Disabled => Ok(None),
+        CsrfMode::Token => {
+            check_token(req, SESSION.read().or_poisoned().as_ref())
+        }
+        CsrfMode::Origin => {
+            check_origin(req, &TRUSTED_ORIGINS.read().or_poisoned())
+                .map(|()| None)
+        }
+    };
+    result.map_err(|reason| Rejection {
+        status: StatusCode::FORBIDDEN,
+        retry_after: None,
+        error: ServerFnErrorErr::Forbidden(reason.to_string()),
+    })
+}
+
+/// Requires the body of the request to repeat the CSRF token, if it was not sent in a header.
+pub(crate) fn require_form_token<Request, E, IS, OS>(
+    req: Request,
+    token: Option<&FormToken>,
+) -> Result<Request, Rejection>
+where
+    Request: Req<E, IS, OS>,
+{
+    match token {
+        Some(token) => {
+            req.require_form_token(token).map_err(|error| Rejection {
+                status: StatusCode::FORBIDDEN,
+                retry_after: None,
+                error,
+            })
+        }
+        None => Ok(req),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn finds_token_in_cookies() {
+        assert_eq!(
+            token_from_cookies("theme=dark; leptos_csrf=abc123; other=1"),
+            Some("abc123")
+        );
+        assert_eq!(token_from_cookies("leptos_csrf=xyz"), Some("xyz"));
+        assert_eq!(token_from_cookies("leptos_csrf="), None);
+        assert_eq!(token_from_cookies("not_leptos_csrf=abc"), None);
+    }
+
+    #[test]
+    fn compares_tokens() {
+        assert!(tokens_match("abc123", "abc123"));
+        assert!(!tokens_match("abc123", "abc124"));
+        assert!(!tokens_match("abc123", "abc12"));
+    }
+
+    #[test]
+    fn checks_form_fields() {
+        let token = FormToken::new("abc123");
+        assert!(token.check(b"name=alice&__csrf=abc123").is_ok());
+        assert!(!token.rejected());
+        assert!(token.check(b"name=alice&__csrf=abc124").is_err());
+        assert!(token.rejected());
+        assert!(FormToken::new("abc123").check(b"name=alice").is_err());
+    }
+
+    #[cfg(feature = "generic")]
+    mod requests {
+        use super::*;
+        use crate::ServerFnError;
+        use http::Request;
+
+        fn request(headers: &[(&str, &str)]) -> Request<Bytes> {
+            let mut req = Request::post("/api/fn");
+            for (name, value) in headers {
+                req = req.header(*name, *value);
+            }
+            req.body(Bytes::new()).unwrap()
+        }
+
+        fn check_token(
+            headers: &[(&str, &str)],
+            session: Option<&SessionBinding>,
+        ) -> Result<bool, &'static str> {
+            super::check_token::<_, ServerFnError, ServerFnError, ServerFnError>(
+                &request(headers),
+                session,
+            )
+            .map(|form_token| form_token.is_some())
+        }
+
+        fn check_origin(headers: &[(&str, &str)]) -> Result<(), &'static str> {
+            super::check_origin::<_, ServerFnError, ServerFnError, ServerFnError>(
+                &request(headers),
+                &["https://example.com".to_owned()],
+            )
+        }
+
+        #[test]
+        fn token_must_match_the_cookie() {
+            let cookie = ("cookie", "leptos_csrf=abc123");
+            assert_eq!(
+                check_token(&[cookie, (CSRF_HEADER, "abc123")], None),
+                Ok(false)
+            );
+            assert!(
+                check_token(&[cookie, (CSRF_HEADER, "abc124")], None).is_err()
+            );
+            assert!(check_token(&[(CSRF_HEADER, "abc123")], None).is_err());
+            assert!(check_token(&[cookie], None).is_err());
+
+            // forms send the token in their body
+            let form = ("content-type", "application/x-www-form-urlencoded");
+            assert_eq!(check_token(&[cookie, form], None), Ok(true));
+        }
+
+        #[test]
+        fn token_must_belong_to_the_session() {
+            let session = SessionBinding {
+                cookie: "session",
+                secret: b"secret".as_slice().into(),
+            };
+            let token = session.sign("nonce", "session=alice");
+            let cookies = format!("session=alice; leptos_csrf={token}");
+            let header = (CSRF_HEADER, token.as_str());
+            assert_eq!(
+                check_token(
+                    &[("cookie", cookies.as_str()), header],
+                    Some(&session)
+                ),
+                Ok(false)
+            );
+
+            // a token issued to one session is not accepted from another
+            let cookies = format!("session=mallory; leptos_csrf={token}");
+            assert!(check_token(
+                &[("cookie", cookies.as_str()), header],
+                Some(&session)
+            )
+            .is_err());
+
+            // nor is a token that was not signed at all
+            assert!(check_token(
+                &[
+                    ("cookie", "session=alice; leptos_csrf=nonce"),
+                    (CSRF_HEADER, "nonce")
+                ],
+                Some(&session)
+            )
+            .is_err());
+        }
+
+        #[test]
+        fn origin_must_be_trusted() {
+            assert!(check_origin(&[("sec-fetch-site", "same-origin")]).is_ok());
+            assert!(check_origin(&[("sec-fetch-site", "none")]).is_ok());
+            assert!(check_origin(&[("sec-fetch-site", "cross-site")]).is_err());
+            assert!(check_origin(&[]).is_ok());
+            assert!(check_origin(&[("origin", "https://example.com")]).is_ok());
+
+            // the Host header is set by the client, and is not trusted
+            assert!(check_origin(&[
+                ("origin", "https://evil.example"),
+                ("host", "evil.example")
+            ])
+            .is_err());
+            assert!(check_origin(&[("origin", "null")]).is_err());
+        }
+    }
+}
@@ -379,6 +379,13 @@ where
             ServerFnErrorErr::UnsupportedRequestMethod(value) => {
                 ServerFnError::Request(value)
             }
+            ServerFnErrorErr::PayloadTooLarge(value) => {
+                ServerFnError::Request(value)
+            }
+            ServerFnErrorErr::TooManyRequests(value) => {
+                ServerFnError::Request(value)
+            }
+            ServerFnErrorErr::Forbidden(value) => ServerFnError::Request(value),
         }
     }
 }
@@ -435,6 +442,15 @@ pub enum ServerFnErrorErr {
     /// Occurs on the server if there is an error creating an HTTP response.
     #[error("error creating response {0}")]
     Response(String),
+    /// Occurs on the server if the request body is larger than the server function allows.
+    #[error("request body too large: {0}")]
+    PayloadTooLarge(String),
+    /// Occurs on the server if the caller has exceeded the server function's rate limit.
+    #[error("too many requests: {0}")]
+    TooManyRequests(String),
+    /// Occurs on the server if the request failed the cross-site request forgery check.
+    #[error("forbidden: {0}")]
+    Forbidden(String),
 }
 
 /// Associates a particular server function error with the server function
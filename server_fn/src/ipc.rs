@@ -0,0 +1,885 @@
+//! Server functions over any transport that carries frames of bytes, such as Tauri IPC, a
+//! `postMessage` channel to a web worker or a Unix socket, instead of HTTP.
+//!
+//! Each call is sent as a sequence of [`IpcFrame`]s that carry the method, path and headers of
+//! the request, followed by the encoded arguments, and answered with frames that carry the
+//! status, headers and encoded output of the response. Calls are multiplexed over a single
+//! connection, so every input and output encoding, including streaming ones, works unchanged.
+//!
+//! On the client, [`connect`] the transport, and use the
+//! [`IpcClient`](crate::client::ipc::IpcClient) for the server functions that should be called
+//! over it. Calls are sent over the connection they are [`run`](IpcConnection::run) with, or
+//! otherwise over the [default connection](IpcConnection::make_default), which can be replaced
+//! when the transport is:
+//!
+//! ```rust,ignore
+//! #[server(client = server_fn::client::ipc::IpcClient)]
+//! pub async fn read_settings() -> Result<Settings, ServerFnError> {
+//!     // ...
+//! }
+//!
+//! // `to_backend` is a `Sink<Bytes>` and `from_backend` a `Stream<Item = Bytes>`
+//! let connection = server_fn::ipc::connect(to_backend, from_backend, |fut| {
+//!     tauri::async_runtime::spawn(fut);
+//! });
+//! connection.make_default();
+//! ```
+//!
+//! On the other end, [`serve`] the connection with any handler that turns an HTTP request into
+//! an HTTP response, e.g., the Axum handler:
+//!
+//! ```rust,ignore
+//! server_fn::ipc::serve(to_frontend, from_frontend, |req: Request<IpcBody>| async move {
+//!     server_fn::axum::handle_server_fn(req.map(Into::into))
+//!         .await
+//!         .map(Into::into)
+//! })
+//! .await;
+//! ```
+
+use crate::error::{FromServerFnError, IntoAppError, ServerFnErrorErr};
+use bytes::{Buf, BufMut, Bytes, BytesMut};
+use futures::{
+    channel::{mpsc, oneshot},
+    future::{AbortHandle, Abortable, BoxFuture},
+    stream::FuturesUnordered,
+    FutureExt, Sink, SinkExt, Stream, StreamExt,
+};
+use http::{
+    header::{HeaderName, HeaderValue},
+    HeaderMap, Method, Request, Response, StatusCode,
+};
+use or_poisoned::OrPoisoned;
+use pin_project_lite::pin_project;
+use std::{
+    cell::RefCell,
+    collections::HashMap,
+    fmt::{self, Debug},
+    future::Future,
+    io,
+    pin::{pin, Pin},
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc, Mutex, RwLock,
+    },
+    task::{Context, Poll},
+};
+
+/// A frame exchanged over an IPC connection.
+///
+/// A call consists of a [`Request`](IpcFrame::Request) frame sent by the client, followed by
+/// any number of [`Data`](IpcFrame::Data) frames with its body and an [`End`](IpcFrame::End)
+/// frame. The server answers with a [`Response`](IpcFrame::Response) frame, followed by the
+/// frames with the body of the response. Frames that belong to the same call share its `id`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum IpcFrame {
+    /// Starts a call.
+    Request {
+        /// The identifier of the call.
+        id: u64,
+        /// The method of the request.
+        method: Method,
+        /// The path of the server function, including any query string.
+        path: String,
+        /// The headers of the request.
+        headers: HeaderMap,
+    },
+    /// Starts the response to a call.
+    Response {
+        /// The identifier of the call.
+        id: u64,
+        /// The status of the response.
+        status: StatusCode,
+        /// The headers of the response.
+        headers: HeaderMap,
+    },
+    /// A chunk of the body of a request or response.
+    Data {
+        /// The identifier of the call.
+        id: u64,
+        /// The chunk.
+        data: Bytes,
+    },
+    /// Ends the body of a request or response.
+    End {
+        /// The identifier of the call.
+        id: u64,
+    },
+    /// Ends the body of a request or response with an error.
+    Error {
+        /// The identifier of the call.
+        id: u64,
+        /// A description of the error.
+        message: String,
+    },
+}
+
+impl IpcFrame {
+    const REQUEST: u8 = 0;
+    const RESPONSE: u8 = 1;
+    const DATA: u8 = 2;
+    const END: u8 = 3;
+    const ERROR: u8 = 4;
+
+    /// The identifier of the call the frame belongs to.
+    pub fn id(&self) -> u64 {
+        match self {
+            Self::Request { id, .. }
+            | Self::Response { id, .. }
+            | Self::Data { id, .. }
+            | Self::End { id }
+            | Self::Error { id, .. } => *id,
+        }
+    }
+
+    /// Encodes the frame as bytes.
+    pub fn encode(&self) -> Bytes {
+        fn put_str(buf: &mut BytesMut, value: &[u8]) {
+            buf.put_u32(value.len() as u32);
+            buf.put_slice(value);
+        }
+
+        fn put_headers(buf: &mut BytesMut, headers: &HeaderMap) {
+            buf.put_u32(headers.len() as u32);
+            for (name, value) in headers {
+                put_str(buf, name.as_str().as_bytes());
+                put_str(buf, value.as_bytes());
+            }
+        }
+
+        let mut buf = BytesMut::new();
+        match self {
+            Self::Request {
+                id,
+                method,
+                path,
+                headers,
+            } => {
+                buf.put_u8(Self::REQUEST);
+                buf.put_u64(*id);
+                put_str(&mut buf, method.as_str().as_bytes());
+                put_str(&mut buf, path.as_bytes());
+                put_headers(&mut buf, headers);
+            }
+            Self::Response {
+                id,
+                status,
+                headers,
+            } => {
+                buf.put_u8(Self::RESPONSE);
+                buf.put_u64(*id);
+                buf.put_u16(status.as_u16());
+                put_headers(&mut buf, headers);
+            }
+            Self::Data { id, data } => {
+                buf.put_u8(Self::DATA);
+                buf.put_u64(*id);
+                buf.put_slice(data);
+            }
+            Self::End { id } => {
+                buf.put_u8(Self::END);
+                buf.put_u64(*id);
+            }
+            Self::Error { id, message } => {
+                buf.put_u8(Self::ERROR);
+                buf.put_u64(*id);
+                buf.put_slice(message.as_bytes());
+            }
+        }
+        buf.freeze()
+    }
+
+    /// Decodes a frame that was encoded with [`encode`](IpcFrame::encode), or returns `None` if
+    /// it is malformed.
+    pub fn decode(mut buf: Bytes) -> Option<Self> {
+        fn get_str(buf: &mut Bytes) -> Option<Bytes> {
+            let len = buf.try_get_u32().ok()? as usize;
+            (buf.remaining() >= len).then(|| buf.split_to(len))
+        }
+
+        fn get_headers(buf: &mut Bytes) -> Option<HeaderMap> {
+            let len = buf.try_get_u32().ok()?;
+            let mut headers = HeaderMap::new();
+            for _ in 0..len {
+                let name = HeaderName::from_bytes(&get_str(buf)?).ok()?;
+                let value =
+                    HeaderValue::from_maybe_shared(get_str(buf)?).ok()?;
+                headers.append(name, value);
+            }
+            Some(headers)
+        }
+
+        let tag = buf.try_get_u8().ok()?;
+        let id = buf.try_get_u64().ok()?;
+        let frame = match tag {
+            Self::REQUEST => Self::Request {
+                id,
+                method: Method::from_bytes(&get_str(&mut buf)?).ok()?,
+                path: String::from_utf8(get_str(&mut buf)?.into()).ok()?,
+                headers: get_headers(&mut buf)?,
+            },
+            Self::RESPONSE => Self::Response {
+                id,
+                status: StatusCode::from_u16(buf.try_get_u16().ok()?).ok()?,
+                headers: get_headers(&mut buf)?,
+            },
+            Self::DATA => return Some(Self::Data { id, data: buf }),
+            Self::END => Self::End { id },
+            Self::ERROR => {
+                return Some(Self::Error {
+                    id,
+                    message: String::from_utf8_lossy(&buf).into_owned(),
+                })
+            }
+            _ => return None,
+        };
+        buf.is_empty().then_some(frame)
+    }
+}
+
+/// The body of a request or response sent over an IPC connection.
+///
+/// It converts to and from the bodies used by the server integrations, so that requests can be
+/// passed to their handlers.
+pub struct IpcBody(Pin<Box<dyn Stream<Item = io::Result<Bytes>> + Send>>);
+
+impl IpcBody {
+    /// Creates an empty body.
+    pub fn empty() -> Self {
+        Self::from_stream(futures::stream::empty())
+    }
+
+    /// Creates a body from a stream of chunks.
+    pub fn from_stream(
+        stream: impl Stream<Item = io::Result<Bytes>> + Send + 'static,
+    ) -> Self {
+        Self(Box::pin(stream))
+    }
+
+    /// Reads the whole body into memory.
+    pub async fn collect(mut self) -> io::Result<Bytes> {
+        let mut body = BytesMut::new();
+        while let Some(chunk) = self.0.next().await {
+            body.extend_from_slice(&chunk?);
+        }
+        Ok(body.freeze())
+    }
+}
+
+impl Debug for IpcBody {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("IpcBody").finish_non_exhaustive()
+    }
+}
+
+impl Default for IpcBody {
+    fn default() -> Self {
+        Self::empty()
+    }
+}
+
+impl Stream for IpcBody {
+    type Item = io::Result<Bytes>;
+
+    fn poll_next(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Self::Item>> {
+        self.0.as_mut().poll_next(cx)
+    }
+}
+
+impl From<Bytes> for IpcBody {
+    fn from(value: Bytes) -> Self {
+        Self::from_stream(futures::stream::once(async move { Ok(value) }))
+    }
+}
+
+#[cfg(feature = "axum-no-default")]
+impl From<IpcBody> for axum::body::Body {
+    fn from(value: IpcBody) -> Self {
+        axum::body::Body::from_stream(value)
+    }
+}
+
+#[cfg(feature = "axum-no-default")]
+impl From<axum::body::Body> for IpcBody {
+    fn from(value: axum::body::Body) -> Self {
+        Self::from_stream(
+            value
+                .into_data_stream()
+                .map(|chunk| chunk.map_err(io::Error::other)),
+        )
+    }
+}
+
+#[cfg(feature = "generic")]
+impl From<crate::response::generic::Body> for IpcBody {
+    fn from(value: crate::response::generic::Body) -> Self {
+        match value {
+            crate::response::generic::Body::Sync(bytes) => bytes.into(),
+            crate::response::generic::Body::Async(stream) => {
+                Self::from_stream(stream.map(|chunk| {
+                    chunk.map_err(|e| io::Error::other(e.to_string()))
+                }))
+            }
+        }
+    }
+}
+
+/// The number of frames that are buffered for a connection or the body of a call, before the
+/// sender has to wait for them to be processed.
+const CHANNEL_CAPACITY: usize = 16;
+
+/// Serves the server function calls that arrive over an IPC connection, by passing each request
+/// to the handler and sending back its response.
+///
+/// Calls are handled concurrently. The returned future completes when the incoming stream ends
+/// and all calls have been answered. Incoming frames are only read while there is room for them
+/// in the body of their call, so a handler that does not read its body holds up the connection.
+pub async fn serve<Si, St, H, Fut>(sink: Si, stream: St, handler: H)
+where
+    Si: Sink<Bytes>,
+    St: Stream<Item = Bytes>,
+    H: Fn(Request<IpcBody>) -> Fut,
+    Fut: Future<Output = Response<IpcBody>>,
+{
+    let (outgoing, frames) = mpsc::channel::<IpcFrame>(CHANNEL_CAPACITY);
+    let writer = frames
+        .map(|frame| Ok::<_, Si::Error>(frame.encode()))
+        .forward(sink);
+
+    let reader = async move {
+        let mut bodies = HashMap::<u64, mpsc::Sender<_>>::new();
+        let mut calls = FuturesUnordered::new();
+        let mut stream = pin!(stream.fuse());
+        loop {
+            futures::select! {
+                frame = stream.next() => {
+                    let Some(frame) = frame else {
+                        break;
+                    };
+                    match IpcFrame::decode(frame) {
+                        Some(IpcFrame::Request { id, method, path, headers }) => {
+                            let (tx, rx) = mpsc::channel(CHANNEL_CAPACITY);
+                            bodies.insert(id, tx);
+                            let mut req = Request::new(IpcBody::from_stream(rx));
+                            *req.method_mut() = method;
+                            *req.headers_mut() = headers;
+                            let res = match path.parse() {
+                                Ok(uri) => {
+                                    *req.uri_mut() = uri;
+                                    Ok(handler(req))
+                                }
+                                Err(_) => Err(StatusCode::BAD_REQUEST),
+                            };
+                            calls.push(respond(id, res, outgoing.clone()));
+                        }
+                        Some(IpcFrame::Data { id, data }) => {
+                            if let Some(body) = bodies.get_mut(&id) {
+                                _ = while_running(body.send(Ok(data)), &mut calls).await;
+                            }
+                        }
+                        Some(IpcFrame::End { id }) => {
+                            bodies.remove(&id);
+                        }
+                        Some(IpcFrame::Error { id, message }) => {
+                            if let Some(mut body) = bodies.remove(&id) {
+                                let error = Err(io::Error::other(message));
+                                _ = while_running(body.send(error), &mut calls).await;
+                            }
+                        }
+                        Some(IpcFrame::Response { .. }) | None => {}
+                    }
+                }
+                _ = calls.select_next_some() => {}
+            }
+        }
+        // the requests can no longer receive the rest of their bodies
+        drop(bodies);
+        while calls.next().await.is_some() {}
+    };
+
+    _ = futures::future::join(reader, writer).await;
+}
+
+/// Waits for a future while running the calls, as it may be waiting for one of them to read
+/// its body.
+async fn while_running<F: Future>(
+    fut: F,
+    calls: &mut FuturesUnordered<impl Future<Output = ()>>,
+) -> F::Output {
+    let mut fut = pin!(fut.fuse());
+    loop {
+        futures::select! {
+            output = fut => return output,
+            _ = calls.select_next_some() => {}
+        }
+    }
+}
+
+/// Runs the handler for a call, and sends its response.
+async fn respond<Fut>(
+    id: u64,
+    res: Result<Fut, StatusCode>,
+    mut outgoing: mpsc::Sender<IpcFrame>,
+) where
+    Fut: Future<Output = Response<IpcBody>>,
+{
+    let (parts, body) = match res {
+        Ok(res) => res.await.into_parts(),
+        Err(status) => {
+            let mut res = Response::new(IpcBody::empty());
+            *res.status_mut() = status;
+            res.into_parts()
+        }
+    };
+    if outgoing
+        .send(IpcFrame::Response {
+            id,
+            status: parts.status,
+            headers: parts.headers,
+        })
+        .await
+        .is_ok()
+    {
+        send_body(id, body, outgoing).await;
+    }
+}
+
+/// Sends a body as the frames of a call.
+async fn send_body(
+    id: u64,
+    mut body: IpcBody,
+    mut outgoing: mpsc::Sender<IpcFrame>,
+) {
+    while let Some(chunk) = body.next().await {
+        let frame = match chunk {
+            Ok(data) => IpcFrame::Data { id, data },
+            Err(e) => {
+                _ = outgoing
+                    .send(IpcFrame::Error {
+                        id,
+                        message: e.to_string(),
+                    })
+                    .await;
+                return;
+            }
+        };
+        if outgoing.send(frame).await.is_err() {
+            return;
+        }
+    }
+    _ = outgoing.send(IpcFrame::End { id }).await;
+}
+
+type Spawn = Box<dyn Fn(BoxFuture<'static, ()>) + Send + Sync>;
+
+/// A call that is waiting for its response.
+struct PendingCall {
+    head: Option<oneshot::Sender<(StatusCode, HeaderMap)>>,
+    body: mpsc::Sender<io::Result<Bytes>>,
+}
+
+struct Connection {
+    next_id: AtomicU64,
+    outgoing: mpsc::Sender<IpcFrame>,
+    pending: Mutex<HashMap<u64, PendingCall>>,
+    spawn: Spawn,
+    abort: AbortHandle,
+}
+
+/// The client side of an IPC connection, created with [`connect`].
+///
+/// The [`IpcClient`](crate::client::ipc::IpcClient) sends calls over the connection they are
+/// [`run`](IpcConnection::run) with, or otherwise over the
+/// [default connection](IpcConnection::make_default). Handles are cheap to clone and refer to
+/// the same connection.
+#[derive(Clone)]
+pub struct IpcConnection(Arc<Connection>);
+
+impl Debug for IpcConnection {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("IpcConnection").finish_non_exhaustive()
+    }
+}
+
+/// The response to a call, as received by the client.
+pub(crate) struct IpcReply {
+    pub status: StatusCode,
+    pub headers: HeaderMap,
+    pub body: mpsc::Receiver<io::Result<Bytes>>,
+}
+
+thread_local! {
+    static CURRENT: RefCell<Option<IpcConnection>> = const { RefCell::new(None) };
+}
+
+static DEFAULT: RwLock<Option<IpcConnection>> = RwLock::new(None);
+
+/// Connects to a transport.
+///
+/// Frames are sent into the sink and received from the stream. `spawn` is used to run the
+/// connection in the background, and to send streaming request bodies while waiting for the
+/// response. The connection is used for the calls [`run`](IpcConnection::run) with it, or for
+/// all other calls once it is [made the default](IpcConnection::make_default).
+pub fn connect<Si, St>(
+    sink: Si,
+    stream: St,
+    spawn: impl Fn(BoxFuture<'static, ()>) + Send + Sync + 'static,
+) -> IpcConnection
+where
+    Si: Sink<Bytes> + Send + 'static,
+    St: Stream<Item = Bytes> + Send + 'static,
+{
+    let (outgoing, frames) = mpsc::channel::<IpcFrame>(CHANNEL_CAPACITY);
+    let (abort, registration) = AbortHandle::new_pair();
+    let connection = IpcConnection(Arc::new(Connection {
+        next_id: AtomicU64::new(0),
+        outgoing,
+        pending: Mutex::new(HashMap::new()),
+        spawn: Box::new(spawn),
+        abort,
+    }));
+
+    let writer = frames
+        .map(|frame| Ok::<_, Si::Error>(frame.encode()))
+        .forward(sink);
+    let reader = {
+        let connection = connection.clone();
+        async move {
+            let mut stream = pin!(stream);
+            while let Some(frame) = stream.next().await {
+                if let Some(frame) = IpcFrame::decode(frame) {
+                    connection.receive(frame).await;
+                }
+            }
+        }
+    };
+    let driver = {
+        let connection = connection.clone();
+        async move {
+            _ = Abortable::new(
+                futures::future::select(pin!(reader), pin!(writer)),
+                registration,
+            )
+            .await;
+            connection.close();
+        }
+    };
+    connection.spawn(Box::pin(driver));
+    connection
+}
+
+/// Returns the connection that calls are currently sent over.
+pub(crate) fn connection() -> Option<IpcConnection> {
+    CURRENT
+        .with(|current| current.borrow().clone())
+        .or_else(|| DEFAULT.read().or_poisoned().clone())
+}
+
+pin_project! {
+    /// A future that sends the calls it makes over an IPC connection.
+    ///
+    /// See [`IpcConnection::run`].
+    pub struct IpcScope<Fut> {
+        connection: IpcConnection,
+        #[pin]
+        inner: Fut,
+    }
+}
+
+impl<Fut: Future> Future for IpcScope<Fut> {
+    type Output = Fut::Output;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = self.project();
+        let prev = CURRENT
+            .with(|current| current.replace(Some(this.connection.clone())));
+        let poll = this.inner.poll(cx);
+        CURRENT.with(|current| *current.borrow_mut() = prev);
+        poll
+    }
+}
+
+impl IpcConnection {
+    /// Runs a future, sending the calls it makes over this connection instead of the default
+    /// one.
+    pub fn run<Fut: Future>(&self, fut: Fut) -> IpcScope<Fut> {
+        IpcScope {
+            connection: self.clone(),
+            inner: fut,
+        }
+    }
+
+    /// Makes this the connection that calls are sent over outside of [`run`](Self::run), and
+    /// returns the previous default connection.
+    ///
+    /// The previous connection stays open until it is [closed](Self::close), or its transport
+    /// ends.
+    pub fn make_default(&self) -> Option<IpcConnection> {
+        DEFAULT.write().or_poisoned().replace(self.clone())
+    }
+
+    /// Closes the connection, and fails all calls that are still waiting for a response.
+    ///
+    /// If this is the default connection, calls made outside of [`run`](Self::run) fail until
+    /// another connection is made the default.
+    pub fn close(&self) {
+        self.0.abort.abort();
+        for (_, mut call) in self.0.pending.lock().or_poisoned().drain() {
+            // the sender kept for the call is never used for anything else, so its guaranteed
+            // slot in the channel is still free
+            _ = call.body.try_send(Err(io::Error::new(
+                io::ErrorKind::ConnectionAborted,
+                "The IPC connection is closed.",
+            )));
+        }
+    }
+
+    /// Sends a request, and waits for the start of its response.
+    pub(crate) async fn call<E: FromServerFnError>(
+        &self,
+        method: Method,
+        path: String,
+        headers: HeaderMap,
+        body: IpcBody,
+    ) -> Result<IpcReply, E> {
+        let closed = || {
+            ServerFnErrorErr::Request("The IPC connection is closed.".into())
+                .into_app_error()
+        };
+
+        let id = self.0.next_id.fetch_add(1, Ordering::Relaxed);
+        let (head_tx, head_rx) = oneshot::channel();
+        let (body_tx, body_rx) = mpsc::channel(CHANNEL_CAPACITY);
+        self.0.pending.lock().or_poisoned().insert(
+            id,
+            PendingCall {
+                head: Some(head_tx),
+                body: body_tx,
+            },
+        );
+        let mut outgoing = self.0.outgoing.clone();
+        outgoing
+            .send(IpcFrame::Request {
+                id,
+                method,
+                path,
+                headers,
+            })
+            .await
+            .map_err(|_| closed())?;
+
+        // the body is sent in the background, as the server may start to respond before it
+        // has received all of it
+        self.spawn(Box::pin(send_body(id, body, outgoing)));
+
+        let (status, headers) = head_rx.await.map_err(|_| closed())?;
+        Ok(IpcReply {
+            status,
+            headers,
+            body: body_rx,
+        })
+    }
+
+    /// Spawns a future with the function passed to [`connect`].
+    pub(crate) fn spawn(&self, future: BoxFuture<'static, ()>) {
+        (self.0.spawn)(future)
+    }
+
+    /// Passes a frame to the call it belongs to, waiting for room in its body if necessary.
+    async fn receive(&self, frame: IpcFrame) {
+        let body = match frame {
+            IpcFrame::Response {
+                id,
+                status,
+                headers,
+            } => {
+                if let Some(head) = self
+                    .0
+                    .pending
+                    .lock()
+                    .or_poisoned()
+                    .get_mut(&id)
+                    .and_then(|call| call.head.take())
+                {
+                    _ = head.send((status, headers));
+                }
+                return;
+            }
+            IpcFrame::Data { id, data } => {
+                let body = self
+                    .0
+                    .pending
+                    .lock()
+                    .or_poisoned()
+                    .get(&id)
+                    .map(|call| call.body.clone());
+                body.map(|body| (body, Ok(data)))
+            }
+            IpcFrame::End { id } => {
+                self.0.pending.lock().or_poisoned().remove(&id);
+                return;
+            }
+            IpcFrame::Error { id, message } => {
+                let call = self.0.pending.lock().or_poisoned().remove(&id);
+                call.map(|call| (call.body, Err(io::Error::other(message))))
+            }
+            IpcFrame::Request { .. } => return,
+        };
+        if let Some((mut body, chunk)) = body {
+            _ = body.send(chunk).await;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn frames_roundtrip() {
+        let mut headers = HeaderMap::new();
+        headers.insert("content-type", HeaderValue::from_static("text/plain"));
+        headers.append("x-tag", HeaderValue::from_static("a"));
+        headers.append("x-tag", HeaderValue::from_static("b"));
+        let frames = [
+            IpcFrame::Request {
+                id: 1,
+                method: Method::POST,
+                path: "/api/add?x=1".into(),
+                headers: headers.clone(),
+            },
+            IpcFrame::Response {
+                id: 1,
+                status: StatusCode::CREATED,
+                headers,
+            },
+            IpcFrame::Data {
+                id: 2,
+                data: Bytes::from_static(b"\0data"),
+            },
+            IpcFrame::End { id: 3 },
+            IpcFrame::Error {
+                id: u64::MAX,
+                message: "failed".into(),
+            },
+        ];
+        for frame in frames {
+            assert_eq!(IpcFrame::decode(frame.encode()), Some(frame));
+        }
+        assert_eq!(IpcFrame::decode(Bytes::from_static(b"\x07")), None);
+        assert_eq!(IpcFrame::decode(Bytes::from_static(&[0, 0, 0])), None);
+    }
+
+    #[test]
+    fn calls_are_served_over_a_connection() {
+        use crate::{
+            client::{ipc::IpcClient, Client},
+            request::{ipc::IpcRequest, ClientReq},
+            response::ClientRes,
+            ServerFnError,
+        };
+        use futures::{executor::block_on, SinkExt};
+
+        let spawn = |fut: BoxFuture<'static, ()>| {
+            std::thread::spawn(move || block_on(fut));
+        };
+        let (to_server, from_client) = mpsc::unbounded::<Bytes>();
+        let (to_client, from_server) = mpsc::unbounded::<Bytes>();
+        spawn(Box::pin(serve(
+            to_client.sink_map_err(|_| ()),
+            from_client,
+            |req: Request<IpcBody>| async move {
+                let (parts, body) = req.into_parts();
+                let body = body.collect().await.unwrap();
+                let mut res =
+                    Response::new(IpcBody::from(Bytes::from(format!(
+                        "{} {} {}",
+                        parts.method,
+                        parts.uri,
+                        String::from_utf8_lossy(&body)
+                    ))));
+                *res.status_mut() = StatusCode::ACCEPTED;
+                res
+            },
+        )));
+        connect(to_server, from_server, spawn).make_default();
+
+        let req: IpcRequest =
+            ClientReq::<ServerFnError>::try_new_req_streaming(
+                "/api/echo",
+                "text/plain",
+                "text/plain",
+                futures::stream::iter(["hello, ", "world"].map(Bytes::from)),
+                Method::POST,
+            )
+            .unwrap();
+        let (status, body) = block_on(async {
+            let res = <IpcClient as Client<ServerFnError>>::send(req)
+                .await
+                .unwrap();
+            let status = ClientRes::<ServerFnError>::status(&res);
+            (
+                status,
+                ClientRes::<ServerFnError>::try_into_string(res).await,
+            )
+        });
+        assert_eq!(status, 202);
+        assert_eq!(body.unwrap(), "POST /api/echo hello, world");
+    }
+
+    #[test]
+    fn calls_use_the_connection_they_run_with() {
+        use crate::{
+            client::{ipc::IpcClient, Client},
+            request::{ipc::IpcRequest, ClientReq},
+            response::ClientRes,
+            ServerFnError,
+        };
+        use futures::{executor::block_on, SinkExt};
+
+        let spawn = |fut: BoxFuture<'static, ()>| {
+            std::thread::spawn(move || block_on(fut));
+        };
+        // each backend answers with its name, in more chunks than the channels buffer
+        let backend = |name: &'static str| {
+            let (to_server, from_client) = mpsc::unbounded::<Bytes>();
+            let (to_client, from_server) = mpsc::unbounded::<Bytes>();
+            spawn(Box::pin(serve(
+                to_client.sink_map_err(|_| ()),
+                from_client,
+                move |_: Request<IpcBody>| async move {
+                    Response::new(IpcBody::from_stream(
+                        futures::stream::repeat(Bytes::from(name))
+                            .take(4 * CHANNEL_CAPACITY)
+                            .map(Ok),
+                    ))
+                },
+            )));
+            connect(to_server, from_server, spawn)
+        };
+        let call = || async {
+            let req: IpcRequest =
+                ClientReq::<ServerFnError>::try_new_req_query(
+                    "/api/name",
+                    "text/plain",
+                    "text/plain",
+                    "",
+                    Method::GET,
+                )?;
+            let res = <IpcClient as Client<ServerFnError>>::send(req).await?;
+            ClientRes::<ServerFnError>::try_into_string(res).await
+        };
+
+        let a = backend("a");
+        let b = backend("b");
+        let expected = |name: &str| Ok(name.repeat(4 * CHANNEL_CAPACITY));
+        assert_eq!(block_on(a.run(call())), expected("a"));
+        assert_eq!(block_on(b.run(call())), expected("b"));
+
+        b.close();
+        assert!(block_on(b.run(call())).is_err());
+    }
+}
@@ -105,12 +105,20 @@ pub mod client;
 /// Implementations of the server side of the server function call.
 pub mod server;
 
+pub mod cache;
 /// Encodings for arguments and results.
 pub mod codec;
+#[cfg(any(feature = "gzip", feature = "brotli", feature = "zstd"))]
+pub mod compression;
 
 #[macro_use]
 /// Error types and utilities.
 pub mod error;
+pub mod csrf;
+#[cfg(feature = "ipc")]
+pub mod ipc;
+pub mod limits;
+pub mod manifest;
 /// Types to add server middleware to a server function.
 pub mod middleware;
 /// Utilities to allow client-side redirects.
@@ -119,6 +127,8 @@ pub mod redirect;
 pub mod request;
 /// Types and traits for HTTP responses.
 pub mod response;
+#[cfg(feature = "worker")]
+pub mod worker;
 
 #[cfg(feature = "actix-no-default")]
 #[doc(hidden)]
@@ -152,6 +162,8 @@ use error::{FromServerFnError, ServerFnErrorErr};
 use futures::{pin_mut, SinkExt, Stream, StreamExt};
 use http::Method;
 use middleware::{BoxedService, Layer, Service};
+#[cfg(feature = "prost")]
+pub use prost;
 use redirect::call_redirect_hook;
 use request::Req;
 use response::{ClientRes, Res, TryRes};
@@ -164,6 +176,7 @@ pub use serde;
 pub use serde_lite;
 use server::Server;
 use std::{
+    borrow::Cow,
     collections::HashMap,
     fmt::{Debug, Display},
     future::Future,
@@ -186,6 +199,15 @@ type ServerFnServerResponse<Fn> = <<Fn as ServerFn>::Server as crate::Server<
     <Fn as ServerFn>::OutputStreamError,
 >>::Response;
 
+/// Converts an error into a response, encoded with the server function's error encoding.
+fn error_response<Fn: ServerFn>(err: &Fn::Error) -> ServerFnServerResponse<Fn> {
+    let mut response =
+        ServerFnServerResponse::<Fn>::error_response(Fn::PATH, err.ser());
+    response
+        .content_type(<Fn::Error as FromServerFnError>::Encoder::CONTENT_TYPE);
+    response
+}
+
 /// Defines a function that runs only on the server, but can be called from the server or the client.
 ///
 /// The type for which `ServerFn` is implemented is actually the type of the arguments to the function,
@@ -264,6 +286,47 @@ pub trait ServerFn: Send + Sized {
     /// Typically [`ServerFnError`], but allowed to be any type that implements [`FromServerFnError`].
     type OutputStreamError: FromServerFnError + Send + Sync;
 
+    /// The largest request body, in bytes, that this server function accepts.
+    ///
+    /// Requests with a larger body are rejected with `413 Payload Too Large`.
+    const MAX_BODY: Option<usize> = None;
+
+    /// The rate limit applied to each caller of this server function.
+    ///
+    /// Callers that exceed it are rejected with `429 Too Many Requests`.
+    const RATE_LIMIT: Option<limits::RateLimit> = None;
+
+    /// Whether this server function is protected by the check configured with
+    /// [`csrf::set_csrf_mode`].
+    ///
+    /// Requests that fail the check are rejected with `403 Forbidden`.
+    const CSRF: bool = true;
+
+    /// The `Cache-Control` header for successful responses, set with
+    /// `#[server(cache = "...")]`.
+    ///
+    /// This only applies to server functions that use `GET` requests. See [`cache`] for details.
+    const CACHE: Option<&'static str> = None;
+
+    /// The version of this server function, set with `#[server(version = N)]`.
+    ///
+    /// Each version from 2 on is served at its own path, so that several versions of a function
+    /// can be served side by side while older clients are still deployed.
+    const VERSION: Option<u32> = None;
+
+    /// Whether responses of this server function may be compressed, which can be turned off
+    /// with `#[server(compress = false)]`.
+    ///
+    /// This only has an effect if one of the `gzip`, `brotli` or `zstd` features is enabled. See
+    /// the `compression` module for details.
+    const COMPRESS: bool = true;
+
+    /// A hash of the source text of this server function's arguments, return type and protocol,
+    /// used to detect breaking changes in a [`manifest::Manifest`].
+    ///
+    /// The hash does not cover the definitions of the types themselves.
+    const SIGNATURE_HASH: u64 = 0;
+
     /// Returns [`Self::PATH`].
     fn url() -> &'static str {
         Self::PATH
@@ -301,27 +364,98 @@ pub trait ServerFn: Send + Sized {
         #[cfg(feature = "form-redirects")]
         let mut referer = req.referer().as_deref().map(ToOwned::to_owned);
 
+        let csrf = csrf::check(&req, Self::Protocol::METHOD, Self::CSRF);
+        let limits =
+            limits::check(Self::PATH, &req, Self::MAX_BODY, Self::RATE_LIMIT);
+        let body_limit = Self::MAX_BODY.map(limits::BodyLimit::new);
+        let form_token = csrf.as_ref().ok().cloned().flatten();
+
         async move {
+            // reject the request before reading its body if it fails the CSRF check or
+            // exceeds the limits, and otherwise cut the body off once it exceeds `MAX_BODY`,
+            // and hold it back until a form has been checked for the CSRF token
+            let checked = match csrf {
+                Ok(_) => limits.await,
+                Err(rejection) => Err(rejection),
+            };
+            let req = match checked.and_then(|()| {
+                let req = limits::limit_body(req, body_limit.as_ref())?;
+                csrf::require_form_token(req, form_token.as_ref())
+            }) {
+                Ok(req) => req,
+                Err(rejection) => {
+                    let mut response = error_response::<Self>(
+                        &Self::Error::from_server_fn_error(rejection.error),
+                    );
+                    response.set_status(rejection.status);
+                    if let Some(retry_after) = rejection.retry_after {
+                        response.insert_header(
+                            "retry-after",
+                            &(retry_after.as_secs_f64().ceil() as u64)
+                                .to_string(),
+                        );
+                    }
+                    return response;
+                }
+            };
+
+            let cacheable = Self::Protocol::METHOD == Method::GET;
+            let if_none_match = cacheable
+                .then(|| req.header("if-none-match").map(Cow::into_owned))
+                .flatten();
+
+            #[cfg(any(feature = "gzip", feature = "brotli", feature = "zstd"))]
+            let encoding = Self::COMPRESS
+                .then(|| req.header("accept-encoding"))
+                .flatten()
+                .and_then(|header| compression::negotiate(&header));
+
+            let (res, directives) = cache::CacheScope::new(
+                Self::CACHE,
+                Self::Protocol::run_server(req, Self::run_body),
+            )
+            .await;
+
             #[allow(unused_variables, unused_mut)]
             // used in form redirects feature
             let (mut res, err) =
-                Self::Protocol::run_server(req, Self::run_body)
-                    .await
-                    .map(|res| (res, None))
-                    .unwrap_or_else(|e| {
-                        let mut response =
-                            <<Self as ServerFn>::Server as crate::Server<
-                                Self::Error,
-                                Self::InputStreamError,
-                                Self::OutputStreamError,
-                            >>::Response::error_response(
-                                Self::PATH, e.ser()
-                            );
-                        let content_type =
-                    <Self::Error as FromServerFnError>::Encoder::CONTENT_TYPE;
-                        response.content_type(content_type);
-                        (response, Some(e))
-                    });
+                res.map(|res| (res, None)).unwrap_or_else(|e| {
+                    let mut response = error_response::<Self>(&e);
+                    // the body turned out to be larger than `MAX_BODY` while it was read
+                    if body_limit
+                        .as_ref()
+                        .is_some_and(limits::BodyLimit::exceeded)
+                    {
+                        response
+                            .set_status(http::StatusCode::PAYLOAD_TOO_LARGE);
+                    }
+                    // the form did not repeat the CSRF token
+                    if form_token
+                        .as_ref()
+                        .is_some_and(csrf::FormToken::rejected)
+                    {
+                        response.set_status(http::StatusCode::FORBIDDEN);
+                    }
+                    (response, Some(e))
+                });
+
+            if cacheable && err.is_none() && !directives.is_empty() {
+                res = Self::apply_cache(res, directives, if_none_match).await;
+            }
+
+            // the `ETag` is computed from the uncompressed body, so that it does not depend on
+            // the encoding
+            #[cfg(any(feature = "gzip", feature = "brotli", feature = "zstd"))]
+            if let (Some(encoding), None) = (encoding, &err) {
+                res =
+                    match res.compress(encoding, compression::min_size()).await
+                    {
+                        Ok(res) => res,
+                        Err(e) => error_response::<Self>(
+                            &Self::Error::from_server_fn_error(e),
+                        ),
+                    };
+            }
 
             // if it accepts HTML, we'll redirect to the Referer
             #[cfg(feature = "form-redirects")]
@@ -348,6 +482,40 @@ pub trait ServerFn: Send + Sized {
         }
     }
 
+    #[doc(hidden)]
+    fn apply_cache(
+        mut res: ServerFnServerResponse<Self>,
+        directives: cache::CacheDirectives,
+        if_none_match: Option<String>,
+    ) -> impl Future<Output = ServerFnServerResponse<Self>> + Send {
+        async move {
+            if let Some(cache_control) = &directives.cache_control {
+                res.insert_header("cache-control", cache_control);
+            }
+            if let Some(vary) = &directives.vary {
+                res.insert_header("vary", vary);
+            }
+            let (mut res, body) = match res.buffer_body().await {
+                Ok(buffered) => buffered,
+                Err(e) => {
+                    return error_response::<Self>(
+                        &Self::Error::from_server_fn_error(e),
+                    )
+                }
+            };
+            if let Some(body) = body {
+                let etag = cache::etag(&body);
+                res.insert_header("etag", &etag);
+                if if_none_match
+                    .is_some_and(|header| cache::if_none_match(&header, &etag))
+                {
+                    res.not_modified();
+                }
+            }
+            res
+        }
+    }
+
     #[doc(hidden)]
     fn run_on_client(
         self,
@@ -857,7 +1025,7 @@ pub trait Decodes<T> {
     fn decode(bytes: Bytes) -> Result<T, Self::Error>;
 }
 
-#[cfg(feature = "ssr")]
+#[cfg(any(feature = "ssr", feature = "worker"))]
 #[doc(hidden)]
 pub use inventory;
 
@@ -1340,4 +1508,107 @@ mod tests {
             Bytes::from_static(b"error details")
         );
     }
+
+    #[cfg(feature = "axum")]
+    mod limits {
+        use super::*;
+        use crate::{
+            axum::AxumServerFnBackend, client::in_process::InProcessClient,
+            codec::Json, limits::RateLimit,
+        };
+        use ::axum::{body::Body, extract::ConnectInfo};
+        use futures::executor::block_on;
+        use http::{Request, StatusCode};
+        use std::net::SocketAddr;
+
+        #[derive(Serialize, Deserialize)]
+        struct Echo {
+            data: String,
+        }
+
+        impl ServerFn for Echo {
+            const PATH: &'static str = "/api/limits_echo";
+            const MAX_BODY: Option<usize> = Some(32);
+            const RATE_LIMIT: Option<RateLimit> =
+                Some(RateLimit::per_minute(2));
+
+            type Client = InProcessClient;
+            type Server = AxumServerFnBackend;
+            type Protocol = Http<Json, Json>;
+            type Output = String;
+            type Error = ServerFnError;
+            type InputStreamError = ServerFnError;
+            type OutputStreamError = ServerFnError;
+
+            async fn run_body(self) -> Result<String, ServerFnError> {
+                Ok(self.data)
+            }
+        }
+
+        fn request(
+            peer: Option<[u8; 4]>,
+            data: &str,
+            content_length: bool,
+        ) -> Request<Body> {
+            let body = format!(r#"{{"data":"{data}"}}"#);
+            let mut req = Request::post(Echo::PATH)
+                .header("content-type", "application/json");
+            if content_length {
+                req = req.header("content-length", body.len());
+            }
+            if let Some(peer) = peer {
+                req = req.extension(ConnectInfo(SocketAddr::from((peer, 80))));
+            }
+            req.body(Body::from(body)).unwrap()
+        }
+
+        #[test]
+        fn rejects_large_bodies_with_413() {
+            let peer = Some([10, 0, 0, 1]);
+            let res = block_on(Echo::run_on_server(request(peer, "ok", true)));
+            assert_eq!(res.status(), StatusCode::OK);
+
+            // rejected by its `Content-Length` before the body is read
+            let data = "x".repeat(64);
+            let res = block_on(Echo::run_on_server(request(
+                Some([10, 0, 0, 2]),
+                &data,
+                true,
+            )));
+            assert_eq!(res.status(), StatusCode::PAYLOAD_TOO_LARGE);
+
+            // cut off while the body is read
+            let res = block_on(Echo::run_on_server(request(
+                Some([10, 0, 0, 3]),
+                &data,
+                false,
+            )));
+            assert_eq!(res.status(), StatusCode::PAYLOAD_TOO_LARGE);
+        }
+
+        #[test]
+        fn rejects_callers_over_the_rate_limit_with_429() {
+            let peer = Some([10, 0, 1, 1]);
+            for _ in 0..2 {
+                let res =
+                    block_on(Echo::run_on_server(request(peer, "ok", true)));
+                assert_eq!(res.status(), StatusCode::OK);
+            }
+            let res = block_on(Echo::run_on_server(request(peer, "ok", true)));
+            assert_eq!(res.status(), StatusCode::TOO_MANY_REQUESTS);
+            assert_eq!(res.headers()["retry-after"], "30");
+
+            // other callers have their own limit
+            let res = block_on(Echo::run_on_server(request(
+                Some([10, 0, 1, 2]),
+                "ok",
+                true,
+            )));
+            assert_eq!(res.status(), StatusCode::OK);
+
+            // callers that cannot be identified are not let through
+            let res = block_on(Echo::run_on_server(request(None, "ok", true)));
+            assert_eq!(res.status(), StatusCode::INTERNAL_SERVER_ERROR);
+        }
+    }
 }
@@ -0,0 +1,494 @@
+//! Request-size limits and rate limiting for server functions.
+//!
+//! Both limits are usually declared on the server function itself:
+//!
+//! ```rust,ignore
+//! #[server(max_body = "1MB", rate_limit = "10/s")]
+//! pub async fn upload(data: String) -> Result<(), ServerFnError> {
+//!     // ...
+//! }
+//! ```
+//!
+//! They are enforced in [`ServerFn::run_on_server`](crate::ServerFn), so every server
+//! integration gets the same behavior. A request whose body is larger than `max_body` is
+//! rejected with `413 Payload Too Large`: requests with a larger `Content-Length` are
+//! rejected before the body is read, and other bodies are cut off as soon as they exceed the
+//! limit while being read (see [`Req::limit_body`]). A caller that has used up its rate limit
+//! is rejected with `429 Too Many Requests` and a `Retry-After` header.
+//!
+//! Rate limits are tracked per server function and per caller in a [`RateLimitStore`].
+//! By default this is an [`InMemoryRateLimitStore`], which is local to a single process;
+//! use [`set_rate_limit_store`] to share limits between several server instances.
+
+use crate::{error::ServerFnErrorErr, request::Req};
+use bytes::Bytes;
+use futures::{Stream, StreamExt};
+use or_poisoned::OrPoisoned;
+use std::{
+    collections::HashMap,
+    future::{self, Future},
+    net::SocketAddr,
+    pin::Pin,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, LazyLock, Mutex, RwLock,
+    },
+    time::{Duration, Instant},
+};
+
+/// The `max_body` limit of a single request, applied while its body is read.
+///
+/// Server integrations apply it in [`Req::limit_body`], usually with [`BodyLimit::limit_stream`].
+#[derive(Debug, Clone)]
+pub struct BodyLimit {
+    max: usize,
+    exceeded: Arc<AtomicBool>,
+}
+
+impl BodyLimit {
+    pub(crate) fn new(max: usize) -> Self {
+        Self {
+            max,
+            exceeded: Arc::new(AtomicBool::new(false)),
+        }
+    }
+
+    /// The largest body, in bytes, that may be read.
+    pub fn max(&self) -> usize {
+        self.max
+    }
+
+    /// Whether more than [`max`](Self::max) bytes of the body have been read.
+    pub fn exceeded(&self) -> bool {
+        self.exceeded.load(Ordering::Relaxed)
+    }
+
+    /// Marks the limit as exceeded, and returns the error to report.
+    pub fn reject(&self) -> ServerFnErrorErr {
+        self.exceeded.store(true, Ordering::Relaxed);
+        ServerFnErrorErr::PayloadTooLarge(format!(
+            "the request body may be at most {} bytes",
+            self.max
+        ))
+    }
+
+    /// Wraps a body stream so that it yields an error and ends as soon as more than
+    /// [`max`](Self::max) bytes have been read.
+    ///
+    /// `into_error` converts the [`PayloadTooLarge`](ServerFnErrorErr::PayloadTooLarge) error
+    /// into the error type of the stream.
+    pub fn limit_stream<S, E>(
+        &self,
+        stream: S,
+        into_error: impl Fn(ServerFnErrorErr) -> E,
+    ) -> impl Stream<Item = Result<Bytes, E>>
+    where
+        S: Stream<Item = Result<Bytes, E>>,
+    {
+        let limit = self.clone();
+        // the number of bytes read so far, or `None` once the limit has been exceeded
+        stream.scan(Some(0usize), move |read, chunk| {
+            let item = read.take().map(|len| match chunk {
+                Ok(chunk) if len + chunk.len() > limit.max => {
+                    Err(into_error(limit.reject()))
+                }
+                Ok(chunk) => {
+                    *read = Some(len + chunk.len());
+                    Ok(chunk)
+                }
+                Err(e) => {
+                    *read = Some(len);
+                    Err(e)
+                }
+            });
+            future::ready(item)
+        })
+    }
+}
+
+/// A rate limit for a server function: at most `requests` calls per `period`, per caller.
+///
+/// Limits are enforced with a token bucket, so a caller can make up to `requests` calls in a
+/// burst, after which the bucket refills steadily over the period.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct RateLimit {
+    requests: u32,
+    period: Duration,
+}
+
+impl RateLimit {
+    /// Creates a new rate limit, allowing `requests` calls per `period`.
+    pub const fn new(requests: u32, period: Duration) -> Self {
+        Self { requests, period }
+    }
+
+    /// Allows `requests` calls per second.
+    pub const fn per_second(requests: u32) -> Self {
+        Self::new(requests, Duration::from_secs(1))
+    }
+
+    /// Allows `requests` calls per minute.
+    pub const fn per_minute(requests: u32) -> Self {
+        Self::new(requests, Duration::from_secs(60))
+    }
+
+    /// Allows `requests` calls per hour.
+    pub const fn per_hour(requests: u32) -> Self {
+        Self::new(requests, Duration::from_secs(60 * 60))
+    }
+
+    /// The number of calls allowed per period.
+    pub const fn requests(&self) -> u32 {
+        self.requests
+    }
+
+    /// The period over which calls are counted.
+    pub const fn period(&self) -> Duration {
+        self.period
+    }
+}
+
+/// A place to record how much of its rate limit each caller has used.
+///
+/// Implement this to share rate limits between several server instances, e.g., in Redis.
+pub trait RateLimitStore: Send + Sync + 'static {
+    /// Records a call for the given key.
+    ///
+    /// Returns `Ok(())` if the call is allowed, or `Err(retry_after)` with the time until the
+    /// caller may try again if the limit has been exceeded.
+    fn acquire<'a>(
+        &'a self,
+        key: &'a str,
+        limit: RateLimit,
+    ) -> Pin<Box<dyn Future<Output = Result<(), Duration>> + Send + 'a>>;
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Bucket {
+    tokens: f64,
+    updated: Instant,
+    period: Duration,
+}
+
+impl Bucket {
+    /// Whether the bucket has refilled completely, so that it can be forgotten.
+    fn is_full(&self, now: Instant) -> bool {
+        now.duration_since(self.updated) >= self.period
+    }
+}
+
+/// Prune whenever the number of buckets has doubled since the last pruning, but not below this.
+const MIN_PRUNE_AT: usize = 1024;
+
+#[derive(Debug)]
+struct Buckets {
+    map: HashMap<String, Bucket>,
+    prune_at: usize,
+}
+
+impl Default for Buckets {
+    fn default() -> Self {
+        Self {
+            map: HashMap::new(),
+            prune_at: MIN_PRUNE_AT,
+        }
+    }
+}
+
+/// An in-process [`RateLimitStore`] that keeps one token bucket per key.
+///
+/// Buckets that have refilled completely are pruned whenever the number of buckets has doubled
+/// since the last pruning, so memory use is bounded by the number of callers that are currently
+/// limited, and pruning takes amortized constant time per request.
+#[derive(Debug, Default)]
+pub struct InMemoryRateLimitStore {
+    buckets: Mutex<Buckets>,
+}
+
+impl InMemoryRateLimitStore {
+    /// Creates a new, empty store.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn acquire_at(
+        &self,
+        key: &str,
+        limit: RateLimit,
+        now: Instant,
+    ) -> Result<(), Duration> {
+        let capacity = f64::from(limit.requests);
+        if capacity == 0.0 {
+            return Err(limit.period);
+        }
+        let per_token = limit.period.as_secs_f64() / capacity;
+
+        let mut buckets = self.buckets.lock().or_poisoned();
+        if buckets.map.len() >= buckets.prune_at {
+            buckets.map.retain(|_, bucket| !bucket.is_full(now));
+            buckets.prune_at = (buckets.map.len() * 2).max(MIN_PRUNE_AT);
+        }
+        let bucket = buckets.map.entry(key.to_owned()).or_insert(Bucket {
+            tokens: capacity,
+            updated: now,
+            period: limit.period,
+        });
+
+        let elapsed = now.duration_since(bucket.updated).as_secs_f64();
+        bucket.tokens = if per_token > 0.0 {
+            (bucket.tokens + elapsed / per_token).min(capacity)
+        } else {
+            capacity
+        };
+        bucket.updated = now;
+        bucket.period = limit.period;
+
+        if bucket.tokens >= 1.0 {
+            bucket.tokens -= 1.0;
+            Ok(())
+        } else {
+            Err(Duration::from_secs_f64((1.0 - bucket.tokens) * per_token))
+        }
+    }
+}
+
+impl RateLimitStore for InMemoryRateLimitStore {
+    fn acquire<'a>(
+        &'a self,
+        key: &'a str,
+        limit: RateLimit,
+    ) -> Pin<Box<dyn Future<Output = Result<(), Duration>> + Send + 'a>> {
+        let result = self.acquire_at(key, limit, Instant::now());
+        Box::pin(async move { result })
+    }
+}
+
+/// Information about the caller of a server function, used to build its rate-limit key.
+#[derive(Debug, Clone)]
+pub struct ClientInfo {
+    /// The address of the peer that sent the request, if the server makes it available.
+    pub peer_addr: Option<SocketAddr>,
+    /// The `X-Forwarded-For` header, if any.
+    pub forwarded_for: Option<String>,
+    /// The `X-Real-IP` header, if any.
+    pub real_ip: Option<String>,
+}
+
+impl ClientInfo {
+    fn from_req<Request, E, IS, OS>(req: &Request) -> Self
+    where
+        Request: Req<E, IS, OS>,
+    {
+        Self {
+            peer_addr: req.peer_addr(),
+            forwarded_for: req
+                .header("x-forwarded-for")
+                .map(|value| value.into_owned()),
+            real_ip: req.header("x-real-ip").map(|value| value.into_owned()),
+        }
+    }
+}
+
+/// Identifies callers by the IP address of the connecting peer.
+///
+/// Forwarding headers are ignored, because they can be set by any client. If your server runs
+/// behind a trusted proxy, use [`set_client_key`] to identify callers by those headers instead.
+///
+/// Returns `None` if the server does not make the peer address available, e.g., if an Axum
+/// app is not served with `into_make_service_with_connect_info::<SocketAddr>()`. Rate-limited
+/// server functions then fail with `500 Internal Server Error` instead of sharing one limit
+/// between all callers.
+pub fn peer_addr_key(info: &ClientInfo) -> Option<String> {
+    info.peer_addr.map(|addr| addr.ip().to_string())
+}
+
+static RATE_LIMIT_STORE: LazyLock<RwLock<Arc<dyn RateLimitStore>>> =
+    LazyLock::new(|| RwLock::new(Arc::new(InMemoryRateLimitStore::new())));
+
+static CLIENT_KEY: RwLock<fn(&ClientInfo) -> Option<String>> =
+    RwLock::new(peer_addr_key);
+
+/// Sets the store used to track rate limits for all server functions.
+pub fn set_rate_limit_store(store: impl RateLimitStore) {
+    *RATE_LIMIT_STORE.write().or_poisoned() = Arc::new(store);
+}
+
+/// Sets the function used to identify callers for rate limiting.
+///
+/// If it returns `None`, the caller cannot be identified, and the request is rejected with
+/// `500 Internal Server Error`. Defaults to [`peer_addr_key`].
+pub fn set_client_key(key: fn(&ClientInfo) -> Option<String>) {
+    *CLIENT_KEY.write().or_poisoned() = key;
+}
+
+/// A request that was rejected before running the server function.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct Rejection {
+    pub status: http::StatusCode,
+    pub retry_after: Option<Duration>,
+    pub error: ServerFnErrorErr,
+}
+
+impl Rejection {
+    fn too_large(error: ServerFnErrorErr) -> Self {
+        Self {
+            status: http::StatusCode::PAYLOAD_TOO_LARGE,
+            retry_after: None,
+            error,
+        }
+    }
+}
+
+/// Checks the request against the server function's limits.
+///
+/// Everything needed from the request is read before the returned future is created, so the
+/// future does not borrow the request.
+pub(crate) fn check<Request, E, IS, OS>(
+    path: &str,
+    req: &Request,
+    max_body: Option<usize>,
+    rate_limit: Option<RateLimit>,
+) -> impl Future<Output = Result<(), Rejection>> + Send + 'static
+where
+    Request: Req<E, IS, OS>,
+{
+    let too_large = max_body.filter(|max_body| {
+        req.header("content-length")
+            .and_then(|len| len.trim().parse::<u64>().ok())
+            .is_some_and(|len| len > *max_body as u64)
+    });
+    let rate_limit = rate_limit.map(|rate_limit| {
+        let client =
+            (CLIENT_KEY.read().or_poisoned())(&ClientInfo::from_req(req));
+        client.map(|client| (format!("{path}|{client}"), rate_limit))
+    });
+
+    async move {
+        if let Some(max_body) = too_large {
+            return Err(Rejection::too_large(
+                BodyLimit::new(max_body).reject(),
+            ));
+        }
+
+        let rate_limit = match rate_limit {
+            Some(None) => {
+                return Err(Rejection {
+                    status: http::StatusCode::INTERNAL_SERVER_ERROR,
+                    retry_after: None,
+                    error: ServerFnErrorErr::ServerError(
+                        "cannot identify the caller to apply the rate limit; \
+                         the server does not provide the peer address, so use \
+                         `server_fn::limits::set_client_key` to identify \
+                         callers"
+                            .into(),
+                    ),
+                })
+            }
+            rate_limit => rate_limit.flatten(),
+        };
+        if let Some((key, rate_limit)) = rate_limit {
+            let store = Arc::clone(&RATE_LIMIT_STORE.read().or_poisoned());
+            if let Err(retry_after) = store.acquire(&key, rate_limit).await {
+                return Err(Rejection {
+                    status: http::StatusCode::TOO_MANY_REQUESTS,
+                    retry_after: Some(retry_after),
+                    error: ServerFnErrorErr::TooManyRequests(format!(
+                        "try again in {} seconds",
+                        retry_after.as_secs_f64().ceil()
+                    )),
+                });
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Applies the server function's `max_body` limit to the body of the request.
+pub(crate) fn limit_body<Request, E, IS, OS>(
+    req: Request,
+    limit: Option<&BodyLimit>,
+) -> Result<Request, Rejection>
+where
+    Request: Req<E, IS, OS>,
+{
+    match limit {
+        Some(limit) => req.limit_body(limit).map_err(Rejection::too_large),
+        None => Ok(req),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn token_bucket_allows_bursts_then_refills() {
+        let store = InMemoryRateLimitStore::new();
+        let limit = RateLimit::per_second(2);
+        let start = Instant::now();
+
+        assert!(store.acquire_at("a", limit, start).is_ok());
+        assert!(store.acquire_at("a", limit, start).is_ok());
+        let retry_after = store.acquire_at("a", limit, start).unwrap_err();
+        assert_eq!(retry_after, Duration::from_millis(500));
+
+        // other keys have their own buckets
+        assert!(store.acquire_at("b", limit, start).is_ok());
+
+        // half a second later, one token has been refilled
+        let later = start + Duration::from_millis(500);
+        assert!(store.acquire_at("a", limit, later).is_ok());
+        assert!(store.acquire_at("a", limit, later).is_err());
+    }
+
+    #[test]
+    fn prunes_full_buckets_by_their_own_period() {
+        let store = InMemoryRateLimitStore::new();
+        let start = Instant::now();
+        for i in 0..MIN_PRUNE_AT / 2 {
+            store
+                .acquire_at(&format!("s{i}"), RateLimit::per_second(1), start)
+                .unwrap();
+            store
+                .acquire_at(&format!("h{i}"), RateLimit::per_hour(1), start)
+                .unwrap();
+        }
+
+        // the per-second buckets have refilled, but the per-hour ones have not
+        let later = start + Duration::from_secs(2);
+        store
+            .acquire_at("new", RateLimit::per_second(1), later)
+            .unwrap();
+        let buckets = store.buckets.lock().unwrap();
+        assert_eq!(buckets.map.len(), MIN_PRUNE_AT / 2 + 1);
+        assert!(buckets.map.keys().all(|key| !key.starts_with('s')));
+        assert_eq!(buckets.prune_at, MIN_PRUNE_AT);
+    }
+
+    #[test]
+    fn limits_body_streams() {
+        let limit = BodyLimit::new(5);
+        let chunks = futures::stream::iter(
+            ["abc", "de", "f", "gh"]
+                .map(|chunk| Ok::<_, ServerFnErrorErr>(Bytes::from(chunk))),
+        );
+        let read = futures::executor::block_on(
+            limit.limit_stream(chunks, |e| e).collect::<Vec<_>>(),
+        );
+        assert_eq!(read.len(), 3);
+        assert!(read[..2].iter().all(Result::is_ok));
+        assert!(matches!(read[2], Err(ServerFnErrorErr::PayloadTooLarge(_))));
+        assert!(limit.exceeded());
+    }
+
+    #[test]
+    fn zero_requests_always_rejects() {
+        let store = InMemoryRateLimitStore::new();
+        let limit = RateLimit::per_minute(0);
+        assert_eq!(
+            store.acquire_at("a", limit, Instant::now()),
+            Err(Duration::from_secs(60))
+        );
+    }
+}
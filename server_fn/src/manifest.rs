@@ -0,0 +1,461 @@
+//! A manifest of the server functions in an application, used to detect changes that would
+//! break clients that are already deployed.
+//!
+//! Each server function is served at a URL that is derived from its name and location unless an
+//! `endpoint` is set, and its arguments and return value are encoded according to its signature.
+//! During a rolling deploy, or while users still have an old version of the app open, clients
+//! built from the previous release keep calling the old URLs with the old encoding. A
+//! [`Manifest`] records the path, method and a hash of the signature of every server function,
+//! so that two releases can be compared before deploying.
+//!
+//! The manifest can be produced at build time: when the `SERVER_FN_MANIFEST_DIR` environment
+//! variable is set while the server is compiled, the `#[server]` macro writes one JSON file per
+//! server function into that directory, and [`Manifest::from_dir`] reads them back, e.g., in a CI
+//! step that compares the manifest with the one of the previous release:
+//!
+//! ```sh
+//! rm -rf target/server_fns
+//! SERVER_FN_MANIFEST_DIR=$PWD/target/server_fns cargo build --features ssr
+//! ```
+//!
+//! The directory must be empty before the build, and the build must compile every crate that
+//! defines server functions, because files are only written when a function is compiled. The
+//! macro only knows the module of a server function from the file it is defined in, so server
+//! functions in inline modules or in modules with a `#[path]` fail to compile while the variable
+//! is set. The macro does not know the HTTP method of a function either, so it is left empty in
+//! these files; a change of protocol still changes the signature hash.
+//!
+//! The manifest can also be built at runtime with [`Manifest::current`], which collects the
+//! server functions that the `#[server]` macro registers with the `inventory` crate. It therefore
+//! has to be built by running code that is linked with all of your server functions, e.g., a
+//! test in your server crate that runs in CI:
+//!
+//! ```rust,ignore
+//! use server_fn::manifest::Manifest;
+//!
+//! #[test]
+//! fn server_fns_are_compatible() {
+//!     let current = Manifest::current();
+//!     let previous = std::fs::read_to_string("server_fns.json").unwrap();
+//!     let previous = Manifest::from_json(&previous).unwrap();
+//!     let changes = previous.breaking_changes(&current);
+//!     assert!(changes.is_empty(), "{changes:#?}");
+//!     std::fs::write("server_fns.json", current.to_json()).unwrap();
+//! }
+//! ```
+//!
+//! The signature hash is computed by the macro from the source text of the function’s argument
+//! names and types, its return type and its protocol, as they are written in the `#[server]`
+//! function. It does not look inside those types: adding a field to a struct that is used as an
+//! argument is *not* detected, while spelling a type differently, e.g., `std::string::String`
+//! instead of `String`, is reported as a change even though the encoding stays the same. Treat
+//! an unchanged hash as a hint, and keep the types used by server functions stable, or version
+//! the function when they change.
+//!
+//! When a server function has to change incompatibly, keep the old version around and add the
+//! new one with `#[server(version = 2)]`. Each version from 2 on has its own URL, so both can be
+//! served side by side. The old version can then be reduced to an adapter with `adapt`: its body
+//! is left empty, and on the server its arguments are converted into the new version with
+//! [`From`], which is run, and its output and error are converted back with [`Into`] (see
+//! [`run_adapted`]):
+//!
+//! ```rust,ignore
+//! #[server(endpoint = "add_todo", adapt = AddTodoV2)]
+//! pub async fn add_todo(title: String) -> Result<(), ServerFnError> {}
+//!
+//! #[server(endpoint = "add_todo", version = 2)]
+//! pub async fn add_todo_v2(
+//!     title: String,
+//!     due: Option<String>,
+//! ) -> Result<(), ServerFnError> {
+//!     // ...
+//! }
+//!
+//! impl From<AddTodo> for AddTodoV2 {
+//!     fn from(older: AddTodo) -> Self {
+//!         AddTodoV2 { title: older.title, due: None }
+//!     }
+//! }
+//! ```
+
+#[cfg(feature = "ssr")]
+use crate::{Protocol, ServerFn};
+#[cfg(feature = "ssr")]
+use http::Method;
+use serde::{Deserialize, Serialize};
+use std::{
+    fmt::{self, Display},
+    io,
+    path::Path,
+};
+
+/// A server function as recorded in the registry, from which a [`Manifest`] is built.
+///
+/// Entries are registered automatically by the `#[server]` macro.
+#[cfg(feature = "ssr")]
+#[derive(Debug, Clone)]
+pub struct ManifestEntry {
+    name: &'static str,
+    path: &'static str,
+    method: Method,
+    version: Option<u32>,
+    signature_hash: u64,
+}
+
+#[cfg(feature = "ssr")]
+impl ManifestEntry {
+    /// Creates the entry for a server function, given its fully-qualified name.
+    pub const fn new<S: ServerFn>(name: &'static str) -> Self {
+        Self {
+            name,
+            path: S::PATH,
+            method: S::Protocol::METHOD,
+            version: S::VERSION,
+            signature_hash: S::SIGNATURE_HASH,
+        }
+    }
+
+    fn to_item(&self) -> ManifestItem {
+        ManifestItem {
+            name: self.name.to_string(),
+            path: self.path.to_string(),
+            method: Some(self.method.to_string()),
+            version: self.version,
+            signature_hash: self.signature_hash,
+        }
+    }
+}
+
+#[cfg(feature = "ssr")]
+inventory::collect!(ManifestEntry);
+
+/// One server function in a [`Manifest`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct ManifestItem {
+    /// The fully-qualified name of the function, e.g. `my_app::todos::add_todo`.
+    pub name: String,
+    /// The URL path at which the function is served.
+    pub path: String,
+    /// The HTTP method the function expects, if it is known.
+    ///
+    /// It is not known for manifests that are written at build time.
+    pub method: Option<String>,
+    /// The version set with `#[server(version = N)]`, if any.
+    pub version: Option<u32>,
+    /// A hash of the source text of the function’s arguments, return type and protocol.
+    ///
+    /// See the [module documentation](self) for what it does and does not detect.
+    pub signature_hash: u64,
+}
+
+/// A list of all the server functions in an application.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Manifest {
+    /// The server functions, sorted by path and method.
+    pub server_fns: Vec<ManifestItem>,
+}
+
+impl Manifest {
+    /// Builds a manifest from the given items.
+    pub fn new(server_fns: impl IntoIterator<Item = ManifestItem>) -> Self {
+        let mut server_fns = server_fns.into_iter().collect::<Vec<_>>();
+        server_fns
+            .sort_by(|a, b| (&a.path, &a.method).cmp(&(&b.path, &b.method)));
+        Self { server_fns }
+    }
+
+    /// Builds a manifest of every server function registered with the `#[server]` macro in the
+    /// current binary.
+    ///
+    /// The functions are collected at runtime, so this only finds the server functions that are
+    /// linked into the binary that calls it.
+    ///
+    /// Functions registered with `register_explicit` are not included.
+    #[cfg(feature = "ssr")]
+    pub fn current() -> Self {
+        Self::new(
+            inventory::iter::<ManifestEntry>
+                .into_iter()
+                .map(ManifestEntry::to_item),
+        )
+    }
+
+    /// Serializes the manifest as pretty-printed JSON.
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self)
+            .expect("a manifest can always be serialized")
+    }
+
+    /// Deserializes a manifest from JSON.
+    pub fn from_json(json: &str) -> Result<Self, serde_json::Error> {
+        serde_json::from_str(json)
+    }
+
+    /// Reads the manifest that the `#[server]` macro wrote into `dir` at build time, from the
+    /// `SERVER_FN_MANIFEST_DIR` environment variable.
+    ///
+    /// See the [module documentation](self) for how to produce it.
+    pub fn from_dir(dir: impl AsRef<Path>) -> io::Result<Self> {
+        let mut server_fns = Vec::new();
+        for entry in std::fs::read_dir(dir)? {
+            let path = entry?.path();
+            if path.extension().is_some_and(|ext| ext == "json") {
+                let json = std::fs::read_to_string(&path)?;
+                let item = serde_json::from_str(&json).map_err(|e| {
+                    io::Error::new(
+                        io::ErrorKind::InvalidData,
+                        format!("{}: {e}", path.display()),
+                    )
+                })?;
+                server_fns.push(item);
+            }
+        }
+        Ok(Self::new(server_fns))
+    }
+
+    /// Lists the changes between this manifest and a newer one that would break clients built
+    /// against this one.
+    ///
+    /// Adding server functions is never a breaking change. Removing one, or changing its path,
+    /// method or signature, is. A change of method is only reported if both manifests know it.
+    pub fn breaking_changes(&self, newer: &Manifest) -> Vec<BreakingChange> {
+        self.server_fns
+            .iter()
+            .filter_map(|old| {
+                let same_path =
+                    newer.server_fns.iter().find(|new| new.path == old.path);
+                let same_fn = || {
+                    newer.server_fns.iter().find(|new| {
+                        new.name == old.name && new.version == old.version
+                    })
+                };
+                match same_path {
+                    Some(ManifestItem {
+                        method: Some(new_method),
+                        ..
+                    }) if old
+                        .method
+                        .as_ref()
+                        .is_some_and(|old| old != new_method) =>
+                    {
+                        Some(BreakingChange::MethodChanged {
+                            path: old.path.clone(),
+                            old: old.method.clone().unwrap_or_default(),
+                            new: new_method.clone(),
+                        })
+                    }
+                    Some(new) if new.signature_hash != old.signature_hash => {
+                        Some(BreakingChange::SignatureChanged {
+                            name: new.name.clone(),
+                            path: old.path.clone(),
+                        })
+                    }
+                    Some(_) => None,
+                    None => Some(match same_fn() {
+                        Some(new) => BreakingChange::PathChanged {
+                            name: old.name.clone(),
+                            old: old.path.clone(),
+                            new: new.path.clone(),
+                        },
+                        None => BreakingChange::Removed {
+                            name: old.name.clone(),
+                            path: old.path.clone(),
+                        },
+                    }),
+                }
+            })
+            .collect()
+    }
+}
+
+/// Runs an older version of a server function by converting it into a newer version, running
+/// that, and converting its output and error back.
+///
+/// This is the body of a server function with `#[server(adapt = Newer)]` on the server.
+#[cfg(feature = "ssr")]
+pub async fn run_adapted<Older, Newer>(
+    older: Older,
+) -> Result<Older::Output, Older::Error>
+where
+    Older: ServerFn + Into<Newer>,
+    Newer: ServerFn,
+    Newer::Output: Into<Older::Output>,
+    Newer::Error: Into<Older::Error>,
+{
+    older
+        .into()
+        .run_body()
+        .await
+        .map(Into::into)
+        .map_err(Into::into)
+}
+
+/// A change between two [`Manifest`]s that would break clients built against the older one.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum BreakingChange {
+    /// A server function is no longer served.
+    Removed {
+        /// The name of the function.
+        name: String,
+        /// The path at which it was served.
+        path: String,
+    },
+    /// A server function is now served at a different path, e.g., because it was moved to
+    /// another module and has no `endpoint`.
+    PathChanged {
+        /// The name of the function.
+        name: String,
+        /// The path at which it was served.
+        old: String,
+        /// The path at which it is now served.
+        new: String,
+    },
+    /// The server function at a path now expects a different HTTP method.
+    MethodChanged {
+        /// The path of the function.
+        path: String,
+        /// The method it used to expect.
+        old: String,
+        /// The method it now expects.
+        new: String,
+    },
+    /// The server function at a path now has different arguments, return type or encodings.
+    SignatureChanged {
+        /// The name of the function now served at the path.
+        name: String,
+        /// The path of the function.
+        path: String,
+    },
+}
+
+impl Display for BreakingChange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BreakingChange::Removed { name, path } => {
+                write!(f, "server function `{name}` at {path} was removed")
+            }
+            BreakingChange::PathChanged { name, old, new } => write!(
+                f,
+                "server function `{name}` moved from {old} to {new}; set \
+                 `endpoint` to keep its path stable"
+            ),
+            BreakingChange::MethodChanged { path, old, new } => write!(
+                f,
+                "server function at {path} changed its method from {old} to \
+                 {new}"
+            ),
+            BreakingChange::SignatureChanged { name, path } => write!(
+                f,
+                "server function `{name}` at {path} changed its signature; \
+                 add a new version with `#[server(version = ...)]` instead"
+            ),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn item(name: &str, path: &str, signature_hash: u64) -> ManifestItem {
+        ManifestItem {
+            name: name.to_string(),
+            path: path.to_string(),
+            method: Some("POST".to_string()),
+            version: None,
+            signature_hash,
+        }
+    }
+
+    #[test]
+    fn reports_breaking_changes() {
+        let old = Manifest::new([
+            item("app::kept", "/api/kept", 1),
+            item("app::moved", "/api/moved123", 2),
+            item("app::changed", "/api/changed", 3),
+            item("app::removed", "/api/removed", 4),
+        ]);
+        let new = Manifest::new([
+            item("app::kept", "/api/kept", 1),
+            item("app::other::moved", "/api/moved456", 2),
+            item("app::moved", "/api/moved789", 2),
+            item("app::changed", "/api/changed", 30),
+            item("app::added", "/api/added", 5),
+        ]);
+
+        let changes = old.breaking_changes(&new);
+        assert_eq!(
+            changes,
+            vec![
+                BreakingChange::SignatureChanged {
+                    name: "app::changed".to_string(),
+                    path: "/api/changed".to_string(),
+                },
+                BreakingChange::PathChanged {
+                    name: "app::moved".to_string(),
+                    old: "/api/moved123".to_string(),
+                    new: "/api/moved789".to_string(),
+                },
+                BreakingChange::Removed {
+                    name: "app::removed".to_string(),
+                    path: "/api/removed".to_string(),
+                },
+            ]
+        );
+        assert!(new.breaking_changes(&new).is_empty());
+    }
+
+    #[test]
+    fn ignores_unknown_methods() {
+        let old = Manifest::new([item("app::a", "/api/a", 1)]);
+        let mut new = old.clone();
+        new.server_fns[0].method = None;
+        assert!(old.breaking_changes(&new).is_empty());
+        new.server_fns[0].method = Some("GET".to_string());
+        assert_eq!(
+            old.breaking_changes(&new),
+            vec![BreakingChange::MethodChanged {
+                path: "/api/a".to_string(),
+                old: "POST".to_string(),
+                new: "GET".to_string(),
+            }]
+        );
+    }
+
+    #[test]
+    fn reads_build_time_entries() {
+        let dir = std::env::temp_dir()
+            .join(format!("server_fn_manifest_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(
+            dir.join("app.todos.add_todo.v2.json"),
+            r#"{
+  "name": "app::todos::add_todo",
+  "path": "/api/add_todo/v2",
+  "method": null,
+  "version": 2,
+  "signature_hash": 18446744073709551615
+}"#,
+        )
+        .unwrap();
+        std::fs::write(dir.join("notes.txt"), "not an entry").unwrap();
+        let manifest = Manifest::from_dir(&dir).unwrap();
+        std::fs::remove_dir_all(&dir).unwrap();
+        assert_eq!(
+            manifest.server_fns,
+            vec![ManifestItem {
+                name: "app::todos::add_todo".to_string(),
+                path: "/api/add_todo/v2".to_string(),
+                method: None,
+                version: Some(2),
+                signature_hash: u64::MAX,
+            }]
+        );
+    }
+
+    #[test]
+    fn round_trips_through_json() {
+        let manifest = Manifest::new([item("app::a", "/api/a", u64::MAX)]);
+        assert_eq!(Manifest::from_json(&manifest.to_json()).unwrap(), manifest);
+    }
+}
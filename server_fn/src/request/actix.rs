@@ -1,14 +1,16 @@
 use crate::{
+    csrf::FormToken,
     error::{FromServerFnError, IntoAppError, ServerFnErrorErr},
+    limits::BodyLimit,
     request::Req,
     response::actix::ActixResponse,
 };
-use actix_web::{web::Payload, FromRequest, HttpRequest};
+use actix_web::{error::PayloadError, web::Payload, FromRequest, HttpRequest};
 use actix_ws::Message;
 use bytes::Bytes;
 use futures::{FutureExt, Stream, StreamExt};
 use send_wrapper::SendWrapper;
-use std::{borrow::Cow, future::Future};
+use std::{borrow::Cow, future::Future, net::SocketAddr, pin::Pin};
 
 /// A wrapped Actix request.
 ///
@@ -23,6 +25,19 @@ impl ActixRequest {
         self.0.take()
     }
 
+    /// Replaces the body of the request with the given stream.
+    fn with_payload(
+        req: HttpRequest,
+        payload: impl Stream<Item = Result<Bytes, PayloadError>> + 'static,
+    ) -> Result<Self, ServerFnErrorErr> {
+        let payload: Pin<Box<dyn Stream<Item = Result<Bytes, PayloadError>>>> =
+            Box::pin(payload);
+        let payload = Payload::from_request(&req, &mut payload.into())
+            .into_inner()
+            .map_err(|e| ServerFnErrorErr::Request(e.to_string()))?;
+        Ok(ActixRequest::from((req, payload)))
+    }
+
     fn header(&self, name: &str) -> Option<Cow<'_, str>> {
         self.0
              .0
@@ -63,6 +78,31 @@ where
         self.header("Referer")
     }
 
+    fn header(&self, name: &str) -> Option<Cow<'_, str>> {
+        ActixRequest::header(self, name)
+    }
+
+    fn peer_addr(&self) -> Option<SocketAddr> {
+        self.0 .0.peer_addr()
+    }
+
+    fn limit_body(self, limit: &BodyLimit) -> Result<Self, ServerFnErrorErr> {
+        let (req, payload) = self.0.take();
+        let payload = limit.limit_stream(payload, |_| PayloadError::Overflow);
+        ActixRequest::with_payload(req, payload)
+    }
+
+    fn require_form_token(
+        self,
+        token: &FormToken,
+    ) -> Result<Self, ServerFnErrorErr> {
+        let (req, payload) = self.0.take();
+        let payload = token.guard_stream(payload, |e| {
+            PayloadError::Io(std::io::Error::other(e))
+        });
+        ActixRequest::with_payload(req, payload)
+    }
+
     fn try_into_bytes(
         self,
     ) -> impl Future<Output = Result<Bytes, Error>> + Send {
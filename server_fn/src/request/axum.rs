@@ -1,5 +1,7 @@
 use crate::{
+    csrf::FormToken,
     error::{FromServerFnError, IntoAppError, ServerFnErrorErr},
+    limits::BodyLimit,
     request::Req,
 };
 use axum::{
@@ -13,7 +15,7 @@ use http::{
     Request,
 };
 use http_body_util::BodyExt;
-use std::borrow::Cow;
+use std::{borrow::Cow, net::SocketAddr};
 
 impl<Error, InputStreamError, OutputStreamError>
     Req<Error, InputStreamError, OutputStreamError> for Request<Body>
@@ -46,6 +48,42 @@ where
             .map(|h| String::from_utf8_lossy(h.as_bytes()))
     }
 
+    fn header(&self, name: &str) -> Option<Cow<'_, str>> {
+        self.headers()
+            .get(name)
+            .map(|h| String::from_utf8_lossy(h.as_bytes()))
+    }
+
+    fn peer_addr(&self) -> Option<SocketAddr> {
+        #[cfg(feature = "axum")]
+        {
+            self.extensions()
+                .get::<axum::extract::ConnectInfo<SocketAddr>>()
+                .map(|info| info.0)
+        }
+        #[cfg(not(feature = "axum"))]
+        {
+            None
+        }
+    }
+
+    fn limit_body(self, limit: &BodyLimit) -> Result<Self, ServerFnErrorErr> {
+        let (parts, body) = self.into_parts();
+        let body =
+            limit.limit_stream(body.into_data_stream(), axum::Error::new);
+        Ok(Request::from_parts(parts, Body::from_stream(body)))
+    }
+
+    fn require_form_token(
+        self,
+        token: &FormToken,
+    ) -> Result<Self, ServerFnErrorErr> {
+        let (parts, body) = self.into_parts();
+        let body =
+            token.guard_stream(body.into_data_stream(), axum::Error::new);
+        Ok(Request::from_parts(parts, Body::from_stream(body)))
+    }
+
     async fn try_into_bytes(self) -> Result<Bytes, Error> {
         let body = self.into_limited_body();
         body.collect().await.map(|c| c.to_bytes()).map_err(|e| {
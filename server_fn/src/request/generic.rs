@@ -13,7 +13,9 @@
 //!   crate under the hood.
 
 use crate::{
+    csrf::FormToken,
     error::{FromServerFnError, IntoAppError, ServerFnErrorErr},
+    limits::BodyLimit,
     request::Req,
 };
 use bytes::Bytes;
@@ -33,6 +35,22 @@ where
 {
     type WebsocketResponse = Response<Bytes>;
 
+    fn limit_body(self, limit: &BodyLimit) -> Result<Self, ServerFnErrorErr> {
+        if self.body().len() > limit.max() {
+            Err(limit.reject())
+        } else {
+            Ok(self)
+        }
+    }
+
+    fn require_form_token(
+        self,
+        token: &FormToken,
+    ) -> Result<Self, ServerFnErrorErr> {
+        token.check(self.body())?;
+        Ok(self)
+    }
+
     async fn try_into_bytes(self) -> Result<Bytes, Error> {
         Ok(self.into_body())
     }
@@ -70,6 +88,12 @@ where
             .map(|val| String::from_utf8_lossy(val.as_bytes()))
     }
 
+    fn header(&self, name: &str) -> Option<Cow<'_, str>> {
+        self.headers()
+            .get(name)
+            .map(|val| String::from_utf8_lossy(val.as_bytes()))
+    }
+
     fn as_query(&self) -> Option<&str> {
         self.uri().query()
     }
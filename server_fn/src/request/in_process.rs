@@ -0,0 +1,116 @@
+use super::ClientReq;
+use crate::error::{FromServerFnError, IntoAppError, ServerFnErrorErr};
+use axum::body::Body;
+use bytes::Bytes;
+use futures::{Stream, StreamExt};
+use http::{
+    header::{ACCEPT, CONTENT_TYPE},
+    Method, Request,
+};
+use std::convert::Infallible;
+
+/// A request that is passed directly to the server function handler, without going over the
+/// network.
+///
+/// See [`InProcessClient`](crate::client::in_process::InProcessClient).
+#[derive(Debug)]
+pub struct InProcessRequest(pub(crate) Request<Body>);
+
+impl InProcessRequest {
+    /// Returns the underlying HTTP request.
+    pub fn into_inner(self) -> Request<Body> {
+        self.0
+    }
+
+    fn try_new<E: FromServerFnError>(
+        path: &str,
+        method: Method,
+        content_type: &str,
+        accepts: &str,
+        body: Body,
+    ) -> Result<Self, E> {
+        Request::builder()
+            .method(method)
+            .uri(path)
+            .header(CONTENT_TYPE, content_type)
+            .header(ACCEPT, accepts)
+            .body(body)
+            .map(InProcessRequest)
+            .map_err(|e| {
+                ServerFnErrorErr::Request(e.to_string()).into_app_error()
+            })
+    }
+}
+
+impl<E> ClientReq<E> for InProcessRequest
+where
+    E: FromServerFnError,
+{
+    /// Multipart form data is only supported by the browser client.
+    type FormData = Infallible;
+
+    fn try_new_req_query(
+        path: &str,
+        content_type: &str,
+        accepts: &str,
+        query: &str,
+        method: Method,
+    ) -> Result<Self, E> {
+        let path = if query.is_empty() {
+            path.to_string()
+        } else {
+            format!("{path}?{query}")
+        };
+        Self::try_new(&path, method, content_type, accepts, Body::empty())
+    }
+
+    fn try_new_req_text(
+        path: &str,
+        content_type: &str,
+        accepts: &str,
+        body: String,
+        method: Method,
+    ) -> Result<Self, E> {
+        Self::try_new(path, method, content_type, accepts, body.into())
+    }
+
+    fn try_new_req_bytes(
+        path: &str,
+        content_type: &str,
+        accepts: &str,
+        body: Bytes,
+        method: Method,
+    ) -> Result<Self, E> {
+        Self::try_new(path, method, content_type, accepts, body.into())
+    }
+
+    fn try_new_req_form_data(
+        _path: &str,
+        _accepts: &str,
+        _content_type: &str,
+        body: Self::FormData,
+        _method: Method,
+    ) -> Result<Self, E> {
+        match body {}
+    }
+
+    fn try_new_req_multipart(
+        _path: &str,
+        _accepts: &str,
+        body: Self::FormData,
+        _method: Method,
+    ) -> Result<Self, E> {
+        match body {}
+    }
+
+    fn try_new_req_streaming(
+        path: &str,
+        accepts: &str,
+        content_type: &str,
+        body: impl Stream<Item = Bytes> + Send + 'static,
+        method: Method,
+    ) -> Result<Self, E> {
+        let body = Body::from_stream(body.map(Ok::<_, Infallible>));
+        Self::try_new(path, method, content_type, accepts, body)
+    }
+}
@@ -0,0 +1,143 @@
+use super::ClientReq;
+use crate::{
+    error::{FromServerFnError, IntoAppError, ServerFnErrorErr},
+    ipc::IpcBody,
+};
+use bytes::Bytes;
+use futures::{Stream, StreamExt};
+use http::{
+    header::{ACCEPT, CONTENT_TYPE},
+    HeaderMap, HeaderValue, Method,
+};
+use std::convert::Infallible;
+
+/// A request sent over an IPC connection.
+///
+/// See [`IpcClient`](crate::client::ipc::IpcClient).
+#[derive(Debug)]
+pub struct IpcRequest {
+    pub(crate) method: Method,
+    pub(crate) path: String,
+    pub(crate) headers: HeaderMap,
+    pub(crate) body: IpcBody,
+}
+
+impl IpcRequest {
+    /// The headers of the request, which can be modified before it is sent.
+    pub fn headers_mut(&mut self) -> &mut HeaderMap {
+        &mut self.headers
+    }
+
+    fn try_new<E: FromServerFnError>(
+        path: String,
+        method: Method,
+        content_type: &str,
+        accepts: &str,
+        body: IpcBody,
+    ) -> Result<Self, E> {
+        let header = |value: &str| {
+            HeaderValue::from_str(value).map_err(|e| {
+                ServerFnErrorErr::Request(e.to_string()).into_app_error()
+            })
+        };
+        let mut headers = HeaderMap::new();
+        headers.insert(CONTENT_TYPE, header(content_type)?);
+        headers.insert(ACCEPT, header(accepts)?);
+        Ok(Self {
+            method,
+            path,
+            headers,
+            body,
+        })
+    }
+}
+
+impl<E> ClientReq<E> for IpcRequest
+where
+    E: FromServerFnError,
+{
+    /// Multipart form data is only supported by the browser client.
+    type FormData = Infallible;
+
+    fn try_new_req_query(
+        path: &str,
+        content_type: &str,
+        accepts: &str,
+        query: &str,
+        method: Method,
+    ) -> Result<Self, E> {
+        let path = if query.is_empty() {
+            path.to_string()
+        } else {
+            format!("{path}?{query}")
+        };
+        Self::try_new(path, method, content_type, accepts, IpcBody::empty())
+    }
+
+    fn try_new_req_text(
+        path: &str,
+        content_type: &str,
+        accepts: &str,
+        body: String,
+        method: Method,
+    ) -> Result<Self, E> {
+        Self::try_new(
+            path.to_string(),
+            method,
+            content_type,
+            accepts,
+            Bytes::from(body).into(),
+        )
+    }
+
+    fn try_new_req_bytes(
+        path: &str,
+        content_type: &str,
+        accepts: &str,
+        body: Bytes,
+        method: Method,
+    ) -> Result<Self, E> {
+        Self::try_new(
+            path.to_string(),
+            method,
+            content_type,
+            accepts,
+            body.into(),
+        )
+    }
+
+    fn try_new_req_form_data(
+        _path: &str,
+        _accepts: &str,
+        _content_type: &str,
+        body: Self::FormData,
+        _method: Method,
+    ) -> Result<Self, E> {
+        match body {}
+    }
+
+    fn try_new_req_multipart(
+        _path: &str,
+        _accepts: &str,
+        body: Self::FormData,
+        _method: Method,
+    ) -> Result<Self, E> {
+        match body {}
+    }
+
+    fn try_new_req_streaming(
+        path: &str,
+        accepts: &str,
+        content_type: &str,
+        body: impl Stream<Item = Bytes> + Send + 'static,
+        method: Method,
+    ) -> Result<Self, E> {
+        Self::try_new(
+            path.to_string(),
+            method,
+            content_type,
+            accepts,
+            IpcBody::from_stream(body.map(Ok)),
+        )
+    }
+}
@@ -1,7 +1,8 @@
+use crate::{csrf::FormToken, error::ServerFnErrorErr, limits::BodyLimit};
 use bytes::Bytes;
 use futures::{Sink, Stream};
 use http::Method;
-use std::{borrow::Cow, future::Future};
+use std::{borrow::Cow, future::Future, net::SocketAddr};
 
 /// Request types for Actix.
 #[cfg(feature = "actix-no-default")]
@@ -14,6 +15,12 @@ pub mod axum;
 pub mod browser;
 #[cfg(feature = "generic")]
 pub mod generic;
+/// Request types for the in-process test client.
+#[cfg(feature = "axum")]
+pub mod in_process;
+/// Request types for server functions called over IPC.
+#[cfg(feature = "ipc")]
+pub mod ipc;
 /// Request types for [`reqwest`].
 #[cfg(feature = "reqwest")]
 pub mod reqwest;
@@ -337,6 +344,52 @@ where
     /// Returns the `Referer` header, if any.
     fn referer(&self) -> Option<Cow<'_, str>>;
 
+    /// Returns the value of the header with the given name, if any.
+    fn header(
+        &self,
+        #[allow(unused_variables)] name: &str,
+    ) -> Option<Cow<'_, str>> {
+        // TODO 0.9: remove this default implementation. It is only included here
+        //  to allow reading arbitrary headers without requiring a semver-incompatible change.
+        None
+    }
+
+    /// Returns the address of the peer that sent the request, if the server makes it available.
+    fn peer_addr(&self) -> Option<SocketAddr> {
+        None
+    }
+
+    /// Limits the body of the request to [`BodyLimit::max`] bytes.
+    ///
+    /// Reading more than that from the body must fail, and call [`BodyLimit::reject`] so that
+    /// the request is answered with `413 Payload Too Large`. Returns an error if the body is
+    /// already known to be too large.
+    ///
+    /// The default implementation does not limit the body, so only the `Content-Length` header
+    /// is checked.
+    fn limit_body(
+        self,
+        #[allow(unused_variables)] limit: &BodyLimit,
+    ) -> Result<Self, ServerFnErrorErr> {
+        Ok(self)
+    }
+
+    /// Requires the URL-encoded form body of the request to repeat the CSRF token in the
+    /// [`CSRF_FIELD`](crate::csrf::CSRF_FIELD) field.
+    ///
+    /// Nothing from the body may be passed on before it has been checked with
+    /// [`FormToken::check`], usually with [`FormToken::guard_stream`].
+    ///
+    /// The default implementation rejects the request, so forms without the
+    /// [`CSRF_HEADER`](crate::csrf::CSRF_HEADER) header only work on servers that implement this.
+    fn require_form_token(
+        self,
+        token: &FormToken,
+    ) -> Result<Self, ServerFnErrorErr> {
+        Err(token
+            .reject("CSRF tokens in forms are not supported by this server"))
+    }
+
     /// Attempts to extract the body of the request into [`Bytes`].
     fn try_into_bytes(
         self,
@@ -396,6 +449,11 @@ where
     fn referer(&self) -> Option<Cow<'_, str>> {
         unreachable!()
     }
+
+    fn header(&self, _name: &str) -> Option<Cow<'_, str>> {
+        unreachable!()
+    }
+
     async fn try_into_bytes(self) -> Result<Bytes, Error> {
         unreachable!()
     }
@@ -1,11 +1,17 @@
 use super::{Res, TryRes};
+#[cfg(any(feature = "gzip", feature = "brotli", feature = "zstd"))]
+use crate::compression;
 use crate::error::{
-    FromServerFnError, ServerFnErrorWrapper, SERVER_FN_ERROR_HEADER,
+    FromServerFnError, ServerFnErrorErr, ServerFnErrorWrapper,
+    SERVER_FN_ERROR_HEADER,
 };
+#[cfg(any(feature = "gzip", feature = "brotli", feature = "zstd"))]
+use actix_web::body::BodyStream;
 use actix_web::{
+    body::{BoxBody, MessageBody},
     http::{
         header,
-        header::{HeaderValue, CONTENT_TYPE, LOCATION},
+        header::{HeaderName, HeaderValue, CONTENT_TYPE, LOCATION},
         StatusCode,
     },
     HttpResponse,
@@ -13,6 +19,8 @@ use actix_web::{
 use bytes::Bytes;
 use futures::{Stream, StreamExt};
 use send_wrapper::SendWrapper;
+#[cfg(any(feature = "gzip", feature = "brotli", feature = "zstd"))]
+use std::{io, pin::Pin};
 
 /// A wrapped Actix response.
 ///
@@ -92,4 +100,112 @@ impl Res for ActixResponse {
             self.0.headers_mut().insert(LOCATION, path);
         }
     }
+
+    fn set_status(&mut self, status: http::StatusCode) {
+        if let Ok(status) = StatusCode::from_u16(status.as_u16()) {
+            *self.0.status_mut() = status;
+        }
+    }
+
+    fn insert_header(&mut self, name: &str, value: &str) {
+        if let (Ok(name), Ok(value)) = (
+            HeaderName::from_bytes(name.as_bytes()),
+            HeaderValue::from_str(value),
+        ) {
+            self.0.headers_mut().insert(name, value);
+        }
+    }
+
+    async fn buffer_body(
+        self,
+    ) -> Result<(Self, Option<Bytes>), ServerFnErrorErr> {
+        let (res, body) = self.0.take().into_parts();
+        Ok(match body.try_into_bytes() {
+            Ok(bytes) => (
+                ActixResponse::from(res.set_body(BoxBody::new(bytes.clone()))),
+                Some(bytes),
+            ),
+            Err(body) => (ActixResponse::from(res.set_body(body)), None),
+        })
+    }
+
+    fn not_modified(&mut self) {
+        *self.0.status_mut() = StatusCode::NOT_MODIFIED;
+        let res = std::mem::replace(
+            &mut *self.0,
+            HttpResponse::NotModified().finish(),
+        );
+        *self.0 = res.set_body(BoxBody::new(()));
+    }
+
+    #[cfg(any(feature = "gzip", feature = "brotli", feature = "zstd"))]
+    async fn compress(
+        self,
+        encoding: compression::ContentEncoding,
+        min_size: usize,
+    ) -> Result<Self, ServerFnErrorErr> {
+        use actix_web::http::header::{CONTENT_ENCODING, ETAG, VARY};
+
+        if self.0.status() != StatusCode::OK
+            || self.0.headers().contains_key(CONTENT_ENCODING)
+        {
+            return Ok(self);
+        }
+        let (mut res, body) = self.0.take().into_parts();
+        let body = match body.try_into_bytes() {
+            Ok(bytes) if bytes.len() < min_size => {
+                return Ok(ActixResponse::from(
+                    res.set_body(BoxBody::new(bytes)),
+                ))
+            }
+            Ok(bytes) => match compression::compress(encoding, &bytes) {
+                Ok(compressed) => BoxBody::new(compressed),
+                Err(_) => {
+                    return Ok(ActixResponse::from(
+                        res.set_body(BoxBody::new(bytes)),
+                    ))
+                }
+            },
+            Err(mut body) => {
+                // the body is not `Send`, but it stays on the same thread as the response
+                let stream =
+                    SendWrapper::new(futures::stream::poll_fn(move |cx| {
+                        Pin::new(&mut body).poll_next(cx).map(|chunk| {
+                            chunk.map(|chunk| {
+                                chunk.map_err(|e| {
+                                    io::Error::other(e.to_string())
+                                })
+                            })
+                        })
+                    }));
+                BoxBody::new(BodyStream::new(compression::compress_stream(
+                    encoding,
+                    stream,
+                    |e| e,
+                )))
+            }
+        };
+
+        let headers = res.headers_mut();
+        headers.insert(
+            CONTENT_ENCODING,
+            HeaderValue::from_static(encoding.as_str()),
+        );
+        let vary = compression::vary(
+            headers.get(VARY).and_then(|value| value.to_str().ok()),
+        );
+        if let Ok(vary) = HeaderValue::from_str(&vary) {
+            headers.insert(VARY, vary);
+        }
+        if let Some(etag) = headers
+            .get(ETAG)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|etag| {
+                HeaderValue::from_str(&compression::weak_etag(etag)).ok()
+            })
+        {
+            headers.insert(ETAG, etag);
+        }
+        Ok(ActixResponse::from(res.set_body(body)))
+    }
 }
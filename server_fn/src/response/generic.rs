@@ -13,13 +13,15 @@
 //!   crate under the hood.
 
 use super::{Res, TryRes};
+#[cfg(any(feature = "gzip", feature = "brotli", feature = "zstd"))]
+use crate::compression;
 use crate::error::{
     FromServerFnError, IntoAppError, ServerFnErrorErr, ServerFnErrorWrapper,
     SERVER_FN_ERROR_HEADER,
 };
 use bytes::Bytes;
 use futures::{Stream, TryStreamExt};
-use http::{header, HeaderValue, Response, StatusCode};
+use http::{header, HeaderName, HeaderValue, Response, StatusCode};
 use std::pin::Pin;
 use throw_error::Error;
 
@@ -113,4 +115,67 @@ impl Res for Response<Body> {
             *self.status_mut() = StatusCode::FOUND;
         }
     }
+
+    fn set_status(&mut self, status: StatusCode) {
+        *self.status_mut() = status;
+    }
+
+    fn insert_header(&mut self, name: &str, value: &str) {
+        if let (Ok(name), Ok(value)) = (
+            HeaderName::from_bytes(name.as_bytes()),
+            HeaderValue::from_str(value),
+        ) {
+            self.headers_mut().insert(name, value);
+        }
+    }
+
+    async fn buffer_body(
+        self,
+    ) -> Result<(Self, Option<Bytes>), ServerFnErrorErr> {
+        let bytes = match self.body() {
+            Body::Sync(bytes) => Some(bytes.clone()),
+            Body::Async(_) => None,
+        };
+        Ok((self, bytes))
+    }
+
+    fn not_modified(&mut self) {
+        *self.status_mut() = StatusCode::NOT_MODIFIED;
+        *self.body_mut() = Body::Sync(Bytes::new());
+    }
+
+    #[cfg(any(feature = "gzip", feature = "brotli", feature = "zstd"))]
+    async fn compress(
+        self,
+        encoding: compression::ContentEncoding,
+        min_size: usize,
+    ) -> Result<Self, ServerFnErrorErr> {
+        if self.status() != StatusCode::OK
+            || self.headers().contains_key(header::CONTENT_ENCODING)
+        {
+            return Ok(self);
+        }
+        let (mut parts, body) = self.into_parts();
+        let body = match body {
+            Body::Sync(bytes) if bytes.len() < min_size => {
+                return Ok(Response::from_parts(parts, Body::Sync(bytes)))
+            }
+            Body::Sync(bytes) => {
+                match compression::compress(encoding, &bytes) {
+                    Ok(compressed) => Body::Sync(compressed),
+                    Err(_) => {
+                        return Ok(Response::from_parts(
+                            parts,
+                            Body::Sync(bytes),
+                        ))
+                    }
+                }
+            }
+            Body::Async(stream) => Body::Async(Box::pin(
+                compression::compress_stream(encoding, stream, Error::from),
+            )),
+        };
+        compression::update_headers(&mut parts.headers, encoding);
+        Ok(Response::from_parts(parts, body))
+    }
 }
@@ -1,12 +1,14 @@
 use super::{Res, TryRes};
+#[cfg(any(feature = "gzip", feature = "brotli", feature = "zstd"))]
+use crate::compression;
 use crate::error::{
     FromServerFnError, IntoAppError, ServerFnErrorErr, ServerFnErrorWrapper,
     SERVER_FN_ERROR_HEADER,
 };
-use axum::body::Body;
+use axum::body::{Body, HttpBody};
 use bytes::Bytes;
 use futures::{Stream, TryStreamExt};
-use http::{header, HeaderValue, Response, StatusCode};
+use http::{header, HeaderName, HeaderValue, Response, StatusCode};
 
 impl<E> TryRes<E> for Response<Body>
 where
@@ -73,4 +75,76 @@ impl Res for Response<Body> {
             *self.status_mut() = StatusCode::FOUND;
         }
     }
+
+    fn set_status(&mut self, status: StatusCode) {
+        *self.status_mut() = status;
+    }
+
+    fn insert_header(&mut self, name: &str, value: &str) {
+        if let (Ok(name), Ok(value)) = (
+            HeaderName::from_bytes(name.as_bytes()),
+            HeaderValue::from_str(value),
+        ) {
+            self.headers_mut().insert(name, value);
+        }
+    }
+
+    async fn buffer_body(
+        self,
+    ) -> Result<(Self, Option<Bytes>), ServerFnErrorErr> {
+        // streamed bodies don't know their length in advance
+        if self.body().size_hint().exact().is_none() {
+            return Ok((self, None));
+        }
+        let (parts, body) = self.into_parts();
+        let bytes = axum::body::to_bytes(body, usize::MAX)
+            .await
+            .map_err(|e| ServerFnErrorErr::Response(e.to_string()))?;
+        Ok((
+            Response::from_parts(parts, Body::from(bytes.clone())),
+            Some(bytes),
+        ))
+    }
+
+    fn not_modified(&mut self) {
+        *self.status_mut() = StatusCode::NOT_MODIFIED;
+        *self.body_mut() = Body::empty();
+    }
+
+    #[cfg(any(feature = "gzip", feature = "brotli", feature = "zstd"))]
+    async fn compress(
+        self,
+        encoding: compression::ContentEncoding,
+        min_size: usize,
+    ) -> Result<Self, ServerFnErrorErr> {
+        if self.status() != StatusCode::OK
+            || self.headers().contains_key(header::CONTENT_ENCODING)
+        {
+            return Ok(self);
+        }
+        let (mut parts, body) = self.into_parts();
+        let body = match body.size_hint().exact() {
+            Some(len) if len < min_size as u64 => {
+                return Ok(Response::from_parts(parts, body))
+            }
+            Some(_) => {
+                let bytes = axum::body::to_bytes(body, usize::MAX)
+                    .await
+                    .map_err(|e| ServerFnErrorErr::Response(e.to_string()))?;
+                match compression::compress(encoding, &bytes) {
+                    Ok(compressed) => Body::from(compressed),
+                    Err(_) => {
+                        return Ok(Response::from_parts(parts, bytes.into()))
+                    }
+                }
+            }
+            None => Body::from_stream(compression::compress_stream(
+                encoding,
+                body.into_data_stream(),
+                axum::Error::new,
+            )),
+        };
+        compression::update_headers(&mut parts.headers, encoding);
+        Ok(Response::from_parts(parts, body))
+    }
 }
@@ -0,0 +1,92 @@
+use super::ClientRes;
+use crate::{
+    client::{in_process::InProcessClient, Client},
+    error::{FromServerFnError, IntoAppError, ServerFnErrorErr},
+    redirect::REDIRECT_HEADER,
+};
+use axum::body::Body;
+use bytes::Bytes;
+use futures::{channel::mpsc, SinkExt, Stream, StreamExt};
+use http::{header::LOCATION, Response};
+
+/// A response returned directly by the server function handler, without going over the
+/// network.
+///
+/// See [`InProcessClient`](crate::client::in_process::InProcessClient).
+#[derive(Debug)]
+pub struct InProcessResponse {
+    pub(crate) path: String,
+    pub(crate) res: Response<Body>,
+}
+
+impl InProcessResponse {
+    /// Returns the underlying HTTP response.
+    pub fn into_inner(self) -> Response<Body> {
+        self.res
+    }
+}
+
+impl<E: FromServerFnError> ClientRes<E> for InProcessResponse {
+    async fn try_into_string(self) -> Result<String, E> {
+        let bytes = ClientRes::<E>::try_into_bytes(self).await?;
+        String::from_utf8(bytes.into()).map_err(|e| {
+            ServerFnErrorErr::Deserialization(e.to_string()).into_app_error()
+        })
+    }
+
+    async fn try_into_bytes(self) -> Result<Bytes, E> {
+        axum::body::to_bytes(self.res.into_body(), usize::MAX)
+            .await
+            .map_err(|e| {
+                ServerFnErrorErr::Deserialization(e.to_string())
+                    .into_app_error()
+            })
+    }
+
+    fn try_into_stream(
+        self,
+    ) -> Result<
+        impl Stream<Item = Result<Bytes, Bytes>> + Send + Sync + 'static,
+        E,
+    > {
+        // the body is not `Sync`, so its chunks are forwarded through a channel, which is, by a
+        // task spawned on the client's executor
+        let (mut tx, rx) = mpsc::channel(16);
+        let mut body = self.res.into_body().into_data_stream();
+        <InProcessClient as Client<E>>::spawn(async move {
+            while let Some(chunk) = body.next().await {
+                let chunk = chunk.map_err(|e| {
+                    E::from_server_fn_error(ServerFnErrorErr::Response(
+                        e.to_string(),
+                    ))
+                    .ser()
+                });
+                if tx.send(chunk).await.is_err() {
+                    break;
+                }
+            }
+        });
+        Ok(rx)
+    }
+
+    fn status(&self) -> u16 {
+        self.res.status().as_u16()
+    }
+
+    fn status_text(&self) -> String {
+        self.res.status().to_string()
+    }
+
+    fn location(&self) -> String {
+        self.res
+            .headers()
+            .get(LOCATION)
+            .map(|value| String::from_utf8_lossy(value.as_bytes()).to_string())
+            .unwrap_or_else(|| self.path.clone())
+    }
+
+    fn has_redirect(&self) -> bool {
+        self.res.headers().contains_key(REDIRECT_HEADER)
+            || self.res.headers().contains_key(LOCATION)
+    }
+}
@@ -0,0 +1,84 @@
+use super::ClientRes;
+use crate::{
+    error::{FromServerFnError, IntoAppError, ServerFnErrorErr},
+    redirect::REDIRECT_HEADER,
+};
+use bytes::{Bytes, BytesMut};
+use futures::{channel::mpsc, Stream, StreamExt};
+use http::{header::LOCATION, HeaderMap, StatusCode};
+use std::io;
+
+/// A response received over an IPC connection.
+///
+/// See [`IpcClient`](crate::client::ipc::IpcClient).
+#[derive(Debug)]
+pub struct IpcResponse {
+    pub(crate) path: String,
+    pub(crate) status: StatusCode,
+    pub(crate) headers: HeaderMap,
+    pub(crate) body: mpsc::Receiver<io::Result<Bytes>>,
+}
+
+impl IpcResponse {
+    /// The headers of the response.
+    pub fn headers(&self) -> &HeaderMap {
+        &self.headers
+    }
+}
+
+impl<E: FromServerFnError> ClientRes<E> for IpcResponse {
+    async fn try_into_string(self) -> Result<String, E> {
+        let bytes = ClientRes::<E>::try_into_bytes(self).await?;
+        String::from_utf8(bytes.into()).map_err(|e| {
+            ServerFnErrorErr::Deserialization(e.to_string()).into_app_error()
+        })
+    }
+
+    async fn try_into_bytes(mut self) -> Result<Bytes, E> {
+        let mut body = BytesMut::new();
+        while let Some(chunk) = self.body.next().await {
+            let chunk = chunk.map_err(|e| {
+                ServerFnErrorErr::Deserialization(e.to_string())
+                    .into_app_error()
+            })?;
+            body.extend_from_slice(&chunk);
+        }
+        Ok(body.freeze())
+    }
+
+    fn try_into_stream(
+        self,
+    ) -> Result<
+        impl Stream<Item = Result<Bytes, Bytes>> + Send + Sync + 'static,
+        E,
+    > {
+        Ok(self.body.map(|chunk| {
+            chunk.map_err(|e| {
+                E::from_server_fn_error(ServerFnErrorErr::Response(
+                    e.to_string(),
+                ))
+                .ser()
+            })
+        }))
+    }
+
+    fn status(&self) -> u16 {
+        self.status.as_u16()
+    }
+
+    fn status_text(&self) -> String {
+        self.status.to_string()
+    }
+
+    fn location(&self) -> String {
+        self.headers
+            .get(LOCATION)
+            .map(|value| String::from_utf8_lossy(value.as_bytes()).to_string())
+            .unwrap_or_else(|| self.path.clone())
+    }
+
+    fn has_redirect(&self) -> bool {
+        self.headers.contains_key(REDIRECT_HEADER)
+            || self.headers.contains_key(LOCATION)
+    }
+}
@@ -9,10 +9,17 @@ pub mod generic;
 /// Response types for Axum.
 #[cfg(feature = "axum-no-default")]
 pub mod http;
+/// Response types for the in-process test client.
+#[cfg(feature = "axum")]
+pub mod in_process;
+/// Response types for server functions called over IPC.
+#[cfg(feature = "ipc")]
+pub mod ipc;
 /// Response types for [`reqwest`].
 #[cfg(feature = "reqwest")]
 pub mod reqwest;
 
+use ::http::StatusCode;
 use bytes::Bytes;
 use futures::Stream;
 use std::future::Future;
@@ -47,6 +54,56 @@ pub trait Res {
     }
     /// Redirect the response by setting a 302 code and Location header.
     fn redirect(&mut self, path: &str);
+    /// Set the status code for the response.
+    fn set_status(&mut self, #[allow(unused_variables)] status: StatusCode) {
+        // TODO 0.9: remove this default implementation. It is only included here
+        //  to allow setting the status for rejected requests without requiring a
+        //  semver-incompatible change.
+    }
+    /// Insert a header into the response, replacing any existing value.
+    fn insert_header(
+        &mut self,
+        #[allow(unused_variables)] name: &str,
+        #[allow(unused_variables)] value: &str,
+    ) {
+        // TODO 0.9: remove this default implementation. It is only included here
+        //  to allow setting headers on responses without requiring a
+        //  semver-incompatible change.
+    }
+    /// Reads the body of the response into memory, if it is not streamed, so that an `ETag` can
+    /// be computed for it. Returns the response, with its body intact, and the body.
+    ///
+    /// Fails if the body could not be read.
+    fn buffer_body(
+        self,
+    ) -> impl Future<
+        Output = Result<(Self, Option<Bytes>), crate::error::ServerFnErrorErr>,
+    > + Send
+    where
+        Self: Sized + Send,
+    {
+        async move { Ok((self, None)) }
+    }
+    /// Turns the response into an empty `304 Not Modified` response, keeping its headers.
+    fn not_modified(&mut self) {
+        self.set_status(StatusCode::NOT_MODIFIED);
+    }
+    /// Compresses the body of a successful response that is not already encoded, if it is
+    /// streamed or at least `min_size` bytes long. See [`compression`](crate::compression).
+    ///
+    /// Fails if the body could not be read.
+    #[cfg(any(feature = "gzip", feature = "brotli", feature = "zstd"))]
+    fn compress(
+        self,
+        #[allow(unused_variables)]
+        encoding: crate::compression::ContentEncoding,
+        #[allow(unused_variables)] min_size: usize,
+    ) -> impl Future<Output = Result<Self, crate::error::ServerFnErrorErr>> + Send
+    where
+        Self: Sized + Send,
+    {
+        async move { Ok(self) }
+    }
 }
 
 /// Represents the response as received by the client.
@@ -115,4 +172,12 @@ impl Res for BrowserMockRes {
     fn redirect(&mut self, _path: &str) {
         unreachable!()
     }
+
+    fn set_status(&mut self, _status: StatusCode) {
+        unreachable!()
+    }
+
+    fn insert_header(&mut self, _name: &str, _value: &str) {
+        unreachable!()
+    }
 }
@@ -0,0 +1,436 @@
+//! Functions that run in a dedicated Web Worker instead of on the server.
+//!
+//! A server function that uses the [`Worker`] protocol is compiled into the client, and its
+//! arguments are posted to a worker that runs the same WebAssembly module, which calls it and
+//! posts back its result. This keeps heavy computations off the main thread without changing
+//! how the function is called:
+//!
+//! ```rust,ignore
+//! use server_fn::{codec::JsonEncoding, worker::Worker};
+//!
+//! #[server(protocol = Worker<JsonEncoding, JsonEncoding>)]
+//! pub async fn render_thumbnail(image: Vec<u8>) -> Result<Vec<u8>, ServerFnError> {
+//!     // runs in the worker
+//! }
+//!
+//! // on the main thread
+//! server_fn::worker::spawn("./worker.js")?;
+//! let thumbnail = render_thumbnail(image).await?;
+//! ```
+//!
+//! The worker script loads the same module and calls [`serve`] once it is initialized:
+//!
+//! ```rust,ignore
+//! #[wasm_bindgen]
+//! pub fn worker_main() {
+//!     server_fn::worker::serve();
+//! }
+//! ```
+//!
+//! Worker functions are registered with `inventory` like other server functions, but they are
+//! not served over HTTP. Until a worker has been spawned and has started serving, and in builds
+//! that do not run in the browser (e.g., tests), they run on the calling thread instead. The
+//! arguments and results are still encoded and decoded, so every encoding behaves the same way
+//! in both cases.
+
+use crate::{
+    error::{FromServerFnError, IntoAppError, ServerFnErrorErr},
+    Decodes, Encodes, Protocol, ServerFn,
+};
+use bytes::{Buf, BufMut, Bytes, BytesMut};
+use futures::{
+    channel::oneshot,
+    future::{BoxFuture, Either},
+};
+use http::Method;
+use js_sys::Uint8Array;
+use send_wrapper::SendWrapper;
+use std::{
+    cell::{Cell, RefCell},
+    collections::HashMap,
+    future::Future,
+    marker::PhantomData,
+    rc::Rc,
+};
+use wasm_bindgen::{closure::Closure, JsCast, JsValue};
+use web_sys::{
+    DedicatedWorkerGlobalScope, MessageEvent, WorkerOptions, WorkerType,
+};
+
+/// A protocol that runs the server function in a Web Worker, encoding its arguments with
+/// `InputEncoding` and its result with `OutputEncoding`.
+///
+/// See the [module documentation](self) for an example.
+pub struct Worker<InputEncoding, OutputEncoding>(
+    PhantomData<(InputEncoding, OutputEncoding)>,
+);
+
+impl<Input, Output, Client, Server, E, InputEncoding, OutputEncoding>
+    Protocol<Input, Output, Client, Server, E>
+    for Worker<InputEncoding, OutputEncoding>
+where
+    Input: Send,
+    Output: Send,
+    InputEncoding: Encodes<Input>,
+    OutputEncoding: Decodes<Output>,
+    E: FromServerFnError + Send,
+    Client: crate::Client<E>,
+    Server: crate::Server<E>,
+{
+    const METHOD: Method = Method::POST;
+
+    async fn run_server<F, Fut>(
+        _request: Server::Request,
+        _server_fn: F,
+    ) -> Result<Server::Response, E>
+    where
+        F: Fn(Input) -> Fut + Send,
+        Fut: Future<Output = Result<Output, E>> + Send,
+    {
+        Err(ServerFnErrorErr::UnsupportedRequestMethod(
+            "This function runs in a web worker, and cannot be called over \
+             HTTP."
+                .into(),
+        )
+        .into_app_error())
+    }
+
+    fn run_client(
+        path: &str,
+        input: Input,
+    ) -> impl Future<Output = Result<Output, E>> + Send {
+        let call = InputEncoding::encode(&input)
+            .map(|args| call(path, args))
+            .map_err(|e| {
+                ServerFnErrorErr::Serialization(e.to_string()).into_app_error()
+            });
+        async move {
+            match call?.await {
+                Reply::Ok(output) => {
+                    OutputEncoding::decode(output).map_err(|e| {
+                        ServerFnErrorErr::Deserialization(e.to_string())
+                            .into_app_error()
+                    })
+                }
+                Reply::Err(err) => Err(E::de(err)),
+                Reply::Failed(message) => {
+                    Err(ServerFnErrorErr::Request(message).into_app_error())
+                }
+            }
+        }
+    }
+}
+
+/// Runs a server function that uses the [`Worker`] protocol from its encoded arguments.
+#[doc(hidden)]
+pub trait RunInWorker<T> {
+    fn run(args: Bytes) -> BoxFuture<'static, Reply>;
+}
+
+impl<T, InputEncoding, OutputEncoding> RunInWorker<T>
+    for Worker<InputEncoding, OutputEncoding>
+where
+    T: ServerFn + 'static,
+    InputEncoding: Decodes<T>,
+    OutputEncoding: Encodes<T::Output>,
+{
+    fn run(args: Bytes) -> BoxFuture<'static, Reply> {
+        let error = |err: ServerFnErrorErr| {
+            Reply::Err(T::Error::from_server_fn_error(err).ser())
+        };
+        let input = InputEncoding::decode(args)
+            .map_err(|e| error(ServerFnErrorErr::Args(e.to_string())));
+        Box::pin(async move {
+            let input = match input {
+                Ok(input) => input,
+                Err(reply) => return reply,
+            };
+            match input.run_body().await {
+                Ok(output) => match OutputEncoding::encode(&output) {
+                    Ok(output) => Reply::Ok(output),
+                    Err(e) => {
+                        error(ServerFnErrorErr::Serialization(e.to_string()))
+                    }
+                },
+                Err(err) => Reply::Err(err.ser()),
+            }
+        })
+    }
+}
+
+/// A function that can be run in a worker, registered by the `#[server]` macro.
+#[doc(hidden)]
+pub struct WorkerFn {
+    path: &'static str,
+    run: fn(Bytes) -> BoxFuture<'static, Reply>,
+}
+
+impl WorkerFn {
+    pub const fn new<T>() -> Self
+    where
+        T: ServerFn,
+        T::Protocol: RunInWorker<T>,
+    {
+        Self {
+            path: T::PATH,
+            run: <T::Protocol as RunInWorker<T>>::run,
+        }
+    }
+}
+
+inventory::collect!(WorkerFn);
+
+/// The result of a call to a worker function.
+#[doc(hidden)]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Reply {
+    /// The encoded output.
+    Ok(Bytes),
+    /// The encoded error returned by the function.
+    Err(Bytes),
+    /// The function could not be called.
+    Failed(String),
+}
+
+/// Runs a worker function on the current thread.
+async fn run_local(path: &str, args: Bytes) -> Reply {
+    match inventory::iter::<WorkerFn>
+        .into_iter()
+        .find(|worker_fn| worker_fn.path == path)
+    {
+        Some(worker_fn) => (worker_fn.run)(args).await,
+        None => Reply::Failed(format!(
+            "No worker function is registered at {path}."
+        )),
+    }
+}
+
+/// A message posted between the main thread and a worker.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Message {
+    Call { id: u64, path: String, args: Bytes },
+    Reply { id: u64, reply: Reply },
+    Ready,
+}
+
+impl Message {
+    fn encode(&self) -> Bytes {
+        let mut buf = BytesMut::new();
+        match self {
+            Message::Call { id, path, args } => {
+                buf.put_u8(0);
+                buf.put_u64(*id);
+                buf.put_u32(path.len() as u32);
+                buf.put_slice(path.as_bytes());
+                buf.put_slice(args);
+            }
+            Message::Reply { id, reply } => {
+                buf.put_u8(1);
+                buf.put_u64(*id);
+                match reply {
+                    Reply::Ok(output) => {
+                        buf.put_u8(0);
+                        buf.put_slice(output);
+                    }
+                    Reply::Err(err) => {
+                        buf.put_u8(1);
+                        buf.put_slice(err);
+                    }
+                    Reply::Failed(message) => {
+                        buf.put_u8(2);
+                        buf.put_slice(message.as_bytes());
+                    }
+                }
+            }
+            Message::Ready => buf.put_u8(2),
+        }
+        buf.freeze()
+    }
+
+    fn decode(mut buf: Bytes) -> Option<Self> {
+        match buf.try_get_u8().ok()? {
+            0 => {
+                let id = buf.try_get_u64().ok()?;
+                let len = buf.try_get_u32().ok()? as usize;
+                let path =
+                    (buf.remaining() >= len).then(|| buf.split_to(len))?;
+                Some(Message::Call {
+                    id,
+                    path: String::from_utf8(path.into()).ok()?,
+                    args: buf,
+                })
+            }
+            1 => {
+                let id = buf.try_get_u64().ok()?;
+                let reply = match buf.try_get_u8().ok()? {
+                    0 => Reply::Ok(buf),
+                    1 => Reply::Err(buf),
+                    2 => Reply::Failed(
+                        String::from_utf8_lossy(&buf).into_owned(),
+                    ),
+                    _ => return None,
+                };
+                Some(Message::Reply { id, reply })
+            }
+            2 => Some(Message::Ready),
+            _ => None,
+        }
+    }
+
+    fn from_event(event: &MessageEvent) -> Option<Self> {
+        let data = event.data().dyn_into::<Uint8Array>().ok()?;
+        Self::decode(data.to_vec().into())
+    }
+
+    fn to_js(&self) -> Uint8Array {
+        Uint8Array::from(&self.encode()[..])
+    }
+}
+
+/// The worker that calls are posted to from the main thread.
+struct Connection {
+    worker: web_sys::Worker,
+    ready: Rc<Cell<bool>>,
+    next_id: Cell<u64>,
+    pending: Rc<RefCell<HashMap<u64, oneshot::Sender<Reply>>>>,
+    _on_message: Closure<dyn FnMut(MessageEvent)>,
+}
+
+thread_local! {
+    static CONNECTION: RefCell<Option<Rc<Connection>>> = const { RefCell::new(None) };
+}
+
+/// Calls a worker function, in the worker if it is ready, or on the current thread otherwise.
+fn call(path: &str, args: Bytes) -> impl Future<Output = Reply> + Send {
+    let path = path.to_owned();
+    let connection = CONNECTION
+        .with(|connection| connection.borrow().clone())
+        .filter(|connection| connection.ready.get());
+    match connection {
+        // only reachable in the browser, where everything runs on the same thread
+        Some(connection) => Either::Left(SendWrapper::new(async move {
+            let id = connection.next_id.get();
+            connection.next_id.set(id + 1);
+            let (tx, rx) = oneshot::channel();
+            connection.pending.borrow_mut().insert(id, tx);
+            let message = Message::Call { id, path, args }.to_js();
+            if let Err(e) = connection.worker.post_message(&message) {
+                connection.pending.borrow_mut().remove(&id);
+                return Reply::Failed(format!("{e:?}"));
+            }
+            rx.await.unwrap_or_else(|_| {
+                Reply::Failed("The worker did not reply.".into())
+            })
+        })),
+        None => Either::Right(async move { run_local(&path, args).await }),
+    }
+}
+
+/// Posts the calls to worker functions that are made on this thread to the given worker, once
+/// it has started to [`serve`] them.
+///
+/// This replaces any worker that was set before.
+pub fn set_worker(worker: web_sys::Worker) {
+    let ready = Rc::new(Cell::new(false));
+    let pending =
+        Rc::new(RefCell::new(HashMap::<u64, oneshot::Sender<Reply>>::new()));
+    let on_message = Closure::<dyn FnMut(MessageEvent)>::new({
+        let ready = Rc::clone(&ready);
+        let pending = Rc::clone(&pending);
+        move |event: MessageEvent| match Message::from_event(&event) {
+            Some(Message::Ready) => ready.set(true),
+            Some(Message::Reply { id, reply }) => {
+                if let Some(tx) = pending.borrow_mut().remove(&id) {
+                    _ = tx.send(reply);
+                }
+            }
+            _ => {}
+        }
+    });
+    worker.set_onmessage(Some(on_message.as_ref().unchecked_ref()));
+    let connection = Connection {
+        worker,
+        ready,
+        next_id: Cell::new(0),
+        pending,
+        _on_message: on_message,
+    };
+    CONNECTION
+        .with(|current| *current.borrow_mut() = Some(Rc::new(connection)));
+}
+
+/// Spawns a module worker from the given script, and posts the calls to worker functions that
+/// are made on this thread to it. See [`set_worker`].
+pub fn spawn(script_url: &str) -> Result<web_sys::Worker, JsValue> {
+    let options = WorkerOptions::new();
+    options.set_type(WorkerType::Module);
+    let worker = web_sys::Worker::new_with_options(script_url, &options)?;
+    set_worker(worker.clone());
+    Ok(worker)
+}
+
+/// Serves the calls to worker functions posted to this worker, and tells the main thread that
+/// it is ready to receive them.
+///
+/// This should be called from the worker, once the WebAssembly module has been initialized.
+pub fn serve() {
+    let scope = js_sys::global().unchecked_into::<DedicatedWorkerGlobalScope>();
+    let on_message =
+        Closure::<dyn FnMut(MessageEvent)>::new(move |event: MessageEvent| {
+            if let Some(Message::Call { id, path, args }) =
+                Message::from_event(&event)
+            {
+                wasm_bindgen_futures::spawn_local(async move {
+                    let reply = run_local(&path, args).await;
+                    let scope = js_sys::global()
+                        .unchecked_into::<DedicatedWorkerGlobalScope>();
+                    _ = scope
+                        .post_message(&Message::Reply { id, reply }.to_js());
+                });
+            }
+        });
+    scope.set_onmessage(Some(on_message.as_ref().unchecked_ref()));
+    // the handler lives as long as the worker
+    on_message.forget();
+    _ = scope.post_message(&Message::Ready.to_js());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::executor::block_on;
+
+    #[test]
+    fn messages_roundtrip() {
+        let messages = [
+            Message::Call {
+                id: 7,
+                path: "/api/render".into(),
+                args: Bytes::from_static(b"{\"x\":1}"),
+            },
+            Message::Reply {
+                id: 7,
+                reply: Reply::Ok(Bytes::from_static(b"2")),
+            },
+            Message::Reply {
+                id: 8,
+                reply: Reply::Err(Bytes::from_static(b"error")),
+            },
+            Message::Reply {
+                id: 9,
+                reply: Reply::Failed("failed".into()),
+            },
+            Message::Ready,
+        ];
+        for message in messages {
+            assert_eq!(Message::decode(message.encode()), Some(message));
+        }
+        assert_eq!(Message::decode(Bytes::from_static(&[0, 1])), None);
+    }
+
+    #[test]
+    fn calls_run_locally_without_a_worker() {
+        let reply = block_on(call("/api/missing", Bytes::new()));
+        assert!(matches!(reply, Reply::Failed(_)));
+    }
+}
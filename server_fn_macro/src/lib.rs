@@ -48,8 +48,18 @@ impl ServerFnCall {
         args: TokenStream2,
         body: TokenStream2,
     ) -> Result<Self> {
-        let args = syn::parse2(args)?;
-        let body = syn::parse2(body)?;
+        let args: ServerFnArgs = syn::parse2(args)?;
+        let body: ServerFnBody = syn::parse2(body)?;
+        if args.adapt.is_some() {
+            let block = syn::parse2::<syn::Block>(body.block.clone())?;
+            if !block.stmts.is_empty() {
+                return Err(syn::Error::new_spanned(
+                    body.block,
+                    "a server function with `adapt` is run by the newer \
+                     version it adapts to, so its body must be empty",
+                ));
+            }
+        }
         let mut myself = ServerFnCall {
             default_path: default_path.into(),
             args,
@@ -276,17 +286,31 @@ impl ServerFnCall {
         }
     }
 
-    fn websocket_protocol(&self) -> bool {
+    fn protocol_is(&self, name: &str) -> bool {
         if let Type::Path(path) = self.protocol() {
             path.path
                 .segments
                 .iter()
-                .any(|segment| segment.ident == "Websocket")
+                .any(|segment| segment.ident == name)
         } else {
             false
         }
     }
 
+    fn websocket_protocol(&self) -> bool {
+        self.protocol_is("Websocket")
+    }
+
+    fn upload_protocol(&self) -> bool {
+        self.protocol_is("ChunkedUpload")
+    }
+
+    /// Whether the server function runs in a web worker, in which case its body is compiled
+    /// into the client as well.
+    fn worker_protocol(&self) -> bool {
+        self.protocol_is("Worker")
+    }
+
     fn serde_path(&self) -> String {
         let path = self
             .server_fn_path()
@@ -347,9 +371,14 @@ impl ServerFnCall {
                     Clone, #server_fn_path::bitcode::Encode, #server_fn_path::bitcode::Decode
                 },
             ),
+            Some("Protobuf") | Some("PatchProtobuf") | Some("PutProtobuf") => {
+                (PathInfo::None, quote! { Clone, Default })
+            }
             Some("MultipartFormData")
             | Some("Streaming")
-            | Some("StreamingText") => (PathInfo::None, quote! {}),
+            | Some("StreamingText")
+            | Some("StreamingJson")
+            | Some("StreamingFramed") => (PathInfo::None, quote! {}),
             Some("SerdeLite") => (
                 PathInfo::Serde,
                 quote! {
@@ -364,6 +393,8 @@ impl ServerFnCall {
                 None => {
                     if self.websocket_protocol() {
                         (PathInfo::None, quote! {})
+                    } else if self.upload_protocol() {
+                        (PathInfo::None, quote! { Clone })
                     } else {
                         (
                             PathInfo::Serde,
@@ -457,12 +488,24 @@ impl ServerFnCall {
 
     /// Generate the code to submit the server function type to inventory.
     pub fn submit_to_inventory(&self) -> TokenStream2 {
+        // worker functions are registered with the worker runtime, not the server
+        if self.worker_protocol() {
+            let server_fn_path = self.server_fn_path();
+            let wrapped_struct_name = self.wrapped_struct_name();
+            return quote! {
+                #server_fn_path::inventory::submit! {{
+                    #server_fn_path::worker::WorkerFn::new::<#wrapped_struct_name>()
+                }}
+            };
+        }
+
         // auto-registration with inventory
         if cfg!(feature = "ssr") {
             let server_fn_path = self.server_fn_path();
             let wrapped_struct_name = self.wrapped_struct_name();
             let wrapped_struct_name_turbofish =
                 self.wrapped_struct_name_turbofish();
+            let fn_name_as_str = self.fn_name_as_str();
             quote! {
                 #server_fn_path::inventory::submit! {{
                     use #server_fn_path::{ServerFn, codec::Encoding};
@@ -470,6 +513,11 @@ impl ServerFnCall {
                         |req| Box::pin(#wrapped_struct_name_turbofish::run_on_server(req)),
                     )
                 }}
+                #server_fn_path::inventory::submit! {{
+                    #server_fn_path::manifest::ManifestEntry::new::<#wrapped_struct_name>(
+                        concat!(module_path!(), "::", #fn_name_as_str),
+                    )
+                }}
             }
         } else {
             quote! {}
@@ -477,8 +525,9 @@ impl ServerFnCall {
     }
 
     /// Generate the server function's URL. This will be the prefix path, then by the
-    /// module path if `SERVER_FN_MOD_PATH` is set, then the function name, and finally
-    /// a hash of the function name and location in the source code.
+    /// module path if `SERVER_FN_MOD_PATH` is set, then the function name, then
+    /// a hash of the function name and location in the source code, and finally
+    /// the version, if one is set.
     pub fn server_fn_url(&self) -> TokenStream2 {
         let default_path = &self.default_path;
         let prefix =
@@ -522,13 +571,16 @@ impl ServerFnCall {
             quote! { "" }
         };
 
+        let version = self.version_suffix();
+
         let fn_name_as_str = self.fn_name_as_str();
         if let Some(fn_path) = fn_path {
             quote! {
                 #server_fn_path::const_format::concatcp!(
                     #prefix,
                     #mod_path,
-                    #fn_path
+                    #fn_path,
+                    #version
                 )
             }
         } else {
@@ -538,10 +590,244 @@ impl ServerFnCall {
                     "/",
                     #mod_path,
                     #fn_name_as_str,
-                    #hash
+                    #hash,
+                    #version
+                )
+            }
+        }
+    }
+
+    /// The suffix that the version adds to the URL. Version 1 is served at the same URL as a
+    /// function without a version, so that adding `version = 1` to it does not move it.
+    fn version_suffix(&self) -> String {
+        self.args
+            .version
+            .filter(|version| *version > 1)
+            .map(|version| format!("/v{version}"))
+            .unwrap_or_default()
+    }
+
+    /// The URL of the server function, as [`Self::server_fn_url`] computes it, given the module
+    /// it is defined in.
+    fn expected_url(&self, module_path: &str) -> Result<String> {
+        let prefix = self
+            .args
+            .prefix
+            .as_ref()
+            .map(LitStr::value)
+            .unwrap_or_else(|| self.default_path.clone());
+        let mod_path = if option_env!("SERVER_FN_MOD_PATH").is_some() {
+            format!("{}/", module_path.replace("::", "/"))
+        } else {
+            String::new()
+        };
+        let version = self.version_suffix();
+        if let Some(fn_path) = &self.args.fn_path {
+            let fn_path = fn_path.value();
+            let fn_path = fn_path.trim_start_matches('/');
+            return Ok(format!("{prefix}{mod_path}/{fn_path}{version}"));
+        }
+        let hash = if option_env!("DISABLE_SERVER_FN_HASH").is_none() {
+            let key_env_var = match option_env!("SERVER_FN_OVERRIDE_KEY") {
+                Some(_) => "SERVER_FN_OVERRIDE_KEY",
+                None => "CARGO_MANIFEST_DIR",
+            };
+            let key = std::env::var(key_env_var).map_err(|_| {
+                syn::Error::new(
+                    Span::call_site(),
+                    format!("`{key_env_var}` is not set"),
+                )
+            })?;
+            xxhash_rust::const_xxh64::xxh64(
+                format!("{key}:{module_path}").as_bytes(),
+                0,
+            )
+            .to_string()
+        } else {
+            String::new()
+        };
+        Ok(format!(
+            "{prefix}/{mod_path}{}{hash}{version}",
+            self.fn_name_as_str()
+        ))
+    }
+
+    /// Writes the server function's entry of the manifest into the directory set with the
+    /// `SERVER_FN_MANIFEST_DIR` environment variable, while it is being compiled.
+    ///
+    /// A macro cannot see the module it is used in, so the module is worked out from the path of
+    /// the source file. The returned code checks at compile time that this module, and the URL
+    /// recorded for it, are the ones the function is actually served at.
+    fn manifest_entry(&self) -> Result<TokenStream2> {
+        let Some(dir) = option_env!("SERVER_FN_MANIFEST_DIR") else {
+            return Ok(quote! {});
+        };
+        if !cfg!(feature = "ssr") || self.worker_protocol() {
+            return Ok(quote! {});
+        }
+        let span = self.body.ident.span();
+        let module_path = self.module_path().ok_or_else(|| {
+            syn::Error::new(
+                span,
+                "the server function manifest could not find the module of \
+                 this server function from its source file",
+            )
+        })?;
+        let fn_name = self.fn_name_as_str();
+        let name = format!("{module_path}::{fn_name}");
+        let path = self.expected_url(&module_path)?;
+        let version = self
+            .args
+            .version
+            .map_or_else(|| "null".to_string(), |version| version.to_string());
+        let entry = format!(
+            "{{\n  \"name\": {},\n  \"path\": {},\n  \"method\": null,\n  \
+             \"version\": {version},\n  \"signature_hash\": {}\n}}\n",
+            json_string(&name),
+            json_string(&path),
+            self.signature_hash()
+        );
+        let file_name = match self.args.version {
+            Some(version) => {
+                format!("{}.v{version}.json", name.replace("::", "."))
+            }
+            None => format!("{}.json", name.replace("::", ".")),
+        };
+        std::fs::create_dir_all(dir)
+            .and_then(|()| {
+                std::fs::write(std::path::Path::new(dir).join(file_name), entry)
+            })
+            .map_err(|e| {
+                syn::Error::new(
+                    span,
+                    format!(
+                        "could not write the server function manifest to \
+                         {dir}: {e}"
+                    ),
                 )
+            })?;
+
+        let server_fn_path = self.server_fn_path();
+        let wrapped_struct_name = self.wrapped_struct_name();
+        let module_message = format!(
+            "the server function manifest recorded `{fn_name}` as defined in \
+             `{module_path}`, which is wrong for server functions in inline \
+             modules or modules with a #[path]; move it into a module of its \
+             own file"
+        );
+        let path_message = format!(
+            "the server function manifest recorded `{fn_name}` as served at \
+             {path}, which is not its URL"
+        );
+        Ok(quote! {
+            const _: () = {
+                assert!(
+                    #server_fn_path::const_str::equal!(module_path!(), #module_path),
+                    #module_message
+                );
+                assert!(
+                    #server_fn_path::const_str::equal!(
+                        <#wrapped_struct_name as #server_fn_path::ServerFn>::PATH,
+                        #path
+                    ),
+                    #path_message
+                );
+            };
+        })
+    }
+
+    /// The module that the server function is defined in, worked out from the path of its
+    /// source file, e.g. `my_app::todos` for `src/todos.rs` or `src/todos/mod.rs`.
+    fn module_path(&self) -> Option<String> {
+        let file = self.body.ident.span().local_file()?;
+        let crate_dir = std::env::var("CARGO_MANIFEST_DIR").ok()?;
+        let crate_name = std::env::var("CARGO_CRATE_NAME").ok()?;
+        let file = file.strip_prefix(crate_dir).unwrap_or(&file);
+        let parts = file
+            .with_extension("")
+            .iter()
+            .map(|part| part.to_str().map(ToString::to_string))
+            .collect::<Option<Vec<_>>>()?;
+        // binaries, tests, examples and benches are crates of their own, whose root is either
+        // the file itself or its `main.rs`
+        let mut modules = match parts.as_slice() {
+            [src, bin, _, modules @ ..] if src == "src" && bin == "bin" => {
+                modules
             }
+            [dir, _, modules @ ..]
+                if ["tests", "examples", "benches"].contains(&dir.as_str()) =>
+            {
+                modules
+            }
+            [src, modules @ ..] if src == "src" => match modules {
+                [root] if root == "lib" => &[],
+                modules => modules,
+            },
+            _ => return None,
+        }
+        .to_vec();
+        if modules == ["main"] {
+            modules.clear();
         }
+        if modules.last().is_some_and(|module| module == "mod") {
+            modules.pop();
+        }
+        Some(
+            std::iter::once(crate_name)
+                .chain(modules)
+                .collect::<Vec<_>>()
+                .join("::"),
+        )
+    }
+
+    /// The server-only body of a server function with `adapt`, which converts its arguments
+    /// into the newer version, runs that, and converts the result back.
+    fn adapter_output(&self, newer: &Type) -> TokenStream2 {
+        let server_fn_path = self.server_fn_path();
+        let struct_name = self.struct_name();
+        let wrapped_struct_name = self.wrapped_struct_name();
+        let dummy_name = self.body.to_dummy_ident();
+        let field_names = self.field_names();
+        let fn_args = self.body.inputs.iter().map(|f| &f.arg);
+        let return_ty = &self.body.return_ty;
+        let vis = &self.body.vis;
+        let data = match &self.args.custom_wrapper {
+            Some(wrapper) => {
+                quote! { #wrapper(#struct_name { #(#field_names),* }) }
+            }
+            None => quote! { #struct_name { #(#field_names),* } },
+        };
+        quote! {
+            #[doc(hidden)]
+            #vis async fn #dummy_name(#(#fn_args),*) -> #return_ty {
+                #server_fn_path::manifest::run_adapted::<#wrapped_struct_name, #newer>(#data).await
+            }
+        }
+    }
+
+    /// Hash the parts of the server function that determine what its clients send and
+    /// receive: the names and types of its arguments, its return type, and its protocol.
+    ///
+    /// Only the tokens as written are hashed; a macro cannot see the definitions of the types.
+    fn signature_hash(&self) -> u64 {
+        let args = self
+            .body
+            .inputs
+            .iter()
+            .map(|f| {
+                let (pat, ty) = (&f.arg.pat, &f.arg.ty);
+                quote!(#pat: #ty).to_string()
+            })
+            .collect::<Vec<_>>()
+            .join(", ");
+        let return_ty = &self.body.return_ty;
+        let protocol = self.protocol();
+        let signature = format!(
+            "({args}) -> {} via {}",
+            quote!(#return_ty),
+            quote!(#protocol)
+        );
+        xxhash_rust::const_xxh64::xxh64(signature.as_bytes(), 0)
     }
 
     /// Get the names of the fields the server function takes as inputs.
@@ -601,7 +887,7 @@ impl ServerFnCall {
         let field_names = self.field_names();
 
         // run_body in the trait implementation
-        let run_body = if cfg!(feature = "ssr") {
+        let run_body = if cfg!(feature = "ssr") || self.worker_protocol() {
             let destructure =
                 if let Some(wrapper) = self.args.custom_wrapper.as_ref() {
                     quote! {
@@ -664,9 +950,54 @@ impl ServerFnCall {
         };
         let wrapped_struct_name = self.wrapped_struct_name();
 
+        let max_body = self.args.max_body.map(|max_body| {
+            let max_body = max_body as usize;
+            quote! {
+                const MAX_BODY: Option<usize> = Some(#max_body);
+            }
+        });
+        let rate_limit = self.args.rate_limit.map(|(requests, secs)| {
+            quote! {
+                const RATE_LIMIT: Option<#server_fn_path::limits::RateLimit> = Some(
+                    #server_fn_path::limits::RateLimit::new(
+                        #requests,
+                        std::time::Duration::from_secs(#secs),
+                    )
+                );
+            }
+        });
+        let csrf = self.args.csrf.as_ref().map(|csrf| {
+            quote! {
+                const CSRF: bool = #csrf;
+            }
+        });
+        let cache = self.args.cache.as_ref().map(|cache| {
+            quote! {
+                const CACHE: Option<&'static str> = Some(#cache);
+            }
+        });
+        let compress = self.args.compress.as_ref().map(|compress| {
+            quote! {
+                const COMPRESS: bool = #compress;
+            }
+        });
+        let version = self.args.version.map(|version| {
+            quote! {
+                const VERSION: Option<u32> = Some(#version);
+            }
+        });
+        let signature_hash = self.signature_hash();
+
         quote! {
             impl #server_fn_path::ServerFn for #wrapped_struct_name {
                 const PATH: &'static str = #path;
+                #max_body
+                #rate_limit
+                #csrf
+                #cache
+                #compress
+                #version
+                const SIGNATURE_HASH: u64 = #signature_hash;
 
                 type Client = #client;
                 type Server = #server;
@@ -701,7 +1032,8 @@ impl ServerFnCall {
             .as_ref()
             .map(|v| v.value)
             .unwrap_or(true)
-            || self.websocket_protocol();
+            || self.websocket_protocol()
+            || self.upload_protocol();
         if !impl_deref {
             return quote! {};
         }
@@ -721,6 +1053,65 @@ impl ServerFnCall {
         }
     }
 
+    fn protobuf_input(&self) -> bool {
+        matches!(
+            self.input_ident().as_deref(),
+            Some("Protobuf") | Some("PatchProtobuf") | Some("PutProtobuf")
+        )
+    }
+
+    /// Implements `prost::Message` for the arguments struct by delegating to its single field,
+    /// so that the request message is encoded on the wire as-is.
+    fn protobuf_impl(&self) -> TokenStream2 {
+        if !self.protobuf_input() {
+            return quote! {};
+        }
+        let Some((name, _)) = self.single_field() else {
+            return syn::Error::new(
+                self.body.ident.span(),
+                "server functions with Protobuf input must take exactly one \
+                 argument, which is the request message",
+            )
+            .to_compile_error();
+        };
+        let server_fn_path = self.server_fn_path();
+        let struct_name = self.struct_name();
+        quote! {
+            impl #server_fn_path::prost::Message for #struct_name {
+                fn encode_raw(
+                    &self,
+                    buf: &mut impl #server_fn_path::prost::bytes::BufMut,
+                ) {
+                    #server_fn_path::prost::Message::encode_raw(&self.#name, buf)
+                }
+
+                fn merge_field(
+                    &mut self,
+                    tag: u32,
+                    wire_type: #server_fn_path::prost::encoding::WireType,
+                    buf: &mut impl #server_fn_path::prost::bytes::Buf,
+                    ctx: #server_fn_path::prost::encoding::DecodeContext,
+                ) -> ::core::result::Result<(), #server_fn_path::prost::DecodeError> {
+                    #server_fn_path::prost::Message::merge_field(
+                        &mut self.#name,
+                        tag,
+                        wire_type,
+                        buf,
+                        ctx,
+                    )
+                }
+
+                fn encoded_len(&self) -> usize {
+                    #server_fn_path::prost::Message::encoded_len(&self.#name)
+                }
+
+                fn clear(&mut self) {
+                    #server_fn_path::prost::Message::clear(&mut self.#name)
+                }
+            }
+        }
+    }
+
     fn impl_from(&self) -> TokenStream2 {
         let impl_from = self
             .args
@@ -728,7 +1119,8 @@ impl ServerFnCall {
             .as_ref()
             .map(|v| v.value)
             .unwrap_or(true)
-            || self.websocket_protocol();
+            || self.websocket_protocol()
+            || self.upload_protocol();
         if !impl_from {
             return quote! {};
         }
@@ -818,13 +1210,26 @@ impl ToTokens for ServerFnCall {
     fn to_tokens(&self, tokens: &mut TokenStream2) {
         let body = &self.body;
 
-        // only emit the dummy (unmodified server-only body) for the server build
-        let dummy = cfg!(feature = "ssr").then(|| body.to_dummy_output());
+        // only emit the dummy (unmodified server-only body) for the server build, or for
+        // worker functions, which also run in the client
+        let dummy =
+            (cfg!(feature = "ssr") || self.worker_protocol()).then(|| {
+                match &self.args.adapt {
+                    Some(newer) => self.adapter_output(newer),
+                    None => body.to_dummy_output(),
+                }
+            });
+
+        let manifest_entry = self
+            .manifest_entry()
+            .unwrap_or_else(|e| e.to_compile_error());
 
         let impl_from = self.impl_from();
 
         let deref_impl = self.deref_impl();
 
+        let protobuf_impl = self.protobuf_impl();
+
         let inventory = self.submit_to_inventory();
 
         let func = self.func_tokens();
@@ -840,6 +1245,8 @@ impl ToTokens for ServerFnCall {
 
             #deref_impl
 
+            #protobuf_impl
+
             #server_fn_impl
 
             #inventory
@@ -847,6 +1254,8 @@ impl ToTokens for ServerFnCall {
             #func
 
             #dummy
+
+            #manifest_entry
         });
     }
 }
@@ -1040,6 +1449,20 @@ pub struct ServerFnArgs {
     pub impl_deref: Option<LitBool>,
     /// The protocol to use for the server function implementation.
     pub protocol: Option<Type>,
+    /// The largest request body, in bytes, that the server function accepts.
+    pub max_body: Option<u64>,
+    /// The rate limit for the server function, as a number of requests per period in seconds.
+    pub rate_limit: Option<(u32, u64)>,
+    /// Whether the server function is protected against cross-site request forgery.
+    pub csrf: Option<LitBool>,
+    /// The `Cache-Control` header for successful responses to `GET` requests.
+    pub cache: Option<LitStr>,
+    /// Whether the responses of the server function may be compressed.
+    pub compress: Option<LitBool>,
+    /// The version of the server function, which is added to its URL from version 2 on.
+    pub version: Option<u32>,
+    /// The newer version of the server function that this one is served by.
+    pub adapt: Option<Type>,
     builtin_encoding: bool,
 }
 
@@ -1061,6 +1484,13 @@ impl Parse for ServerFnArgs {
         let mut impl_from: Option<LitBool> = None;
         let mut impl_deref: Option<LitBool> = None;
         let mut protocol: Option<Type> = None;
+        let mut max_body: Option<u64> = None;
+        let mut rate_limit: Option<(u32, u64)> = None;
+        let mut csrf: Option<LitBool> = None;
+        let mut cache: Option<LitStr> = None;
+        let mut compress: Option<LitBool> = None;
+        let mut version: Option<u32> = None;
+        let mut adapt: Option<Type> = None;
 
         let mut use_key_and_value = false;
         let mut arg_pos = 0;
@@ -1192,6 +1622,69 @@ impl Parse for ServerFnArgs {
                             ));
                         }
                         protocol = Some(stream.parse()?);
+                    } else if key == "max_body" {
+                        if max_body.is_some() {
+                            return Err(syn::Error::new(
+                                key.span(),
+                                "keyword argument repeated: `max_body`",
+                            ));
+                        }
+                        max_body = Some(parse_body_size(&stream.parse()?)?);
+                    } else if key == "rate_limit" {
+                        if rate_limit.is_some() {
+                            return Err(syn::Error::new(
+                                key.span(),
+                                "keyword argument repeated: `rate_limit`",
+                            ));
+                        }
+                        rate_limit = Some(parse_rate_limit(&stream.parse()?)?);
+                    } else if key == "csrf" {
+                        if csrf.is_some() {
+                            return Err(syn::Error::new(
+                                key.span(),
+                                "keyword argument repeated: `csrf`",
+                            ));
+                        }
+                        csrf = Some(stream.parse()?);
+                    } else if key == "cache" {
+                        if cache.is_some() {
+                            return Err(syn::Error::new(
+                                key.span(),
+                                "keyword argument repeated: `cache`",
+                            ));
+                        }
+                        cache = Some(stream.parse()?);
+                    } else if key == "compress" {
+                        if compress.is_some() {
+                            return Err(syn::Error::new(
+                                key.span(),
+                                "keyword argument repeated: `compress`",
+                            ));
+                        }
+                        compress = Some(stream.parse()?);
+                    } else if key == "version" {
+                        if version.is_some() {
+                            return Err(syn::Error::new(
+                                key.span(),
+                                "keyword argument repeated: `version`",
+                            ));
+                        }
+                        let value: LitInt = stream.parse()?;
+                        if value.base10_parse::<u32>()? == 0 {
+                            return Err(syn::Error::new(
+                                value.span(),
+                                "versions start at 1",
+                            ));
+                        }
+                        version = Some(value.base10_parse()?);
+                    } else if key == "adapt" {
+                        if adapt.is_some() {
+                            return Err(syn::Error::new(
+                                key.span(),
+                                "keyword argument repeated: `adapt`",
+                            ));
+                        }
+                        adapt = Some(stream.parse()?);
                     } else {
                         return Err(lookahead.error());
                     }
@@ -1289,10 +1782,96 @@ impl Parse for ServerFnArgs {
             impl_from,
             impl_deref,
             protocol,
+            max_body,
+            rate_limit,
+            csrf,
+            cache,
+            compress,
+            version,
+            adapt,
         })
     }
 }
 
+/// Parses a body size like `1048576`, `"512KB"` or `"1MB"` into a number of bytes.
+///
+/// Units are powers of 1024, so `"1MB"` is 1,048,576 bytes.
+fn parse_body_size(lit: &Lit) -> Result<u64> {
+    match lit {
+        Lit::Int(int) => int.base10_parse(),
+        Lit::Str(s) => {
+            let value = s.value();
+            let value = value.trim();
+            let split = value
+                .find(|c: char| !c.is_ascii_digit())
+                .unwrap_or(value.len());
+            let (number, unit) = value.split_at(split);
+            let multiplier: u64 =
+                match unit.trim().to_ascii_lowercase().as_str() {
+                    "" | "b" => 1,
+                    "k" | "kb" | "kib" => 1024,
+                    "m" | "mb" | "mib" => 1024 * 1024,
+                    "g" | "gb" | "gib" => 1024 * 1024 * 1024,
+                    _ => {
+                        return Err(syn::Error::new(
+                            s.span(),
+                            "expected a size like \"512KB\" or \"1MB\"",
+                        ))
+                    }
+                };
+            number
+                .parse::<u64>()
+                .ok()
+                .and_then(|number| number.checked_mul(multiplier))
+                .ok_or_else(|| {
+                    syn::Error::new(
+                        s.span(),
+                        "expected a size like \"512KB\" or \"1MB\"",
+                    )
+                })
+        }
+        _ => Err(syn::Error::new(
+            lit.span(),
+            "expected a size like \"512KB\" or \"1MB\"",
+        )),
+    }
+}
+
+/// Parses a rate limit like `"10/s"`, `"100/min"` or `"5/30s"` into a number of requests
+/// and a period in seconds.
+fn parse_rate_limit(lit: &LitStr) -> Result<(u32, u64)> {
+    let err = || {
+        syn::Error::new(
+            lit.span(),
+            "expected a rate limit like \"10/s\", \"100/min\" or \"5/30s\"",
+        )
+    };
+    let value = lit.value();
+    let (requests, period) = value.split_once('/').ok_or_else(err)?;
+    let requests = requests.trim().parse::<u32>().map_err(|_| err())?;
+    let period = period.trim();
+    let split = period
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(period.len());
+    let (count, unit) = period.split_at(split);
+    let count = if count.is_empty() {
+        1
+    } else {
+        count.parse::<u64>().map_err(|_| err())?
+    };
+    let unit_secs = match unit.trim().to_ascii_lowercase().as_str() {
+        "s" | "sec" | "second" | "seconds" => 1,
+        "m" | "min" | "minute" | "minutes" => 60,
+        "h" | "hour" | "hours" => 60 * 60,
+        "d" | "day" | "days" => 24 * 60 * 60,
+        _ => return Err(err()),
+    };
+    match count.checked_mul(unit_secs) {
+        Some(0) | None => Err(err()),
+        Some(secs) => Ok((requests, secs)),
+    }
+}
+
 /// An argument type in a server function.
 #[derive(Debug, Clone)]
 pub struct ServerFnArg {
@@ -1602,6 +2181,23 @@ impl ServerFnBody {
     }
 }
 
+/// A string as a JSON string literal.
+fn json_string(value: &str) -> String {
+    let mut json = String::from('"');
+    for c in value.chars() {
+        match c {
+            '"' => json.push_str("\\\""),
+            '\\' => json.push_str("\\\\"),
+            c if c.is_control() => {
+                json.push_str(&format!("\\u{:04x}", c as u32))
+            }
+            c => json.push(c),
+        }
+    }
+    json.push('"');
+    json
+}
+
 fn is_lint_attr(attr: &Attribute) -> bool {
     let path = &attr.path();
     path.is_ident("allow")
@@ -1610,3 +2206,37 @@ fn is_lint_attr(attr: &Attribute) -> bool {
         || path.is_ident("deny")
         || path.is_ident("forbid")
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_body_sizes() {
+        let size = |lit: Lit| parse_body_size(&lit).ok();
+        assert_eq!(size(parse_quote!(4096)), Some(4096));
+        assert_eq!(size(parse_quote!("512")), Some(512));
+        assert_eq!(size(parse_quote!("512B")), Some(512));
+        assert_eq!(size(parse_quote!("512KB")), Some(512 * 1024));
+        assert_eq!(size(parse_quote!("1 mb")), Some(1024 * 1024));
+        assert_eq!(size(parse_quote!("2GiB")), Some(2 * 1024 * 1024 * 1024));
+        assert_eq!(size(parse_quote!("1TB")), None);
+        assert_eq!(size(parse_quote!("MB")), None);
+        assert_eq!(size(parse_quote!("99999999999999999999KB")), None);
+        assert_eq!(size(parse_quote!(true)), None);
+    }
+
+    #[test]
+    fn parses_rate_limits() {
+        let limit = |lit: LitStr| parse_rate_limit(&lit).ok();
+        assert_eq!(limit(parse_quote!("10/s")), Some((10, 1)));
+        assert_eq!(limit(parse_quote!("100/min")), Some((100, 60)));
+        assert_eq!(limit(parse_quote!("5/30s")), Some((5, 30)));
+        assert_eq!(limit(parse_quote!("1000 / hour")), Some((1000, 60 * 60)));
+        assert_eq!(limit(parse_quote!("1/2d")), Some((1, 2 * 24 * 60 * 60)));
+        assert_eq!(limit(parse_quote!("10")), None);
+        assert_eq!(limit(parse_quote!("10/0s")), None);
+        assert_eq!(limit(parse_quote!("10/week")), None);
+        assert_eq!(limit(parse_quote!("-1/s")), None);
+    }
+}